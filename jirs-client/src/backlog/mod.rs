@@ -0,0 +1,5 @@
+pub use update::update;
+pub use view::view;
+
+mod update;
+mod view;
@@ -0,0 +1,160 @@
+use seed::prelude::*;
+
+use jirs_data::{IssueId, SprintId, UpdateSprintPayload, WsMsg};
+
+use crate::changes::{BacklogPageChange, PageChanged};
+use crate::model::{BacklogPage, Model, Page, PageContent};
+use crate::ws::{enqueue_ws_msg, send_ws_msg};
+use crate::{Msg, WebSocketChanged};
+
+pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Orders<Msg>) {
+    if let Msg::ChangePage(Page::Backlog) = msg {
+        build_page_content(model);
+    }
+
+    let page = match &mut model.page_content {
+        PageContent::Backlog(page) => page,
+        _ => return,
+    };
+
+    if model.user.is_none() {
+        return;
+    }
+    page.edit_name.update(&msg);
+    page.edit_goal.update(&msg);
+    page.edit_starts_at.update(&msg);
+    page.edit_ends_at.update(&msg);
+
+    match msg {
+        Msg::UserChanged(Some(..))
+        | Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::AuthorizeLoaded(..)))
+        | Msg::ChangePage(Page::Backlog) => {
+            init_load(model, orders);
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::IssueDragStarted(issue_id))) => {
+            page.issue_drag.drag(issue_id);
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::IssueDragStopped(_))) => {
+            page.issue_drag.clear();
+            page.drop_target = None;
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::DragOverSprint(sprint_id))) => {
+            if page.issue_drag.dragged_id.is_some() {
+                page.drop_target = Some(sprint_id);
+            }
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::DropOnSprint(sprint_id))) => {
+            let dragged_id = page.issue_drag.dragged_id;
+            page.issue_drag.clear();
+            page.drop_target = None;
+            if let Some(issue_id) = dragged_id {
+                move_issue(model, orders, issue_id, sprint_id);
+            }
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::CreateSprint)) => {
+            let name = format!("Sprint {}", model.sprints.len() + 1);
+            send_ws_msg(WsMsg::SprintCreateRequest(name), model.ws.as_ref(), orders);
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::EditSprint(Some(sprint_id)))) => {
+            if let Some(sprint) = model.sprints.iter().find(|s| s.id == sprint_id) {
+                page.edited_sprint_id = Some(sprint.id);
+                page.edit_name.value = sprint.name.clone();
+                page.edit_goal.value = sprint.goal.clone().unwrap_or_default();
+                page.edit_starts_at.value = format_date(sprint.starts_at);
+                page.edit_ends_at.value = format_date(sprint.ends_at);
+            }
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::EditSprint(None))) => {
+            page.edited_sprint_id = None;
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::SubmitEditSprintForm)) => {
+            let sprint_id = match page.edited_sprint_id.take() {
+                Some(id) => id,
+                _ => return,
+            };
+            let goal = page.edit_goal.value.trim().to_string();
+            let payload = UpdateSprintPayload {
+                id: sprint_id,
+                name: page.edit_name.value.trim().to_string(),
+                goal: if goal.is_empty() { None } else { Some(goal) },
+                starts_at: parse_date(page.edit_starts_at.value.as_str()),
+                ends_at: parse_date(page.edit_ends_at.value.as_str()),
+            };
+            send_ws_msg(
+                WsMsg::SprintUpdateRequest(payload),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::StartSprint(sprint_id))) => {
+            send_ws_msg(
+                WsMsg::SprintStartRequest(sprint_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::CompleteSprint(sprint_id))) => {
+            send_ws_msg(
+                WsMsg::SprintCompleteRequest(sprint_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::DeleteSprint(sprint_id))) => {
+            send_ws_msg(
+                WsMsg::SprintDeleteRequest(sprint_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Sub-tasks are moved by server together with their parent
+fn move_issue(
+    model: &mut Model,
+    orders: &mut impl Orders<Msg>,
+    issue_id: IssueId,
+    sprint_id: Option<SprintId>,
+) {
+    let issue = match model.issues.iter_mut().find(|issue| issue.id == issue_id) {
+        Some(issue) if issue.sprint_id != sprint_id && issue.parent_id.is_none() => issue,
+        _ => return,
+    };
+    issue.sprint_id = sprint_id;
+    send_ws_msg(
+        WsMsg::IssueSprintUpdateRequest(issue_id, sprint_id),
+        model.ws.as_ref(),
+        orders,
+    );
+}
+
+fn format_date(date: Option<chrono::NaiveDate>) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn parse_date(s: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+fn build_page_content(model: &mut Model) {
+    model.page_content = PageContent::Backlog(Box::new(BacklogPage::default()))
+}
+
+fn init_load(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if model.user.is_none() {
+        return;
+    }
+
+    enqueue_ws_msg(
+        vec![
+            WsMsg::ProjectIssuesRequest,
+            WsMsg::IssueStatusesRequest,
+            WsMsg::SprintsRequest,
+        ],
+        model.ws.as_ref(),
+        orders,
+    );
+}
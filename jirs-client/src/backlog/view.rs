@@ -0,0 +1,321 @@
+use seed::{prelude::*, *};
+
+use jirs_data::*;
+
+use crate::model::{BacklogPage, Model, PageContent};
+use crate::shared::styled_button::StyledButton;
+use crate::shared::styled_icon::{Icon, StyledIcon};
+use crate::shared::styled_input::StyledInput;
+use crate::shared::{inner_layout, ToNode};
+use crate::{BacklogPageChange, FieldId, Msg, PageChanged};
+
+pub fn view(model: &Model) -> Node<Msg> {
+    let page = match &model.page_content {
+        PageContent::Backlog(page) => page,
+        _ => return empty![],
+    };
+    let is_manager = model.current_user_role() > UserRole::User;
+
+    let mut sprints: Vec<&Sprint> = model
+        .sprints
+        .iter()
+        .filter(|sprint| sprint.state != SprintState::Completed)
+        .collect();
+    sprints.sort_by_key(|sprint| (sprint.state != SprintState::Active, sprint.id));
+
+    let mut sections: Vec<Node<Msg>> = sprints
+        .into_iter()
+        .map(|sprint| sprint_section(model, page, Some(sprint), is_manager))
+        .collect();
+    sections.push(sprint_section(model, page, None, is_manager));
+
+    let create_sprint = if is_manager {
+        StyledButton::build()
+            .secondary()
+            .text("Create sprint")
+            .icon(Icon::Plus)
+            .on_click(mouse_ev(Ev::Click, |ev| {
+                ev.prevent_default();
+                Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::CreateSprint))
+            }))
+            .build()
+            .into_node()
+    } else {
+        empty![]
+    };
+
+    let body = section![
+        class!["top"],
+        div![
+            class!["header"],
+            h1!["Backlog"],
+            div![class!["actions"], create_sprint]
+        ],
+        sections
+    ];
+
+    inner_layout(model, "backlog", vec![body])
+}
+
+/// Issues planned into sprint, `None` is backlog itself
+fn sprint_section(
+    model: &Model,
+    page: &BacklogPage,
+    sprint: Option<&Sprint>,
+    is_manager: bool,
+) -> Node<Msg> {
+    let sprint_id = sprint.map(|s| s.id);
+    let issues: Vec<Node<Msg>> = model
+        .issues
+        .iter()
+        .filter(|issue| issue.sprint_id == sprint_id && issue.parent_id.is_none())
+        .map(|issue| backlog_issue(model, issue))
+        .collect();
+    let count = format!("{} issues", issues.len());
+
+    let header = match sprint {
+        Some(sprint) => sprint_header(page, sprint, count, is_manager),
+        None => div![
+            class!["sprintHeader"],
+            div![
+                class!["sprintInfo"],
+                span![class!["sprintName"], "Backlog"],
+                span![class!["issuesCount"], count]
+            ]
+        ],
+    };
+
+    let drop_handler = drag_ev(Ev::Drop, move |ev| {
+        ev.prevent_default();
+        Some(Msg::PageChanged(PageChanged::Backlog(
+            BacklogPageChange::DropOnSprint(sprint_id),
+        )))
+    });
+    let drag_over_handler = drag_ev(Ev::DragOver, move |ev| {
+        ev.prevent_default();
+        Some(Msg::PageChanged(PageChanged::Backlog(
+            BacklogPageChange::DragOverSprint(sprint_id),
+        )))
+    });
+
+    let empty_info = if issues.is_empty() {
+        li![class!["emptyInfo"], "Drag issues here to plan them"]
+    } else {
+        empty![]
+    };
+
+    section![
+        class!["sprint"],
+        class!["dropTarget" => page.drop_target == Some(sprint_id)],
+        header,
+        ul![
+            attrs![At::Class => "issues"; At::DropZone => "link"],
+            drop_handler,
+            drag_over_handler,
+            issues,
+            empty_info
+        ]
+    ]
+}
+
+fn sprint_header(
+    page: &BacklogPage,
+    sprint: &Sprint,
+    count: String,
+    is_manager: bool,
+) -> Node<Msg> {
+    if page.edited_sprint_id == Some(sprint.id) {
+        return sprint_form(page);
+    }
+    let sprint_id = sprint.id;
+
+    let dates = match (sprint.starts_at, sprint.ends_at) {
+        (None, None) => empty![],
+        (starts_at, ends_at) => span![
+            class!["sprintDates"],
+            format!(
+                "{} - {}",
+                starts_at
+                    .map(|d| d.format("%d %b").to_string())
+                    .unwrap_or_default(),
+                ends_at
+                    .map(|d| d.format("%d %b").to_string())
+                    .unwrap_or_default(),
+            )
+        ],
+    };
+    let goal = match sprint.goal.as_ref() {
+        Some(goal) => div![class!["sprintGoal"], goal.as_str()],
+        _ => empty![],
+    };
+
+    let mut actions = vec![];
+    if is_manager {
+        let transition = match sprint.state {
+            SprintState::Planned => StyledButton::build()
+                .primary()
+                .text("Start sprint")
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::StartSprint(
+                        sprint_id,
+                    )))
+                }))
+                .build()
+                .into_node(),
+            SprintState::Active => StyledButton::build()
+                .primary()
+                .text("Complete sprint")
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::CompleteSprint(
+                        sprint_id,
+                    )))
+                }))
+                .build()
+                .into_node(),
+            SprintState::Completed => empty![],
+        };
+        let edit = StyledButton::build()
+            .empty()
+            .icon(Icon::EditAlt)
+            .on_click(mouse_ev(Ev::Click, move |ev| {
+                ev.prevent_default();
+                Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::EditSprint(Some(
+                    sprint_id,
+                ))))
+            }))
+            .build()
+            .into_node();
+        let delete = if sprint.state == SprintState::Planned {
+            StyledButton::build()
+                .empty()
+                .icon(Icon::Trash)
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::DeleteSprint(
+                        sprint_id,
+                    )))
+                }))
+                .build()
+                .into_node()
+        } else {
+            empty![]
+        };
+        actions = vec![transition, edit, delete];
+    }
+
+    div![
+        class!["sprintHeader"],
+        div![
+            class!["sprintInfo"],
+            span![class!["sprintName"], sprint.name.as_str()],
+            span![
+                class!["sprintState", sprint.state.to_string()],
+                sprint.state.to_label()
+            ],
+            dates,
+            span![class!["issuesCount"], count]
+        ],
+        div![class!["actions"], actions],
+        goal
+    ]
+}
+
+fn sprint_form(page: &BacklogPage) -> Node<Msg> {
+    let name = StyledInput::build(FieldId::Backlog(SprintFieldId::Name))
+        .state(&page.edit_name)
+        .build()
+        .into_node();
+    let goal = StyledInput::build(FieldId::Backlog(SprintFieldId::Goal))
+        .value(page.edit_goal.value.as_str())
+        .valid(true)
+        .build()
+        .into_node();
+    let starts_at = date_input(SprintFieldId::StartsAt, page.edit_starts_at.value.as_str());
+    let ends_at = date_input(SprintFieldId::EndsAt, page.edit_ends_at.value.as_str());
+
+    let save = StyledButton::build()
+        .primary()
+        .text("Save")
+        .disabled(page.edit_name.value.trim().is_empty())
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::PageChanged(PageChanged::Backlog(
+                BacklogPageChange::SubmitEditSprintForm,
+            ))
+        }))
+        .build()
+        .into_node();
+    let cancel = StyledButton::build()
+        .empty()
+        .text("Cancel")
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::PageChanged(PageChanged::Backlog(BacklogPageChange::EditSprint(None)))
+        }))
+        .build()
+        .into_node();
+
+    div![
+        class!["sprintHeader", "sprintForm"],
+        div![class!["field"], label!["Name"], name],
+        div![class!["field"], label!["Goal"], goal],
+        div![class!["field"], label!["Start date"], starts_at],
+        div![class!["field"], label!["End date"], ends_at],
+        div![class!["actions"], save, cancel]
+    ]
+}
+
+fn date_input(field: SprintFieldId, value: &str) -> Node<Msg> {
+    seed::input![
+        class!["dateInput"],
+        attrs![At::Type => "date"; At::Value => value],
+        input_ev(Ev::Input, move |value| {
+            Msg::StrInputChanged(FieldId::Backlog(field), value)
+        }),
+    ]
+}
+
+fn backlog_issue(model: &Model, issue: &Issue) -> Node<Msg> {
+    let issue_id = issue.id;
+    let type_icon = StyledIcon::build(issue.issue_type.clone().into())
+        .add_style(format!("color: var(--{})", issue.issue_type))
+        .build()
+        .into_node();
+    let priority_icon = StyledIcon::build(issue.priority.clone().into())
+        .add_style(format!("color: var(--{})", issue.priority))
+        .build()
+        .into_node();
+    let status = model
+        .issue_statuses
+        .iter()
+        .find(|status| status.id == issue.issue_status_id)
+        .map(|status| status.name.as_str())
+        .unwrap_or_default();
+
+    let drag_started = drag_ev(Ev::DragStart, move |_| {
+        Some(Msg::PageChanged(PageChanged::Backlog(
+            BacklogPageChange::IssueDragStarted(issue_id),
+        )))
+    });
+    let drag_stopped = drag_ev(Ev::DragEnd, move |_| {
+        Some(Msg::PageChanged(PageChanged::Backlog(
+            BacklogPageChange::IssueDragStopped(issue_id),
+        )))
+    });
+
+    let href = format!("/issues/{id}", id = issue_id);
+    li![
+        attrs![At::Class => "issue"; At::Draggable => true],
+        drag_started,
+        drag_stopped,
+        span![class!["type"], type_icon],
+        span![class!["priority"], priority_icon],
+        a![
+            attrs![At::Class => "title"; At::Href => href],
+            issue.title.as_str()
+        ],
+        span![class!["status"], status]
+    ]
+}
@@ -1,7 +1,10 @@
 use seed::prelude::WebSocketMessage;
 
-use jirs_data::{IssueId, IssueStatusId, WsMsg};
+use jirs_data::{
+    CustomFieldId, IssueId, IssueStatusId, IssueStatusTransitionId, LabelId, SprintId, WsMsg,
+};
 
+use crate::model::IssueActivityTab;
 use crate::shared::styled_editor::Mode as TabMode;
 use crate::FieldId;
 
@@ -11,6 +14,7 @@ pub enum FieldChange {
     TabChanged(FieldId, TabMode),
     ToggleCommentForm(FieldId, bool),
     EditComment(FieldId, i32),
+    ActivityTabChanged(IssueActivityTab),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -43,6 +47,16 @@ pub enum ProjectPageChange {
     // edit issue status name
     EditIssueStatusName(Option<IssueStatusId>),
     SubmitIssueStatusForm,
+    ColumnWipLimitChanged(IssueStatusId, String),
+    // labels
+    SubmitLabelForm,
+    DeleteLabel(LabelId),
+    // custom fields
+    SubmitCustomFieldForm,
+    DeleteCustomField(CustomFieldId),
+    // workflow
+    SubmitTransitionForm,
+    DeleteTransition(IssueStatusTransitionId),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -59,6 +73,24 @@ pub enum InvitationPageChange {
 pub enum ReportsPageChange {
     DayHovered(Option<chrono::NaiveDate>),
     DaySelected(Option<chrono::NaiveDate>),
+    FirstDayChanged(chrono::NaiveDate),
+    LastDayChanged(chrono::NaiveDate),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BacklogPageChange {
+    // dragging
+    IssueDragStarted(IssueId),
+    IssueDragStopped(IssueId),
+    DragOverSprint(Option<SprintId>),
+    DropOnSprint(Option<SprintId>),
+    // sprints
+    CreateSprint,
+    EditSprint(Option<SprintId>),
+    SubmitEditSprintForm,
+    StartSprint(SprintId),
+    CompleteSprint(SprintId),
+    DeleteSprint(SprintId),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -69,6 +101,7 @@ pub enum PageChanged {
     Board(BoardPageChange),
     Invitation(InvitationPageChange),
     Reports(ReportsPageChange),
+    Backlog(BacklogPageChange),
 }
 
 #[derive(Debug)]
@@ -1,14 +1,19 @@
 use jirs_data::{
-    CommentFieldId, InviteFieldId, IssueFieldId, ProjectFieldId, SignInFieldId, SignUpFieldId,
-    UsersFieldId,
+    CommentFieldId, InviteFieldId, IssueFieldId, IssueLinkFieldId, ProjectFieldId, SignInFieldId,
+    SignUpFieldId, SprintFieldId, UsersFieldId, WorklogFieldId,
 };
 
 pub type AvatarFilterActive = bool;
+pub type LabelFilterActive = bool;
+pub type CustomFilterActive = bool;
 
 #[derive(Clone, Debug, PartialOrd, PartialEq, Hash)]
 pub enum EditIssueModalSection {
     Issue(IssueFieldId),
     Comment(CommentFieldId),
+    Subtask(IssueFieldId),
+    Link(IssueLinkFieldId),
+    Worklog(WorklogFieldId),
 }
 
 #[derive(Clone, Debug, PartialOrd, PartialEq, Hash)]
@@ -23,9 +28,13 @@ pub enum FieldId {
     EditIssueModal(EditIssueModalSection),
     // project boards
     TextFilterBoard,
+    JqlFilterBoard,
+    SavedFilterName,
     CopyButtonLabel,
+    SearchIssues,
 
     ProjectSettings(ProjectFieldId),
+    Backlog(SprintFieldId),
 }
 
 impl std::fmt::Display for FieldId {
@@ -68,6 +77,34 @@ impl std::fmt::Display for FieldId {
                 EditIssueModalSection::Issue(IssueFieldId::ListPosition) => {
                     f.write_str("editIssue-listPosition")
                 }
+                EditIssueModalSection::Issue(IssueFieldId::Epic) => {
+                    f.write_str("epicIssueEditModal")
+                }
+                EditIssueModalSection::Issue(IssueFieldId::Parent) => {
+                    f.write_str("parentIssueEditModal")
+                }
+                EditIssueModalSection::Issue(IssueFieldId::Labels) => {
+                    f.write_str("labelsIssueEditModal")
+                }
+                EditIssueModalSection::Issue(IssueFieldId::CustomField(id)) => {
+                    write!(f, "customField{}IssueEditModal", id)
+                }
+                EditIssueModalSection::Subtask(_) => f.write_str("editIssue-subtaskTitle"),
+                EditIssueModalSection::Link(IssueLinkFieldId::LinkType) => {
+                    f.write_str("editIssue-linkType")
+                }
+                EditIssueModalSection::Link(IssueLinkFieldId::TargetIssue) => {
+                    f.write_str("editIssue-linkTarget")
+                }
+                EditIssueModalSection::Worklog(WorklogFieldId::TimeSpent) => {
+                    f.write_str("editIssue-worklogTimeSpent")
+                }
+                EditIssueModalSection::Worklog(WorklogFieldId::StartedAt) => {
+                    f.write_str("editIssue-worklogStartedAt")
+                }
+                EditIssueModalSection::Worklog(WorklogFieldId::Comment) => {
+                    f.write_str("editIssue-worklogComment")
+                }
             },
             FieldId::AddIssueModal(sub) => match sub {
                 IssueFieldId::Type => f.write_str("issueTypeAddIssueModal"),
@@ -81,16 +118,43 @@ impl std::fmt::Display for FieldId {
                 IssueFieldId::TimeSpent => f.write_str("addIssueModal-timeSpend"),
                 IssueFieldId::TimeRemaining => f.write_str("addIssueModal-timeRemaining"),
                 IssueFieldId::ListPosition => f.write_str("addIssueModal-listPosition"),
+                IssueFieldId::Epic => f.write_str("addIssueModal-epic"),
+                IssueFieldId::Parent => f.write_str("addIssueModal-parent"),
+                IssueFieldId::Labels => f.write_str("addIssueModal-labels"),
+                IssueFieldId::CustomField(id) => write!(f, "addIssueModal-customField{}", id),
             },
             FieldId::TextFilterBoard => f.write_str("textFilterBoard"),
+            FieldId::JqlFilterBoard => f.write_str("jqlFilterBoard"),
+            FieldId::SavedFilterName => f.write_str("savedFilterName"),
             FieldId::CopyButtonLabel => f.write_str("copyButtonLabel"),
+            FieldId::SearchIssues => f.write_str("searchIssues"),
             FieldId::ProjectSettings(sub) => match sub {
                 ProjectFieldId::Name => f.write_str("projectSettings-name"),
+                ProjectFieldId::Key => f.write_str("projectSettings-key"),
                 ProjectFieldId::Url => f.write_str("projectSettings-url"),
                 ProjectFieldId::Description => f.write_str("projectSettings-description"),
                 ProjectFieldId::Category => f.write_str("projectSettings-category"),
                 ProjectFieldId::TimeTracking => f.write_str("projectSettings-timeTracking"),
+                ProjectFieldId::WipLimitPolicy => f.write_str("projectSettings-wipLimitPolicy"),
                 ProjectFieldId::IssueStatusName => f.write_str("projectSettings-issueStatusName"),
+                ProjectFieldId::LabelName => f.write_str("projectSettings-labelName"),
+                ProjectFieldId::LabelColor => f.write_str("projectSettings-labelColor"),
+                ProjectFieldId::CustomFieldName => f.write_str("projectSettings-customFieldName"),
+                ProjectFieldId::CustomFieldType => f.write_str("projectSettings-customFieldType"),
+                ProjectFieldId::CustomFieldOptions => {
+                    f.write_str("projectSettings-customFieldOptions")
+                }
+                ProjectFieldId::TransitionFrom => f.write_str("projectSettings-transitionFrom"),
+                ProjectFieldId::TransitionTo => f.write_str("projectSettings-transitionTo"),
+                ProjectFieldId::TransitionRequirement => {
+                    f.write_str("projectSettings-transitionRequirement")
+                }
+            },
+            FieldId::Backlog(sub) => match sub {
+                SprintFieldId::Name => f.write_str("backlog-sprintName"),
+                SprintFieldId::Goal => f.write_str("backlog-sprintGoal"),
+                SprintFieldId::StartsAt => f.write_str("backlog-sprintStartsAt"),
+                SprintFieldId::EndsAt => f.write_str("backlog-sprintEndsAt"),
             },
             FieldId::SignIn(sub) => match sub {
                 SignInFieldId::Email => f.write_str("login-email"),
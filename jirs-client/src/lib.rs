@@ -9,9 +9,10 @@ use crate::model::{ModalType, Model, Page};
 use crate::shared::styled_rte::RteMsg;
 use crate::shared::styled_select::StyledSelectChange;
 use crate::shared::styled_tooltip::{Variant as StyledTooltip, Variant};
-use crate::shared::{go_to_board, go_to_login, styled_tooltip};
+use crate::shared::{browse_issue, go_to_board, go_to_login, styled_tooltip};
 use crate::ws::{flush_queue, open_socket, read_incoming, send_ws_msg};
 
+mod backlog;
 mod changes;
 mod fields;
 mod invite;
@@ -41,6 +42,7 @@ pub enum Msg {
     },
     PageChanged(PageChanged),
     ChangePage(model::Page),
+    BrowseIssue(String),
 
     UserChanged(Option<User>),
     ProjectChanged(Option<Project>),
@@ -66,9 +68,17 @@ pub enum Msg {
 
     // project
     ProjectAvatarFilterChanged(UserId, AvatarFilterActive),
+    ProjectLabelFilterChanged(LabelId, LabelFilterActive),
+    ProjectCustomFilterChanged(CustomFieldId, String, CustomFilterActive),
     ProjectToggleOnlyMy,
     ProjectToggleRecentlyUpdated,
+    ProjectToggleGroupByEpic,
     ProjectClearFilters,
+    ProjectSavedFilterApplied(SavedFilterId),
+    ProjectSavedFilterFormToggle,
+    ProjectSavedFilterToggleShared,
+    ProjectSavedFilterSave,
+    ProjectSavedFilterDelete(SavedFilterId),
 
     // inputs
     StrInputChanged(FieldId, String),
@@ -78,7 +88,9 @@ pub enum Msg {
 
     // issues
     AddIssue,
+    AddSubtask,
     DeleteIssue(IssueId),
+    IssueWatchToggled,
 
     // issue statuses
     DeleteIssueStatus(IssueStatusId),
@@ -87,6 +99,13 @@ pub enum Msg {
     SaveComment,
     DeleteComment(CommentId),
 
+    // worklogs
+    SaveWorklog,
+    DeleteWorklog(WorklogId),
+
+    // issue links
+    DeleteIssueLink(IssueLinkId),
+
     // profile
     AvatarUpdateFetched(String),
 
@@ -94,6 +113,10 @@ pub enum Msg {
     ModalOpened(Box<ModalType>),
     ModalDropped,
     ModalChanged(FieldChange),
+    SearchIssuesToggleAllProjects,
+
+    // toasts
+    ToastDismissed(usize),
 
     // popups
     MessageSeen(MessageId),
@@ -176,6 +199,10 @@ fn update(msg: Msg, model: &mut model::Model, orders: &mut impl Orders<Msg>) {
         Msg::ChangePage(page) => {
             model.page = *page;
         }
+        Msg::BrowseIssue(key) => {
+            model.browse_key = Some(key.clone());
+            browse_issue(model, orders);
+        }
         Msg::ToggleTooltip(variant) => match variant {
             styled_tooltip::Variant::About => {
                 model.about_tooltip_visible = !model.about_tooltip_visible;
@@ -189,6 +216,7 @@ fn update(msg: Msg, model: &mut model::Model, orders: &mut impl Orders<Msg>) {
     }
     crate::shared::aside::update(&msg, model, orders);
     crate::shared::navbar_left::update(&msg, model, orders);
+    crate::shared::toast::update(&msg, model, orders);
     crate::modal::update(&msg, model, orders);
     match model.page {
         Page::Project | Page::AddIssue | Page::EditIssue(..) => project::update(msg, model, orders),
@@ -199,6 +227,7 @@ fn update(msg: Msg, model: &mut model::Model, orders: &mut impl Orders<Msg>) {
         Page::Users => users::update(msg, model, orders),
         Page::Profile => profile::update(msg, model, orders),
         Page::Reports => reports::update(msg, model, orders),
+        Page::Backlog => backlog::update(msg, model, orders),
     }
     if cfg!(debug_assertions) {
         // debug!(model);
@@ -216,10 +245,14 @@ fn view(model: &model::Model) -> Node<Msg> {
         Page::Users => users::view(model),
         Page::Profile => profile::view(model),
         Page::Reports => reports::view(model),
+        Page::Backlog => backlog::view(model),
     }
 }
 
 fn routes(url: Url) -> Option<Msg> {
+    if let Some(key) = browse_key(&url) {
+        return Some(Msg::BrowseIssue(key));
+    }
     match resolve_page(url) {
         Some(page) => Some(Msg::ChangePage(page)),
         _ => None,
@@ -245,11 +278,20 @@ fn resolve_page(url: Url) -> Option<Page> {
         "invite" => Page::Invite,
         "users" => Page::Users,
         "reports" => Page::Reports,
+        "backlog" => Page::Backlog,
         _ => Page::Project,
     };
     Some(page)
 }
 
+/// Issue key from `/browse/JIRS-12` url
+fn browse_key(url: &Url) -> Option<String> {
+    match (url.path().get(0), url.path().get(1)) {
+        (Some(page), Some(key)) if page == "browse" => Some(key.clone()),
+        _ => None,
+    }
+}
+
 pub static mut HOST_URL: String = String::new();
 pub static mut WS_URL: String = String::new();
 
@@ -275,6 +317,7 @@ fn after_mount(url: Url, orders: &mut impl Orders<Msg>) -> AfterMount<Model> {
         HOST_URL = "".to_string();
         WS_URL = "".to_string();
     }
+    model.browse_key = browse_key(&url);
     model.page = resolve_page(url).unwrap_or_else(|| Page::Project);
     open_socket(&mut model, orders);
     AfterMount::new(model).url_handling(UrlHandling::PassToRoutes)
@@ -49,6 +49,7 @@ pub fn update(msg: &Msg, model: &mut crate::model::Model, orders: &mut impl Orde
                 project_id: modal.project_id.unwrap_or(project_id),
                 user_ids: modal.user_ids.clone(),
                 reporter_id: modal.reporter_id.unwrap_or_else(|| user_id),
+                parent_id: None,
             };
             send_ws_msg(
                 jirs_data::WsMsg::IssueCreateRequest(payload),
@@ -56,8 +57,7 @@ pub fn update(msg: &Msg, model: &mut crate::model::Model, orders: &mut impl Orde
                 orders,
             );
         }
-        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueCreated(issue))) => {
-            model.issues.push(issue.clone());
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueCreated(_issue))) => {
             orders.skip().send_msg(Msg::ModalDropped);
         }
 
@@ -130,6 +130,7 @@ pub fn view(model: &Model, modal: &AddIssueModal) -> Node<Msg> {
         .options(
             IssueType::ordered()
                 .iter()
+                .filter(|t| **t != IssueType::Subtask)
                 .map(|t| t.to_child().name("type"))
                 .collect(),
         )
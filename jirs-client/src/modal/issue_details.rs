@@ -3,17 +3,18 @@ use seed::{prelude::*, *};
 use jirs_data::*;
 
 use crate::modal::time_tracking::time_tracking_field;
-use crate::model::{CommentForm, EditIssueModal, ModalType, Model};
+use crate::model::{CommentForm, EditIssueModal, IssueActivityTab, ModalType, Model};
 use crate::shared::styled_avatar::StyledAvatar;
 use crate::shared::styled_button::StyledButton;
 use crate::shared::styled_editor::StyledEditor;
 use crate::shared::styled_field::StyledField;
-use crate::shared::styled_icon::Icon;
+use crate::shared::styled_icon::{Icon, StyledIcon};
 use crate::shared::styled_input::StyledInput;
-use crate::shared::styled_select::{StyledSelect, StyledSelectChange};
+use crate::shared::styled_select::{StyledSelect, StyledSelectChange, StyledSelectState};
+use crate::shared::styled_select_child::{StyledSelectChild, StyledSelectChildBuilder};
 use crate::shared::styled_textarea::StyledTextarea;
 use crate::shared::tracking_widget::tracking_link;
-use crate::shared::{ToChild, ToNode};
+use crate::shared::{find_issue, ToChild, ToNode};
 use crate::ws::send_ws_msg;
 use crate::{EditIssueModalSection, FieldChange, FieldId, Msg, WebSocketChanged};
 
@@ -26,17 +27,83 @@ pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     modal.status_state.update(msg, orders);
     modal.reporter_state.update(msg, orders);
     modal.assignees_state.update(msg, orders);
+    modal.labels_state.update(msg, orders);
     modal.priority_state.update(msg, orders);
+    modal.epic_state.update(msg, orders);
+    modal.parent_state.update(msg, orders);
+    modal.link_type_state.update(msg, orders);
+    modal.link_target_state.update(msg, orders);
     modal.estimate.update(msg);
     modal.estimate_select.update(msg, orders);
-    modal.time_spent.update(msg);
-    modal.time_spent_select.update(msg, orders);
+    modal.worklog_time_spent.update(msg);
+    modal.worklog_time_spent_select.update(msg, orders);
+    modal.worklog_started_at.update(msg);
+    modal.worklog_comment.update(msg);
     modal.time_remaining.update(msg);
     modal.time_remaining_select.update(msg, orders);
+    modal.sync_custom_field_states(&model.custom_fields);
+    for state in modal.custom_field_states.iter_mut() {
+        state.update(msg, orders);
+    }
 
     match msg {
-        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueUpdated(issue))) => {
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueUpdated(issue)))
+            if issue.id == modal.id =>
+        {
             modal.payload = issue.clone().into();
+            // new assignees and reporter start watching issue
+            send_ws_msg(
+                WsMsg::IssueWatchersRequest(modal.id),
+                model.ws.as_ref(),
+                orders,
+            );
+            if modal.activity_tab == IssueActivityTab::History {
+                send_ws_msg(
+                    WsMsg::IssueEventsRequest(modal.id),
+                    model.ws.as_ref(),
+                    orders,
+                );
+            }
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueEventsLoaded(
+            issue_id,
+            events,
+        ))) if *issue_id == modal.id => {
+            modal.events = events.clone();
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueWatchersLoaded(
+            issue_id,
+            watchers,
+        ))) if *issue_id == modal.id => {
+            modal.watchers = watchers.clone();
+        }
+        // commenting starts watching issue
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueCommentsLoaded(comments)))
+            if comments.iter().any(|c| c.issue_id == modal.id) =>
+        {
+            send_ws_msg(
+                WsMsg::IssueWatchersRequest(modal.id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::IssueWatchToggled => {
+            let user_id = model.user.as_ref().map(|u| u.id).unwrap_or_default();
+            send_ws_msg(
+                WsMsg::IssueWatchRequest(modal.id, !modal.watchers.contains(&user_id)),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::ModalChanged(FieldChange::ActivityTabChanged(tab)) => {
+            modal.activity_tab = *tab;
+            if *tab == IssueActivityTab::History {
+                send_ws_msg(
+                    WsMsg::IssueEventsRequest(modal.id),
+                    model.ws.as_ref(),
+                    orders,
+                );
+            }
         }
         Msg::StyledSelectChanged(
             FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Type)),
@@ -120,6 +187,37 @@ pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 orders,
             );
         }
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Labels)),
+            StyledSelectChange::Changed(value),
+        ) => {
+            modal.payload.label_ids.push(*value as LabelId);
+            send_ws_msg(
+                WsMsg::IssueUpdateRequest(
+                    modal.id,
+                    IssueFieldId::Labels,
+                    PayloadVariant::VecI32(modal.payload.label_ids.clone()),
+                ),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Labels)),
+            StyledSelectChange::RemoveMulti(value),
+        ) => {
+            let dropped = *value as LabelId;
+            modal.payload.label_ids.retain(|id| *id != dropped);
+            send_ws_msg(
+                WsMsg::IssueUpdateRequest(
+                    modal.id,
+                    IssueFieldId::Labels,
+                    PayloadVariant::VecI32(modal.payload.label_ids.clone()),
+                ),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
         Msg::StyledSelectChanged(
             FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Priority)),
             StyledSelectChange::Changed(value),
@@ -135,70 +233,218 @@ pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 orders,
             );
         }
-        Msg::StrInputChanged(
-            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Title)),
-            value,
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Epic)),
+            StyledSelectChange::Changed(value),
         ) => {
-            modal.payload.title = value.clone();
+            let msg = match *value as IssueId {
+                0 => {
+                    modal.payload.epic_id = None;
+                    WsMsg::EpicDetachIssueRequest(modal.id)
+                }
+                epic_id => {
+                    modal.payload.epic_id = Some(epic_id);
+                    WsMsg::EpicAttachIssueRequest(epic_id, modal.id)
+                }
+            };
+            send_ws_msg(msg, model.ws.as_ref(), orders);
+        }
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Parent)),
+            StyledSelectChange::Changed(value),
+        ) => {
+            let parent_id = match *value as IssueId {
+                0 => None,
+                parent_id => Some(parent_id),
+            };
+            modal.payload.parent_id = parent_id;
+            send_ws_msg(
+                WsMsg::IssueParentUpdateRequest(modal.id, parent_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        //
+        // custom fields
+        //
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::CustomField(
+                custom_field_id,
+            ))),
+            change,
+        ) => {
+            let field = match model
+                .custom_fields
+                .iter()
+                .find(|f| f.id == *custom_field_id)
+            {
+                Some(field) => field,
+                _ => return,
+            };
+            let mut values = modal.custom_values(field.id);
+            match change {
+                StyledSelectChange::Changed(value) => match custom_field_value(field, *value) {
+                    Some(value) if field.field_type.is_multi() => {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                    Some(value) => values = vec![value],
+                    None => values.clear(),
+                },
+                StyledSelectChange::RemoveMulti(value) => {
+                    if let Some(value) = custom_field_value(field, *value) {
+                        values.retain(|v| *v != value);
+                    }
+                }
+                _ => return,
+            };
+            modal.set_custom_values(field.id, values.clone());
             send_ws_msg(
                 WsMsg::IssueUpdateRequest(
                     modal.id,
-                    IssueFieldId::Title,
-                    PayloadVariant::String(modal.payload.title.clone()),
+                    IssueFieldId::CustomField(field.id),
+                    PayloadVariant::VecString(values),
                 ),
                 model.ws.as_ref(),
                 orders,
             );
         }
         Msg::StrInputChanged(
-            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Description)),
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::CustomField(
+                custom_field_id,
+            ))),
             value,
         ) => {
-            modal.payload.description = Some(value.clone());
-            modal.payload.description_text = Some(value.clone());
+            let values = match value.trim() {
+                "" => vec![],
+                value => vec![value.to_string()],
+            };
+            modal.set_custom_values(*custom_field_id, values.clone());
             send_ws_msg(
                 WsMsg::IssueUpdateRequest(
                     modal.id,
-                    IssueFieldId::Description,
-                    PayloadVariant::String(
-                        modal
-                            .payload
-                            .description
-                            .as_ref()
-                            .cloned()
-                            .unwrap_or_default(),
-                    ),
+                    IssueFieldId::CustomField(*custom_field_id),
+                    PayloadVariant::VecString(values),
                 ),
                 model.ws.as_ref(),
                 orders,
             );
         }
-        // TimeSpent
+        //
+        // links
+        //
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueLinksLoaded(issue_id, links)))
+            if *issue_id == modal.id =>
+        {
+            modal.links = links.clone();
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueLinkCreated(link)))
+            if link.source_issue_id == modal.id || link.target_issue_id == modal.id =>
+        {
+            if !modal.links.iter().any(|l| l.id == link.id) {
+                modal.links.push(link.clone());
+            }
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueLinkDeleted(link_id))) => {
+            modal.links.retain(|link| link.id != *link_id);
+        }
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Link(IssueLinkFieldId::LinkType)),
+            StyledSelectChange::Changed(value),
+        ) => {
+            modal.link_type = (*value).into();
+        }
+        Msg::StyledSelectChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Link(IssueLinkFieldId::TargetIssue)),
+            StyledSelectChange::Changed(value),
+        ) => {
+            send_ws_msg(
+                WsMsg::IssueLinkCreateRequest(CreateIssueLinkPayload {
+                    source_issue_id: modal.id,
+                    target_issue_id: *value as IssueId,
+                    link_type: modal.link_type,
+                }),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::DeleteIssueLink(link_id) => {
+            send_ws_msg(
+                WsMsg::IssueLinkDeleteRequest(*link_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        //
+        // sub-tasks
+        //
+        Msg::StrInputChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Subtask(IssueFieldId::Title)),
+            value,
+        ) => {
+            modal.subtask_title = value.clone();
+        }
+        Msg::AddSubtask if modal.subtask_title.len() >= 3 => {
+            let issue_status_id = model
+                .issue_statuses
+                .first()
+                .map(|is| is.id)
+                .unwrap_or(modal.payload.issue_status_id);
+            let payload = CreateIssuePayload {
+                title: std::mem::replace(&mut modal.subtask_title, String::new()),
+                issue_type: IssueType::Subtask,
+                priority: modal.payload.priority,
+                description: None,
+                description_text: None,
+                estimate: None,
+                time_spent: None,
+                time_remaining: None,
+                project_id: modal.payload.project_id,
+                user_ids: vec![],
+                reporter_id: model.user.as_ref().map(|u| u.id).unwrap_or_default(),
+                issue_status_id,
+                parent_id: Some(modal.id),
+            };
+            send_ws_msg(
+                WsMsg::IssueCreateRequest(payload),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
         Msg::StrInputChanged(
-            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeSpent)),
-            ..,
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Title)),
+            value,
         ) => {
-            modal.payload.time_spent = modal.time_spent.represent_f64_as_i32();
+            modal.payload.title = value.clone();
             send_ws_msg(
                 WsMsg::IssueUpdateRequest(
                     modal.id,
-                    IssueFieldId::TimeSpent,
-                    PayloadVariant::OptionI32(modal.payload.time_spent),
+                    IssueFieldId::Title,
+                    PayloadVariant::String(modal.payload.title.clone()),
                 ),
                 model.ws.as_ref(),
                 orders,
             );
         }
-        Msg::StyledSelectChanged(
-            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeSpent)),
-            StyledSelectChange::Changed(..),
+        Msg::StrInputChanged(
+            FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Description)),
+            value,
         ) => {
-            modal.payload.time_spent = modal.time_spent_select.values.get(0).map(|n| *n as i32);
+            modal.payload.description = Some(value.clone());
+            modal.payload.description_text = Some(value.clone());
             send_ws_msg(
                 WsMsg::IssueUpdateRequest(
                     modal.id,
-                    IssueFieldId::TimeSpent,
-                    PayloadVariant::OptionI32(modal.payload.time_spent),
+                    IssueFieldId::Description,
+                    PayloadVariant::String(
+                        modal
+                            .payload
+                            .description
+                            .as_ref()
+                            .cloned()
+                            .unwrap_or_default(),
+                    ),
                 ),
                 model.ws.as_ref(),
                 orders,
@@ -335,6 +581,59 @@ pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             );
             orders.skip().send_msg(Msg::ModalDropped);
         }
+        //
+        // worklogs
+        //
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::WorklogsLoaded(
+            issue_id,
+            worklogs,
+        ))) if *issue_id == modal.id => {
+            modal.worklogs = worklogs.clone();
+        }
+        Msg::SaveWorklog => {
+            let time_tracking_type = model
+                .project
+                .as_ref()
+                .map(|p| p.time_tracking)
+                .unwrap_or_else(|| TimeTracking::Untracked);
+            let time_spent = match time_tracking_type {
+                TimeTracking::Untracked => None,
+                TimeTracking::Fibonacci => modal
+                    .worklog_time_spent_select
+                    .values
+                    .get(0)
+                    .map(|n| *n as i32),
+                TimeTracking::Hourly => modal.worklog_time_spent.represent_f64_as_i32(),
+            };
+            let started_at = chrono::NaiveDate::parse_from_str(
+                modal.worklog_started_at.value.as_str(),
+                "%Y-%m-%d",
+            );
+            let (time_spent, started_at) = match (time_spent, started_at) {
+                (Some(time_spent), Ok(started_at)) if time_spent > 0 => (time_spent, started_at),
+                _ => return,
+            };
+            send_ws_msg(
+                WsMsg::WorklogCreateRequest(CreateWorklogPayload {
+                    issue_id: modal.id,
+                    time_spent,
+                    started_at,
+                    comment: modal.worklog_comment.value.clone(),
+                }),
+                model.ws.as_ref(),
+                orders,
+            );
+            modal.worklog_time_spent.reset();
+            modal.worklog_time_spent_select.values.clear();
+            modal.worklog_comment.reset();
+        }
+        Msg::DeleteWorklog(worklog_id) => {
+            send_ws_msg(
+                WsMsg::WorklogDeleteRequest(*worklog_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
 
         // global
         Msg::GlobalKeyDown { key, .. } if key.as_str() == "m" && !modal.comment_form.creating => {
@@ -362,7 +661,7 @@ pub fn view(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
     ]
 }
 
-fn top_modal_row(_model: &Model, modal: &EditIssueModal) -> Node<Msg> {
+fn top_modal_row(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
     let EditIssueModal {
         id,
         payload,
@@ -372,9 +671,13 @@ fn top_modal_row(_model: &Model, modal: &EditIssueModal) -> Node<Msg> {
     } = modal;
 
     let issue_id = *id;
+    let issue_key = match (model.project.as_ref(), find_issue(model, issue_id)) {
+        (Some(project), Some(issue)) => project.issue_key(issue),
+        _ => issue_id.to_string(),
+    };
 
+    let link = format!("http://localhost:7000/browse/{key}", key = issue_key);
     let click_handler = mouse_ev(Ev::Click, move |_| {
-        let link = format!("http://localhost:7000/issues/{id}", id = issue_id);
         let el = match seed::html_document().create_element("textarea") {
             Ok(el) => el
                 .dyn_ref::<web_sys::HtmlTextAreaElement>()
@@ -409,6 +712,21 @@ fn top_modal_row(_model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         }]])
         .build()
         .into_node();
+    let watching = model
+        .user
+        .as_ref()
+        .map_or(false, |user| modal.watchers.contains(&user.id));
+    let watch_button = StyledButton::build()
+        .empty()
+        .active(watching)
+        .icon(Icon::Pin)
+        .on_click(mouse_ev(Ev::Click, |_| Msg::IssueWatchToggled))
+        .children(vec![span![
+            if watching { "Watching" } else { "Watch" },
+            span![class!["watchersCount"], modal.watchers.len().to_string()]
+        ]])
+        .build()
+        .into_node();
     let delete_button = StyledButton::build()
         .empty()
         .icon(Icon::Trash.into_styled_builder().size(19).build())
@@ -433,16 +751,17 @@ fn top_modal_row(_model: &Model, modal: &EditIssueModal) -> Node<Msg> {
     .options(
         IssueType::ordered()
             .into_iter()
+            // issue becomes a sub-task only by choosing parent
+            .filter(|t| *t != IssueType::Subtask || payload.issue_type == IssueType::Subtask)
             .map(|t| t.to_child().name("type"))
             .collect(),
     )
     .selected(vec![{
-        let id = modal.id;
         let issue_type = &payload.issue_type;
         issue_type
             .to_child()
             .name("type")
-            .text(format!("{} - {}", issue_type, id))
+            .text(format!("{} - {}", issue_type, issue_key))
     }])
     .build()
     .into_node();
@@ -452,6 +771,7 @@ fn top_modal_row(_model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         issue_type_select,
         div![
             attrs![At::Class => "topActionsRight"],
+            watch_button,
             copy_button,
             delete_button,
             close_button
@@ -516,20 +836,14 @@ fn left_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         vec![div![class!["fakeTextArea"], "Add a comment...", handler]]
     };
 
-    let comments: Vec<Node<Msg>> = model
-        .comments
-        .iter()
-        .flat_map(|c| comment(model, modal, c))
-        .collect();
-
-    div![
-        class!["left"],
-        title,
-        description_field,
-        div![
-            class!["comments"],
-            div![class!["title"], "Comments"],
-            div![
+    let activity = match modal.activity_tab {
+        IssueActivityTab::Comments => {
+            let comments: Vec<Node<Msg>> = model
+                .comments
+                .iter()
+                .flat_map(|c| comment(model, modal, c))
+                .collect();
+            let mut nodes = vec![div![
                 class!["create"],
                 user_avatar,
                 div![
@@ -543,12 +857,238 @@ fn left_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
                         " to comment"
                     ]
                 ]
+            ]];
+            nodes.extend(comments);
+            nodes
+        }
+        IssueActivityTab::History => modal
+            .events
+            .iter()
+            .flat_map(|event| issue_event(model, event))
+            .collect(),
+    };
+
+    div![
+        class!["left"],
+        title,
+        description_field,
+        subtasks_section(model, modal),
+        div![
+            class!["comments"],
+            div![
+                class!["title"],
+                activity_tab(modal, IssueActivityTab::Comments, "Comments"),
+                activity_tab(modal, IssueActivityTab::History, "History"),
             ],
-            comments
+            activity
         ],
     ]
 }
 
+fn subtasks_section(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
+    match modal.payload.issue_type {
+        IssueType::Epic | IssueType::Subtask => return empty![],
+        _ => (),
+    };
+
+    let subtasks: Vec<&Issue> = model
+        .issues
+        .iter()
+        .filter(|issue| issue.parent_id == Some(modal.id))
+        .collect();
+
+    let rows: Vec<Node<Msg>> = subtasks
+        .iter()
+        .map(|subtask| {
+            let status = model
+                .issue_statuses
+                .iter()
+                .find(|is| is.id == subtask.issue_status_id)
+                .map(|is| is.name.as_str())
+                .unwrap_or_default();
+            a![
+                attrs![At::Class => "subtask"; At::Href => format!("/issues/{}", subtask.id)],
+                StyledIcon::build(subtask.issue_type.into())
+                    .build()
+                    .into_node(),
+                span![class!["subtaskTitle"], subtask.title.as_str()],
+                span![class!["subtaskStatus"], status],
+            ]
+        })
+        .collect();
+
+    // parent values are included, sub-task time is part of parent work
+    let rollup = if subtasks.is_empty() {
+        empty![]
+    } else {
+        let estimate: i32 = modal.payload.estimate.unwrap_or_default()
+            + subtasks.iter().filter_map(|s| s.estimate).sum::<i32>();
+        let time_spent: i32 = modal.payload.time_spent.unwrap_or_default()
+            + subtasks.iter().filter_map(|s| s.time_spent).sum::<i32>();
+        div![
+            class!["rollup"],
+            span![format!("Total estimate: {}h", estimate)],
+            span![format!("Total time spent: {}h", time_spent)],
+        ]
+    };
+
+    let title_input = StyledInput::build(FieldId::EditIssueModal(EditIssueModalSection::Subtask(
+        IssueFieldId::Title,
+    )))
+    .value(modal.subtask_title.as_str())
+    .valid(true)
+    .build()
+    .into_node();
+    let create_button = StyledButton::build()
+        .secondary()
+        .text("Create sub-task")
+        .disabled(modal.subtask_title.len() < 3)
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.stop_propagation();
+            Msg::AddSubtask
+        }))
+        .build()
+        .into_node();
+
+    div![
+        class!["subtasks"],
+        div![class!["title"], "Sub-tasks"],
+        rows,
+        rollup,
+        div![class!["create"], title_input, create_button],
+    ]
+}
+
+fn activity_tab(modal: &EditIssueModal, tab: IssueActivityTab, label: &str) -> Node<Msg> {
+    let mut class_list = vec!["tab"];
+    if modal.activity_tab == tab {
+        class_list.push("active");
+    }
+    let handler = mouse_ev(Ev::Click, move |ev| {
+        ev.stop_propagation();
+        Msg::ModalChanged(FieldChange::ActivityTabChanged(tab))
+    });
+    span![attrs![At::Class => class_list.join(" ")], label, handler]
+}
+
+fn issue_event(model: &Model, event: &IssueEvent) -> Option<Node<Msg>> {
+    let user = model.users.iter().find(|u| u.id == event.user_id)?;
+
+    let avatar = StyledAvatar::build()
+        .size(32)
+        .avatar_url(user.avatar_url.as_ref().cloned().unwrap_or_default())
+        .add_class("userAvatar")
+        .build()
+        .into_node();
+
+    let action = match event.event_type {
+        IssueEventType::CommentAdded => "added a comment".to_string(),
+        IssueEventType::CommentDeleted => "deleted a comment".to_string(),
+        IssueEventType::Deleted => "deleted the issue".to_string(),
+        event_type => format!("changed the {}", event_type),
+    };
+
+    let change = match event.event_type {
+        IssueEventType::Description | IssueEventType::Deleted => empty![],
+        IssueEventType::CommentAdded => {
+            p![
+                class!["body"],
+                event.new_value.as_deref().unwrap_or_default()
+            ]
+        }
+        IssueEventType::CommentDeleted => {
+            p![
+                class!["body"],
+                event.old_value.as_deref().unwrap_or_default()
+            ]
+        }
+        _ => div![
+            class!["change"],
+            span![
+                class!["oldValue"],
+                event_value(model, event, &event.old_value)
+            ],
+            span![class!["arrow"], "→"],
+            span![
+                class!["newValue"],
+                event_value(model, event, &event.new_value)
+            ],
+        ],
+    };
+
+    let node = div![
+        class!["styledComment issueEvent"],
+        avatar,
+        div![
+            class!["content"],
+            div![class!["userName"], user.name.as_str()],
+            div![class!["createdAt"], action],
+            div![
+                class!["createdAt"],
+                event.created_at.format("%Y-%m-%d %H:%M").to_string()
+            ],
+            change,
+        ],
+    ];
+    Some(node)
+}
+
+fn event_value(model: &Model, event: &IssueEvent, value: &Option<String>) -> String {
+    let value = match value {
+        Some(value) => value.as_str(),
+        _ => return "None".to_string(),
+    };
+    let user_name = |id: &str| {
+        model
+            .users
+            .iter()
+            .find(|u| u.id.to_string() == id)
+            .map(|u| u.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+    match event.event_type {
+        IssueEventType::Status => model
+            .issue_statuses
+            .iter()
+            .find(|s| s.id.to_string() == value)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| value.to_string()),
+        IssueEventType::Reporter => user_name(value),
+        IssueEventType::Epic | IssueEventType::Parent => model
+            .issues
+            .iter()
+            .find(|issue| issue.id.to_string() == value)
+            .map(|issue| issue.title.clone())
+            .unwrap_or_else(|| value.to_string()),
+        IssueEventType::Assignees if value.is_empty() => "Unassigned".to_string(),
+        IssueEventType::Assignees => value
+            .split(',')
+            .map(user_name)
+            .collect::<Vec<String>>()
+            .join(", "),
+        IssueEventType::Labels if value.is_empty() => "No labels".to_string(),
+        IssueEventType::Labels => value
+            .split(',')
+            .map(|id| {
+                model
+                    .labels
+                    .iter()
+                    .find(|l| l.id.to_string() == id)
+                    .map(|l| l.name.clone())
+                    .unwrap_or_else(|| id.to_string())
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+        IssueEventType::Sprint => model
+            .sprints
+            .iter()
+            .find(|s| s.id.to_string() == value)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| value.to_string()),
+        _ => value.to_string(),
+    }
+}
+
 fn build_comment_form(form: &CommentForm) -> Vec<Node<Msg>> {
     let submit_comment_form = mouse_ev(Ev::Click, move |ev| {
         ev.stop_propagation();
@@ -651,7 +1191,12 @@ fn right_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         status_state,
         reporter_state,
         assignees_state,
+        labels_state,
         priority_state,
+        epic_state,
+        parent_state,
+        link_type_state,
+        link_target_state,
         ..
     } = modal;
 
@@ -666,6 +1211,16 @@ fn right_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         model
             .issue_statuses
             .iter()
+            .filter(|is| {
+                model
+                    .issues
+                    .iter()
+                    .find(|issue| issue.id == modal.id)
+                    .map_or(true, |issue| {
+                        check_status_transition(&model.issue_status_transitions, issue, is.id)
+                            .is_ok()
+                    })
+            })
             .map(|opt| opt.to_child().name("status"))
             .collect(),
     )
@@ -717,6 +1272,37 @@ fn right_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         .build()
         .into_node();
 
+    let labels = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Issue(
+        IssueFieldId::Labels,
+    )))
+    .name("labels")
+    .opened(labels_state.opened)
+    .empty()
+    .multi()
+    .text_filter(labels_state.text_filter.as_str())
+    .options(
+        model
+            .labels
+            .iter()
+            .map(|label| label.to_child().name("labels"))
+            .collect(),
+    )
+    .selected(
+        model
+            .labels
+            .iter()
+            .filter(|label| payload.label_ids.contains(&label.id))
+            .map(|label| label.to_child().name("labels"))
+            .collect(),
+    )
+    .build()
+    .into_node();
+    let labels_field = StyledField::build()
+        .input(labels)
+        .label("Labels")
+        .build()
+        .into_node();
+
     let reporter = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Issue(
         IssueFieldId::Reporter,
     )))
@@ -769,6 +1355,105 @@ fn right_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         .build()
         .into_node();
 
+    let epic_field =
+        if payload.issue_type != IssueType::Epic && payload.issue_type != IssueType::Subtask {
+            let no_epic = || {
+                StyledSelectChild::build()
+                    .value(0)
+                    .text("No epic")
+                    .name("epic")
+            };
+            let mut options = vec![no_epic()];
+            options.extend(
+                model
+                    .issues
+                    .iter()
+                    .filter(|issue| issue.issue_type == IssueType::Epic)
+                    .map(|epic| epic.to_child().name("epic")),
+            );
+            let selected = model
+                .issues
+                .iter()
+                .find(|issue| Some(issue.id) == payload.epic_id)
+                .map(|epic| epic.to_child().name("epic"))
+                .unwrap_or_else(no_epic);
+            let epic = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Issue(
+                IssueFieldId::Epic,
+            )))
+            .name("epic")
+            .opened(epic_state.opened)
+            .empty()
+            .text_filter(epic_state.text_filter.as_str())
+            .options(options)
+            .selected(vec![selected])
+            .build()
+            .into_node();
+            StyledField::build()
+                .input(epic)
+                .label("Epic")
+                .build()
+                .into_node()
+        } else {
+            empty![]
+        };
+
+    let has_subtasks = model
+        .issues
+        .iter()
+        .any(|issue| issue.parent_id == Some(modal.id));
+    let parent_field = if payload.issue_type != IssueType::Epic && !has_subtasks {
+        let no_parent = || {
+            StyledSelectChild::build()
+                .value(0)
+                .text("No parent")
+                .name("parent")
+        };
+        let mut options = vec![no_parent()];
+        options.extend(
+            model
+                .issues
+                .iter()
+                .filter(|issue| {
+                    issue.id != modal.id
+                        && issue.issue_type != IssueType::Epic
+                        && issue.issue_type != IssueType::Subtask
+                })
+                .map(|issue| issue.to_child().name("parent")),
+        );
+        let selected = model
+            .issues
+            .iter()
+            .find(|issue| Some(issue.id) == payload.parent_id)
+            .map(|issue| issue.to_child().name("parent"))
+            .unwrap_or_else(no_parent);
+        let parent = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Issue(
+            IssueFieldId::Parent,
+        )))
+        .name("parent")
+        .opened(parent_state.opened)
+        .empty()
+        .text_filter(parent_state.text_filter.as_str())
+        .options(options)
+        .selected(vec![selected])
+        .build()
+        .into_node();
+        StyledField::build()
+            .input(parent)
+            .label("Parent")
+            .build()
+            .into_node()
+    } else {
+        empty![]
+    };
+
+    let custom_fields: Vec<Node<Msg>> = model
+        .custom_fields
+        .iter()
+        .map(|field| custom_field(model, modal, field))
+        .collect();
+
+    let links_field = links_field(model, modal, link_type_state, link_target_state);
+
     let time_tracking_type = model
         .project
         .as_ref()
@@ -800,9 +1485,200 @@ fn right_modal_column(model: &Model, modal: &EditIssueModal) -> Node<Msg> {
         attrs![At::Class => "right"],
         status_field,
         assignees_field,
+        labels_field,
         reporter_field,
         priority_field,
+        epic_field,
+        parent_field,
+        custom_fields,
+        links_field,
         estimate_field,
         tracking_field,
     ]
 }
+
+/// Select values of options are their positions counted from 1, users use
+/// their ids. `0` clears single value fields.
+fn custom_field_value(field: &CustomField, value: u32) -> Option<String> {
+    match (value, field.field_type) {
+        (0, _) => None,
+        (user_id, CustomFieldType::User) => Some(user_id.to_string()),
+        (position, _) => field.options.get(position as usize - 1).cloned(),
+    }
+}
+
+fn custom_field(model: &Model, modal: &EditIssueModal, field: &CustomField) -> Node<Msg> {
+    let field_id = FieldId::EditIssueModal(EditIssueModalSection::Issue(
+        IssueFieldId::CustomField(field.id),
+    ));
+    let values = modal.custom_values(field.id);
+
+    let input = match field.field_type {
+        CustomFieldType::Text | CustomFieldType::Number | CustomFieldType::Date => {
+            let input_type = match field.field_type {
+                CustomFieldType::Number => "number",
+                CustomFieldType::Date => "date",
+                _ => "text",
+            };
+            let value = values.first().cloned().unwrap_or_default();
+            seed::input![
+                class!["customFieldInput"],
+                attrs![At::Type => input_type; At::Value => value],
+                input_ev(Ev::Change, move |value| Msg::StrInputChanged(
+                    field_id, value
+                )),
+            ]
+        }
+        _ => {
+            let state = match modal
+                .custom_field_states
+                .iter()
+                .find(|state| state.field_id == field_id)
+            {
+                Some(state) => state,
+                _ => return empty![],
+            };
+            let mut options: Vec<StyledSelectChildBuilder> = vec![];
+            if !field.field_type.is_multi() {
+                options.push(
+                    StyledSelectChild::build()
+                        .value(0)
+                        .text("None")
+                        .name("customField"),
+                );
+            }
+            let mut selected = vec![];
+            if field.field_type == CustomFieldType::User {
+                for user in model.users.iter() {
+                    options.push(user.to_child().name("customField"));
+                    if values.contains(&user.id.to_string()) {
+                        selected.push(user.to_child().name("customField"));
+                    }
+                }
+            } else {
+                for (idx, option) in field.options.iter().enumerate() {
+                    let child = || {
+                        StyledSelectChild::build()
+                            .value(idx as u32 + 1)
+                            .text(option.as_str())
+                            .name("customField")
+                    };
+                    options.push(child());
+                    if values.contains(option) {
+                        selected.push(child());
+                    }
+                }
+            }
+            if selected.is_empty() && !field.field_type.is_multi() {
+                selected.push(
+                    StyledSelectChild::build()
+                        .value(0)
+                        .text("None")
+                        .name("customField"),
+                );
+            }
+            let select = StyledSelect::build(field_id)
+                .name("customField")
+                .state(state)
+                .empty()
+                .options(options)
+                .selected(selected);
+            let select = if field.field_type.is_multi() {
+                select.multi()
+            } else {
+                select
+            };
+            select.build().into_node()
+        }
+    };
+
+    StyledField::build()
+        .input(input)
+        .label(field.name.as_str())
+        .build()
+        .into_node()
+}
+
+fn links_field(
+    model: &Model,
+    modal: &EditIssueModal,
+    link_type_state: &StyledSelectState,
+    link_target_state: &StyledSelectState,
+) -> Node<Msg> {
+    let rows: Vec<Node<Msg>> = modal
+        .links
+        .iter()
+        .filter_map(|link| {
+            let (label, other_id) = link.seen_from(modal.id);
+            let other = model.issues.iter().find(|issue| issue.id == other_id)?;
+            let link_id = link.id;
+            let remove = StyledButton::build()
+                .empty()
+                .icon(Icon::Close)
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.stop_propagation();
+                    Msg::DeleteIssueLink(link_id)
+                }))
+                .build()
+                .into_node();
+            Some(div![
+                class!["issueLink"],
+                span![class!["linkType"], label],
+                a![
+                    attrs![At::Class => "linkedIssue"; At::Href => format!("/issues/{}", other.id)],
+                    StyledIcon::build(other.issue_type.into())
+                        .build()
+                        .into_node(),
+                    span![class!["linkedTitle"], other.title.as_str()],
+                ],
+                remove,
+            ])
+        })
+        .collect();
+
+    let link_type = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Link(
+        IssueLinkFieldId::LinkType,
+    )))
+    .name("linkType")
+    .opened(link_type_state.opened)
+    .normal()
+    .text_filter(link_type_state.text_filter.as_str())
+    .options(
+        IssueLinkType::ordered()
+            .into_iter()
+            .map(|t| t.to_child().name("linkType"))
+            .collect(),
+    )
+    .selected(vec![modal.link_type.to_child().name("linkType")])
+    .build()
+    .into_node();
+    let link_target = StyledSelect::build(FieldId::EditIssueModal(EditIssueModalSection::Link(
+        IssueLinkFieldId::TargetIssue,
+    )))
+    .name("linkTarget")
+    .opened(link_target_state.opened)
+    .empty()
+    .text_filter(link_target_state.text_filter.as_str())
+    .options(
+        model
+            .issues
+            .iter()
+            .filter(|issue| issue.id != modal.id)
+            .map(|issue| issue.to_child().name("linkTarget"))
+            .collect(),
+    )
+    .selected(vec![])
+    .build()
+    .into_node();
+
+    let input = div![
+        class!["issueLinks"],
+        rows,
+        div![class!["createLink"], link_type, link_target],
+    ];
+    StyledField::build()
+        .input(input)
+        .label("Links")
+        .build()
+        .into_node()
+}
@@ -15,6 +15,7 @@ mod confirm_delete_issue;
 mod debug_modal;
 mod delete_issue_status;
 mod issue_details;
+mod search_issues;
 pub mod time_tracking;
 
 pub fn update(msg: &Msg, model: &mut model::Model, orders: &mut impl Orders<Msg>) {
@@ -72,6 +73,7 @@ pub fn update(msg: &Msg, model: &mut model::Model, orders: &mut impl Orders<Msg>
     add_issue::update(msg, model, orders);
     issue_details::update(msg, model, orders);
     delete_issue_status::update(msg, model, orders);
+    search_issues::update(msg, model, orders);
 }
 
 pub fn view(model: &model::Model) -> Node<Msg> {
@@ -108,6 +110,7 @@ pub fn view(model: &model::Model) -> Node<Msg> {
             ModalType::DeleteIssueStatusModal(delete_issue_modal) => {
                 delete_issue_status::view(model, delete_issue_modal.delete_id)
             }
+            ModalType::SearchIssues(modal) => search_issues::view(model, modal),
             #[cfg(debug_assertions)]
             ModalType::DebugModal => debug_modal::view(model),
         })
@@ -136,5 +139,21 @@ fn push_edit_modal(issue_id: i32, model: &mut Model, orders: &mut impl Orders<Ms
         model.ws.as_ref(),
         orders,
     );
+    send_ws_msg(
+        WsMsg::IssueLinksRequest(issue_id),
+        model.ws.as_ref(),
+        orders,
+    );
+    send_ws_msg(
+        WsMsg::IssueWatchersRequest(issue_id),
+        model.ws.as_ref(),
+        orders,
+    );
+    send_ws_msg(WsMsg::WorklogsRequest(issue_id), model.ws.as_ref(), orders);
+    // opening sub-task or parent from details replaces current issue
+    model.modals.retain(|modal| match modal {
+        ModalType::EditIssue(..) => false,
+        _ => true,
+    });
     model.modals.push(modal);
 }
@@ -0,0 +1,144 @@
+use seed::{prelude::*, *};
+
+use jirs_data::{IssueSearchHit, SearchSnippetPart, WsMsg};
+
+use crate::model::{ModalType, Model, SearchIssuesModal};
+use crate::shared::styled_button::StyledButton;
+use crate::shared::styled_icon::Icon;
+use crate::shared::styled_input::StyledInput;
+use crate::shared::styled_modal::StyledModal;
+use crate::shared::ToNode;
+use crate::ws::send_ws_msg;
+use crate::{FieldId, Msg, WebSocketChanged};
+
+pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if let Msg::BrowseIssue(_) = msg {
+        model.modals.retain(|modal| match modal {
+            ModalType::SearchIssues(..) => false,
+            _ => true,
+        });
+        return;
+    }
+
+    let modal: &mut Box<SearchIssuesModal> =
+        match model.modals.iter_mut().find_map(|modal| match modal {
+            ModalType::SearchIssues(modal) => Some(modal),
+            _ => None,
+        }) {
+            Some(m) => m,
+            _ => return,
+        };
+
+    let request = match msg {
+        Msg::StrInputChanged(FieldId::SearchIssues, _) => {
+            modal.text.update(msg);
+            search_request(modal)
+        }
+        Msg::SearchIssuesToggleAllProjects => {
+            modal.all_projects = !modal.all_projects;
+            search_request(modal)
+        }
+        // typing is faster than search, only hits of current text are shown
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueSearchLoaded(text, hits)))
+            if text == &modal.text.value =>
+        {
+            modal.hits = hits.clone();
+            None
+        }
+        _ => None,
+    };
+    if let Some(request) = request {
+        send_ws_msg(request, model.ws.as_ref(), orders);
+    }
+}
+
+fn search_request(modal: &mut SearchIssuesModal) -> Option<WsMsg> {
+    if modal.text.value.trim().is_empty() {
+        modal.hits.clear();
+        return None;
+    }
+    Some(WsMsg::IssueSearchRequest {
+        text: modal.text.value.clone(),
+        all_projects: modal.all_projects,
+    })
+}
+
+pub fn view(model: &Model, modal: &SearchIssuesModal) -> Node<Msg> {
+    let input = StyledInput::build(FieldId::SearchIssues)
+        .icon(Icon::Search)
+        .valid(true)
+        .state(&modal.text)
+        .build()
+        .into_node();
+    let all_projects = StyledButton::build()
+        .empty()
+        .active(modal.all_projects)
+        .text("All my projects")
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::SearchIssuesToggleAllProjects
+        }))
+        .build()
+        .into_node();
+
+    let results = if modal.hits.is_empty() && !modal.text.value.trim().is_empty() {
+        div![class!["noResults"], "No matching issues"]
+    } else {
+        ul![
+            class!["hits"],
+            modal
+                .hits
+                .iter()
+                .map(|hit| search_hit(model, hit, modal.all_projects))
+                .collect::<Vec<Node<Msg>>>()
+        ]
+    };
+
+    StyledModal::build()
+        .add_class("searchIssuesModal")
+        .width(800)
+        .children(vec![
+            div![class!["modalTitle"], "Search issues"],
+            div![class!["searchBar"], input, all_projects],
+            results,
+        ])
+        .build()
+        .into_node()
+}
+
+fn search_hit(model: &Model, hit: &IssueSearchHit, all_projects: bool) -> Node<Msg> {
+    let project_name = if all_projects {
+        model
+            .projects
+            .iter()
+            .find(|project| project.id == hit.project_id)
+            .map(|project| span![class!["project"], project.name.as_str()])
+            .unwrap_or_else(|| empty![])
+    } else {
+        empty![]
+    };
+    let snippet: Vec<Node<Msg>> = hit.snippet.iter().map(snippet_part).collect();
+    let href = format!("/browse/{}", hit.key);
+
+    li![
+        class!["hit"],
+        a![
+            attrs![At::Href => href],
+            div![
+                class!["header"],
+                span![class!["issueKey"], hit.key.as_str()],
+                span![class!["title"], hit.title.as_str()],
+                project_name
+            ],
+            div![class!["snippet"], snippet]
+        ]
+    ]
+}
+
+fn snippet_part(part: &SearchSnippetPart) -> Node<Msg> {
+    if part.highlighted {
+        mark![part.text.as_str()]
+    } else {
+        span![part.text.as_str()]
+    }
+}
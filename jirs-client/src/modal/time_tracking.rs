@@ -1,8 +1,8 @@
 use seed::{prelude::*, *};
 
-use jirs_data::{IssueFieldId, IssueId, TimeTracking};
+use jirs_data::{IssueFieldId, IssueId, TimeTracking, Worklog, WorklogFieldId};
 
-use crate::model::{ModalType, Model};
+use crate::model::{EditIssueModal, ModalType, Model};
 use crate::shared::styled_button::StyledButton;
 use crate::shared::styled_field::StyledField;
 use crate::shared::styled_input::{StyledInput, StyledInputState};
@@ -42,13 +42,8 @@ pub fn view(model: &Model, issue_id: IssueId) -> Node<Msg> {
 
     let tracking = tracking_widget(model, edit_issue_modal);
 
-    let time_spent_field = time_tracking_field(
-        time_tracking_type,
-        FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeSpent)),
-        "Time spent",
-        &edit_issue_modal.time_spent,
-        &edit_issue_modal.time_spent_select,
-    );
+    let log_work = log_work_form(time_tracking_type, edit_issue_modal);
+
     let time_remaining_field = time_tracking_field(
         time_tracking_type,
         FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeRemaining)),
@@ -59,10 +54,15 @@ pub fn view(model: &Model, issue_id: IssueId) -> Node<Msg> {
 
     let inputs = div![
         class!["inputs"],
-        div![class!["inputContainer"], time_spent_field],
         div![class!["inputContainer"], time_remaining_field]
     ];
 
+    let worklogs: Vec<Node<Msg>> = edit_issue_modal
+        .worklogs
+        .iter()
+        .map(|worklog| worklog_entry(model, time_tracking_type, worklog))
+        .collect();
+
     let close = StyledButton::build()
         .text("Done")
         .on_click(mouse_ev(Ev::Click, |_| Msg::ModalDropped))
@@ -74,7 +74,9 @@ pub fn view(model: &Model, issue_id: IssueId) -> Node<Msg> {
         .children(vec![
             modal_title,
             tracking,
+            log_work,
             inputs,
+            div![class!["worklogs"], worklogs],
             div![class!["actions"], close],
         ])
         .width(400)
@@ -82,6 +84,109 @@ pub fn view(model: &Model, issue_id: IssueId) -> Node<Msg> {
         .into_node()
 }
 
+/// New worklog, time spent of issue can't be edited directly because it's
+/// sum of all worklogs.
+fn log_work_form(time_tracking_type: TimeTracking, modal: &EditIssueModal) -> Node<Msg> {
+    let time_spent_field = time_tracking_field(
+        time_tracking_type,
+        FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::TimeSpent)),
+        "Time spent",
+        &modal.worklog_time_spent,
+        &modal.worklog_time_spent_select,
+    );
+    let started_at_field = {
+        let field_id =
+            FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::StartedAt));
+        let input = seed::input![
+            class!["dateInput"],
+            attrs![At::Type => "date"; At::Value => modal.worklog_started_at.value.as_str()],
+            input_ev(Ev::Input, move |value| {
+                Msg::StrInputChanged(field_id.clone(), value)
+            }),
+        ];
+        StyledField::build()
+            .input(input)
+            .label("Date")
+            .build()
+            .into_node()
+    };
+    let comment_field = {
+        let input = StyledInput::build(FieldId::EditIssueModal(EditIssueModalSection::Worklog(
+            WorklogFieldId::Comment,
+        )))
+        .state(&modal.worklog_comment)
+        .valid(true)
+        .build()
+        .into_node();
+        StyledField::build()
+            .input(input)
+            .label("Work description")
+            .build()
+            .into_node()
+    };
+    let submit = StyledButton::build()
+        .primary()
+        .text("Log work")
+        .on_click(mouse_ev(Ev::Click, |_| Msg::SaveWorklog))
+        .build()
+        .into_node();
+
+    div![
+        class!["logWork"],
+        div![
+            class!["inputs"],
+            div![class!["inputContainer"], time_spent_field],
+            div![class!["inputContainer"], started_at_field]
+        ],
+        comment_field,
+        submit
+    ]
+}
+
+fn worklog_entry(model: &Model, time_tracking_type: TimeTracking, worklog: &Worklog) -> Node<Msg> {
+    let user_name = model
+        .users
+        .iter()
+        .find(|user| user.id == worklog.user_id)
+        .map(|user| user.name.as_str())
+        .unwrap_or_default();
+    let time_spent = value_for_time_tracking(&Some(worklog.time_spent), &time_tracking_type);
+    let time_spent = match time_tracking_type {
+        TimeTracking::Hourly => format!("{}h", time_spent),
+        _ => time_spent,
+    };
+    let delete = if model.user.as_ref().map(|u| u.id) == Some(worklog.user_id) {
+        let worklog_id = worklog.id;
+        StyledButton::build()
+            .text("Delete")
+            .empty()
+            .on_click(mouse_ev(Ev::Click, move |_| Msg::DeleteWorklog(worklog_id)))
+            .build()
+            .into_node()
+    } else {
+        empty![]
+    };
+
+    div![
+        class!["worklog"],
+        div![
+            class!["header"],
+            span![class!["userName"], user_name],
+            span![
+                class!["startedAt"],
+                worklog.started_at.format("%Y-%m-%d").to_string()
+            ],
+            span![class!["timeSpent"], time_spent],
+            delete
+        ],
+        if worklog.comment.is_empty() {
+            empty![]
+        } else {
+            div![class!["comment"], worklog.comment.as_str()]
+        }
+    ]
+}
+
 pub fn time_tracking_field(
     time_tracking_type: TimeTracking,
     field_id: FieldId,
@@ -17,6 +17,9 @@ use crate::shared::styled_rte::StyledRteState;
 use crate::shared::styled_select::StyledSelectState;
 use crate::{EditIssueModalSection, FieldId, ProjectFieldId /*HOST_URL*/};
 
+/// Same as database default of `labels.color`
+pub static DEFAULT_LABEL_COLOR: &str = "#0052cc";
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub enum ModalType {
     AddIssue(Box<AddIssueModal>),
@@ -25,10 +28,23 @@ pub enum ModalType {
     DeleteCommentConfirm(CommentId),
     TimeTracking(IssueId),
     DeleteIssueStatusModal(Box<DeleteIssueStatusModal>),
+    SearchIssues(Box<SearchIssuesModal>),
     #[cfg(debug_assertions)]
     DebugModal,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToastContent {
+    Error(WsError),
+    Warning(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    pub id: usize,
+    pub content: ToastContent,
+}
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub struct CommentForm {
     pub id: Option<CommentId>,
@@ -36,6 +52,12 @@ pub struct CommentForm {
     pub creating: bool,
 }
 
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+pub enum IssueActivityTab {
+    Comments,
+    History,
+}
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub struct DeleteIssueStatusModal {
     pub delete_id: IssueStatusId,
@@ -51,6 +73,23 @@ impl DeleteIssueStatusModal {
     }
 }
 
+#[derive(Clone, Debug, PartialOrd, PartialEq)]
+pub struct SearchIssuesModal {
+    pub text: StyledInputState,
+    pub all_projects: bool,
+    pub hits: Vec<IssueSearchHit>,
+}
+
+impl Default for SearchIssuesModal {
+    fn default() -> Self {
+        Self {
+            text: StyledInputState::new(FieldId::SearchIssues, ""),
+            all_projects: false,
+            hits: vec![],
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub struct EditIssueModal {
     pub id: IssueId,
@@ -60,12 +99,17 @@ pub struct EditIssueModal {
     pub status_state: StyledSelectState,
     pub reporter_state: StyledSelectState,
     pub assignees_state: StyledSelectState,
+    pub labels_state: StyledSelectState,
     pub priority_state: StyledSelectState,
+    pub epic_state: StyledSelectState,
+    pub parent_state: StyledSelectState,
+    pub link_type_state: StyledSelectState,
+    pub link_target_state: StyledSelectState,
+    /// Select states of project custom fields, see `sync_custom_field_states`
+    pub custom_field_states: Vec<StyledSelectState>,
 
     pub estimate: StyledInputState,
     pub estimate_select: StyledSelectState,
-    pub time_spent: StyledInputState,
-    pub time_spent_select: StyledSelectState,
     pub time_remaining: StyledInputState,
     pub time_remaining_select: StyledSelectState,
 
@@ -73,6 +117,24 @@ pub struct EditIssueModal {
 
     // comments
     pub comment_form: CommentForm,
+
+    // history
+    pub activity_tab: IssueActivityTab,
+    pub events: Vec<IssueEvent>,
+
+    // sub-tasks
+    pub subtask_title: String,
+    // links
+    pub links: Vec<IssueLink>,
+    pub link_type: IssueLinkType,
+    // users notified about changes
+    pub watchers: Vec<UserId>,
+    // worklogs, time spent of issue is their sum
+    pub worklogs: Vec<Worklog>,
+    pub worklog_time_spent: StyledInputState,
+    pub worklog_time_spent_select: StyledSelectState,
+    pub worklog_started_at: StyledInputState,
+    pub worklog_comment: StyledInputState,
 }
 
 impl EditIssueModal {
@@ -94,6 +156,11 @@ impl EditIssueModal {
                 project_id: issue.project_id,
                 reporter_id: issue.reporter_id,
                 user_ids: issue.user_ids.clone(),
+                label_ids: issue.label_ids.clone(),
+                custom_values: issue.custom_values.clone(),
+                epic_id: issue.epic_id,
+                parent_id: issue.parent_id,
+                sprint_id: issue.sprint_id,
             },
             top_type_state: StyledSelectState::new(
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Type)),
@@ -111,10 +178,34 @@ impl EditIssueModal {
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Assignees)),
                 issue.user_ids.iter().map(|n| *n as u32).collect(),
             ),
+            labels_state: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Labels)),
+                issue.label_ids.iter().map(|n| *n as u32).collect(),
+            ),
             priority_state: StyledSelectState::new(
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Priority)),
                 vec![issue.priority.into()],
             ),
+            epic_state: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Epic)),
+                issue.epic_id.map(|id| vec![id as u32]).unwrap_or_default(),
+            ),
+            parent_state: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Parent)),
+                issue
+                    .parent_id
+                    .map(|id| vec![id as u32])
+                    .unwrap_or_default(),
+            ),
+            link_type_state: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Link(IssueLinkFieldId::LinkType)),
+                vec![IssueLinkType::default().into()],
+            ),
+            link_target_state: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Link(IssueLinkFieldId::TargetIssue)),
+                vec![],
+            ),
+            custom_field_states: vec![],
             estimate: StyledInputState::new(
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Estimate)),
                 value_for_time_tracking(&issue.estimate, &time_tracking_type),
@@ -123,14 +214,6 @@ impl EditIssueModal {
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::Estimate)),
                 issue.estimate.map(|n| vec![n as u32]).unwrap_or_default(),
             ),
-            time_spent: StyledInputState::new(
-                FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeSpent)),
-                value_for_time_tracking(&issue.time_spent, &time_tracking_type),
-            ),
-            time_spent_select: StyledSelectState::new(
-                FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeSpent)),
-                issue.time_spent.map(|n| vec![n as u32]).unwrap_or_default(),
-            ),
             time_remaining: StyledInputState::new(
                 FieldId::EditIssueModal(EditIssueModalSection::Issue(IssueFieldId::TimeRemaining)),
                 value_for_time_tracking(&issue.time_remaining, &time_tracking_type),
@@ -148,6 +231,71 @@ impl EditIssueModal {
                 body: String::new(),
                 creating: false,
             },
+            activity_tab: IssueActivityTab::Comments,
+            events: vec![],
+            subtask_title: String::new(),
+            links: vec![],
+            link_type: IssueLinkType::default(),
+            watchers: vec![],
+            worklogs: vec![],
+            worklog_time_spent: StyledInputState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::TimeSpent)),
+                "",
+            ),
+            worklog_time_spent_select: StyledSelectState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::TimeSpent)),
+                vec![],
+            ),
+            worklog_started_at: StyledInputState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::StartedAt)),
+                Local::today().naive_local().format("%Y-%m-%d").to_string(),
+            ),
+            worklog_comment: StyledInputState::new(
+                FieldId::EditIssueModal(EditIssueModalSection::Worklog(WorklogFieldId::Comment)),
+                "",
+            ),
+        }
+    }
+
+    /// Fields can be defined while modal is opened so select states are
+    /// created on demand. Text, number and date fields use plain inputs.
+    pub fn sync_custom_field_states(&mut self, custom_fields: &[CustomField]) {
+        for field in custom_fields.iter() {
+            let field_id = FieldId::EditIssueModal(EditIssueModalSection::Issue(
+                IssueFieldId::CustomField(field.id),
+            ));
+            let uses_select =
+                field.field_type.has_options() || field.field_type == CustomFieldType::User;
+            if uses_select
+                && !self
+                    .custom_field_states
+                    .iter()
+                    .any(|s| s.field_id == field_id)
+            {
+                self.custom_field_states
+                    .push(StyledSelectState::new(field_id, vec![]));
+            }
+        }
+    }
+
+    pub fn custom_values(&self, custom_field_id: CustomFieldId) -> Vec<String> {
+        self.payload
+            .custom_values
+            .iter()
+            .find(|v| v.custom_field_id == custom_field_id)
+            .map(|v| v.values.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_custom_values(&mut self, custom_field_id: CustomFieldId, values: Vec<String>) {
+        self.payload
+            .custom_values
+            .retain(|v| v.custom_field_id != custom_field_id);
+        if !values.is_empty() {
+            self.payload.custom_values.push(CustomFieldValue {
+                custom_field_id,
+                values,
+            });
         }
     }
 }
@@ -218,6 +366,7 @@ pub enum Page {
     Users,
     Profile,
     Reports,
+    Backlog,
 }
 
 impl Page {
@@ -233,6 +382,7 @@ impl Page {
             Page::Users => "/users".to_string(),
             Page::Profile => "/profile".to_string(),
             Page::Reports => "/reports".to_string(),
+            Page::Backlog => "/backlog".to_string(),
         }
     }
 }
@@ -257,9 +407,44 @@ pub struct UpdateProjectForm {
 pub struct ProjectPage {
     pub text_filter: String,
     pub active_avatar_filters: Vec<UserId>,
+    pub active_label_filters: Vec<LabelId>,
+    pub active_custom_filters: Vec<(CustomFieldId, String)>,
     pub only_my_filter: bool,
     pub recently_updated_filter: bool,
+    pub group_by_epic: bool,
     pub issue_drag: DragState,
+    /// Statuses allowed by project workflow for currently dragged issue
+    pub allowed_drop_statuses: Vec<IssueStatusId>,
+    pub jql: String,
+    pub jql_error: Option<jql::JqlError>,
+    /// Issues matching `jql`, `None` when query is blank
+    pub jql_issue_ids: Option<Vec<IssueId>>,
+    pub saving_filter: bool,
+    pub saved_filter_name: String,
+    pub saved_filter_shared: bool,
+}
+
+impl ProjectPage {
+    pub fn board_filter(&self) -> BoardFilter {
+        BoardFilter {
+            text: self.text_filter.clone(),
+            user_ids: self.active_avatar_filters.clone(),
+            label_ids: self.active_label_filters.clone(),
+            only_my: self.only_my_filter,
+            recently_updated: self.recently_updated_filter,
+            jql: self.jql.clone(),
+        }
+    }
+
+    /// Replaces all board filters except `jql` which needs to be sent to server
+    pub fn set_board_filter(&mut self, filter: BoardFilter) {
+        self.text_filter = filter.text;
+        self.active_avatar_filters = filter.user_ids;
+        self.active_label_filters = filter.label_ids;
+        self.active_custom_filters = vec![];
+        self.only_my_filter = filter.only_my;
+        self.recently_updated_filter = filter.recently_updated;
+    }
 }
 
 #[derive(Debug, Default)]
@@ -275,11 +460,20 @@ pub struct ProjectSettingsPage {
     pub project_category_state: StyledSelectState,
     pub description_mode: crate::shared::styled_editor::Mode,
     pub time_tracking: StyledCheckboxState,
+    pub wip_limit_policy: StyledCheckboxState,
     pub column_drag: DragState,
     pub edit_column_id: Option<IssueStatusId>,
     pub creating_issue_status: bool,
     pub name: StyledInputState,
     pub description_rte: StyledRteState,
+    pub label_name: StyledInputState,
+    pub label_color: StyledInputState,
+    pub custom_field_name: StyledInputState,
+    pub custom_field_type_state: StyledSelectState,
+    pub custom_field_options: StyledInputState,
+    pub transition_from_state: StyledSelectState,
+    pub transition_to_state: StyledSelectState,
+    pub transition_requirement_state: StyledSelectState,
 }
 
 impl ProjectSettingsPage {
@@ -292,6 +486,8 @@ impl ProjectSettingsPage {
             description,
             category,
             time_tracking,
+            wip_limit_policy,
+            key,
             ..
         } = project;
         Self {
@@ -302,6 +498,8 @@ impl ProjectSettingsPage {
                 description: Some(description.clone()),
                 category: Some(*category),
                 time_tracking: Some(*time_tracking),
+                wip_limit_policy: Some(*wip_limit_policy),
+                key: Some(key.clone()),
             },
             description_mode: EditorMode::View,
             project_category_state: StyledSelectState::new(
@@ -312,6 +510,10 @@ impl ProjectSettingsPage {
                 FieldId::ProjectSettings(ProjectFieldId::TimeTracking),
                 (*time_tracking).into(),
             ),
+            wip_limit_policy: StyledCheckboxState::new(
+                FieldId::ProjectSettings(ProjectFieldId::WipLimitPolicy),
+                (*wip_limit_policy).into(),
+            ),
             column_drag: Default::default(),
             edit_column_id: None,
             creating_issue_status: false,
@@ -322,6 +524,38 @@ impl ProjectSettingsPage {
             description_rte: StyledRteState::new(FieldId::ProjectSettings(
                 ProjectFieldId::Description,
             )),
+            label_name: StyledInputState::new(
+                FieldId::ProjectSettings(ProjectFieldId::LabelName),
+                "",
+            ),
+            label_color: StyledInputState::new(
+                FieldId::ProjectSettings(ProjectFieldId::LabelColor),
+                DEFAULT_LABEL_COLOR,
+            ),
+            custom_field_name: StyledInputState::new(
+                FieldId::ProjectSettings(ProjectFieldId::CustomFieldName),
+                "",
+            ),
+            custom_field_type_state: StyledSelectState::new(
+                FieldId::ProjectSettings(ProjectFieldId::CustomFieldType),
+                vec![CustomFieldType::default().into()],
+            ),
+            custom_field_options: StyledInputState::new(
+                FieldId::ProjectSettings(ProjectFieldId::CustomFieldOptions),
+                "",
+            ),
+            transition_from_state: StyledSelectState::new(
+                FieldId::ProjectSettings(ProjectFieldId::TransitionFrom),
+                vec![],
+            ),
+            transition_to_state: StyledSelectState::new(
+                FieldId::ProjectSettings(ProjectFieldId::TransitionTo),
+                vec![],
+            ),
+            transition_requirement_state: StyledSelectState::new(
+                FieldId::ProjectSettings(ProjectFieldId::TransitionRequirement),
+                vec![0],
+            ),
         }
     }
 
@@ -442,6 +676,9 @@ pub struct ReportsPage {
     pub hovered_day: Option<chrono::NaiveDate>,
     pub first_day: NaiveDate,
     pub last_day: NaiveDate,
+    pub time_tracking_report: Option<TimeTrackingReport>,
+    pub burndown: Option<BurndownReport>,
+    pub cumulative_flow: Option<CumulativeFlowReport>,
 }
 
 impl Default for ReportsPage {
@@ -456,6 +693,36 @@ impl Default for ReportsPage {
             last_day,
             selected_day: None,
             hovered_day: None,
+            time_tracking_report: None,
+            burndown: None,
+            cumulative_flow: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BacklogPage {
+    pub issue_drag: DragState,
+    /// Sprint section under dragged issue, `Some(None)` is backlog
+    pub drop_target: Option<Option<SprintId>>,
+    // edit sprint
+    pub edited_sprint_id: Option<SprintId>,
+    pub edit_name: StyledInputState,
+    pub edit_goal: StyledInputState,
+    pub edit_starts_at: StyledInputState,
+    pub edit_ends_at: StyledInputState,
+}
+
+impl Default for BacklogPage {
+    fn default() -> Self {
+        Self {
+            issue_drag: Default::default(),
+            drop_target: None,
+            edited_sprint_id: None,
+            edit_name: StyledInputState::new(FieldId::Backlog(SprintFieldId::Name), ""),
+            edit_goal: StyledInputState::new(FieldId::Backlog(SprintFieldId::Goal), ""),
+            edit_starts_at: StyledInputState::new(FieldId::Backlog(SprintFieldId::StartsAt), ""),
+            edit_ends_at: StyledInputState::new(FieldId::Backlog(SprintFieldId::EndsAt), ""),
         }
     }
 }
@@ -470,6 +737,7 @@ pub enum PageContent {
     Users(Box<UsersPage>),
     Profile(Box<ProfilePage>),
     Reports(Box<ReportsPage>),
+    Backlog(Box<BacklogPage>),
 }
 
 #[derive(Debug)]
@@ -493,6 +761,9 @@ pub struct Model {
     // modals
     pub modals: Vec<ModalType>,
 
+    // toasts
+    pub toasts: Vec<Toast>,
+
     // pages
     pub page: Page,
     pub page_content: PageContent,
@@ -500,10 +771,17 @@ pub struct Model {
     pub project: Option<Project>,
     pub user: Option<User>,
     pub current_user_project: Option<UserProject>,
+    // issue key from `/browse/...` url waiting for project issues
+    pub browse_key: Option<String>,
     pub issues: Vec<Issue>,
     pub users: Vec<User>,
     pub comments: Vec<Comment>,
     pub issue_statuses: Vec<IssueStatus>,
+    pub labels: Vec<Label>,
+    pub custom_fields: Vec<CustomField>,
+    pub saved_filters: Vec<SavedFilter>,
+    pub issue_status_transitions: Vec<IssueStatusTransition>,
+    pub sprints: Vec<Sprint>,
     pub messages: Vec<Message>,
     pub user_projects: Vec<UserProject>,
     pub projects: Vec<Project>,
@@ -525,14 +803,21 @@ impl Model {
             ws_url,
             page_content: PageContent::Project(Box::new(ProjectPage::default())),
             modals: vec![],
+            toasts: vec![],
             project: None,
             current_user_project: None,
+            browse_key: None,
             about_tooltip_visible: false,
             messages_tooltip_visible: false,
             issues: vec![],
             users: vec![],
             comments: vec![],
             issue_statuses: vec![],
+            labels: vec![],
+            custom_fields: vec![],
+            saved_filters: vec![],
+            issue_status_transitions: vec![],
+            sprints: vec![],
             messages: vec![],
             user_projects: vec![],
             projects: vec![],
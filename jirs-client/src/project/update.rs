@@ -1,6 +1,8 @@
-use seed::prelude::Orders;
+use seed::browser::web_socket::WebSocket;
+use seed::prelude::{Orders, Url, UrlSearch};
 
-use jirs_data::{Issue, IssueFieldId, WsMsg};
+use jirs_data::jql::parse_jql;
+use jirs_data::{BoardFilter, CreateSavedFilterPayload, Issue, IssueFieldId, IssueType, WsMsg};
 
 use crate::model::{ModalType, Model, Page, PageContent, ProjectPage};
 use crate::shared::styled_select::StyledSelectChange;
@@ -35,12 +37,24 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
             init_load(model, orders);
         }
         Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueUpdated(issue))) => {
+            // changed issue may no longer match query
+            if project_page.jql_issue_ids.is_some() {
+                send_ws_msg(
+                    WsMsg::IssueQueryRequest(project_page.jql.clone()),
+                    model.ws.as_ref(),
+                    orders,
+                );
+            }
             let mut old: Vec<Issue> = vec![];
             std::mem::swap(&mut old, &mut model.issues);
-            for is in old {
+            for mut is in old {
                 if is.id == issue.id {
                     model.issues.push(issue.clone())
                 } else {
+                    // server detaches issues from epic which is no longer an epic
+                    if issue.issue_type != IssueType::Epic && is.epic_id == Some(issue.id) {
+                        is.epic_id = None;
+                    }
                     model.issues.push(is);
                 }
             }
@@ -48,8 +62,12 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
         Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueDeleted(id))) => {
             let mut old: Vec<Issue> = vec![];
             std::mem::swap(&mut old, &mut model.issues);
-            for is in old {
-                if is.id != id {
+            for mut is in old {
+                // server deletes sub-tasks together with parent
+                if is.id != id && is.parent_id != Some(id) {
+                    if is.epic_id == Some(id) {
+                        is.epic_id = None;
+                    }
                     model.issues.push(is);
                 }
             }
@@ -74,6 +92,67 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
         Msg::StrInputChanged(FieldId::TextFilterBoard, text) => {
             project_page.text_filter = text;
         }
+        Msg::StrInputChanged(FieldId::JqlFilterBoard, text) => {
+            change_jql(project_page, text, model.ws.as_ref(), orders);
+        }
+        Msg::StrInputChanged(FieldId::SavedFilterName, text) => {
+            project_page.saved_filter_name = text;
+        }
+        Msg::ProjectSavedFilterApplied(saved_filter_id) => {
+            if let Some(filter) = model
+                .saved_filters
+                .iter()
+                .find(|f| f.id == saved_filter_id)
+                .map(|f| f.filter())
+            {
+                let jql = filter.jql.clone();
+                project_page.set_board_filter(filter);
+                change_jql(project_page, jql, model.ws.as_ref(), orders);
+            }
+        }
+        Msg::ProjectSavedFilterFormToggle => {
+            project_page.saving_filter = !project_page.saving_filter;
+            project_page.saved_filter_name.clear();
+            project_page.saved_filter_shared = false;
+        }
+        Msg::ProjectSavedFilterToggleShared => {
+            project_page.saved_filter_shared = !project_page.saved_filter_shared;
+        }
+        Msg::ProjectSavedFilterSave => {
+            if project_page.saved_filter_name.trim().is_empty() {
+                return;
+            }
+            send_ws_msg(
+                WsMsg::SavedFilterCreateRequest(CreateSavedFilterPayload {
+                    name: project_page.saved_filter_name.trim().to_string(),
+                    shared: project_page.saved_filter_shared,
+                    filter: project_page.board_filter(),
+                }),
+                model.ws.as_ref(),
+                orders,
+            );
+            project_page.saving_filter = false;
+            project_page.saved_filter_name.clear();
+            project_page.saved_filter_shared = false;
+        }
+        Msg::ProjectSavedFilterDelete(saved_filter_id) => {
+            send_ws_msg(
+                WsMsg::SavedFilterDeleteRequest(saved_filter_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueQueryLoaded(query, ids)))
+            if query == project_page.jql =>
+        {
+            project_page.jql_error = None;
+            project_page.jql_issue_ids = Some(ids);
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueQueryFailed(query, error)))
+            if query == project_page.jql =>
+        {
+            project_page.jql_error = Some(error);
+        }
         Msg::ProjectAvatarFilterChanged(user_id, active) => {
             if active {
                 project_page.active_avatar_filters = project_page
@@ -85,14 +164,39 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
                 project_page.active_avatar_filters.push(user_id);
             }
         }
+        Msg::ProjectLabelFilterChanged(label_id, active) => {
+            if active {
+                project_page
+                    .active_label_filters
+                    .retain(|id| *id != label_id);
+            } else {
+                project_page.active_label_filters.push(label_id);
+            }
+        }
+        Msg::ProjectCustomFilterChanged(custom_field_id, value, active) => {
+            if active {
+                project_page
+                    .active_custom_filters
+                    .retain(|(id, v)| *id != custom_field_id || *v != value);
+            } else {
+                project_page
+                    .active_custom_filters
+                    .push((custom_field_id, value));
+            }
+        }
         Msg::ProjectToggleOnlyMy => {
             project_page.only_my_filter = !project_page.only_my_filter;
         }
         Msg::ProjectToggleRecentlyUpdated => {
             project_page.recently_updated_filter = !project_page.recently_updated_filter;
         }
+        Msg::ProjectToggleGroupByEpic => {
+            project_page.group_by_epic = !project_page.group_by_epic;
+        }
         Msg::ProjectClearFilters => {
             project_page.active_avatar_filters = vec![];
+            project_page.active_label_filters = vec![];
+            project_page.active_custom_filters = vec![];
             project_page.recently_updated_filter = false;
             project_page.only_my_filter = false;
         }
@@ -123,16 +227,115 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
         }
         _ => (),
     }
+
+    // keep active filter in address so board can be bookmarked
+    if model.page != Page::Project {
+        return;
+    }
+    let filter = project_page.board_filter();
+    let url = Url::current();
+    if filter != url_filter(&url) {
+        url.set_search(url_search(&filter)).go_and_replace();
+    }
+}
+
+/// Syntax is checked locally, server reports only unknown names
+fn change_jql(
+    project_page: &mut ProjectPage,
+    text: String,
+    ws: Option<&WebSocket>,
+    orders: &mut impl Orders<Msg>,
+) {
+    project_page.jql_error = None;
+    if text.trim().is_empty() {
+        project_page.jql_issue_ids = None;
+    } else {
+        match parse_jql(text.as_str()) {
+            Ok(_) => send_ws_msg(WsMsg::IssueQueryRequest(text.clone()), ws, orders),
+            Err(error) => project_page.jql_error = Some(error),
+        }
+    }
+    project_page.jql = text;
+}
+
+fn url_filter(url: &Url) -> BoardFilter {
+    let search = url.search();
+    let value = |key: &str| {
+        search
+            .get(key)
+            .and_then(|values| values.first().cloned())
+            .unwrap_or_default()
+    };
+    let ids = |key: &str| {
+        value(key)
+            .split(',')
+            .filter_map(|id| id.parse::<i32>().ok())
+            .collect()
+    };
+    BoardFilter {
+        text: value("text"),
+        user_ids: ids("users"),
+        label_ids: ids("labels"),
+        only_my: value("only_my") == "1",
+        recently_updated: value("recent") == "1",
+        jql: value("jql"),
+    }
+}
+
+fn url_search(filter: &BoardFilter) -> UrlSearch {
+    let join = |ids: &[i32]| {
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+    let params = vec![
+        ("text", filter.text.clone()),
+        ("users", join(filter.user_ids.as_slice())),
+        ("labels", join(filter.label_ids.as_slice())),
+        ("only_my", if filter.only_my { "1" } else { "" }.to_string()),
+        (
+            "recent",
+            if filter.recently_updated { "1" } else { "" }.to_string(),
+        ),
+        ("jql", filter.jql.clone()),
+    ];
+    UrlSearch::new(
+        params
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| (key, vec![value])),
+    )
 }
 
 fn init_load(model: &mut Model, orders: &mut impl Orders<Msg>) {
-    enqueue_ws_msg(
-        vec![WsMsg::ProjectIssuesRequest, WsMsg::IssueStatusesRequest],
-        model.ws.as_ref(),
-        orders,
-    );
+    let mut messages = vec![
+        WsMsg::ProjectIssuesRequest,
+        WsMsg::IssueStatusesRequest,
+        WsMsg::LabelsRequest,
+        WsMsg::SprintsRequest,
+        WsMsg::CustomFieldsRequest,
+        WsMsg::IssueStatusTransitionsRequest,
+        WsMsg::SavedFiltersRequest,
+    ];
+    // query restored from address
+    if let PageContent::Project(project_page) = &model.page_content {
+        if project_page.jql_error.is_none() && !project_page.jql.trim().is_empty() {
+            messages.push(WsMsg::IssueQueryRequest(project_page.jql.clone()));
+        }
+    }
+    enqueue_ws_msg(messages, model.ws.as_ref(), orders);
 }
 
 fn build_page_content(model: &mut Model) {
-    model.page_content = PageContent::Project(Box::new(ProjectPage::default()));
+    let filter = url_filter(&Url::current());
+    let mut project_page = ProjectPage::default();
+    project_page.jql_error = if filter.jql.trim().is_empty() {
+        None
+    } else {
+        parse_jql(filter.jql.as_str()).err()
+    };
+    project_page.jql = filter.jql.clone();
+    project_page.set_board_filter(filter);
+    model.page_content = PageContent::Project(Box::new(project_page));
 }
@@ -16,6 +16,8 @@ pub fn view(model: &Model) -> Node<Msg> {
         breadcrumbs(model),
         header(),
         project_board_filters(model),
+        project_board_saved_filters(model),
+        project_board_jql(model),
         project_board_lists(model),
     ];
 
@@ -83,9 +85,19 @@ fn project_board_filters(model: &Model) -> Node<Msg> {
         .build()
         .into_node();
 
+    let group_by_epic = StyledButton::build()
+        .empty()
+        .active(project_page.group_by_epic)
+        .text("Group by Epic")
+        .on_click(mouse_ev(Ev::Click, |_| Msg::ProjectToggleGroupByEpic))
+        .build()
+        .into_node();
+
     let clear_all = if project_page.only_my_filter
         || project_page.recently_updated_filter
         || !project_page.active_avatar_filters.is_empty()
+        || !project_page.active_label_filters.is_empty()
+        || !project_page.active_custom_filters.is_empty()
     {
         seed::button![
             id!["clearAllFilters"],
@@ -100,12 +112,152 @@ fn project_board_filters(model: &Model) -> Node<Msg> {
         id!["projectBoardFilters"],
         search_input,
         avatars_filters(model),
+        labels_filters(model),
+        custom_fields_filters(model),
         only_my,
         recently_updated,
+        group_by_epic,
         clear_all
     ]
 }
 
+/// Saved filters as presets, current filter can be saved under new name
+fn project_board_saved_filters(model: &Model) -> Node<Msg> {
+    let project_page = match &model.page_content {
+        PageContent::Project(page_content) => page_content,
+        _ => return empty![],
+    };
+    let current_filter = project_page.board_filter();
+    let user_id = model.user.as_ref().map(|u| u.id).unwrap_or_default();
+
+    let presets: Vec<Node<Msg>> = model
+        .saved_filters
+        .iter()
+        .map(|saved_filter| {
+            let saved_filter_id = saved_filter.id;
+            let delete = if saved_filter.can_be_removed_by(user_id) {
+                span![
+                    class!["delete"],
+                    StyledIcon::build(Icon::Close).size(12).build().into_node(),
+                    mouse_ev(Ev::Click, move |ev| {
+                        ev.stop_propagation();
+                        Msg::ProjectSavedFilterDelete(saved_filter_id)
+                    }),
+                ]
+            } else {
+                empty![]
+            };
+            let shared = if saved_filter.shared {
+                StyledIcon::build(Icon::Link).build().into_node()
+            } else {
+                empty![]
+            };
+            span![
+                class!["savedFilter"],
+                class!["isActive" => saved_filter.filter() == current_filter],
+                shared,
+                saved_filter.name.as_str(),
+                delete,
+                mouse_ev(Ev::Click, move |_| {
+                    Msg::ProjectSavedFilterApplied(saved_filter_id)
+                }),
+            ]
+        })
+        .collect();
+
+    let form = if project_page.saving_filter {
+        let name = StyledInput::build(FieldId::SavedFilterName)
+            .valid(true)
+            .value(project_page.saved_filter_name.as_str())
+            .build()
+            .into_node();
+        let shared = StyledButton::build()
+            .empty()
+            .active(project_page.saved_filter_shared)
+            .text("Shared with project")
+            .on_click(mouse_ev(Ev::Click, |_| Msg::ProjectSavedFilterToggleShared))
+            .build()
+            .into_node();
+        let save = StyledButton::build()
+            .primary()
+            .text("Save")
+            .disabled(project_page.saved_filter_name.trim().is_empty())
+            .on_click(mouse_ev(Ev::Click, |_| Msg::ProjectSavedFilterSave))
+            .build()
+            .into_node();
+        let cancel = StyledButton::build()
+            .empty()
+            .text("Cancel")
+            .on_click(mouse_ev(Ev::Click, |_| Msg::ProjectSavedFilterFormToggle))
+            .build()
+            .into_node();
+        div![class!["saveFilterForm"], name, shared, save, cancel]
+    } else {
+        StyledButton::build()
+            .empty()
+            .icon(Icon::Plus)
+            .text("Save filter")
+            .on_click(mouse_ev(Ev::Click, |_| Msg::ProjectSavedFilterFormToggle))
+            .build()
+            .into_node()
+    };
+
+    div![id!["projectBoardSavedFilters"], presets, form]
+}
+
+/// Query input with error range of invalid query underlined below it
+fn project_board_jql(model: &Model) -> Node<Msg> {
+    let project_page = match &model.page_content {
+        PageContent::Project(page_content) => page_content,
+        _ => return empty![],
+    };
+
+    let input = seed::input![
+        class!["jqlInput"],
+        class!["invalid" => project_page.jql_error.is_some()],
+        attrs![
+            At::Value => project_page.jql.as_str();
+            At::Placeholder => "assignee = me AND priority >= High ORDER BY updated DESC";
+            At::SpellCheck => false;
+        ],
+        input_ev(Ev::Input, |value| {
+            Msg::StrInputChanged(FieldId::JqlFilterBoard, value)
+        }),
+    ];
+
+    let error = match project_page.jql_error.as_ref() {
+        Some(error) => {
+            let chars: Vec<char> = project_page.jql.chars().collect();
+            let start = error.span.start.min(chars.len());
+            let end = error.span.end.max(start).min(chars.len());
+            let before: String = chars[..start].iter().collect();
+            let marked: String = chars[start..end].iter().collect();
+            let after: String = chars[end..].iter().collect();
+            div![
+                class!["jqlError"],
+                div![
+                    class!["query"],
+                    span![before],
+                    // error at the end of query is marked with single space
+                    span![
+                        class!["mark"],
+                        if marked.is_empty() {
+                            " ".to_string()
+                        } else {
+                            marked
+                        }
+                    ],
+                    span![after]
+                ],
+                div![class!["message"], error.message.as_str()]
+            ]
+        }
+        None => empty![],
+    };
+
+    div![id!["projectBoardJql"], input, error]
+}
+
 fn avatars_filters(model: &Model) -> Node<Msg> {
     let project_page = match &model.page_content {
         PageContent::Project(project_page) => project_page,
@@ -139,16 +291,179 @@ fn avatars_filters(model: &Model) -> Node<Msg> {
     div![id!["avatars"], avatars]
 }
 
+fn labels_filters(model: &Model) -> Node<Msg> {
+    let project_page = match &model.page_content {
+        PageContent::Project(project_page) => project_page,
+        _ => return empty![],
+    };
+    if model.labels.is_empty() {
+        return empty![];
+    }
+    let labels: Vec<Node<Msg>> = model
+        .labels
+        .iter()
+        .map(|label| {
+            let label_id = label.id;
+            let active = project_page.active_label_filters.contains(&label_id);
+            let mut class_list = vec!["labelFilter"];
+            if active {
+                class_list.push("isActive");
+            }
+            span![
+                attrs![
+                    At::Class => class_list.join(" ");
+                    At::Style => format!("background: {}", label.color)
+                ],
+                label.name.as_str(),
+                mouse_ev(Ev::Click, move |_| {
+                    Msg::ProjectLabelFilterChanged(label_id, active)
+                }),
+            ]
+        })
+        .collect();
+
+    div![id!["labels"], labels]
+}
+
+/// Only select fields have finite set of values worth to be filtered by
+fn custom_fields_filters(model: &Model) -> Node<Msg> {
+    let project_page = match &model.page_content {
+        PageContent::Project(project_page) => project_page,
+        _ => return empty![],
+    };
+    let mut filters: Vec<Node<Msg>> = vec![];
+    for field in model
+        .custom_fields
+        .iter()
+        .filter(|field| field.field_type.has_options())
+    {
+        for option in field.options.iter() {
+            let custom_field_id = field.id;
+            let value = option.clone();
+            let active = project_page
+                .active_custom_filters
+                .iter()
+                .any(|(id, v)| *id == custom_field_id && v == option);
+            let mut class_list = vec!["customFieldFilter"];
+            if active {
+                class_list.push("isActive");
+            }
+            filters.push(span![
+                attrs![At::Class => class_list.join(" ")],
+                format!("{}: {}", field.name, option),
+                mouse_ev(Ev::Click, move |_| {
+                    Msg::ProjectCustomFilterChanged(custom_field_id, value, active)
+                }),
+            ]);
+        }
+    }
+    if filters.is_empty() {
+        return empty![];
+    }
+
+    div![id!["customFieldFilters"], filters]
+}
+
 fn project_board_lists(model: &Model) -> Node<Msg> {
+    let group_by_epic = match &model.page_content {
+        PageContent::Project(project_page) => project_page.group_by_epic,
+        _ => return empty![],
+    };
+    if !group_by_epic {
+        let columns: Vec<Node<Msg>> = model
+            .issue_statuses
+            .iter()
+            .map(|is| project_issue_list(model, is, None))
+            .collect();
+        return div![class!["projectBoardLists"], columns];
+    }
+
+    let mut epics: Vec<&Issue> = model
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::Epic)
+        .collect();
+    epics.sort_by(|a, b| a.list_position.cmp(&b.list_position));
+    let mut lanes: Vec<Node<Msg>> = epics
+        .into_iter()
+        .map(|epic| epic_swimlane(model, Some(epic)))
+        .collect();
+    lanes.push(epic_swimlane(model, None));
+    div![id!["projectBoardLanes"], lanes]
+}
+
+/// Issues of single epic, `None` is lane of issues without epic
+fn epic_swimlane(model: &Model, epic: Option<&Issue>) -> Node<Msg> {
+    let epic_id = epic.map(|e| e.id);
     let columns: Vec<Node<Msg>> = model
         .issue_statuses
         .iter()
-        .map(|is| project_issue_list(model, is))
+        .map(|is| project_issue_list(model, is, Some(epic_id)))
         .collect();
-    div![id!["projectBoardLists"], columns]
+
+    let header = match epic {
+        Some(epic) => {
+            let (done, total) = epic_progress(model, epic.id);
+            let percent = if total == 0 { 0 } else { done * 100 / total };
+            let icon = StyledIcon::build(Icon::Epic).build().into_node();
+            div![
+                class!["laneHeader"],
+                a![
+                    attrs![At::Class => "laneTitle"; At::Href => format!("/issues/{}", epic.id)],
+                    icon,
+                    epic.title.as_str()
+                ],
+                div![
+                    class!["progress"],
+                    div![
+                        class!["value"],
+                        attrs![At::Style => format!("width: {}%", percent)]
+                    ]
+                ],
+                span![
+                    class!["progressLabel"],
+                    format!("{} of {} done", done, total)
+                ],
+            ]
+        }
+        None => div![
+            class!["laneHeader"],
+            span![class!["laneTitle"], "Issues without epic"]
+        ],
+    };
+
+    div![
+        class!["swimlane"],
+        header,
+        div![class!["projectBoardLists"], columns]
+    ]
 }
 
-fn project_issue_list(model: &Model, status: &jirs_data::IssueStatus) -> Node<Msg> {
+/// Issues in last column are counted as done
+fn epic_progress(model: &Model, epic_id: IssueId) -> (usize, usize) {
+    let done_status = model
+        .issue_statuses
+        .iter()
+        .max_by_key(|is| is.position)
+        .map(|is| is.id);
+    model
+        .issues
+        .iter()
+        .filter(|issue| issue.epic_id == Some(epic_id))
+        .fold((0, 0), |(done, total), issue| {
+            if Some(issue.issue_status_id) == done_status {
+                (done + 1, total + 1)
+            } else {
+                (done, total + 1)
+            }
+        })
+}
+
+fn project_issue_list(
+    model: &Model,
+    status: &jirs_data::IssueStatus,
+    epic_lane: Option<Option<IssueId>>,
+) -> Node<Msg> {
     let project_page = match &model.page_content {
         PageContent::Project(project_page) => project_page,
         _ => return empty![],
@@ -167,15 +482,25 @@ fn project_issue_list(model: &Model, status: &jirs_data::IssueStatus) -> Node<Ms
     } else {
         model.issues.iter().map(|issue| issue.id).collect()
     };
+    let active_sprint_id = model
+        .sprints
+        .iter()
+        .find(|sprint| sprint.state == SprintState::Active)
+        .map(|sprint| sprint.id);
     let issues: Vec<Node<Msg>> = model
         .issues
         .iter()
         .filter(|issue| {
             issue_filter_status(issue, status)
                 && issue_filter_with_avatars(issue, &project_page.active_avatar_filters)
+                && issue_filter_with_labels(issue, &project_page.active_label_filters)
+                && issue_filter_with_custom_values(issue, &project_page.active_custom_filters)
                 && issue_filter_with_text(issue, project_page.text_filter.as_str())
+                && issue_filter_with_jql(issue, project_page.jql_issue_ids.as_ref())
                 && issue_filter_with_only_my(issue, project_page.only_my_filter, &model.user)
                 && issue_filter_with_only_recent(issue, ids.as_slice())
+                && issue_filter_with_epic_lane(issue, epic_lane)
+                && issue_filter_with_sprint(issue, active_sprint_id)
         })
         .map(|issue| project_issue(model, issue))
         .collect();
@@ -197,12 +522,27 @@ fn project_issue_list(model: &Model, status: &jirs_data::IssueStatus) -> Node<Ms
         )))
     });
 
+    let illegal_target = project_page.issue_drag.dragged_id.is_some()
+        && !project_page.allowed_drop_statuses.contains(&status.id);
+
+    let column_count = model
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_status_id == status.id)
+        .count();
+    let issues_count = match status.wip_limit {
+        Some(limit) => format!("{}/{}", column_count, limit),
+        None => String::new(),
+    };
+
     div![
-        attrs![At::Class => "list";],
+        class!["list"],
+        class!["illegalTarget" => illegal_target],
+        class!["overLimit" => status.is_over_limit(column_count)],
         div![
             attrs![At::Class => "title"],
             label,
-            div![attrs![At::Class => "issuesCount"]]
+            div![attrs![At::Class => "issuesCount"], issues_count]
         ],
         div![
             attrs![At::Class => "issues"; At::DropZone => "link"],
@@ -221,14 +561,43 @@ fn issue_filter_with_avatars(issue: &Issue, user_ids: &[UserId]) -> bool {
     user_ids.contains(&issue.reporter_id) || issue.user_ids.iter().any(|id| user_ids.contains(id))
 }
 
+#[inline]
+fn issue_filter_with_labels(issue: &Issue, label_ids: &[LabelId]) -> bool {
+    label_ids.is_empty() || issue.label_ids.iter().any(|id| label_ids.contains(id))
+}
+
 #[inline]
 fn issue_filter_status(issue: &Issue, status: &IssueStatus) -> bool {
     issue.issue_status_id == status.id
 }
 
+/// Issue has to match at least one chosen value of every filtered field
+#[inline]
+fn issue_filter_with_custom_values(issue: &Issue, filters: &[(CustomFieldId, String)]) -> bool {
+    filters.iter().all(|(field_id, _)| {
+        issue.custom_values.iter().any(|custom_value| {
+            custom_value.custom_field_id == *field_id
+                && custom_value
+                    .values
+                    .iter()
+                    .any(|value| filters.iter().any(|(id, v)| id == field_id && v == value))
+        })
+    })
+}
+
 #[inline]
 fn issue_filter_with_text(issue: &Issue, text: &str) -> bool {
-    text.is_empty() || issue.title.contains(text)
+    text.is_empty()
+        || issue.title.contains(text)
+        || issue
+            .custom_values
+            .iter()
+            .any(|custom_value| custom_value.values.iter().any(|v| v.contains(text)))
+}
+
+#[inline]
+fn issue_filter_with_jql(issue: &Issue, ids: Option<&Vec<IssueId>>) -> bool {
+    ids.map_or(true, |ids| ids.contains(&issue.id))
 }
 
 #[inline]
@@ -242,6 +611,20 @@ fn issue_filter_with_only_recent(issue: &Issue, ids: &[IssueId]) -> bool {
     ids.is_empty() || ids.contains(&issue.id)
 }
 
+#[inline]
+fn issue_filter_with_epic_lane(issue: &Issue, epic_lane: Option<Option<IssueId>>) -> bool {
+    match epic_lane {
+        Some(epic_id) => issue.issue_type != IssueType::Epic && issue.epic_id == epic_id,
+        None => true,
+    }
+}
+
+/// When sprint is running board shows only its issues
+#[inline]
+fn issue_filter_with_sprint(issue: &Issue, active_sprint_id: Option<SprintId>) -> bool {
+    active_sprint_id.is_none() || issue.sprint_id == active_sprint_id
+}
+
 fn project_issue(model: &Model, issue: &Issue) -> Node<Msg> {
     let avatars: Vec<Node<Msg>> = model
         .users
@@ -304,9 +687,39 @@ fn project_issue(model: &Model, issue: &Issue) -> Node<Msg> {
         )))
     });
 
+    let epic_tag = match issue
+        .epic_id
+        .and_then(|epic_id| model.issues.iter().find(|i| i.id == epic_id))
+    {
+        Some(epic) => div![attrs![At::Class => "epicTag"], epic.title.as_str()],
+        _ => empty![],
+    };
+
+    let labels: Vec<Node<Msg>> = model
+        .labels
+        .iter()
+        .filter(|label| issue.label_ids.contains(&label.id))
+        .map(|label| {
+            span![
+                attrs![At::Class => "labelTag"; At::Style => format!("background: {}", label.color)],
+                label.name.as_str()
+            ]
+        })
+        .collect();
+    let labels = if labels.is_empty() {
+        empty![]
+    } else {
+        div![attrs![At::Class => "labels"], labels]
+    };
+
     let class_list = vec!["issue"];
 
     let href = format!("/issues/{id}", id = issue_id);
+    let issue_key = model
+        .project
+        .as_ref()
+        .map(|project| project.issue_key(issue))
+        .unwrap_or_default();
 
     a![
         drag_started,
@@ -317,11 +730,14 @@ fn project_issue(model: &Model, issue: &Issue) -> Node<Msg> {
             drag_over_handler,
             drag_out,
             p![attrs![At::Class => "title"], issue.title.as_str()],
+            epic_tag,
+            labels,
             div![
                 attrs![At::Class => "bottom"],
                 div![
                     div![attrs![At::Class => "issueTypeIcon"], issue_type_icon],
-                    div![attrs![At::Class => "issuePriorityIcon"], priority_icon]
+                    div![attrs![At::Class => "issuePriorityIcon"], priority_icon],
+                    div![attrs![At::Class => "issueKey"], issue_key]
                 ],
                 div![attrs![At::Class => "assignees"], avatars,],
             ]
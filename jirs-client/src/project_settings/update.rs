@@ -3,7 +3,10 @@ use std::collections::HashSet;
 use seed::error;
 use seed::prelude::Orders;
 
-use jirs_data::{IssueStatus, IssueStatusId, ProjectFieldId, UpdateProjectPayload, WsMsg};
+use jirs_data::{
+    CreateCustomFieldPayload, CreateIssueStatusTransitionPayload, CustomFieldType, IssueStatus,
+    IssueStatusId, ProjectFieldId, UpdateProjectPayload, WsMsg,
+};
 
 use crate::model::{Model, Page, PageContent, ProjectSettingsPage};
 use crate::shared::styled_select::StyledSelectChange;
@@ -53,13 +56,25 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     };
     page.project_category_state.update(&msg, orders);
     page.time_tracking.update(&msg);
+    page.wip_limit_policy.update(&msg);
     page.name.update(&msg);
     page.description_rte.update(&msg);
+    page.label_name.update(&msg);
+    page.label_color.update(&msg);
+    page.custom_field_name.update(&msg);
+    page.custom_field_type_state.update(&msg, orders);
+    page.custom_field_options.update(&msg);
+    page.transition_from_state.update(&msg, orders);
+    page.transition_to_state.update(&msg, orders);
+    page.transition_requirement_state.update(&msg, orders);
 
     match msg {
         Msg::StrInputChanged(FieldId::ProjectSettings(ProjectFieldId::Name), text) => {
             page.payload.name = Some(text);
         }
+        Msg::StrInputChanged(FieldId::ProjectSettings(ProjectFieldId::Key), text) => {
+            page.payload.key = Some(text.trim().to_uppercase());
+        }
         Msg::StrInputChanged(FieldId::ProjectSettings(ProjectFieldId::Url), text) => {
             page.payload.url = Some(text);
         }
@@ -90,6 +105,8 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                     description: page.payload.description.clone(),
                     category: page.payload.category,
                     time_tracking: Some(page.time_tracking.value.into()),
+                    wip_limit_policy: Some(page.wip_limit_policy.value.into()),
+                    key: page.payload.key.clone(),
                 }),
                 model.ws.as_ref(),
                 orders,
@@ -122,14 +139,14 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             if page.edit_column_id.is_some() && id.is_none() {
                 let old_id = page.edit_column_id.as_ref().cloned();
                 let name = page.name.value.clone();
-                if let Some((id, pos)) = model
+                if let Some((id, pos, wip_limit)) = model
                     .issue_statuses
                     .iter()
                     .find(|is| Some(is.id) == old_id)
-                    .map(|is| (is.id, is.position))
+                    .map(|is| (is.id, is.position, is.wip_limit))
                 {
                     send_ws_msg(
-                        WsMsg::IssueStatusUpdate(id, name, pos),
+                        WsMsg::IssueStatusUpdate(id, name, pos, wip_limit),
                         model.ws.as_ref(),
                         orders,
                     );
@@ -156,13 +173,119 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             let ws_msg = WsMsg::IssueStatusCreate(name, position as i32);
             send_ws_msg(ws_msg, model.ws.as_ref(), orders);
         }
+        Msg::PageChanged(PageChanged::ProjectSettings(
+            ProjectPageChange::ColumnWipLimitChanged(id, value),
+        )) => {
+            let wip_limit = value.trim().parse::<i32>().ok().filter(|limit| *limit > 0);
+            if let Some(is) = model.issue_statuses.iter().find(|is| is.id == id) {
+                let ws_msg = WsMsg::IssueStatusUpdate(id, is.name.clone(), is.position, wip_limit);
+                send_ws_msg(ws_msg, model.ws.as_ref(), orders);
+            }
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(ProjectPageChange::SubmitLabelForm)) => {
+            let name = page.label_name.value.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let ws_msg = WsMsg::LabelCreateRequest(name, page.label_color.value.clone());
+            send_ws_msg(ws_msg, model.ws.as_ref(), orders);
+            page.label_name.reset();
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(ProjectPageChange::DeleteLabel(
+            label_id,
+        ))) => {
+            send_ws_msg(
+                WsMsg::LabelDeleteRequest(label_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(
+            ProjectPageChange::SubmitCustomFieldForm,
+        )) => {
+            let name = page.custom_field_name.value.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let field_type: CustomFieldType = page
+                .custom_field_type_state
+                .values
+                .get(0)
+                .map(|v| (*v).into())
+                .unwrap_or_default();
+            let options = if field_type.has_options() {
+                page.custom_field_options
+                    .value
+                    .split(',')
+                    .map(|option| option.trim().to_string())
+                    .filter(|option| !option.is_empty())
+                    .collect()
+            } else {
+                vec![]
+            };
+            let ws_msg = WsMsg::CustomFieldCreateRequest(CreateCustomFieldPayload {
+                name,
+                field_type,
+                options,
+            });
+            send_ws_msg(ws_msg, model.ws.as_ref(), orders);
+            page.custom_field_name.reset();
+            page.custom_field_options.reset();
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(ProjectPageChange::DeleteCustomField(
+            custom_field_id,
+        ))) => {
+            send_ws_msg(
+                WsMsg::CustomFieldDeleteRequest(custom_field_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(ProjectPageChange::SubmitTransitionForm)) => {
+            let (from_status_id, to_status_id) = match (
+                page.transition_from_state.values.get(0),
+                page.transition_to_state.values.get(0),
+            ) {
+                (Some(from), Some(to)) if from != to => {
+                    (*from as IssueStatusId, *to as IssueStatusId)
+                }
+                _ => return,
+            };
+            let required_field = match page.transition_requirement_state.values.get(0) {
+                Some(0) | None => None,
+                Some(value) => Some((*value).into()),
+            };
+            let ws_msg =
+                WsMsg::IssueStatusTransitionCreateRequest(CreateIssueStatusTransitionPayload {
+                    from_status_id,
+                    to_status_id,
+                    required_field,
+                });
+            send_ws_msg(ws_msg, model.ws.as_ref(), orders);
+            page.transition_to_state.reset();
+        }
+        Msg::PageChanged(PageChanged::ProjectSettings(ProjectPageChange::DeleteTransition(
+            transition_id,
+        ))) => {
+            send_ws_msg(
+                WsMsg::IssueStatusTransitionDeleteRequest(transition_id),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
         _ => (),
     }
 }
 
 fn init_load(model: &mut Model, orders: &mut impl Orders<Msg>) {
     enqueue_ws_msg(
-        vec![WsMsg::IssueStatusesRequest, WsMsg::ProjectIssuesRequest],
+        vec![
+            WsMsg::IssueStatusesRequest,
+            WsMsg::ProjectIssuesRequest,
+            WsMsg::LabelsRequest,
+            WsMsg::CustomFieldsRequest,
+            WsMsg::IssueStatusTransitionsRequest,
+        ],
         model.ws.as_ref(),
         orders,
     );
@@ -228,13 +351,17 @@ fn sync(model: &mut Model, orders: &mut impl Orders<Msg>) {
         _ => return error!("bad content type"),
     };
     for id in dirty {
-        let IssueStatus { name, position, .. } =
-            match model.issue_statuses.iter().find(|is| is.id == id) {
-                Some(is) => is,
-                _ => continue,
-            };
+        let IssueStatus {
+            name,
+            position,
+            wip_limit,
+            ..
+        } = match model.issue_statuses.iter().find(|is| is.id == id) {
+            Some(is) => is,
+            _ => continue,
+        };
         send_ws_msg(
-            WsMsg::IssueStatusUpdate(id, name.clone(), *position),
+            WsMsg::IssueStatusUpdate(id, name.clone(), *position, *wip_limit),
             model.ws.as_ref(),
             orders,
         );
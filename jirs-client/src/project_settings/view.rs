@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use seed::{prelude::*, *};
 
-use jirs_data::{IssueStatus, ProjectCategory, TimeTracking, ToVec};
+use jirs_data::{
+    CustomFieldType, IssueStatus, ProjectCategory, TimeTracking, ToVec, TransitionRequirement,
+    WipLimitPolicy,
+};
 
 use crate::model::{DeleteIssueStatusModal, ModalType, Model, PageContent, ProjectSettingsPage};
 use crate::shared::styled_button::StyledButton;
@@ -13,7 +16,8 @@ use crate::shared::styled_form::StyledForm;
 use crate::shared::styled_icon::{Icon, StyledIcon};
 use crate::shared::styled_input::StyledInput;
 use crate::shared::styled_rte::StyledRte;
-use crate::shared::styled_select::StyledSelect;
+use crate::shared::styled_select::{StyledSelect, StyledSelectState};
+use crate::shared::styled_select_child::StyledSelectChild;
 use crate::shared::styled_textarea::StyledTextarea;
 use crate::shared::{inner_layout, ToChild, ToNode};
 use crate::{model, FieldId, Msg, PageChanged, ProjectFieldId, ProjectPageChange};
@@ -28,6 +32,8 @@ pub fn view(model: &model::Model) -> Node<Msg> {
     };
     let name_field = name_field(page);
 
+    let key_field = key_field(page);
+
     let url_field = url_field(page);
 
     let description_field = description_field(page);
@@ -66,8 +72,31 @@ pub fn view(model: &model::Model) -> Node<Msg> {
         .build()
         .into_node();
 
+    let wip_limit_policy =
+        StyledCheckbox::build(FieldId::ProjectSettings(ProjectFieldId::WipLimitPolicy))
+            .options(vec![
+                WipLimitPolicy::Warn.to_child(),
+                WipLimitPolicy::Block.to_child(),
+            ])
+            .state(&page.wip_limit_policy)
+            .add_class("wipLimitPolicy")
+            .build()
+            .into_node();
+    let wip_limit_policy_field = StyledField::build()
+        .label("Columns limit")
+        .input(wip_limit_policy)
+        .tip("What happens when issue is moved into column which reached its WIP limit.")
+        .build()
+        .into_node();
+
     let columns_field = columns_section(model, page);
 
+    let labels_field = labels_section(model, page);
+
+    let custom_fields_field = custom_fields_section(model, page);
+
+    let workflow_field = workflow_section(model, page);
+
     let save_button = StyledButton::build()
         .add_class("actionButton")
         .on_click(mouse_ev(Ev::Click, |ev| {
@@ -89,13 +118,18 @@ pub fn view(model: &model::Model) -> Node<Msg> {
             ))
         }))
         .add_field(name_field)
+        .add_field(key_field)
         .add_field(url_field)
         .add_field(desc_rte)
         .add_field(description_field)
         .add_field(category_field)
         .add_field(time_tracking_field)
+        .add_field(wip_limit_policy_field)
         .add_field(save_button)
         .add_field(columns_field)
+        .add_field(labels_field)
+        .add_field(custom_fields_field)
+        .add_field(workflow_field)
         .build()
         .into_node();
 
@@ -121,6 +155,23 @@ fn name_field(page: &ProjectSettingsPage) -> Node<Msg> {
         .into_node()
 }
 
+/// Build project key input with styled field wrapper
+fn key_field(page: &ProjectSettingsPage) -> Node<Msg> {
+    let key = StyledTextarea::build(FieldId::ProjectSettings(ProjectFieldId::Key))
+        .height(39)
+        .max_height(39)
+        .disable_auto_resize()
+        .value(page.payload.key.as_ref().cloned().unwrap_or_default())
+        .build()
+        .into_node();
+    StyledField::build()
+        .label("Key")
+        .input(key)
+        .tip("Prefix of issue keys, for example JIRS-12. Changing it breaks old links.")
+        .build()
+        .into_node()
+}
+
 /// Build project url input with styled field wrapper
 fn url_field(page: &ProjectSettingsPage) -> Node<Msg> {
     let url = StyledTextarea::build(FieldId::ProjectSettings(ProjectFieldId::Url))
@@ -217,7 +268,324 @@ fn columns_section(model: &Model, page: &ProjectSettingsPage) -> Node<Msg> {
         .add_class("columnsField")
         .input(columns_section)
         .label("Columns")
-        .tip("Double-click on name to change it.")
+        .tip("Double-click on name to change it. Empty WIP limit means column has no limit.")
+        .build()
+        .into_node()
+}
+
+/// Build list of project labels with option to remove and add new labels
+fn labels_section(model: &Model, page: &ProjectSettingsPage) -> Node<Msg> {
+    let labels: Vec<Node<Msg>> = model
+        .labels
+        .iter()
+        .map(|label| {
+            let label_id = label.id;
+            let delete = StyledButton::build()
+                .empty()
+                .icon(Icon::Close)
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::ProjectSettings(
+                        ProjectPageChange::DeleteLabel(label_id),
+                    ))
+                }))
+                .build()
+                .into_node();
+            div![
+                class!["labelPreview"],
+                span![
+                    class!["labelChip"],
+                    attrs![At::Style => format!("background: {}", label.color)],
+                    label.name.as_str()
+                ],
+                delete
+            ]
+        })
+        .collect();
+
+    let name = StyledInput::build(FieldId::ProjectSettings(ProjectFieldId::LabelName))
+        .state(&page.label_name)
+        .valid(true)
+        .build()
+        .into_node();
+    let color = seed::input![
+        class!["labelColor"],
+        attrs![At::Type => "color"; At::Value => page.label_color.value.as_str()],
+        input_ev(Ev::Input, |value| {
+            Msg::StrInputChanged(FieldId::ProjectSettings(ProjectFieldId::LabelColor), value)
+        }),
+    ];
+    let add = StyledButton::build()
+        .secondary()
+        .text("Add label")
+        .disabled(page.label_name.value.trim().is_empty())
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::PageChanged(PageChanged::ProjectSettings(
+                ProjectPageChange::SubmitLabelForm,
+            ))
+        }))
+        .build()
+        .into_node();
+
+    let labels_section = section![
+        class!["labelsSection"],
+        div![class!["labels"], labels],
+        div![class!["addLabel"], name, color, add]
+    ];
+    StyledField::build()
+        .add_class("labelsField")
+        .input(labels_section)
+        .label("Labels")
+        .build()
+        .into_node()
+}
+
+/// Build list of project custom fields with option to remove and define new
+/// fields. Options of select fields are separated with comma.
+fn custom_fields_section(model: &Model, page: &ProjectSettingsPage) -> Node<Msg> {
+    let fields: Vec<Node<Msg>> = model
+        .custom_fields
+        .iter()
+        .map(|field| {
+            let custom_field_id = field.id;
+            let delete = StyledButton::build()
+                .empty()
+                .icon(Icon::Close)
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::ProjectSettings(
+                        ProjectPageChange::DeleteCustomField(custom_field_id),
+                    ))
+                }))
+                .build()
+                .into_node();
+            let options = if field.field_type.has_options() {
+                span![class!["fieldOptions"], field.options.join(", ")]
+            } else {
+                empty![]
+            };
+            div![
+                class!["customFieldPreview"],
+                span![class!["fieldName"], field.name.as_str()],
+                span![class!["fieldType"], field.field_type.to_label()],
+                options,
+                delete
+            ]
+        })
+        .collect();
+
+    let field_type: CustomFieldType = page
+        .custom_field_type_state
+        .values
+        .get(0)
+        .map(|v| (*v).into())
+        .unwrap_or_default();
+
+    let name = StyledInput::build(FieldId::ProjectSettings(ProjectFieldId::CustomFieldName))
+        .state(&page.custom_field_name)
+        .valid(true)
+        .build()
+        .into_node();
+    let type_select =
+        StyledSelect::build(FieldId::ProjectSettings(ProjectFieldId::CustomFieldType))
+            .name("customFieldType")
+            .state(&page.custom_field_type_state)
+            .normal()
+            .options(
+                CustomFieldType::ordered()
+                    .into_iter()
+                    .map(|t| t.to_child().name("customFieldType"))
+                    .collect(),
+            )
+            .selected(vec![field_type.to_child().name("customFieldType")])
+            .build()
+            .into_node();
+    let options = if field_type.has_options() {
+        StyledInput::build(FieldId::ProjectSettings(ProjectFieldId::CustomFieldOptions))
+            .state(&page.custom_field_options)
+            .valid(true)
+            .build()
+            .into_node()
+    } else {
+        empty![]
+    };
+    let add = StyledButton::build()
+        .secondary()
+        .text("Add field")
+        .disabled(page.custom_field_name.value.trim().is_empty())
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::PageChanged(PageChanged::ProjectSettings(
+                ProjectPageChange::SubmitCustomFieldForm,
+            ))
+        }))
+        .build()
+        .into_node();
+
+    let custom_fields_section = section![
+        class!["customFieldsSection"],
+        div![class!["customFields"], fields],
+        div![class!["addCustomField"], name, type_select, options, add]
+    ];
+    StyledField::build()
+        .add_class("customFieldsField")
+        .input(custom_fields_section)
+        .label("Custom fields")
+        .tip("Options of select fields are separated with comma")
+        .build()
+        .into_node()
+}
+
+/// Build list of allowed moves between columns. Without any transition
+/// issues can be moved freely.
+fn workflow_section(model: &Model, page: &ProjectSettingsPage) -> Node<Msg> {
+    let status_name = |id| {
+        model
+            .issue_statuses
+            .iter()
+            .find(|status| status.id == id)
+            .map(|status| status.name.as_str())
+            .unwrap_or_default()
+    };
+    let transitions: Vec<Node<Msg>> = model
+        .issue_status_transitions
+        .iter()
+        .map(|transition| {
+            let transition_id = transition.id;
+            let delete = StyledButton::build()
+                .empty()
+                .icon(Icon::Close)
+                .on_click(mouse_ev(Ev::Click, move |ev| {
+                    ev.prevent_default();
+                    Msg::PageChanged(PageChanged::ProjectSettings(
+                        ProjectPageChange::DeleteTransition(transition_id),
+                    ))
+                }))
+                .build()
+                .into_node();
+            let requirement = match transition.required_field {
+                Some(required) => span![
+                    class!["requirement"],
+                    format!("requires {}", required.to_label().to_lowercase())
+                ],
+                _ => empty![],
+            };
+            div![
+                class!["transitionPreview"],
+                span![class!["status"], status_name(transition.from_status_id)],
+                StyledIcon::build(Icon::ArrowRight).build().into_node(),
+                span![class!["status"], status_name(transition.to_status_id)],
+                requirement,
+                delete
+            ]
+        })
+        .collect();
+
+    let from = transition_status_select(
+        model,
+        ProjectFieldId::TransitionFrom,
+        &page.transition_from_state,
+    );
+    let to = transition_status_select(
+        model,
+        ProjectFieldId::TransitionTo,
+        &page.transition_to_state,
+    );
+
+    let no_requirement = || {
+        StyledSelectChild::build()
+            .text("No requirement")
+            .value(0)
+            .name("transitionRequirement")
+    };
+    let requirement_value = page
+        .transition_requirement_state
+        .values
+        .get(0)
+        .cloned()
+        .unwrap_or_default();
+    let selected_requirement = if requirement_value == 0 {
+        no_requirement()
+    } else {
+        let required: TransitionRequirement = requirement_value.into();
+        required.to_child().name("transitionRequirement")
+    };
+    let mut requirement_options = vec![no_requirement()];
+    requirement_options.extend(
+        TransitionRequirement::ordered()
+            .into_iter()
+            .map(|r| r.to_child().name("transitionRequirement")),
+    );
+    let requirement = StyledSelect::build(FieldId::ProjectSettings(
+        ProjectFieldId::TransitionRequirement,
+    ))
+    .name("transitionRequirement")
+    .state(&page.transition_requirement_state)
+    .normal()
+    .options(requirement_options)
+    .selected(vec![selected_requirement])
+    .build()
+    .into_node();
+
+    let can_add = match (
+        page.transition_from_state.values.get(0),
+        page.transition_to_state.values.get(0),
+    ) {
+        (Some(from), Some(to)) => from != to,
+        _ => false,
+    };
+    let add = StyledButton::build()
+        .secondary()
+        .text("Add transition")
+        .disabled(!can_add)
+        .on_click(mouse_ev(Ev::Click, |ev| {
+            ev.prevent_default();
+            Msg::PageChanged(PageChanged::ProjectSettings(
+                ProjectPageChange::SubmitTransitionForm,
+            ))
+        }))
+        .build()
+        .into_node();
+
+    let workflow_section = section![
+        class!["workflowSection"],
+        div![class!["transitions"], transitions],
+        div![class!["addTransition"], from, to, requirement, add]
+    ];
+    StyledField::build()
+        .add_class("workflowField")
+        .input(workflow_section)
+        .label("Workflow")
+        .tip("Once any transition is defined issues can be moved only between listed columns")
+        .build()
+        .into_node()
+}
+
+fn transition_status_select(
+    model: &Model,
+    field_id: ProjectFieldId,
+    state: &StyledSelectState,
+) -> Node<Msg> {
+    StyledSelect::build(FieldId::ProjectSettings(field_id))
+        .name("transitionStatus")
+        .state(state)
+        .normal()
+        .options(
+            model
+                .issue_statuses
+                .iter()
+                .map(|status| status.to_child().name("transitionStatus"))
+                .collect(),
+        )
+        .selected(
+            model
+                .issue_statuses
+                .iter()
+                .filter(|status| state.values.contains(&(status.id as u32)))
+                .map(|status| status.to_child().name("transitionStatus"))
+                .collect(),
+        )
         .build()
         .into_node()
 }
@@ -348,6 +716,20 @@ fn show_column_preview(
             format!("Issues in column: {}", issue_count_in_column)
         ]
     };
+    let wip_limit = seed::input![
+        class!["wipLimit"],
+        attrs![
+            At::Type => "number";
+            At::Min => 0;
+            At::Placeholder => "WIP limit";
+            At::Value => is.wip_limit.map(|limit| limit.to_string()).unwrap_or_default()
+        ],
+        input_ev(Ev::Change, move |value| {
+            Msg::PageChanged(PageChanged::ProjectSettings(
+                ProjectPageChange::ColumnWipLimitChanged(id, value),
+            ))
+        }),
+    ];
 
     div![
         class!["columnPreview"],
@@ -358,6 +740,7 @@ fn show_column_preview(
             on_edit,
             delete_row
         ],
+        wip_limit,
         drag_started,
         drag_stopped,
         drag_over_handler,
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use seed::prelude::*;
 
 use jirs_data::WsMsg;
@@ -32,6 +33,43 @@ pub fn update(msg: Msg, model: &mut crate::model::Model, orders: &mut impl Order
         Msg::PageChanged(PageChanged::Reports(ReportsPageChange::DaySelected(v))) => {
             page.selected_day = v;
         }
+        Msg::PageChanged(PageChanged::Reports(ReportsPageChange::FirstDayChanged(day)))
+            if day <= page.last_day =>
+        {
+            page.first_day = day;
+            page.selected_day = None;
+            enqueue_ws_msg(
+                range_requests(page.first_day, page.last_day),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::PageChanged(PageChanged::Reports(ReportsPageChange::LastDayChanged(day)))
+            if day >= page.first_day =>
+        {
+            page.last_day = day;
+            page.selected_day = None;
+            enqueue_ws_msg(
+                range_requests(page.first_day, page.last_day),
+                model.ws.as_ref(),
+                orders,
+            );
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::TimeTrackingReportLoaded(report)))
+            if report.from == page.first_day && report.to == page.last_day =>
+        {
+            page.time_tracking_report = Some(report);
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::BurndownLoaded(report)))
+            if report.from == page.first_day && report.to == page.last_day =>
+        {
+            page.burndown = Some(report);
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::CumulativeFlowLoaded(report)))
+            if report.from == page.first_day && report.to == page.last_day =>
+        {
+            page.cumulative_flow = Some(report);
+        }
         _ => {}
     }
 }
@@ -44,10 +82,25 @@ fn init_load(model: &mut Model, orders: &mut impl Orders<Msg>) {
     if model.user.is_none() {
         return;
     }
+    let (first_day, last_day) = match &model.page_content {
+        PageContent::Reports(page) => (page.first_day, page.last_day),
+        _ => return,
+    };
+
+    let mut requests = vec![
+        WsMsg::ProjectIssuesRequest,
+        WsMsg::IssueStatusesRequest,
+        WsMsg::ProjectUsersRequest,
+    ];
+    requests.extend(range_requests(first_day, last_day));
+    enqueue_ws_msg(requests, model.ws.as_ref(), orders);
+}
 
-    enqueue_ws_msg(
-        vec![WsMsg::ProjectIssuesRequest, WsMsg::IssueStatusesRequest],
-        model.ws.as_ref(),
-        orders,
-    );
+/// Reports computed by server for selected date range
+fn range_requests(first_day: NaiveDate, last_day: NaiveDate) -> Vec<WsMsg> {
+    vec![
+        WsMsg::TimeTrackingReportRequest(first_day, last_day),
+        WsMsg::BurndownRequest(first_day, last_day),
+        WsMsg::CumulativeFlowRequest(first_day, last_day),
+    ]
 }
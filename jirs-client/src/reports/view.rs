@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
-use chrono::Datelike;
 use seed::{prelude::*, *};
 
-use jirs_data::Issue;
+use jirs_data::{
+    BurndownReport, CumulativeFlowReport, FlowTimeStats, Issue, TimeTracking, TimeTrackingReport,
+    TimeTrackingSummary,
+};
 
+use crate::modal::time_tracking::value_for_time_tracking;
 use crate::model::{Model, PageContent, ReportsPage};
 use crate::shared::styled_icon::StyledIcon;
 use crate::shared::{inner_layout, ToNode};
@@ -15,6 +18,7 @@ const SVG_DRAWABLE_HEIGHT: u32 = 300;
 const SVG_HEIGHT: u32 = SVG_DRAWABLE_HEIGHT + 30;
 const SVG_WIDTH: u32 = 1060;
 const SVG_BAR_MARGIN: u32 = 10;
+const SVG_LEGEND_WIDTH: f64 = 40.0;
 
 pub fn view(model: &Model) -> Node<Msg> {
     let page = match &model.page_content {
@@ -23,26 +27,88 @@ pub fn view(model: &Model) -> Node<Msg> {
     };
 
     let this_month_updated = this_month_updated(model, page);
+    let range = date_range(page);
     let graph = this_month_graph(page, &this_month_updated);
     let list = issue_list(page, this_month_updated.as_slice());
+    let burndown = match page.burndown.as_ref() {
+        Some(report) => burndown_charts(report),
+        None => empty![],
+    };
+    let cumulative_flow = match page.cumulative_flow.as_ref() {
+        Some(report) => cumulative_flow_chart(model, report),
+        None => empty![],
+    };
+    let time_tracking = match page.time_tracking_report.as_ref() {
+        Some(report) => time_tracking_report(model, report),
+        None => empty![],
+    };
 
-    let body = section![class!["top"], h1![class!["header"], "Reports"], graph, list];
+    let body = section![
+        class!["top"],
+        h1![class!["header"], "Reports"],
+        range,
+        graph,
+        list,
+        burndown,
+        cumulative_flow,
+        time_tracking
+    ];
 
     inner_layout(model, "reports", vec![body])
 }
 
+fn date_range(page: &ReportsPage) -> Node<Msg> {
+    let first_day = seed::input![
+        class!["dateInput"],
+        attrs![At::Type => "date"; At::Value => page.first_day.format("%Y-%m-%d").to_string()],
+        input_ev(Ev::Input, |value| {
+            parse_date(value.as_str()).map(|day| {
+                Msg::PageChanged(PageChanged::Reports(ReportsPageChange::FirstDayChanged(
+                    day,
+                )))
+            })
+        }),
+    ];
+    let last_day = seed::input![
+        class!["dateInput"],
+        attrs![At::Type => "date"; At::Value => page.last_day.format("%Y-%m-%d").to_string()],
+        input_ev(Ev::Input, |value| {
+            parse_date(value.as_str()).map(|day| {
+                Msg::PageChanged(PageChanged::Reports(ReportsPageChange::LastDayChanged(day)))
+            })
+        }),
+    ];
+    div![
+        class!["dateRange"],
+        label!["From"],
+        first_day,
+        label!["To"],
+        last_day
+    ]
+}
+
+fn parse_date(s: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
 fn this_month_graph(page: &ReportsPage, this_month_updated: &[&Issue]) -> Node<Msg> {
     let mut dominant = 0;
     let mut issues: HashMap<u32, Vec<&Issue>> = HashMap::new();
 
     for issue in this_month_updated {
         let date = issue.updated_at.date();
-        let v = issues.entry(date.day0()).or_default();
+        let v = issues
+            .entry((date - page.first_day).num_days() as u32)
+            .or_default();
         v.push(issue);
         if dominant < v.len() {
             dominant = v.len();
         }
     }
+    let num_days = (page.last_day - page.first_day).num_days() as u32 + 1;
+    // long ranges have narrow bars so margin and labels are reduced
+    let bar_margin = if num_days > 31 { 1 } else { SVG_BAR_MARGIN };
+    let label_step = num_days / 31 + 1;
 
     // take log10 from largest amount of issue to calculate how long text will be
     let legend_margin_width = (dominant as f64).log10() * SVG_MARGIN_X as f64;
@@ -56,8 +122,8 @@ fn this_month_graph(page: &ReportsPage, this_month_updated: &[&Issue]) -> Node<M
     let piece_width = (SVG_WIDTH as f64
         - legend_margin_width
         - SVG_MARGIN_X as f64
-        - (SVG_BAR_MARGIN as f64 * page.last_day.day() as f64))
-        / page.last_day.day() as f64;
+        - (bar_margin as f64 * num_days as f64))
+        / num_days as f64;
 
     let resolution = 10;
     let mut legend_parts: Vec<Node<Msg>> = vec![];
@@ -82,14 +148,14 @@ fn this_month_graph(page: &ReportsPage, this_month_updated: &[&Issue]) -> Node<M
     }
     svg_parts.push(seed::g![legend_parts]);
 
-    for day in (page.first_day.day0())..(page.last_day.day()) {
-        let num_issues = issues.get(&day).map(|v| v.len()).unwrap_or_default() as u32;
-        let x = (piece_width * day as f64)
-            + (SVG_BAR_MARGIN * day) as f64
+    for offset in 0..num_days {
+        let num_issues = issues.get(&offset).map(|v| v.len()).unwrap_or_default() as u32;
+        let x = (piece_width * offset as f64)
+            + (bar_margin * offset) as f64
             + (legend_margin_width + SVG_MARGIN_X as f64);
         let height = num_issues as f64 * piece_height;
 
-        let day = page.first_day.with_day0(day).unwrap();
+        let day = page.first_day + chrono::Duration::days(offset as i64);
 
         let on_hover: EventHandler<Msg> = mouse_ev(Ev::MouseEnter, move |_| {
             Some(Msg::PageChanged(PageChanged::Reports(
@@ -126,14 +192,18 @@ fn this_month_graph(page: &ReportsPage, this_month_updated: &[&Issue]) -> Node<M
                     At::Title => format!("Number of issues: {}", num_issues),
                 ]
             ],
-            seed::text![
-                attrs![
-                    At::X => x,
-                    At::Y => SVG_HEIGHT,
-                    At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
-                ],
-                day.format("%d/%m").to_string(),
-            ]
+            if offset % label_step == 0 {
+                seed::text![
+                    attrs![
+                        At::X => x,
+                        At::Y => SVG_HEIGHT,
+                        At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
+                    ],
+                    day.format("%d/%m").to_string(),
+                ]
+            } else {
+                empty![]
+            }
         ]);
     }
 
@@ -187,7 +257,7 @@ fn issue_list(page: &ReportsPage, this_month_updated: &[&Issue]) -> Node<Msg> {
     }
     div![
         class!["issueList"],
-        h5![class!["issueListHeader"], "Issues this month"],
+        h5![class!["issueListHeader"], "Updated issues"],
         children
     ]
 }
@@ -201,3 +271,368 @@ fn this_month_updated<'a>(model: &'a Model, page: &ReportsPage) -> Vec<&'a Issue
         })
         .collect()
 }
+
+fn time_tracking_report(model: &Model, report: &TimeTrackingReport) -> Node<Msg> {
+    if report.time_tracking == TimeTracking::Untracked {
+        return empty![];
+    }
+    let by_user: Vec<(String, &TimeTrackingSummary)> = report
+        .by_user
+        .iter()
+        .map(|(user_id, summary)| {
+            let name = model
+                .users
+                .iter()
+                .find(|user| user.id == *user_id)
+                .map(|user| user.name.clone())
+                .unwrap_or_default();
+            (name, summary)
+        })
+        .collect();
+    let by_issue_type: Vec<(String, &TimeTrackingSummary)> = report
+        .by_issue_type
+        .iter()
+        .map(|(issue_type, summary)| (issue_type.to_label().to_string(), summary))
+        .collect();
+    let by_status: Vec<(String, &TimeTrackingSummary)> = report
+        .by_status
+        .iter()
+        .map(|(issue_status_id, summary)| {
+            let name = model
+                .issue_statuses
+                .iter()
+                .find(|status| status.id == *issue_status_id)
+                .map(|status| status.name.clone())
+                .unwrap_or_default();
+            (name, summary)
+        })
+        .collect();
+    let header = match report.time_tracking {
+        TimeTracking::Fibonacci => "Story points",
+        _ => "Time tracking",
+    };
+
+    div![
+        class!["timeTracking"],
+        h5![class!["timeTrackingHeader"], header],
+        time_tracking_table(
+            "User",
+            report.time_tracking,
+            &report.total,
+            by_user.as_slice()
+        ),
+        time_tracking_table(
+            "Issue type",
+            report.time_tracking,
+            &report.total,
+            by_issue_type.as_slice()
+        ),
+        time_tracking_table(
+            "Status",
+            report.time_tracking,
+            &report.total,
+            by_status.as_slice()
+        ),
+    ]
+}
+
+fn time_tracking_table(
+    group: &str,
+    time_tracking: TimeTracking,
+    total: &TimeTrackingSummary,
+    rows: &[(String, &TimeTrackingSummary)],
+) -> Node<Msg> {
+    // bars are relative to the largest value in table
+    let max = rows
+        .iter()
+        .map(|(_, s)| s.estimate.max(s.time_spent).max(s.time_remaining))
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let format_value = |v: i32| {
+        let value = value_for_time_tracking(&Some(v), &time_tracking);
+        match time_tracking {
+            TimeTracking::Hourly => format!("{}h", value),
+            _ => format!("{} pt", value),
+        }
+    };
+    let bar = |name: &str, v: i32| {
+        div![
+            class!["bar", name],
+            attrs![At::Style => format!("width: {}%", v * 100 / max)],
+            attrs![At::Title => format_value(v)],
+        ]
+    };
+
+    let header = div![
+        class!["row", "header"],
+        span![class!["name"], group],
+        span![class!["issues"], "Issues"],
+        span![class!["value"], "Estimated"],
+        span![class!["value"], "Spent"],
+        span![class!["value"], "Remaining"],
+        span![class!["bars"]],
+    ];
+    let mut children = vec![header];
+    for (name, summary) in rows {
+        children.push(div![
+            class!["row"],
+            span![class!["name"], name.as_str()],
+            span![class!["issues"], summary.issues.to_string()],
+            span![class!["value"], format_value(summary.estimate)],
+            span![class!["value"], format_value(summary.time_spent)],
+            span![class!["value"], format_value(summary.time_remaining)],
+            div![
+                class!["bars"],
+                bar("estimate", summary.estimate),
+                bar("spent", summary.time_spent),
+                bar("remaining", summary.time_remaining),
+            ],
+        ]);
+    }
+    children.push(div![
+        class!["row", "total"],
+        span![class!["name"], "Total"],
+        span![class!["issues"], total.issues.to_string()],
+        span![class!["value"], format_value(total.estimate)],
+        span![class!["value"], format_value(total.time_spent)],
+        span![class!["value"], format_value(total.time_remaining)],
+        span![class!["bars"]],
+    ]);
+
+    div![class!["timeTrackingTable"], children]
+}
+
+fn burndown_charts(report: &BurndownReport) -> Node<Msg> {
+    if report.time_tracking == TimeTracking::Untracked || report.points.is_empty() {
+        return empty![];
+    }
+    let remaining: Vec<i32> = report
+        .points
+        .iter()
+        .map(|point| point.scope - point.completed)
+        .collect();
+    let time_remaining: Vec<i32> = report
+        .points
+        .iter()
+        .map(|point| point.time_remaining)
+        .collect();
+    // straight line from first day remaining estimate to zero at last day
+    let first_remaining = remaining.first().cloned().unwrap_or_default() as f64;
+    let last = (remaining.len() as f64 - 1.0).max(1.0);
+    let guideline: Vec<i32> = (0..remaining.len())
+        .map(|n| (first_remaining - first_remaining * n as f64 / last) as i32)
+        .collect();
+    let scope: Vec<i32> = report.points.iter().map(|point| point.scope).collect();
+    let completed: Vec<i32> = report.points.iter().map(|point| point.completed).collect();
+
+    div![
+        class!["burndown"],
+        line_chart(
+            "Burndown",
+            report,
+            vec![
+                ("guideline", "Guideline", guideline),
+                ("remaining", "Remaining estimate", remaining),
+                ("timeRemaining", "Time remaining", time_remaining),
+            ],
+        ),
+        line_chart(
+            "Burnup",
+            report,
+            vec![
+                ("scope", "Scope", scope),
+                ("completed", "Completed", completed),
+            ],
+        ),
+    ]
+}
+
+/// Each series is drawn as line, its first value is CSS class
+fn line_chart(
+    title: &str,
+    report: &BurndownReport,
+    series: Vec<(&str, &str, Vec<i32>)>,
+) -> Node<Msg> {
+    let max = series
+        .iter()
+        .flat_map(|(_, _, values)| values.iter())
+        .cloned()
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+    let days = report.points.len();
+    let step = (SVG_WIDTH as f64 - SVG_LEGEND_WIDTH) / (days as f64 - 1.0).max(1.0);
+    let x = |n: usize| SVG_LEGEND_WIDTH + step * n as f64;
+    let y = |v: i32| SVG_DRAWABLE_HEIGHT as f64 - v as f64 * SVG_DRAWABLE_HEIGHT as f64 / max;
+
+    let mut svg_parts: Vec<Node<Msg>> = vec![];
+    let resolution = 5;
+    for n in 0..(resolution + 1) {
+        let value = (max * n as f64 / resolution as f64) as i32;
+        svg_parts.push(seed::text![
+            attrs![
+                At::X => 0,
+                At::Y => y(value) + 12f64,
+                At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
+            ],
+            value_for_time_tracking(&Some(value), &report.time_tracking),
+        ]);
+        svg_parts.push(seed::rect![attrs![
+            At::X => SVG_LEGEND_WIDTH,
+            At::Y => y(value),
+            At::Width => SVG_WIDTH as f64 - SVG_LEGEND_WIDTH,
+            At::Height => 1,
+            At::Style => "fill: var(--textLight);",
+        ]]);
+    }
+    let label_step = days / 15 + 1;
+    for (n, point) in report.points.iter().enumerate() {
+        if n % label_step != 0 {
+            continue;
+        }
+        svg_parts.push(seed::text![
+            attrs![
+                At::X => x(n),
+                At::Y => SVG_HEIGHT,
+                At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
+            ],
+            point.day.format("%d/%m").to_string(),
+        ]);
+    }
+    let mut legend: Vec<Node<Msg>> = vec![];
+    for (name, label, values) in series {
+        let points: Vec<String> = values
+            .iter()
+            .enumerate()
+            .map(|(n, v)| format!("{:.1},{:.1}", x(n), y(*v)))
+            .collect();
+        svg_parts.push(seed::polyline![
+            class!["line", name],
+            attrs![At::Points => points.join(" ")],
+        ]);
+        legend.push(span![class!["legendItem", name], label]);
+    }
+
+    div![
+        class!["graph"],
+        h5![class!["graphHeader"], title],
+        div![class!["legend"], legend],
+        svg![
+            attrs![At::Height => SVG_HEIGHT + 10, At::Width => SVG_WIDTH],
+            svg_parts,
+        ],
+    ]
+}
+
+/// Stacked areas of issue count in each status, last column is at the bottom
+fn cumulative_flow_chart(model: &Model, report: &CumulativeFlowReport) -> Node<Msg> {
+    if report.points.is_empty() {
+        return empty![];
+    }
+    let max = report
+        .points
+        .iter()
+        .map(|point| point.counts.iter().map(|(_, count)| count).sum::<i32>())
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+    let days = report.points.len();
+    let step = (SVG_WIDTH as f64 - SVG_LEGEND_WIDTH) / (days as f64 - 1.0).max(1.0);
+    let x = |n: usize| SVG_LEGEND_WIDTH + step * n as f64;
+    let y = |v: i32| SVG_DRAWABLE_HEIGHT as f64 - v as f64 * SVG_DRAWABLE_HEIGHT as f64 / max;
+
+    let mut svg_parts: Vec<Node<Msg>> = vec![];
+    let resolution = 5;
+    for n in 0..(resolution + 1) {
+        let value = (max * n as f64 / resolution as f64) as i32;
+        svg_parts.push(seed::text![
+            attrs![
+                At::X => 0,
+                At::Y => y(value) + 12f64,
+                At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
+            ],
+            value.to_string(),
+        ]);
+    }
+    let label_step = days / 15 + 1;
+    for (n, point) in report.points.iter().enumerate() {
+        if n % label_step != 0 {
+            continue;
+        }
+        svg_parts.push(seed::text![
+            attrs![
+                At::X => x(n),
+                At::Y => SVG_HEIGHT,
+                At::Style => "fill: var(--textLight); font-family: var(--font-regular); font-size: 10px;",
+            ],
+            point.day.format("%d/%m").to_string(),
+        ]);
+    }
+
+    let statuses = report.points[0].counts.len();
+    let mut below = vec![0; days];
+    let mut legend: Vec<Node<Msg>> = vec![];
+    for (band, index) in (0..statuses).rev().enumerate() {
+        let above: Vec<i32> = report
+            .points
+            .iter()
+            .zip(below.iter())
+            .map(|(point, below)| below + point.counts.get(index).map(|c| c.1).unwrap_or_default())
+            .collect();
+        // upper edge from left to right and lower edge back
+        let points: Vec<String> = above
+            .iter()
+            .enumerate()
+            .chain(below.iter().enumerate().rev())
+            .map(|(n, v)| format!("{:.1},{:.1}", x(n), y(*v)))
+            .collect();
+        let class_name = format!("band{}", band % 6);
+        svg_parts.push(seed::polygon![
+            class!["area", class_name.as_str()],
+            attrs![At::Points => points.join(" ")],
+        ]);
+        let status_id = report.points[0].counts[index].0;
+        let name = model
+            .issue_statuses
+            .iter()
+            .find(|status| status.id == status_id)
+            .map(|status| status.name.as_str())
+            .unwrap_or_default();
+        legend.insert(0, span![class!["legendItem", class_name.as_str()], name]);
+        below = above;
+    }
+
+    div![
+        class!["cumulativeFlow"],
+        div![
+            class!["graph"],
+            h5![class!["graphHeader"], "Cumulative flow"],
+            div![class!["legend"], legend],
+            svg![
+                attrs![At::Height => SVG_HEIGHT + 10, At::Width => SVG_WIDTH],
+                svg_parts,
+            ],
+        ],
+        div![
+            class!["flowTimes"],
+            flow_time_stats("Cycle time", &report.cycle_time),
+            flow_time_stats("Lead time", &report.lead_time),
+        ],
+    ]
+}
+
+fn flow_time_stats(title: &str, stats: &FlowTimeStats) -> Node<Msg> {
+    let days = |value: f64| format!("{:.1} days", value);
+    div![
+        class!["flowTime"],
+        h5![class!["graphHeader"], title],
+        div![
+            span![class!["name"], "Finished issues"],
+            stats.issues.to_string()
+        ],
+        div![span![class!["name"], "Average"], days(stats.average)],
+        div![span![class!["name"], "Median"], days(stats.median)],
+        div![span![class!["name"], "Longest"], days(stats.max)],
+    ]
+}
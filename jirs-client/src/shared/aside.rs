@@ -78,6 +78,7 @@ pub fn render(model: &Model) -> Node<Msg> {
         ul![
             project_info,
             sidebar_link_item(model, "Kanban Board", Icon::Board, Some(Page::Project)),
+            sidebar_link_item(model, "Backlog", Icon::Issues, Some(Page::Backlog)),
             links,
         ]
     ]
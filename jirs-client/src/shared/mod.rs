@@ -6,6 +6,7 @@ use jirs_data::*;
 
 use crate::model::Model;
 use crate::model::Page;
+use crate::ws::send_ws_msg;
 use crate::Msg;
 
 pub mod aside;
@@ -28,6 +29,7 @@ pub mod styled_select;
 pub mod styled_select_child;
 pub mod styled_textarea;
 pub mod styled_tooltip;
+pub mod toast;
 pub mod tracking_widget;
 
 pub trait ToChild {
@@ -50,6 +52,35 @@ pub fn go_to(url: &str) {
     seed::push_route(Url::from_str(url).unwrap());
 }
 
+pub fn go_to_issue(issue_id: IssueId, orders: &mut impl Orders<Msg>) {
+    let page = Page::EditIssue(issue_id);
+    go_to(page.to_path().as_str());
+    orders.skip().send_msg(Msg::ChangePage(page));
+}
+
+/// Open issue requested by `/browse/...` url. Keys of current project are
+/// resolved locally, other ones are resolved by server.
+pub fn browse_issue(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if model.project.is_none() {
+        return;
+    }
+    let key = match model.browse_key.take() {
+        Some(key) => key,
+        _ => return,
+    };
+    let issue_id = model.project.as_ref().and_then(|project| {
+        model
+            .issues
+            .iter()
+            .find(|issue| project.issue_key(issue).eq_ignore_ascii_case(key.as_str()))
+            .map(|issue| issue.id)
+    });
+    match issue_id {
+        Some(issue_id) => go_to_issue(issue_id, orders),
+        None => send_ws_msg(WsMsg::IssueByKeyRequest(key), model.ws.as_ref(), orders),
+    }
+}
+
 pub fn find_issue(model: &Model, issue_id: IssueId) -> Option<&Issue> {
     model.issues.iter().find(|issue| issue.id == issue_id)
 }
@@ -71,6 +102,7 @@ pub fn inner_layout(model: &Model, page_name: &str, children: Vec<Node<Msg>>) ->
         navbar_left::render(model),
         aside::render(model),
         children,
+        toast::render(model),
     ]
 }
 
@@ -80,7 +112,8 @@ pub fn outer_layout(model: &Model, page_name: &str, children: Vec<Node<Msg>>) ->
         class!["outer-layout", "outerPage"],
         id![page_name],
         modal,
-        children
+        children,
+        toast::render(model),
     ]
 }
 
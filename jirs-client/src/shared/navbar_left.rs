@@ -2,7 +2,7 @@ use seed::{prelude::*, *};
 
 use jirs_data::{InvitationToken, Message, MessageType, WsMsg};
 
-use crate::model::Model;
+use crate::model::{ModalType, Model, SearchIssuesModal};
 use crate::shared::styled_avatar::StyledAvatar;
 use crate::shared::styled_button::StyledButton;
 use crate::shared::styled_icon::{Icon, StyledIcon};
@@ -92,7 +92,17 @@ pub fn render(model: &Model) -> Vec<Node<Msg>> {
                 attrs![At::Href => "/"],
                 div![class!["styledLogo"], logo_svg]
             ],
-            navbar_left_item("Search issues", Icon::Search, None, None),
+            navbar_left_item(
+                "Search issues",
+                Icon::Search,
+                None,
+                Some(mouse_ev(Ev::Click, |ev| {
+                    ev.prevent_default();
+                    Msg::ModalOpened(Box::new(ModalType::SearchIssues(Box::new(
+                        SearchIssuesModal::default(),
+                    ))))
+                })),
+            ),
             navbar_left_item("Create Issue", Icon::Plus, Some("/add-issue"), None),
             div![
                 class!["bottom"],
@@ -251,6 +261,12 @@ fn message_ui(model: &Model, message: &Message) -> Option<Node<Msg>> {
             div![class!["description"], message_description],
             hyperlink,
         ],
+        MessageType::IssueChanged | MessageType::IssueCommented => div![
+            class!["message watchedIssue"],
+            top,
+            div![class!["description"], message_description],
+            hyperlink,
+        ],
     };
     Some(node)
 }
@@ -1,6 +1,6 @@
 use seed::{prelude::*, *};
 
-use jirs_data::TimeTracking;
+use jirs_data::{TimeTracking, WipLimitPolicy};
 
 use crate::shared::{ToChild, ToNode};
 use crate::{FieldId, Msg};
@@ -229,3 +229,24 @@ impl ToChild for TimeTracking {
             })
     }
 }
+
+impl ToChild for WipLimitPolicy {
+    type Builder = ChildBuilder;
+
+    fn to_child(&self) -> Self::Builder {
+        Self::Builder::default()
+            .label(match self {
+                WipLimitPolicy::Warn => "Warn about full columns",
+                WipLimitPolicy::Block => "Block moves into full columns",
+            })
+            .name(match self {
+                WipLimitPolicy::Warn => "warn",
+                WipLimitPolicy::Block => "block",
+            })
+            .value((*self).into())
+            .add_class(match self {
+                WipLimitPolicy::Warn => "warn",
+                WipLimitPolicy::Block => "block",
+            })
+    }
+}
@@ -12,6 +12,8 @@ pub enum Icon {
     Stopwatch,
     Task,
     Story,
+    Epic,
+    Subtask,
     ArrowDown,
     ArrowLeftCircle,
     ArrowUp,
@@ -96,7 +98,9 @@ pub enum Icon {
 impl Icon {
     pub fn to_color(self) -> Option<String> {
         match self {
-            Icon::Bug | Icon::Task | Icon::Story => Some(format!("var(--{})", self)),
+            Icon::Bug | Icon::Task | Icon::Story | Icon::Epic | Icon::Subtask => {
+                Some(format!("var(--{})", self))
+            }
             _ => None,
         }
     }
@@ -113,6 +117,8 @@ impl std::fmt::Display for Icon {
             Icon::Stopwatch => "stopwatch",
             Icon::Task => "task",
             Icon::Story => "story",
+            Icon::Epic => "epic",
+            Icon::Subtask => "subtask",
             Icon::ArrowDown => "arrowDown",
             Icon::ArrowLeftCircle => "arrowLeftCircle",
             Icon::ArrowUp => "arrowUp",
@@ -203,6 +209,8 @@ impl From<IssueType> for Icon {
             IssueType::Task => Icon::Task,
             IssueType::Bug => Icon::Bug,
             IssueType::Story => Icon::Story,
+            IssueType::Epic => Icon::Epic,
+            IssueType::Subtask => Icon::Subtask,
         }
     }
 }
@@ -242,6 +242,48 @@ impl ToChild for jirs_data::IssueType {
     }
 }
 
+impl ToChild for jirs_data::Issue {
+    type Builder = StyledSelectChildBuilder;
+
+    fn to_child(&self) -> StyledSelectChildBuilder {
+        let type_icon = crate::shared::styled_icon::StyledIcon::build(self.issue_type.into())
+            .add_class(self.issue_type.to_label())
+            .build()
+            .into_node();
+
+        StyledSelectChild::build()
+            .text(self.title.as_str())
+            .icon(type_icon)
+            .value(self.id as u32)
+    }
+}
+
+impl ToChild for jirs_data::Label {
+    type Builder = StyledSelectChildBuilder;
+
+    fn to_child(&self) -> StyledSelectChildBuilder {
+        let color = span![
+            class!["labelColor"],
+            attrs![At::Style => format!("background: {}", self.color)]
+        ];
+
+        StyledSelectChild::build()
+            .text(self.name.as_str())
+            .icon(color)
+            .value(self.id as u32)
+    }
+}
+
+impl ToChild for jirs_data::IssueLinkType {
+    type Builder = StyledSelectChildBuilder;
+
+    fn to_child(&self) -> StyledSelectChildBuilder {
+        StyledSelectChild::build()
+            .text(self.to_label())
+            .value((*self).into())
+    }
+}
+
 impl ToChild for jirs_data::ProjectCategory {
     type Builder = StyledSelectChildBuilder;
 
@@ -269,6 +311,26 @@ impl ToChild for jirs_data::UserRole {
     }
 }
 
+impl ToChild for jirs_data::CustomFieldType {
+    type Builder = StyledSelectChildBuilder;
+
+    fn to_child(&self) -> StyledSelectChildBuilder {
+        StyledSelectChild::build()
+            .text(self.to_label())
+            .value((*self).into())
+    }
+}
+
+impl ToChild for jirs_data::TransitionRequirement {
+    type Builder = StyledSelectChildBuilder;
+
+    fn to_child(&self) -> StyledSelectChildBuilder {
+        StyledSelectChild::build()
+            .text(self.to_label())
+            .value((*self).into())
+    }
+}
+
 impl ToChild for jirs_data::Project {
     type Builder = StyledSelectChildBuilder;
 
@@ -0,0 +1,90 @@
+use seed::{prelude::*, *};
+
+use jirs_data::WsMsg;
+
+use crate::model::{Model, Toast, ToastContent};
+use crate::shared::styled_icon::{Icon, StyledIcon};
+use crate::shared::ToNode;
+use crate::{Msg, WebSocketChanged};
+
+const TOAST_TIMEOUT: u32 = 5000;
+
+pub fn update(msg: &Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    match msg {
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::Error(error))) => {
+            push(model, orders, ToastContent::Error(error.clone()));
+        }
+        Msg::WebSocketChange(WebSocketChanged::WsMsg(WsMsg::IssueStatusWipLimitExceeded(
+            issue_status_id,
+        ))) => {
+            let name = model
+                .issue_statuses
+                .iter()
+                .find(|status| status.id == *issue_status_id)
+                .map(|status| status.name.clone())
+                .unwrap_or_default();
+            push(
+                model,
+                orders,
+                ToastContent::Warning(format!("Column {} is over its WIP limit", name)),
+            );
+        }
+        Msg::ToastDismissed(id) => {
+            model.toasts.retain(|toast| toast.id != *id);
+        }
+        _ => (),
+    }
+}
+
+fn push(model: &mut Model, orders: &mut impl Orders<Msg>, content: ToastContent) {
+    let id = model.toasts.iter().map(|t| t.id).max().unwrap_or_default() + 1;
+    model.toasts.push(Toast { id, content });
+    orders.perform_cmd(cmds::timeout(TOAST_TIMEOUT, move || {
+        Msg::ToastDismissed(id)
+    }));
+}
+
+pub fn render(model: &Model) -> Node<Msg> {
+    if model.toasts.is_empty() {
+        return empty![];
+    }
+    let toasts: Vec<Node<Msg>> = model.toasts.iter().map(toast).collect();
+    section![id!["toasts"], toasts]
+}
+
+fn toast(toast: &Toast) -> Node<Msg> {
+    let id = toast.id;
+    let close_handler = mouse_ev(Ev::Click, move |_| Msg::ToastDismissed(id));
+    let close_icon = StyledIcon::build(Icon::Close).build().into_node();
+
+    match &toast.content {
+        ToastContent::Error(error) => {
+            let fields: Vec<Node<Msg>> = error
+                .fields
+                .iter()
+                .map(|field| {
+                    li![
+                        class!["field"],
+                        format!("{}: {}", field.field, field.message)
+                    ]
+                })
+                .collect();
+
+            div![
+                class!["toast", "error"],
+                attrs![At::Role => "alert"],
+                div![class!["title"], error.kind.to_string()],
+                div![class!["message"], error.message.as_str()],
+                ul![class!["fields"], fields],
+                div![class!["close"], close_handler, close_icon],
+            ]
+        }
+        ToastContent::Warning(message) => div![
+            class!["toast", "warning"],
+            attrs![At::Role => "alert"],
+            div![class!["title"], "Warning"],
+            div![class!["message"], message.as_str()],
+            div![class!["close"], close_handler, close_icon],
+        ],
+    }
+}
@@ -8,11 +8,42 @@ use crate::ws::send_ws_msg;
 use crate::Msg;
 
 pub fn drag_started(issue_id: IssueId, model: &mut Model) {
+    let policy = model.project.as_ref().map(|p| p.wip_limit_policy);
+    let column_count = |status_id: IssueStatusId| {
+        model
+            .issues
+            .iter()
+            .filter(|issue| issue.issue_status_id == status_id)
+            .count()
+    };
+    let allowed: Vec<IssueStatusId> = match model.issues.iter().find(|i| i.id == issue_id) {
+        Some(issue) => model
+            .issue_statuses
+            .iter()
+            .filter(|status| {
+                check_status_transition(&model.issue_status_transitions, issue, status.id).is_ok()
+            })
+            .filter(|status| {
+                let applied = policy.and_then(|policy| {
+                    check_wip_limit(
+                        policy,
+                        issue.issue_status_id,
+                        status,
+                        column_count(status.id),
+                    )
+                });
+                applied != Some(WipLimitPolicy::Block)
+            })
+            .map(|status| status.id)
+            .collect(),
+        _ => vec![],
+    };
     let project_page = match &mut model.page_content {
         PageContent::Project(project_page) => project_page,
         _ => return,
     };
     project_page.issue_drag.drag(issue_id);
+    project_page.allowed_drop_statuses = allowed;
 }
 
 pub fn exchange_position(issue_bellow_id: IssueId, model: &mut Model) {
@@ -23,6 +54,15 @@ pub fn exchange_position(issue_bellow_id: IssueId, model: &mut Model) {
     if project_page.issue_drag.dragged_or_last(issue_bellow_id) {
         return;
     }
+    let below_status_id = model
+        .issues
+        .iter()
+        .find(|issue| issue.id == issue_bellow_id)
+        .map(|issue| issue.issue_status_id);
+    match below_status_id {
+        Some(id) if !project_page.allowed_drop_statuses.contains(&id) => return,
+        _ => (),
+    };
     let dragged_id = match project_page.issue_drag.dragged_id.as_ref().cloned() {
         Some(id) => id,
         _ => return error!("Nothing is dragged"),
@@ -112,6 +152,7 @@ pub fn sync(model: &mut Model, orders: &mut impl Orders<Msg>) {
         );
     }
     project_page.issue_drag.clear();
+    project_page.allowed_drop_statuses.clear();
 }
 
 pub fn change_status(status_id: IssueStatusId, model: &mut Model) {
@@ -124,6 +165,9 @@ pub fn change_status(status_id: IssueStatusId, model: &mut Model) {
         Some(issue_id) => issue_id,
         _ => return error!("Nothing is dragged"),
     };
+    if !project_page.allowed_drop_statuses.contains(&status_id) {
+        return;
+    }
 
     let mut old: Vec<Issue> = vec![];
     let mut pos = 0;
@@ -1,13 +1,21 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
 use seed::prelude::*;
 
-use jirs_data::WsMsg;
+use jirs_data::{RequestId, WsMsg};
 
 use crate::model::*;
-use crate::shared::{go_to_board, write_auth_token};
+use crate::shared::{browse_issue, go_to_board, go_to_issue, write_auth_token};
 use crate::{Msg, WebSocketChanged};
 
 pub mod issue;
 
+static REQUEST_ID: AtomicU32 = AtomicU32::new(1);
+
+fn next_request_id() -> RequestId {
+    REQUEST_ID.fetch_add(1, Ordering::SeqCst)
+}
+
 pub fn flush_queue(model: &mut Model, orders: &mut impl Orders<Msg>) {
     use seed::browser::web_socket::State;
     match model.ws.as_ref() {
@@ -39,6 +47,10 @@ pub fn send_ws_msg(msg: WsMsg, ws: Option<&WebSocket>, orders: &mut impl Orders<
             return;
         }
     };
+    let msg = match msg {
+        WsMsg::Ping | WsMsg::Pong => msg,
+        _ => WsMsg::Request(next_request_id(), Box::new(msg)),
+    };
     let binary = bincode::serialize(&msg).unwrap();
     ws.send_bytes(binary.as_slice())
         .expect("Failed to send ws msg");
@@ -121,6 +133,29 @@ pub fn update(msg: &WsMsg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             let mut v = v.clone();
             v.sort_by(|a, b| (a.list_position as i64).cmp(&(b.list_position as i64)));
             model.issues = v;
+            browse_issue(model, orders);
+        }
+        WsMsg::IssueByKeyLoaded(project_id, issue_id) => {
+            // issue of other project is opened after switching to its project
+            if model.project.as_ref().map(|p| p.id) != Some(*project_id) {
+                if let Some(up) = model
+                    .user_projects
+                    .iter()
+                    .find(|up| up.project_id == *project_id)
+                {
+                    send_ws_msg(
+                        WsMsg::UserProjectSetCurrent(up.id),
+                        model.ws.as_ref(),
+                        orders,
+                    );
+                }
+            }
+            go_to_issue(*issue_id, orders);
+        }
+        WsMsg::IssueCreated(issue) => {
+            if !model.issues.iter().any(|i| i.id == issue.id) {
+                model.issues.push(issue.clone());
+            }
         }
         // issue statuses
         WsMsg::IssueStatusesResponse(v) => {
@@ -150,6 +185,9 @@ pub fn update(msg: &WsMsg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .sort_by(|a, b| a.position.cmp(&b.position));
         }
         WsMsg::IssueStatusDeleted(dropped_id) => {
+            model.issue_status_transitions.retain(|transition| {
+                transition.from_status_id != *dropped_id && transition.to_status_id != *dropped_id
+            });
             let mut old = vec![];
             std::mem::swap(&mut model.issue_statuses, &mut old);
             for is in old {
@@ -174,6 +212,108 @@ pub fn update(msg: &WsMsg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .issue_statuses
                 .sort_by(|a, b| a.position.cmp(&b.position));
         }
+        // labels
+        WsMsg::LabelsLoaded(v) => {
+            model.labels = v.clone();
+        }
+        WsMsg::LabelCreated(label) => {
+            if !model.labels.iter().any(|l| l.id == label.id) {
+                model.labels.push(label.clone());
+                model.labels.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+        WsMsg::LabelDeleted(label_id) => {
+            model.labels.retain(|label| label.id != *label_id);
+            for issue in model.issues.iter_mut() {
+                issue.label_ids.retain(|id| id != label_id);
+            }
+        }
+        // custom fields
+        WsMsg::CustomFieldsLoaded(v) => {
+            model.custom_fields = v.clone();
+        }
+        WsMsg::CustomFieldCreated(custom_field) => {
+            if !model.custom_fields.iter().any(|f| f.id == custom_field.id) {
+                model.custom_fields.push(custom_field.clone());
+            }
+        }
+        WsMsg::CustomFieldDeleted(custom_field_id) => {
+            model
+                .custom_fields
+                .retain(|field| field.id != *custom_field_id);
+            for issue in model.issues.iter_mut() {
+                issue
+                    .custom_values
+                    .retain(|value| value.custom_field_id != *custom_field_id);
+            }
+        }
+        // saved filters
+        WsMsg::SavedFiltersLoaded(v) => {
+            model.saved_filters = v.clone();
+        }
+        WsMsg::SavedFilterCreated(saved_filter) => {
+            let user_id = model.user.as_ref().map(|u| u.id).unwrap_or_default();
+            if saved_filter.is_visible_to(user_id)
+                && !model.saved_filters.iter().any(|f| f.id == saved_filter.id)
+            {
+                model.saved_filters.push(saved_filter.clone());
+                model.saved_filters.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+        WsMsg::SavedFilterDeleted(saved_filter_id) => {
+            model
+                .saved_filters
+                .retain(|filter| filter.id != *saved_filter_id);
+        }
+        // workflow
+        WsMsg::IssueStatusTransitionsLoaded(v) => {
+            model.issue_status_transitions = v.clone();
+        }
+        WsMsg::IssueStatusTransitionCreated(transition) => {
+            if !model
+                .issue_status_transitions
+                .iter()
+                .any(|t| t.id == transition.id)
+            {
+                model.issue_status_transitions.push(transition.clone());
+            }
+        }
+        WsMsg::IssueStatusTransitionDeleted(transition_id) => {
+            model
+                .issue_status_transitions
+                .retain(|transition| transition.id != *transition_id);
+        }
+        // sprints
+        WsMsg::SprintsLoaded(v) => {
+            model.sprints = v.clone();
+        }
+        WsMsg::SprintCreated(sprint) => {
+            if !model.sprints.iter().any(|s| s.id == sprint.id) {
+                model.sprints.push(sprint.clone());
+            }
+        }
+        WsMsg::SprintUpdated(sprint) => {
+            for s in model.sprints.iter_mut() {
+                if s.id == sprint.id {
+                    *s = sprint.clone();
+                }
+            }
+        }
+        WsMsg::SprintDeleted(sprint_id) => {
+            model.sprints.retain(|sprint| sprint.id != *sprint_id);
+            for issue in model.issues.iter_mut() {
+                if issue.sprint_id == Some(*sprint_id) {
+                    issue.sprint_id = None;
+                }
+            }
+        }
+        WsMsg::IssuesSprintChanged(issue_ids, sprint_id) => {
+            for issue in model.issues.iter_mut() {
+                if issue_ids.contains(&issue.id) {
+                    issue.sprint_id = *sprint_id;
+                }
+            }
+        }
         // users
         WsMsg::ProjectUsersLoaded(v) => {
             model.users = v.clone();
@@ -0,0 +1,752 @@
+//! Parser of JQL-like issue queries, e.g.
+//! `assignee = me AND priority >= High AND status != Done ORDER BY updated DESC`.
+//!
+//! Parsing is shared by client and server. Client can validate query while
+//! typing and server compiles parsed query into database query.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{IssuePriority, IssueType, ToVec};
+
+/// Range of query characters (not bytes), `end` is exclusive
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JqlSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JqlError {
+    pub message: String,
+    pub span: JqlSpan,
+}
+
+impl JqlError {
+    pub fn new<S: Into<String>>(message: S, span: JqlSpan) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for JqlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JqlField {
+    Assignee,
+    Reporter,
+    Priority,
+    Status,
+    Type,
+    Summary,
+    Description,
+    Created,
+    Updated,
+}
+
+impl JqlField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "assignee" => Some(JqlField::Assignee),
+            "reporter" => Some(JqlField::Reporter),
+            "priority" => Some(JqlField::Priority),
+            "status" => Some(JqlField::Status),
+            "type" | "issuetype" => Some(JqlField::Type),
+            "summary" | "title" => Some(JqlField::Summary),
+            "description" => Some(JqlField::Description),
+            "created" => Some(JqlField::Created),
+            "updated" => Some(JqlField::Updated),
+            _ => None,
+        }
+    }
+
+    fn accepts(self, operator: JqlOperator) -> bool {
+        use JqlOperator::*;
+        match self {
+            JqlField::Assignee => {
+                matches!(operator, Eq | NotEq | In | NotIn | IsEmpty | IsNotEmpty)
+            }
+            JqlField::Description => {
+                matches!(operator, Contains | NotContains | IsEmpty | IsNotEmpty)
+            }
+            JqlField::Reporter | JqlField::Status | JqlField::Type => {
+                matches!(operator, Eq | NotEq | In | NotIn)
+            }
+            JqlField::Priority => {
+                !matches!(operator, Contains | NotContains | IsEmpty | IsNotEmpty)
+            }
+            JqlField::Summary => matches!(operator, Contains | NotContains),
+            JqlField::Created | JqlField::Updated => {
+                matches!(operator, Eq | Greater | GreaterEq | Less | LessEq)
+            }
+        }
+    }
+
+    fn is_orderable(self) -> bool {
+        matches!(
+            self,
+            JqlField::Priority
+                | JqlField::Type
+                | JqlField::Summary
+                | JqlField::Created
+                | JqlField::Updated
+        )
+    }
+}
+
+impl std::fmt::Display for JqlField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JqlField::Assignee => f.write_str("assignee"),
+            JqlField::Reporter => f.write_str("reporter"),
+            JqlField::Priority => f.write_str("priority"),
+            JqlField::Status => f.write_str("status"),
+            JqlField::Type => f.write_str("type"),
+            JqlField::Summary => f.write_str("summary"),
+            JqlField::Description => f.write_str("description"),
+            JqlField::Created => f.write_str("created"),
+            JqlField::Updated => f.write_str("updated"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JqlOperator {
+    Eq,
+    NotEq,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Contains,
+    NotContains,
+    In,
+    NotIn,
+    IsEmpty,
+    IsNotEmpty,
+}
+
+impl std::fmt::Display for JqlOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JqlOperator::Eq => f.write_str("="),
+            JqlOperator::NotEq => f.write_str("!="),
+            JqlOperator::Greater => f.write_str(">"),
+            JqlOperator::GreaterEq => f.write_str(">="),
+            JqlOperator::Less => f.write_str("<"),
+            JqlOperator::LessEq => f.write_str("<="),
+            JqlOperator::Contains => f.write_str("~"),
+            JqlOperator::NotContains => f.write_str("!~"),
+            JqlOperator::In => f.write_str("IN"),
+            JqlOperator::NotIn => f.write_str("NOT IN"),
+            JqlOperator::IsEmpty => f.write_str("IS EMPTY"),
+            JqlOperator::IsNotEmpty => f.write_str("IS NOT EMPTY"),
+        }
+    }
+}
+
+/// Relative dates further back are rejected so subtracting them from now
+/// can't overflow
+pub const MAX_DAYS_AGO: i64 = 100 * 366;
+
+/// Date used by `created` and `updated`, relative dates like `-7d` are
+/// counted back from now.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JqlDate {
+    Day(NaiveDate),
+    DaysAgo(i64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JqlValue {
+    CurrentUser,
+    Text(String),
+    Priority(IssuePriority),
+    Type(IssueType),
+    Date(JqlDate),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JqlCondition {
+    pub field: JqlField,
+    pub operator: JqlOperator,
+    pub values: Vec<JqlValue>,
+    pub span: JqlSpan,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JqlExpr {
+    And(Box<JqlExpr>, Box<JqlExpr>),
+    Or(Box<JqlExpr>, Box<JqlExpr>),
+    Not(Box<JqlExpr>),
+    Condition(JqlCondition),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JqlDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JqlOrder {
+    pub field: JqlField,
+    pub direction: JqlDirection,
+}
+
+/// Parsed query, without filter it matches all issues
+#[derive(Clone, Debug, PartialEq)]
+pub struct JqlQuery {
+    pub filter: Option<JqlExpr>,
+    pub order_by: Vec<JqlOrder>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Operator(JqlOperator),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    span: JqlSpan,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match &self.kind {
+            TokenKind::Word(word) => word.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '@'
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, JqlError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < chars.len() {
+        let start = pos;
+        let c = chars[pos];
+        let next = chars.get(pos + 1).cloned();
+        let kind = match c {
+            _ if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            '(' => TokenKind::LeftParen,
+            ')' => TokenKind::RightParen,
+            ',' => TokenKind::Comma,
+            '=' => TokenKind::Operator(JqlOperator::Eq),
+            '~' => TokenKind::Operator(JqlOperator::Contains),
+            '!' if next == Some('=') => TokenKind::Operator(JqlOperator::NotEq),
+            '!' if next == Some('~') => TokenKind::Operator(JqlOperator::NotContains),
+            '>' if next == Some('=') => TokenKind::Operator(JqlOperator::GreaterEq),
+            '<' if next == Some('=') => TokenKind::Operator(JqlOperator::LessEq),
+            '>' => TokenKind::Operator(JqlOperator::Greater),
+            '<' => TokenKind::Operator(JqlOperator::Less),
+            '"' | '\'' => {
+                let mut value = String::new();
+                pos += 1;
+                loop {
+                    match chars.get(pos) {
+                        None => {
+                            return Err(JqlError::new(
+                                "Unterminated string",
+                                JqlSpan { start, end: pos },
+                            ))
+                        }
+                        Some('\\') if pos + 1 < chars.len() => {
+                            value.push(chars[pos + 1]);
+                            pos += 2;
+                        }
+                        Some(q) if *q == c => break,
+                        Some(other) => {
+                            value.push(*other);
+                            pos += 1;
+                        }
+                    }
+                }
+                TokenKind::Quoted(value)
+            }
+            _ if is_word_char(c) => {
+                while pos + 1 < chars.len() && is_word_char(chars[pos + 1]) {
+                    pos += 1;
+                }
+                TokenKind::Word(chars[start..=pos].iter().collect())
+            }
+            _ => {
+                return Err(JqlError::new(
+                    format!("Unexpected character {}", c),
+                    JqlSpan {
+                        start,
+                        end: start + 1,
+                    },
+                ))
+            }
+        };
+        pos += match kind {
+            TokenKind::Operator(JqlOperator::NotEq)
+            | TokenKind::Operator(JqlOperator::NotContains)
+            | TokenKind::Operator(JqlOperator::GreaterEq)
+            | TokenKind::Operator(JqlOperator::LessEq) => 2,
+            _ => 1,
+        };
+        tokens.push(Token {
+            kind,
+            span: JqlSpan { start, end: pos },
+        });
+    }
+    Ok(tokens)
+}
+
+const KEYWORDS: [&str; 10] = [
+    "AND", "OR", "NOT", "IN", "IS", "EMPTY", "ORDER", "BY", "ASC", "DESC",
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(t) if t.is_keyword(keyword))
+    }
+
+    /// Span of current token or empty span at the end of query
+    fn current_span(&self) -> JqlSpan {
+        self.peek().map(|t| t.span).unwrap_or(JqlSpan {
+            start: self.len,
+            end: self.len,
+        })
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<JqlSpan, JqlError> {
+        if self.peek_keyword(keyword) {
+            Ok(self
+                .next()
+                .map(|t| t.span)
+                .unwrap_or_else(|| self.current_span()))
+        } else {
+            Err(JqlError::new(
+                format!("Expected {}", keyword),
+                self.current_span(),
+            ))
+        }
+    }
+
+    fn query(&mut self) -> Result<JqlQuery, JqlError> {
+        let filter = if self.peek().is_none() || self.peek_keyword("ORDER") {
+            None
+        } else {
+            Some(self.or_expr()?)
+        };
+        let mut order_by = vec![];
+        if self.peek_keyword("ORDER") {
+            self.next();
+            self.expect_keyword("BY")?;
+            loop {
+                order_by.push(self.order()?);
+                match self.peek().map(|t| &t.kind) {
+                    Some(TokenKind::Comma) => {
+                        self.next();
+                    }
+                    _ => break,
+                }
+            }
+        }
+        match self.peek() {
+            None => Ok(JqlQuery { filter, order_by }),
+            Some(token) => Err(JqlError::new("Expected AND, OR or ORDER BY", token.span)),
+        }
+    }
+
+    fn or_expr(&mut self) -> Result<JqlExpr, JqlError> {
+        let mut expr = self.and_expr()?;
+        while self.peek_keyword("OR") {
+            self.next();
+            expr = JqlExpr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<JqlExpr, JqlError> {
+        let mut expr = self.unary()?;
+        while self.peek_keyword("AND") {
+            self.next();
+            expr = JqlExpr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<JqlExpr, JqlError> {
+        if self.peek_keyword("NOT") {
+            self.next();
+            return Ok(JqlExpr::Not(Box::new(self.unary()?)));
+        }
+        if let Some(TokenKind::LeftParen) = self.peek().map(|t| &t.kind) {
+            let open = self.current_span();
+            self.next();
+            let expr = self.or_expr()?;
+            return match self.next() {
+                Some(Token {
+                    kind: TokenKind::RightParen,
+                    ..
+                }) => Ok(expr),
+                _ => Err(JqlError::new("Unclosed parenthesis", open)),
+            };
+        }
+        self.condition()
+    }
+
+    fn field(&mut self) -> Result<(JqlField, JqlSpan), JqlError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(name),
+                span,
+            }) => match JqlField::from_name(name.as_str()) {
+                Some(field) => Ok((field, span)),
+                None => Err(JqlError::new(format!("Unknown field {}", name), span)),
+            },
+            Some(token) => Err(JqlError::new("Expected field name", token.span)),
+            None => Err(JqlError::new("Expected field name", self.current_span())),
+        }
+    }
+
+    fn operator(&mut self) -> Result<JqlOperator, JqlError> {
+        let span = self.current_span();
+        match self.next().map(|t| t.kind) {
+            Some(TokenKind::Operator(operator)) => Ok(operator),
+            Some(TokenKind::Word(ref w)) if w.eq_ignore_ascii_case("IN") => Ok(JqlOperator::In),
+            Some(TokenKind::Word(ref w)) if w.eq_ignore_ascii_case("NOT") => {
+                self.expect_keyword("IN")?;
+                Ok(JqlOperator::NotIn)
+            }
+            Some(TokenKind::Word(ref w)) if w.eq_ignore_ascii_case("IS") => {
+                let operator = if self.peek_keyword("NOT") {
+                    self.next();
+                    JqlOperator::IsNotEmpty
+                } else {
+                    JqlOperator::IsEmpty
+                };
+                self.expect_keyword("EMPTY")?;
+                Ok(operator)
+            }
+            _ => Err(JqlError::new("Expected operator", span)),
+        }
+    }
+
+    fn condition(&mut self) -> Result<JqlExpr, JqlError> {
+        let (field, field_span) = self.field()?;
+        let operator_span = self.current_span();
+        let operator = self.operator()?;
+        if !field.accepts(operator) {
+            return Err(JqlError::new(
+                format!("Operator {} can't be used with {}", operator, field),
+                JqlSpan {
+                    start: operator_span.start,
+                    end: self.tokens[self.pos - 1].span.end,
+                },
+            ));
+        }
+
+        let values = match operator {
+            JqlOperator::IsEmpty | JqlOperator::IsNotEmpty => vec![],
+            JqlOperator::In | JqlOperator::NotIn => self.value_list(field)?,
+            _ => vec![self.value(field)?],
+        };
+        Ok(JqlExpr::Condition(JqlCondition {
+            field,
+            operator,
+            values,
+            span: JqlSpan {
+                start: field_span.start,
+                end: self.tokens[self.pos - 1].span.end,
+            },
+        }))
+    }
+
+    fn value_list(&mut self, field: JqlField) -> Result<Vec<JqlValue>, JqlError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::LeftParen) => {
+                self.next();
+            }
+            _ => return Err(JqlError::new("Expected (", self.current_span())),
+        }
+        let mut values = vec![self.value(field)?];
+        loop {
+            let span = self.current_span();
+            match self.next().map(|t| t.kind) {
+                Some(TokenKind::Comma) => values.push(self.value(field)?),
+                Some(TokenKind::RightParen) => return Ok(values),
+                _ => return Err(JqlError::new("Expected , or )", span)),
+            }
+        }
+    }
+
+    fn value(&mut self, field: JqlField) -> Result<JqlValue, JqlError> {
+        let span = self.current_span();
+        let (text, quoted) = match self.next().map(|t| t.kind) {
+            Some(TokenKind::Word(word)) => {
+                if KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k)) {
+                    return Err(JqlError::new("Expected value", span));
+                }
+                (word, false)
+            }
+            Some(TokenKind::Quoted(text)) => (text, true),
+            _ => return Err(JqlError::new("Expected value", span)),
+        };
+        let invalid = || JqlError::new(format!("{} is not valid {}", text, field), span);
+
+        match field {
+            JqlField::Assignee | JqlField::Reporter
+                if !quoted && text.eq_ignore_ascii_case("me") =>
+            {
+                Ok(JqlValue::CurrentUser)
+            }
+            JqlField::Priority => text
+                .parse::<IssuePriority>()
+                .map(JqlValue::Priority)
+                .map_err(|_| invalid()),
+            JqlField::Type => IssueType::ordered()
+                .into_iter()
+                .find(|t| {
+                    t.to_string().eq_ignore_ascii_case(text.as_str())
+                        || t.to_label().eq_ignore_ascii_case(text.as_str())
+                })
+                .map(JqlValue::Type)
+                .ok_or_else(invalid),
+            JqlField::Created | JqlField::Updated => parse_date(text.as_str())
+                .map(JqlValue::Date)
+                .ok_or_else(invalid),
+            _ => Ok(JqlValue::Text(text)),
+        }
+    }
+
+    fn order(&mut self) -> Result<JqlOrder, JqlError> {
+        let (field, span) = self.field()?;
+        if !field.is_orderable() {
+            return Err(JqlError::new(
+                format!("Issues can't be ordered by {}", field),
+                span,
+            ));
+        }
+        let direction = if self.peek_keyword("DESC") {
+            self.next();
+            JqlDirection::Desc
+        } else {
+            if self.peek_keyword("ASC") {
+                self.next();
+            }
+            JqlDirection::Asc
+        };
+        Ok(JqlOrder { field, direction })
+    }
+}
+
+/// Accepts `2020-06-01` or number of days (`-7d`) or weeks (`-2w`) ago
+fn parse_date(text: &str) -> Option<JqlDate> {
+    if let Ok(day) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(JqlDate::Day(day));
+    }
+    let text = text.strip_prefix('-')?;
+    let (unit_start, unit) = text.char_indices().last()?;
+    let number = text[..unit_start]
+        .parse::<i64>()
+        .ok()
+        .filter(|number| *number >= 0)?;
+    let days = match unit {
+        'd' => Some(number),
+        'w' => number.checked_mul(7),
+        _ => None,
+    }?;
+    if days > MAX_DAYS_AGO {
+        return None;
+    }
+    Some(JqlDate::DaysAgo(days))
+}
+
+pub fn parse_jql(text: &str) -> Result<JqlQuery, JqlError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        len: text.chars().count(),
+    };
+    parser.query()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(field: JqlField, operator: JqlOperator, values: Vec<JqlValue>) -> JqlExpr {
+        JqlExpr::Condition(JqlCondition {
+            field,
+            operator,
+            values,
+            span: JqlSpan { start: 0, end: 0 },
+        })
+    }
+
+    /// Spans are checked separately
+    fn without_spans(expr: JqlExpr) -> JqlExpr {
+        match expr {
+            JqlExpr::And(a, b) => {
+                JqlExpr::And(Box::new(without_spans(*a)), Box::new(without_spans(*b)))
+            }
+            JqlExpr::Or(a, b) => {
+                JqlExpr::Or(Box::new(without_spans(*a)), Box::new(without_spans(*b)))
+            }
+            JqlExpr::Not(a) => JqlExpr::Not(Box::new(without_spans(*a))),
+            JqlExpr::Condition(c) => condition(c.field, c.operator, c.values),
+        }
+    }
+
+    #[test]
+    fn parses_full_query() {
+        let query = parse_jql(
+            "assignee = me AND priority >= High AND status != Done ORDER BY updated DESC",
+        )
+        .unwrap();
+        assert_eq!(
+            without_spans(query.filter.unwrap()),
+            JqlExpr::And(
+                Box::new(JqlExpr::And(
+                    Box::new(condition(
+                        JqlField::Assignee,
+                        JqlOperator::Eq,
+                        vec![JqlValue::CurrentUser]
+                    )),
+                    Box::new(condition(
+                        JqlField::Priority,
+                        JqlOperator::GreaterEq,
+                        vec![JqlValue::Priority(IssuePriority::High)]
+                    )),
+                )),
+                Box::new(condition(
+                    JqlField::Status,
+                    JqlOperator::NotEq,
+                    vec![JqlValue::Text("Done".to_string())]
+                )),
+            )
+        );
+        assert_eq!(
+            query.order_by,
+            vec![JqlOrder {
+                field: JqlField::Updated,
+                direction: JqlDirection::Desc
+            }]
+        );
+    }
+
+    #[test]
+    fn and_binds_stronger_than_or() {
+        let query = parse_jql("type = bug OR type = story AND NOT (assignee IS EMPTY)").unwrap();
+        assert_eq!(
+            without_spans(query.filter.unwrap()),
+            JqlExpr::Or(
+                Box::new(condition(
+                    JqlField::Type,
+                    JqlOperator::Eq,
+                    vec![JqlValue::Type(IssueType::Bug)]
+                )),
+                Box::new(JqlExpr::And(
+                    Box::new(condition(
+                        JqlField::Type,
+                        JqlOperator::Eq,
+                        vec![JqlValue::Type(IssueType::Story)]
+                    )),
+                    Box::new(JqlExpr::Not(Box::new(condition(
+                        JqlField::Assignee,
+                        JqlOperator::IsEmpty,
+                        vec![]
+                    )))),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn parses_lists_and_dates() {
+        let query = parse_jql(r#"status NOT IN ("In Progress", Done) and created >= -2w"#).unwrap();
+        assert_eq!(
+            without_spans(query.filter.unwrap()),
+            JqlExpr::And(
+                Box::new(condition(
+                    JqlField::Status,
+                    JqlOperator::NotIn,
+                    vec![
+                        JqlValue::Text("In Progress".to_string()),
+                        JqlValue::Text("Done".to_string())
+                    ]
+                )),
+                Box::new(condition(
+                    JqlField::Created,
+                    JqlOperator::GreaterEq,
+                    vec![JqlValue::Date(JqlDate::DaysAgo(14))]
+                )),
+            )
+        );
+        assert_eq!(
+            parse_jql("").unwrap(),
+            JqlQuery {
+                filter: None,
+                order_by: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let span = |text: &str| parse_jql(text).unwrap_err().span;
+        assert_eq!(span("assigne = me"), JqlSpan { start: 0, end: 7 });
+        assert_eq!(span("priority >= Urgent"), JqlSpan { start: 12, end: 18 });
+        assert_eq!(span("summary = bug"), JqlSpan { start: 8, end: 9 });
+        assert_eq!(span("status = Done AND"), JqlSpan { start: 17, end: 17 });
+        assert_eq!(span("status = \"Done"), JqlSpan { start: 9, end: 14 });
+        assert_eq!(span("(status = Done"), JqlSpan { start: 0, end: 1 });
+        assert_eq!(
+            span("status = Done priority"),
+            JqlSpan { start: 14, end: 22 }
+        );
+        assert_eq!(span("ORDER BY status"), JqlSpan { start: 9, end: 15 });
+        assert_eq!(span("created >= -2é"), JqlSpan { start: 11, end: 14 });
+        assert_eq!(
+            span("created >= -2000000000000000000w"),
+            JqlSpan { start: 11, end: 32 }
+        );
+        assert_eq!(span("updated < -36601d"), JqlSpan { start: 10, end: 17 });
+        assert_eq!(span("updated < --1d"), JqlSpan { start: 10, end: 14 });
+    }
+}
@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 use std::str::FromStr;
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 #[cfg(feature = "backend")]
 use diesel::*;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,8 @@ pub use sql::*;
 #[cfg(feature = "backend")]
 pub mod sql;
 
+pub mod jql;
+
 pub trait ToVec {
     type Item;
     fn ordered() -> Vec<Self::Item>;
@@ -28,11 +30,20 @@ pub type IssueStatusId = i32;
 pub type InvitationId = i32;
 pub type Position = i32;
 pub type MessageId = i32;
+pub type IssueEventId = i32;
+pub type IssueLinkId = i32;
+pub type LabelId = i32;
+pub type SprintId = i32;
+pub type CustomFieldId = i32;
+pub type IssueStatusTransitionId = i32;
+pub type SavedFilterId = i32;
+pub type WorklogId = i32;
 pub type EmailString = String;
 pub type UsernameString = String;
 pub type TitleString = String;
 pub type BindToken = Uuid;
 pub type InvitationToken = Uuid;
+pub type RequestId = u32;
 
 #[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
 #[cfg_attr(feature = "backend", sql_type = "IssueTypeType")]
@@ -41,13 +52,21 @@ pub enum IssueType {
     Task,
     Bug,
     Story,
+    Epic,
+    Subtask,
 }
 
 impl ToVec for IssueType {
     type Item = IssueType;
 
     fn ordered() -> Vec<Self> {
-        vec![IssueType::Task, IssueType::Bug, IssueType::Story]
+        vec![
+            IssueType::Task,
+            IssueType::Bug,
+            IssueType::Story,
+            IssueType::Epic,
+            IssueType::Subtask,
+        ]
     }
 }
 
@@ -63,6 +82,8 @@ impl IssueType {
             IssueType::Task => "Task",
             IssueType::Bug => "Bug",
             IssueType::Story => "Story",
+            IssueType::Epic => "Epic",
+            IssueType::Subtask => "Sub-task",
         }
     }
 }
@@ -73,6 +94,8 @@ impl Into<u32> for IssueType {
             IssueType::Task => 1,
             IssueType::Bug => 2,
             IssueType::Story => 3,
+            IssueType::Epic => 4,
+            IssueType::Subtask => 5,
         }
     }
 }
@@ -83,17 +106,36 @@ impl Into<IssueType> for u32 {
             1 => IssueType::Task,
             2 => IssueType::Bug,
             3 => IssueType::Story,
+            4 => IssueType::Epic,
+            5 => IssueType::Subtask,
             _ => IssueType::Task,
         }
     }
 }
 
+impl FromStr for IssueType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "task" => Ok(IssueType::Task),
+            "bug" => Ok(IssueType::Bug),
+            "story" => Ok(IssueType::Story),
+            "epic" => Ok(IssueType::Epic),
+            "subtask" | "sub-task" => Ok(IssueType::Subtask),
+            _ => Err(format!("Unknown type {}", s)),
+        }
+    }
+}
+
 impl std::fmt::Display for IssueType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             IssueType::Task => f.write_str("task"),
             IssueType::Bug => f.write_str("bug"),
             IssueType::Story => f.write_str("story"),
+            IssueType::Epic => f.write_str("epic"),
+            IssueType::Subtask => f.write_str("subtask"),
         }
     }
 }
@@ -389,11 +431,86 @@ impl Into<TimeTracking> for u32 {
     }
 }
 
+/// What happens when issue is moved into column which reached its WIP limit
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "WipLimitPolicyType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum WipLimitPolicy {
+    Warn,
+    Block,
+}
+
+impl Default for WipLimitPolicy {
+    fn default() -> Self {
+        WipLimitPolicy::Warn
+    }
+}
+
+impl Into<u32> for WipLimitPolicy {
+    fn into(self) -> u32 {
+        match self {
+            WipLimitPolicy::Warn => 0,
+            WipLimitPolicy::Block => 1,
+        }
+    }
+}
+
+impl Into<WipLimitPolicy> for u32 {
+    fn into(self) -> WipLimitPolicy {
+        match self {
+            0 => WipLimitPolicy::Warn,
+            1 => WipLimitPolicy::Block,
+            _ => WipLimitPolicy::Warn,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Debug, PartialEq)]
 pub struct ErrorResponse {
     pub errors: Vec<String>,
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Hash)]
+pub enum WsErrorKind {
+    Unauthorized,
+    Forbidden,
+    DatabaseConnectionLost,
+    DatabaseQueryFailed,
+    RecordNotFound,
+    RegisterCollision,
+    InvalidPayload,
+    MailNotSent,
+}
+
+impl std::fmt::Display for WsErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WsErrorKind::Unauthorized => f.write_str("Unauthorized"),
+            WsErrorKind::Forbidden => f.write_str("Forbidden"),
+            WsErrorKind::DatabaseConnectionLost => f.write_str("Database connection lost"),
+            WsErrorKind::DatabaseQueryFailed => f.write_str("Database query failed"),
+            WsErrorKind::RecordNotFound => f.write_str("Record not found"),
+            WsErrorKind::RegisterCollision => f.write_str("Register collision"),
+            WsErrorKind::InvalidPayload => f.write_str("Invalid payload"),
+            WsErrorKind::MailNotSent => f.write_str("Mail not sent"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WsError {
+    pub request_id: Option<RequestId>,
+    pub kind: WsErrorKind,
+    pub message: String,
+    pub fields: Vec<FieldError>,
+}
+
 #[cfg_attr(feature = "backend", derive(Queryable))]
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Project {
@@ -405,6 +522,64 @@ pub struct Project {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub time_tracking: TimeTracking,
+    pub wip_limit_policy: WipLimitPolicy,
+    pub key: String,
+}
+
+impl Project {
+    /// Human-readable key of project issue, for example `JIRS-12`
+    pub fn issue_key(&self, issue: &Issue) -> String {
+        format!("{}-{}", self.key, issue.number)
+    }
+}
+
+/// Project key is 2 to 10 uppercase letters and digits starting with letter
+pub fn is_valid_project_key(key: &str) -> bool {
+    (2..=10).contains(&key.len())
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Builds project key from initials of project name. Single word names use
+/// their first letters instead.
+pub fn project_key_from_name(name: &str) -> String {
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let key: String = if words.len() > 1 {
+        words
+            .iter()
+            .filter_map(|word| word.chars().next())
+            .collect()
+    } else {
+        words.concat().chars().take(4).collect()
+    };
+    let key: String = key
+        .to_ascii_uppercase()
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .chars()
+        .take(10)
+        .collect();
+    if is_valid_project_key(key.as_str()) {
+        key
+    } else {
+        "PROJ".to_string()
+    }
+}
+
+/// Splits issue key like `JIRS-12` into project key and issue number
+pub fn parse_issue_key(key: &str) -> Option<(String, i32)> {
+    let mut parts = key.trim().rsplitn(2, '-');
+    let number = parts.next()?.parse::<i32>().ok().filter(|n| *n > 0)?;
+    let project_key = parts.next()?.to_ascii_uppercase();
+    if is_valid_project_key(project_key.as_str()) {
+        Some((project_key, number))
+    } else {
+        None
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -424,8 +599,14 @@ pub struct Issue {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub issue_status_id: IssueStatusId,
+    pub epic_id: Option<IssueId>,
+    pub parent_id: Option<IssueId>,
+    pub sprint_id: Option<SprintId>,
+    pub number: i32,
 
     pub user_ids: Vec<i32>,
+    pub label_ids: Vec<LabelId>,
+    pub custom_values: Vec<CustomFieldValue>,
 }
 
 #[cfg_attr(feature = "backend", derive(Queryable))]
@@ -437,6 +618,18 @@ pub struct IssueStatus {
     pub project_id: ProjectId,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub wip_limit: Option<i32>,
+}
+
+impl IssueStatus {
+    /// Column can't take another issue without exceeding its WIP limit
+    pub fn is_full(&self, issues_count: usize) -> bool {
+        matches!(self.wip_limit, Some(limit) if issues_count as i32 >= limit)
+    }
+
+    pub fn is_over_limit(&self, issues_count: usize) -> bool {
+        matches!(self.wip_limit, Some(limit) if issues_count as i32 > limit)
+    }
 }
 
 #[cfg_attr(feature = "backend", derive(Queryable))]
@@ -515,7 +708,12 @@ pub struct UpdateIssuePayload {
     pub project_id: ProjectId,
     pub reporter_id: UserId,
     pub issue_status_id: IssueStatusId,
+    pub epic_id: Option<IssueId>,
+    pub parent_id: Option<IssueId>,
+    pub sprint_id: Option<SprintId>,
     pub user_ids: Vec<UserId>,
+    pub label_ids: Vec<LabelId>,
+    pub custom_values: Vec<CustomFieldValue>,
 }
 
 #[cfg_attr(feature = "backend", derive(Queryable))]
@@ -528,6 +726,28 @@ pub struct IssueAssignee {
     pub updated_at: NaiveDateTime,
 }
 
+/// Project scoped tag, color is hex string like `#0052cc`
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Label {
+    pub id: LabelId,
+    pub project_id: ProjectId,
+    pub name: String,
+    pub color: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IssueLabel {
+    pub id: i32,
+    pub issue_id: IssueId,
+    pub label_id: LabelId,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
 impl From<Issue> for UpdateIssuePayload {
     fn from(issue: Issue) -> Self {
         Self {
@@ -543,7 +763,12 @@ impl From<Issue> for UpdateIssuePayload {
             project_id: issue.project_id,
             reporter_id: issue.reporter_id,
             user_ids: issue.user_ids,
+            label_ids: issue.label_ids,
+            custom_values: issue.custom_values,
             issue_status_id: issue.issue_status_id,
+            epic_id: issue.epic_id,
+            parent_id: issue.parent_id,
+            sprint_id: issue.sprint_id,
         }
     }
 }
@@ -555,6 +780,8 @@ pub enum MessageType {
     ReceivedInvitation,
     AssignedToIssue,
     Mention,
+    IssueChanged,
+    IssueCommented,
 }
 
 impl Into<u32> for MessageType {
@@ -563,6 +790,8 @@ impl Into<u32> for MessageType {
             MessageType::ReceivedInvitation => 0,
             MessageType::AssignedToIssue => 1,
             MessageType::Mention => 2,
+            MessageType::IssueChanged => 3,
+            MessageType::IssueCommented => 4,
         }
     }
 }
@@ -573,6 +802,8 @@ impl Into<MessageType> for u32 {
             0 => MessageType::ReceivedInvitation,
             1 => MessageType::AssignedToIssue,
             2 => MessageType::Mention,
+            3 => MessageType::IssueChanged,
+            4 => MessageType::IssueCommented,
             _ => MessageType::Mention,
         }
     }
@@ -584,6 +815,8 @@ impl std::fmt::Display for MessageType {
             MessageType::ReceivedInvitation => f.write_str("ReceivedInvitation"),
             MessageType::AssignedToIssue => f.write_str("AssignedToIssue"),
             MessageType::Mention => f.write_str("Mention"),
+            MessageType::IssueChanged => f.write_str("IssueChanged"),
+            MessageType::IssueCommented => f.write_str("IssueCommented"),
         }
     }
 }
@@ -602,6 +835,791 @@ pub struct Message {
     pub updated_at: NaiveDateTime,
 }
 
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "IssueEventTypeType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum IssueEventType {
+    Title,
+    Type,
+    Priority,
+    Description,
+    Status,
+    Reporter,
+    Assignees,
+    Estimate,
+    TimeSpent,
+    TimeRemaining,
+    Epic,
+    Parent,
+    Labels,
+    Sprint,
+    CommentAdded,
+    CommentDeleted,
+    Deleted,
+}
+
+impl Into<u32> for IssueEventType {
+    fn into(self) -> u32 {
+        match self {
+            IssueEventType::Title => 0,
+            IssueEventType::Type => 1,
+            IssueEventType::Priority => 2,
+            IssueEventType::Description => 3,
+            IssueEventType::Status => 4,
+            IssueEventType::Reporter => 5,
+            IssueEventType::Assignees => 6,
+            IssueEventType::Estimate => 7,
+            IssueEventType::TimeSpent => 8,
+            IssueEventType::TimeRemaining => 9,
+            IssueEventType::CommentAdded => 10,
+            IssueEventType::CommentDeleted => 11,
+            IssueEventType::Deleted => 12,
+            IssueEventType::Epic => 13,
+            IssueEventType::Parent => 14,
+            IssueEventType::Labels => 15,
+            IssueEventType::Sprint => 16,
+        }
+    }
+}
+
+impl Into<IssueEventType> for u32 {
+    fn into(self) -> IssueEventType {
+        match self {
+            0 => IssueEventType::Title,
+            1 => IssueEventType::Type,
+            2 => IssueEventType::Priority,
+            3 => IssueEventType::Description,
+            4 => IssueEventType::Status,
+            5 => IssueEventType::Reporter,
+            6 => IssueEventType::Assignees,
+            7 => IssueEventType::Estimate,
+            8 => IssueEventType::TimeSpent,
+            9 => IssueEventType::TimeRemaining,
+            10 => IssueEventType::CommentAdded,
+            11 => IssueEventType::CommentDeleted,
+            12 => IssueEventType::Deleted,
+            13 => IssueEventType::Epic,
+            14 => IssueEventType::Parent,
+            15 => IssueEventType::Labels,
+            16 => IssueEventType::Sprint,
+            _ => IssueEventType::Title,
+        }
+    }
+}
+
+impl std::fmt::Display for IssueEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueEventType::Title => f.write_str("Title"),
+            IssueEventType::Type => f.write_str("Type"),
+            IssueEventType::Priority => f.write_str("Priority"),
+            IssueEventType::Description => f.write_str("Description"),
+            IssueEventType::Status => f.write_str("Status"),
+            IssueEventType::Reporter => f.write_str("Reporter"),
+            IssueEventType::Assignees => f.write_str("Assignees"),
+            IssueEventType::Estimate => f.write_str("Original Estimate"),
+            IssueEventType::TimeSpent => f.write_str("Time spent"),
+            IssueEventType::TimeRemaining => f.write_str("Time remaining"),
+            IssueEventType::Epic => f.write_str("Epic"),
+            IssueEventType::Parent => f.write_str("Parent issue"),
+            IssueEventType::Labels => f.write_str("Labels"),
+            IssueEventType::Sprint => f.write_str("Sprint"),
+            IssueEventType::CommentAdded => f.write_str("Comment added"),
+            IssueEventType::CommentDeleted => f.write_str("Comment deleted"),
+            IssueEventType::Deleted => f.write_str("Issue deleted"),
+        }
+    }
+}
+
+/// Single entry of issue history. Issue id is kept even after the issue was
+/// deleted so `Deleted` events are still readable.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IssueEvent {
+    pub id: IssueEventId,
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub event_type: IssueEventType,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "IssueLinkTypeType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum IssueLinkType {
+    Blocks,
+    IsBlockedBy,
+    Duplicates,
+    RelatesTo,
+}
+
+impl ToVec for IssueLinkType {
+    type Item = IssueLinkType;
+
+    fn ordered() -> Vec<Self> {
+        vec![
+            IssueLinkType::Blocks,
+            IssueLinkType::IsBlockedBy,
+            IssueLinkType::Duplicates,
+            IssueLinkType::RelatesTo,
+        ]
+    }
+}
+
+impl Default for IssueLinkType {
+    fn default() -> Self {
+        IssueLinkType::RelatesTo
+    }
+}
+
+impl IssueLinkType {
+    pub fn to_label(&self) -> &str {
+        match self {
+            IssueLinkType::Blocks => "blocks",
+            IssueLinkType::IsBlockedBy => "is blocked by",
+            IssueLinkType::Duplicates => "duplicates",
+            IssueLinkType::RelatesTo => "relates to",
+        }
+    }
+
+    /// Label of link seen from the target issue
+    pub fn to_inverse_label(&self) -> &str {
+        match self {
+            IssueLinkType::Blocks => "is blocked by",
+            IssueLinkType::IsBlockedBy => "blocks",
+            IssueLinkType::Duplicates => "is duplicated by",
+            IssueLinkType::RelatesTo => "relates to",
+        }
+    }
+}
+
+impl Into<u32> for IssueLinkType {
+    fn into(self) -> u32 {
+        match self {
+            IssueLinkType::Blocks => 0,
+            IssueLinkType::IsBlockedBy => 1,
+            IssueLinkType::Duplicates => 2,
+            IssueLinkType::RelatesTo => 3,
+        }
+    }
+}
+
+impl Into<IssueLinkType> for u32 {
+    fn into(self) -> IssueLinkType {
+        match self {
+            0 => IssueLinkType::Blocks,
+            1 => IssueLinkType::IsBlockedBy,
+            2 => IssueLinkType::Duplicates,
+            3 => IssueLinkType::RelatesTo,
+            _ => IssueLinkType::RelatesTo,
+        }
+    }
+}
+
+impl std::fmt::Display for IssueLinkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueLinkType::Blocks => f.write_str("blocks"),
+            IssueLinkType::IsBlockedBy => f.write_str("is_blocked_by"),
+            IssueLinkType::Duplicates => f.write_str("duplicates"),
+            IssueLinkType::RelatesTo => f.write_str("relates_to"),
+        }
+    }
+}
+
+/// Directed link between two issues of the same project, `link_type` is read
+/// from source to target.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IssueLink {
+    pub id: IssueLinkId,
+    pub source_issue_id: IssueId,
+    pub target_issue_id: IssueId,
+    pub link_type: IssueLinkType,
+    pub user_id: UserId,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl IssueLink {
+    /// Label and other issue of link seen from given issue, links are stored
+    /// once so target issue reads them backwards
+    pub fn seen_from(&self, issue_id: IssueId) -> (&str, IssueId) {
+        if self.source_issue_id == issue_id {
+            (self.link_type.to_label(), self.target_issue_id)
+        } else {
+            (self.link_type.to_inverse_label(), self.source_issue_id)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateIssueLinkPayload {
+    pub source_issue_id: IssueId,
+    pub target_issue_id: IssueId,
+    pub link_type: IssueLinkType,
+}
+
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "SprintStateType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum SprintState {
+    Planned,
+    Active,
+    Completed,
+}
+
+impl ToVec for SprintState {
+    type Item = SprintState;
+
+    fn ordered() -> Vec<Self> {
+        vec![
+            SprintState::Planned,
+            SprintState::Active,
+            SprintState::Completed,
+        ]
+    }
+}
+
+impl Default for SprintState {
+    fn default() -> Self {
+        SprintState::Planned
+    }
+}
+
+impl SprintState {
+    pub fn to_label(&self) -> &str {
+        match self {
+            SprintState::Planned => "Planned",
+            SprintState::Active => "Active",
+            SprintState::Completed => "Completed",
+        }
+    }
+}
+
+impl Into<u32> for SprintState {
+    fn into(self) -> u32 {
+        match self {
+            SprintState::Planned => 0,
+            SprintState::Active => 1,
+            SprintState::Completed => 2,
+        }
+    }
+}
+
+impl Into<SprintState> for u32 {
+    fn into(self) -> SprintState {
+        match self {
+            0 => SprintState::Planned,
+            1 => SprintState::Active,
+            2 => SprintState::Completed,
+            _ => SprintState::Planned,
+        }
+    }
+}
+
+impl std::fmt::Display for SprintState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SprintState::Planned => f.write_str("planned"),
+            SprintState::Active => f.write_str("active"),
+            SprintState::Completed => f.write_str("completed"),
+        }
+    }
+}
+
+/// Time box of project work. Only one sprint of project can be active.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Sprint {
+    pub id: SprintId,
+    pub project_id: ProjectId,
+    pub name: String,
+    pub goal: Option<String>,
+    pub starts_at: Option<NaiveDate>,
+    pub ends_at: Option<NaiveDate>,
+    pub state: SprintState,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UpdateSprintPayload {
+    pub id: SprintId,
+    pub name: String,
+    pub goal: Option<String>,
+    pub starts_at: Option<NaiveDate>,
+    pub ends_at: Option<NaiveDate>,
+}
+
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "CustomFieldTypeType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    SingleSelect,
+    MultiSelect,
+    User,
+}
+
+impl ToVec for CustomFieldType {
+    type Item = CustomFieldType;
+
+    fn ordered() -> Vec<Self> {
+        vec![
+            CustomFieldType::Text,
+            CustomFieldType::Number,
+            CustomFieldType::Date,
+            CustomFieldType::SingleSelect,
+            CustomFieldType::MultiSelect,
+            CustomFieldType::User,
+        ]
+    }
+}
+
+impl Default for CustomFieldType {
+    fn default() -> Self {
+        CustomFieldType::Text
+    }
+}
+
+impl CustomFieldType {
+    pub fn to_label(&self) -> &str {
+        match self {
+            CustomFieldType::Text => "Text",
+            CustomFieldType::Number => "Number",
+            CustomFieldType::Date => "Date",
+            CustomFieldType::SingleSelect => "Single select",
+            CustomFieldType::MultiSelect => "Multi select",
+            CustomFieldType::User => "User",
+        }
+    }
+
+    /// Select types keep their values within field options
+    pub fn has_options(&self) -> bool {
+        matches!(
+            self,
+            CustomFieldType::SingleSelect | CustomFieldType::MultiSelect
+        )
+    }
+
+    pub fn is_multi(&self) -> bool {
+        *self == CustomFieldType::MultiSelect
+    }
+}
+
+impl Into<u32> for CustomFieldType {
+    fn into(self) -> u32 {
+        match self {
+            CustomFieldType::Text => 0,
+            CustomFieldType::Number => 1,
+            CustomFieldType::Date => 2,
+            CustomFieldType::SingleSelect => 3,
+            CustomFieldType::MultiSelect => 4,
+            CustomFieldType::User => 5,
+        }
+    }
+}
+
+impl Into<CustomFieldType> for u32 {
+    fn into(self) -> CustomFieldType {
+        match self {
+            0 => CustomFieldType::Text,
+            1 => CustomFieldType::Number,
+            2 => CustomFieldType::Date,
+            3 => CustomFieldType::SingleSelect,
+            4 => CustomFieldType::MultiSelect,
+            5 => CustomFieldType::User,
+            _ => CustomFieldType::Text,
+        }
+    }
+}
+
+impl std::fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomFieldType::Text => f.write_str("text"),
+            CustomFieldType::Number => f.write_str("number"),
+            CustomFieldType::Date => f.write_str("date"),
+            CustomFieldType::SingleSelect => f.write_str("single_select"),
+            CustomFieldType::MultiSelect => f.write_str("multi_select"),
+            CustomFieldType::User => f.write_str("user"),
+        }
+    }
+}
+
+/// Project defined issue field. Options are used only by select types.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CustomField {
+    pub id: CustomFieldId,
+    pub project_id: ProjectId,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub options: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateCustomFieldPayload {
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub options: Vec<String>,
+}
+
+/// Value of custom field for single issue. Every type is stored as text,
+/// numbers as written, dates as `YYYY-MM-DD` and users as their ids.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub struct CustomFieldValue {
+    pub custom_field_id: CustomFieldId,
+    pub values: Vec<String>,
+}
+
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IssueCustomFieldValue {
+    pub id: i32,
+    pub issue_id: IssueId,
+    pub custom_field_id: CustomFieldId,
+    pub values: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<IssueCustomFieldValue> for CustomFieldValue {
+    fn from(value: IssueCustomFieldValue) -> Self {
+        Self {
+            custom_field_id: value.custom_field_id,
+            values: value.values,
+        }
+    }
+}
+
+#[cfg_attr(feature = "backend", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "backend", sql_type = "TransitionRequirementType")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialOrd, PartialEq, Hash)]
+pub enum TransitionRequirement {
+    Assignee,
+    Estimate,
+    TimeSpent,
+    Description,
+}
+
+impl ToVec for TransitionRequirement {
+    type Item = TransitionRequirement;
+
+    fn ordered() -> Vec<Self> {
+        vec![
+            TransitionRequirement::Assignee,
+            TransitionRequirement::Estimate,
+            TransitionRequirement::TimeSpent,
+            TransitionRequirement::Description,
+        ]
+    }
+}
+
+impl Default for TransitionRequirement {
+    fn default() -> Self {
+        TransitionRequirement::Assignee
+    }
+}
+
+impl TransitionRequirement {
+    pub fn to_label(&self) -> &str {
+        match self {
+            TransitionRequirement::Assignee => "Assignee",
+            TransitionRequirement::Estimate => "Estimate",
+            TransitionRequirement::TimeSpent => "Time spent",
+            TransitionRequirement::Description => "Description",
+        }
+    }
+
+    pub fn is_met(&self, issue: &Issue) -> bool {
+        match self {
+            TransitionRequirement::Assignee => !issue.user_ids.is_empty(),
+            TransitionRequirement::Estimate => issue.estimate.is_some(),
+            TransitionRequirement::TimeSpent => issue.time_spent.unwrap_or_default() > 0,
+            TransitionRequirement::Description => {
+                matches!(&issue.description_text, Some(text) if !text.trim().is_empty())
+            }
+        }
+    }
+}
+
+/// `0` is left for transitions without requirement
+impl Into<u32> for TransitionRequirement {
+    fn into(self) -> u32 {
+        match self {
+            TransitionRequirement::Assignee => 1,
+            TransitionRequirement::Estimate => 2,
+            TransitionRequirement::TimeSpent => 3,
+            TransitionRequirement::Description => 4,
+        }
+    }
+}
+
+impl Into<TransitionRequirement> for u32 {
+    fn into(self) -> TransitionRequirement {
+        match self {
+            1 => TransitionRequirement::Assignee,
+            2 => TransitionRequirement::Estimate,
+            3 => TransitionRequirement::TimeSpent,
+            4 => TransitionRequirement::Description,
+            _ => TransitionRequirement::Assignee,
+        }
+    }
+}
+
+impl std::fmt::Display for TransitionRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionRequirement::Assignee => f.write_str("assignee"),
+            TransitionRequirement::Estimate => f.write_str("estimate"),
+            TransitionRequirement::TimeSpent => f.write_str("time_spent"),
+            TransitionRequirement::Description => f.write_str("description"),
+        }
+    }
+}
+
+/// Fragment of search hit snippet, highlighted parts matched searched text
+#[derive(Clone, Debug, Serialize, Deserialize, PartialOrd, PartialEq)]
+pub struct SearchSnippetPart {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialOrd, PartialEq)]
+pub struct IssueSearchHit {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+    pub key: String,
+    pub title: String,
+    pub rank: f32,
+    pub snippet: Vec<SearchSnippetPart>,
+}
+
+/// State of project board filters. Empty `jql` means no query is active.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct BoardFilter {
+    pub text: String,
+    pub user_ids: Vec<UserId>,
+    pub label_ids: Vec<LabelId>,
+    pub only_my: bool,
+    pub recently_updated: bool,
+    pub jql: String,
+}
+
+/// Named board filter of user, shared filters are visible to every
+/// project member but only owner can remove them.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SavedFilter {
+    pub id: SavedFilterId,
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub shared: bool,
+    pub text: String,
+    pub user_ids: Vec<UserId>,
+    pub label_ids: Vec<LabelId>,
+    pub only_my: bool,
+    pub recently_updated: bool,
+    pub jql: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl SavedFilter {
+    pub fn filter(&self) -> BoardFilter {
+        BoardFilter {
+            text: self.text.clone(),
+            user_ids: self.user_ids.clone(),
+            label_ids: self.label_ids.clone(),
+            only_my: self.only_my,
+            recently_updated: self.recently_updated,
+            jql: self.jql.clone(),
+        }
+    }
+
+    pub fn is_visible_to(&self, user_id: UserId) -> bool {
+        self.shared || self.user_id == user_id
+    }
+
+    pub fn can_be_removed_by(&self, user_id: UserId) -> bool {
+        self.user_id == user_id
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateSavedFilterPayload {
+    pub name: String,
+    pub shared: bool,
+    pub filter: BoardFilter,
+}
+
+/// Time logged by user on issue, `time_spent` uses same unit as
+/// `Issue::time_spent` which is sum of all issue worklogs.
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Worklog {
+    pub id: WorklogId,
+    pub issue_id: IssueId,
+    pub user_id: UserId,
+    pub time_spent: i32,
+    pub started_at: NaiveDate,
+    pub comment: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateWorklogPayload {
+    pub issue_id: IssueId,
+    pub time_spent: i32,
+    pub started_at: NaiveDate,
+    pub comment: String,
+}
+
+/// Summed time tracking of issues, values use same unit as issue fields
+/// which depends on `Project::time_tracking`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TimeTrackingSummary {
+    pub issues: i32,
+    pub estimate: i32,
+    pub time_spent: i32,
+    pub time_remaining: i32,
+}
+
+/// Work logged on project issues between `from` and `to`. Time spent is sum
+/// of worklogs started within range, estimate and time remaining are current
+/// values of issues with such worklogs. Issue is counted for each user who
+/// logged work on it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TimeTrackingReport {
+    pub time_tracking: TimeTracking,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total: TimeTrackingSummary,
+    pub by_user: Vec<(UserId, TimeTrackingSummary)>,
+    pub by_issue_type: Vec<(IssueType, TimeTrackingSummary)>,
+    pub by_status: Vec<(IssueStatusId, TimeTrackingSummary)>,
+}
+
+/// Project issues at the end of day. `scope` is sum of all estimates,
+/// `completed` of issues in done status and `time_remaining` of other issues.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BurndownPoint {
+    pub day: NaiveDate,
+    pub scope: i32,
+    pub completed: i32,
+    pub time_remaining: i32,
+}
+
+/// Daily points between `from` and `to`, issues in last column are done
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BurndownReport {
+    pub time_tracking: TimeTracking,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub points: Vec<BurndownPoint>,
+}
+
+/// Number of project issues in every status at the end of day, statuses are
+/// ordered by their position on board.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CumulativeFlowPoint {
+    pub day: NaiveDate,
+    pub counts: Vec<(IssueStatusId, i32)>,
+}
+
+/// Durations in days of issues finished in report range
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FlowTimeStats {
+    pub issues: i32,
+    pub average: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+/// Daily points between `from` and `to`. Lead time is measured from issue
+/// creation and cycle time from first status change until issue was moved
+/// to last column.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CumulativeFlowReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub points: Vec<CumulativeFlowPoint>,
+    pub cycle_time: FlowTimeStats,
+    pub lead_time: FlowTimeStats,
+}
+
+/// Allowed move of issue between two project statuses
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IssueStatusTransition {
+    pub id: IssueStatusTransitionId,
+    pub project_id: ProjectId,
+    pub from_status_id: IssueStatusId,
+    pub to_status_id: IssueStatusId,
+    pub required_field: Option<TransitionRequirement>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateIssueStatusTransitionPayload {
+    pub from_status_id: IssueStatusId,
+    pub to_status_id: IssueStatusId,
+    pub required_field: Option<TransitionRequirement>,
+}
+
+/// Checks if issue can be moved to given status. Project without any
+/// transition keeps free-form workflow, otherwise only defined transitions
+/// are allowed and their required field must be filled.
+pub fn check_status_transition(
+    transitions: &[IssueStatusTransition],
+    issue: &Issue,
+    to_status_id: IssueStatusId,
+) -> Result<(), String> {
+    if issue.issue_status_id == to_status_id || transitions.is_empty() {
+        return Ok(());
+    }
+    let transition = transitions
+        .iter()
+        .find(|t| t.from_status_id == issue.issue_status_id && t.to_status_id == to_status_id)
+        .ok_or_else(|| "Issue can't be moved to this status".to_string())?;
+    match transition.required_field {
+        Some(required) if !required.is_met(issue) => Err(format!(
+            "{} is required before moving issue to this status",
+            required.to_label()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns project policy which applies when issue is moved from one column
+/// into another one, `None` when target column still has room. Issue staying
+/// in its column is never limited.
+pub fn check_wip_limit(
+    policy: WipLimitPolicy,
+    from_status_id: IssueStatusId,
+    to_status: &IssueStatus,
+    to_status_issues_count: usize,
+) -> Option<WipLimitPolicy> {
+    if from_status_id == to_status.id || !to_status.is_full(to_status_issues_count) {
+        None
+    } else {
+        Some(policy)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CreateCommentPayload {
     pub user_id: Option<UserId>,
@@ -629,6 +1647,26 @@ pub struct CreateIssuePayload {
     pub user_ids: Vec<UserId>,
     pub reporter_id: UserId,
     pub issue_status_id: IssueStatusId,
+    pub parent_id: Option<IssueId>,
+}
+
+/// Problem with single row of imported CSV file, rows are counted from 1
+/// and header is not counted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CsvRowError {
+    pub row: i32,
+    pub field: String,
+    pub message: String,
+}
+
+/// Outcome of CSV import. Issues are saved only when file has no errors and
+/// import is not a dry run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IssuesImportReport {
+    pub dry_run: bool,
+    pub rows: i32,
+    pub imported: i32,
+    pub errors: Vec<CsvRowError>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -639,12 +1677,15 @@ pub struct UpdateProjectPayload {
     pub description: Option<String>,
     pub category: Option<ProjectCategory>,
     pub time_tracking: Option<TimeTracking>,
+    pub wip_limit_policy: Option<WipLimitPolicy>,
+    pub key: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum PayloadVariant {
     OptionI32(Option<i32>),
     VecI32(Vec<i32>),
+    VecString(Vec<String>),
     I32(i32),
     String(String),
     IssueType(IssueType),
@@ -655,11 +1696,21 @@ pub enum PayloadVariant {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
 pub enum ProjectFieldId {
     Name,
+    Key,
     Url,
     Description,
     Category,
     TimeTracking,
+    WipLimitPolicy,
     IssueStatusName,
+    LabelName,
+    LabelColor,
+    CustomFieldName,
+    CustomFieldType,
+    CustomFieldOptions,
+    TransitionFrom,
+    TransitionTo,
+    TransitionRequirement,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
@@ -694,6 +1745,27 @@ pub enum CommentFieldId {
     Body,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
+pub enum SprintFieldId {
+    Name,
+    Goal,
+    StartsAt,
+    EndsAt,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
+pub enum IssueLinkFieldId {
+    LinkType,
+    TargetIssue,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
+pub enum WorklogFieldId {
+    TimeSpent,
+    StartedAt,
+    Comment,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialOrd, PartialEq, Hash)]
 pub enum IssueFieldId {
     Type,
@@ -707,6 +1779,10 @@ pub enum IssueFieldId {
     TimeSpent,
     TimeRemaining,
     IssueStatusId,
+    Epic,
+    Parent,
+    Labels,
+    CustomField(CustomFieldId),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -715,6 +1791,10 @@ pub enum WsMsg {
     Pong,
     Die,
 
+    // requests
+    Request(RequestId, Box<WsMsg>),
+    Error(WsError),
+
     // auth
     AuthorizeRequest(Uuid),
     AuthorizeLoaded(Result<User, String>),
@@ -776,16 +1856,19 @@ pub enum WsMsg {
     IssueDeleted(IssueId),
     IssueCreateRequest(CreateIssuePayload),
     IssueCreated(Issue),
+    IssueByKeyRequest(String),
+    IssueByKeyLoaded(ProjectId, IssueId),
 
     // issue status
     IssueStatusesRequest,
     IssueStatusesResponse(Vec<IssueStatus>),
-    IssueStatusUpdate(IssueStatusId, TitleString, Position),
+    IssueStatusUpdate(IssueStatusId, TitleString, Position, Option<i32>),
     IssueStatusUpdated(IssueStatus),
     IssueStatusCreate(TitleString, Position),
     IssueStatusCreated(IssueStatus),
     IssueStatusDelete(IssueStatusId),
     IssueStatusDeleted(IssueStatusId),
+    IssueStatusWipLimitExceeded(IssueStatusId),
 
     // comments
     IssueCommentsRequest(IssueId),
@@ -795,6 +1878,101 @@ pub enum WsMsg {
     CommentDeleteRequest(CommentId),
     CommentDeleted(CommentId),
 
+    // epics
+    EpicCreateRequest(TitleString),
+    EpicAttachIssueRequest(IssueId, IssueId),
+    EpicDetachIssueRequest(IssueId),
+
+    // sub-tasks
+    IssueParentUpdateRequest(IssueId, Option<IssueId>),
+
+    // issue links
+    IssueLinksRequest(IssueId),
+    IssueLinksLoaded(IssueId, Vec<IssueLink>),
+    IssueLinkCreateRequest(CreateIssueLinkPayload),
+    IssueLinkCreated(IssueLink),
+    IssueLinkDeleteRequest(IssueLinkId),
+    IssueLinkDeleted(IssueLinkId),
+
+    // labels
+    LabelsRequest,
+    LabelsLoaded(Vec<Label>),
+    LabelCreateRequest(TitleString, String),
+    LabelCreated(Label),
+    LabelDeleteRequest(LabelId),
+    LabelDeleted(LabelId),
+
+    // sprints
+    SprintsRequest,
+    SprintsLoaded(Vec<Sprint>),
+    SprintCreateRequest(TitleString),
+    SprintCreated(Sprint),
+    SprintUpdateRequest(UpdateSprintPayload),
+    SprintUpdated(Sprint),
+    SprintStartRequest(SprintId),
+    SprintCompleteRequest(SprintId),
+    SprintDeleteRequest(SprintId),
+    SprintDeleted(SprintId),
+    IssueSprintUpdateRequest(IssueId, Option<SprintId>),
+    IssuesSprintChanged(Vec<IssueId>, Option<SprintId>),
+
+    // custom fields
+    CustomFieldsRequest,
+    CustomFieldsLoaded(Vec<CustomField>),
+    CustomFieldCreateRequest(CreateCustomFieldPayload),
+    CustomFieldCreated(CustomField),
+    CustomFieldDeleteRequest(CustomFieldId),
+    CustomFieldDeleted(CustomFieldId),
+
+    // workflow
+    IssueStatusTransitionsRequest,
+    IssueStatusTransitionsLoaded(Vec<IssueStatusTransition>),
+    IssueStatusTransitionCreateRequest(CreateIssueStatusTransitionPayload),
+    IssueStatusTransitionCreated(IssueStatusTransition),
+    IssueStatusTransitionDeleteRequest(IssueStatusTransitionId),
+    IssueStatusTransitionDeleted(IssueStatusTransitionId),
+
+    // search
+    IssueSearchRequest {
+        text: String,
+        all_projects: bool,
+    },
+    IssueSearchLoaded(String, Vec<IssueSearchHit>),
+    IssueQueryRequest(String),
+    IssueQueryLoaded(String, Vec<IssueId>),
+    IssueQueryFailed(String, jql::JqlError),
+
+    // saved filters
+    SavedFiltersRequest,
+    SavedFiltersLoaded(Vec<SavedFilter>),
+    SavedFilterCreateRequest(CreateSavedFilterPayload),
+    SavedFilterCreated(SavedFilter),
+    SavedFilterDeleteRequest(SavedFilterId),
+    SavedFilterDeleted(SavedFilterId),
+
+    // issue watchers
+    IssueWatchersRequest(IssueId),
+    IssueWatchersLoaded(IssueId, Vec<UserId>),
+    IssueWatchRequest(IssueId, bool),
+
+    // worklogs
+    WorklogsRequest(IssueId),
+    WorklogsLoaded(IssueId, Vec<Worklog>),
+    WorklogCreateRequest(CreateWorklogPayload),
+    WorklogDeleteRequest(WorklogId),
+
+    // reports
+    TimeTrackingReportRequest(NaiveDate, NaiveDate),
+    TimeTrackingReportLoaded(TimeTrackingReport),
+    BurndownRequest(NaiveDate, NaiveDate),
+    BurndownLoaded(BurndownReport),
+    CumulativeFlowRequest(NaiveDate, NaiveDate),
+    CumulativeFlowLoaded(CumulativeFlowReport),
+
+    // issue history
+    IssueEventsRequest(IssueId),
+    IssueEventsLoaded(IssueId, Vec<IssueEvent>),
+
     // users
     AvatarUrlChanged(UserId, String),
     ProfileUpdate(EmailString, UsernameString),
@@ -813,3 +1991,215 @@ pub enum WsMsg {
     MessageMarkSeen(MessageId),
     MessageMarkedSeen(MessageId),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(issue_status_id: IssueStatusId) -> Issue {
+        let now = chrono::Utc::now().naive_utc();
+        Issue {
+            id: 1,
+            title: "Fix login".to_string(),
+            issue_type: IssueType::Task,
+            priority: IssuePriority::Medium,
+            list_position: 0,
+            description: None,
+            description_text: None,
+            estimate: None,
+            time_spent: None,
+            time_remaining: None,
+            reporter_id: 1,
+            project_id: 1,
+            created_at: now,
+            updated_at: now,
+            issue_status_id,
+            epic_id: None,
+            parent_id: None,
+            sprint_id: None,
+            number: 1,
+            user_ids: vec![],
+            label_ids: vec![],
+            custom_values: vec![],
+        }
+    }
+
+    fn transition(
+        from_status_id: IssueStatusId,
+        to_status_id: IssueStatusId,
+        required_field: Option<TransitionRequirement>,
+    ) -> IssueStatusTransition {
+        let now = chrono::Utc::now().naive_utc();
+        IssueStatusTransition {
+            id: 1,
+            project_id: 1,
+            from_status_id,
+            to_status_id,
+            required_field,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn allows_any_move_without_workflow() {
+        assert!(check_status_transition(&[], &issue(1), 3).is_ok());
+    }
+
+    #[test]
+    fn allows_only_defined_transitions() {
+        let transitions = vec![transition(1, 2, None)];
+        assert!(check_status_transition(&transitions, &issue(1), 2).is_ok());
+        assert!(check_status_transition(&transitions, &issue(1), 1).is_ok());
+        assert!(check_status_transition(&transitions, &issue(1), 3).is_err());
+        assert!(check_status_transition(&transitions, &issue(2), 1).is_err());
+    }
+
+    #[test]
+    fn checks_required_field() {
+        let transitions = vec![transition(1, 2, Some(TransitionRequirement::Assignee))];
+        let mut unassigned = issue(1);
+        assert!(check_status_transition(&transitions, &unassigned, 2).is_err());
+        unassigned.user_ids.push(1);
+        assert!(check_status_transition(&transitions, &unassigned, 2).is_ok());
+    }
+
+    fn link(link_type: IssueLinkType) -> IssueLink {
+        let now = chrono::Utc::now().naive_utc();
+        IssueLink {
+            id: 1,
+            source_issue_id: 1,
+            target_issue_id: 2,
+            link_type,
+            user_id: 1,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn link_type_round_trips_through_u32() {
+        for link_type in IssueLinkType::ordered() {
+            let n: u32 = link_type.into();
+            let restored: IssueLinkType = n.into();
+            assert_eq!(restored, link_type);
+        }
+        let unknown: IssueLinkType = 99.into();
+        assert_eq!(unknown, IssueLinkType::RelatesTo);
+    }
+
+    #[test]
+    fn link_is_read_backwards_from_target() {
+        let link_types = IssueLinkType::ordered();
+        let labels: Vec<(&str, &str)> = link_types
+            .iter()
+            .map(|link_type| (link_type.to_label(), link_type.to_inverse_label()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("blocks", "is blocked by"),
+                ("is blocked by", "blocks"),
+                ("duplicates", "is duplicated by"),
+                ("relates to", "relates to"),
+            ]
+        );
+
+        let blocks = link(IssueLinkType::Blocks);
+        assert_eq!(blocks.seen_from(1), ("blocks", 2));
+        assert_eq!(blocks.seen_from(2), ("is blocked by", 1));
+    }
+
+    fn status(id: IssueStatusId, wip_limit: Option<i32>) -> IssueStatus {
+        let now = chrono::Utc::now().naive_utc();
+        IssueStatus {
+            id,
+            name: "In progress".to_string(),
+            position: id,
+            project_id: 1,
+            created_at: now,
+            updated_at: now,
+            wip_limit,
+        }
+    }
+
+    #[test]
+    fn column_is_full_at_its_wip_limit() {
+        let limited = status(2, Some(2));
+        assert!(!limited.is_full(1));
+        assert!(limited.is_full(2));
+        assert!(!limited.is_over_limit(2));
+        assert!(limited.is_over_limit(3));
+        assert!(!status(2, None).is_full(100));
+    }
+
+    #[test]
+    fn moving_into_full_column_applies_project_policy() {
+        for policy in [WipLimitPolicy::Warn, WipLimitPolicy::Block].iter() {
+            let policy = *policy;
+            assert_eq!(
+                check_wip_limit(policy, 1, &status(2, Some(2)), 2),
+                Some(policy)
+            );
+            assert_eq!(check_wip_limit(policy, 1, &status(2, Some(2)), 1), None);
+            assert_eq!(check_wip_limit(policy, 1, &status(2, None), 2), None);
+        }
+    }
+
+    #[test]
+    fn issue_can_stay_in_full_column() {
+        let full = status(2, Some(1));
+        assert_eq!(check_wip_limit(WipLimitPolicy::Block, 2, &full, 3), None);
+    }
+
+    fn saved_filter(user_id: UserId, shared: bool) -> SavedFilter {
+        let now = chrono::Utc::now().naive_utc();
+        SavedFilter {
+            id: 1,
+            project_id: 1,
+            user_id,
+            name: "Mine".to_string(),
+            shared,
+            text: "".to_string(),
+            user_ids: vec![],
+            label_ids: vec![],
+            only_my: true,
+            recently_updated: false,
+            jql: "".to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn private_filter_is_visible_only_to_owner() {
+        let private = saved_filter(1, false);
+        assert!(private.is_visible_to(1));
+        assert!(!private.is_visible_to(2));
+    }
+
+    #[test]
+    fn shared_filter_is_visible_to_everyone_but_removed_by_owner() {
+        let shared = saved_filter(1, true);
+        assert!(shared.is_visible_to(1));
+        assert!(shared.is_visible_to(2));
+        assert!(shared.can_be_removed_by(1));
+        assert!(!shared.can_be_removed_by(2));
+    }
+
+    #[test]
+    fn builds_project_key_from_name() {
+        assert_eq!(project_key_from_name("initial"), "INIT");
+        assert_eq!(project_key_from_name("Jira Clone in Rust"), "JCIR");
+        assert_eq!(project_key_from_name("2020 roadmap"), "PROJ");
+    }
+
+    #[test]
+    fn parses_issue_key() {
+        assert_eq!(parse_issue_key("JIRS-12"), Some(("JIRS".to_string(), 12)));
+        assert_eq!(parse_issue_key("jirs-12"), Some(("JIRS".to_string(), 12)));
+        assert_eq!(parse_issue_key("JIRS-0"), None);
+        assert_eq!(parse_issue_key("JIRS12"), None);
+        assert_eq!(parse_issue_key("1A-12"), None);
+    }
+}
@@ -3,7 +3,9 @@ use std::io::Write;
 use diesel::{deserialize::*, pg::*, serialize::*, *};
 
 use crate::{
-    InvitationState, IssuePriority, IssueType, MessageType, ProjectCategory, TimeTracking, UserRole,
+    CustomFieldType, InvitationState, IssueEventType, IssueLinkType, IssuePriority, IssueType,
+    MessageType, ProjectCategory, SprintState, TimeTracking, TransitionRequirement, UserRole,
+    WipLimitPolicy,
 };
 
 #[derive(SqlType)]
@@ -55,6 +57,8 @@ fn issue_type_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueType> {
         b"task" => Ok(IssueType::Task),
         b"bug" => Ok(IssueType::Bug),
         b"story" => Ok(IssueType::Story),
+        b"epic" => Ok(IssueType::Epic),
+        b"subtask" => Ok(IssueType::Subtask),
         _ => Ok(IssueType::Task),
     }
 }
@@ -76,6 +80,8 @@ impl ToSql<IssueTypeType, Pg> for IssueType {
         match *self {
             IssueType::Task => out.write_all(b"task")?,
             IssueType::Story => out.write_all(b"story")?,
+            IssueType::Epic => out.write_all(b"epic")?,
+            IssueType::Subtask => out.write_all(b"subtask")?,
             IssueType::Bug => out.write_all(b"bug")?,
         }
         Ok(IsNull::No)
@@ -255,6 +261,8 @@ fn message_type_type_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Messa
         b"received_invitation" => Ok(MessageType::ReceivedInvitation),
         b"assigned_to_issue" => Ok(MessageType::AssignedToIssue),
         b"mention" => Ok(MessageType::Mention),
+        b"issue_changed" => Ok(MessageType::IssueChanged),
+        b"issue_commented" => Ok(MessageType::IssueCommented),
         _ => Ok(MessageType::Mention),
     }
 }
@@ -277,6 +285,286 @@ impl ToSql<MessageTypeType, Pg> for MessageType {
             MessageType::ReceivedInvitation => out.write_all(b"received_invitation")?,
             MessageType::AssignedToIssue => out.write_all(b"assigned_to_issue")?,
             MessageType::Mention => out.write_all(b"mention")?,
+            MessageType::IssueChanged => out.write_all(b"issue_changed")?,
+            MessageType::IssueCommented => out.write_all(b"issue_commented")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "IssueEventTypeType")]
+pub struct IssueEventTypeType;
+
+impl diesel::query_builder::QueryId for IssueEventTypeType {
+    type QueryId = IssueEventType;
+}
+
+fn issue_event_type_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueEventType> {
+    match not_none!(bytes) {
+        b"title" => Ok(IssueEventType::Title),
+        b"type" => Ok(IssueEventType::Type),
+        b"priority" => Ok(IssueEventType::Priority),
+        b"description" => Ok(IssueEventType::Description),
+        b"status" => Ok(IssueEventType::Status),
+        b"reporter" => Ok(IssueEventType::Reporter),
+        b"assignees" => Ok(IssueEventType::Assignees),
+        b"estimate" => Ok(IssueEventType::Estimate),
+        b"time_spent" => Ok(IssueEventType::TimeSpent),
+        b"time_remaining" => Ok(IssueEventType::TimeRemaining),
+        b"epic" => Ok(IssueEventType::Epic),
+        b"parent" => Ok(IssueEventType::Parent),
+        b"labels" => Ok(IssueEventType::Labels),
+        b"sprint" => Ok(IssueEventType::Sprint),
+        b"comment_added" => Ok(IssueEventType::CommentAdded),
+        b"comment_deleted" => Ok(IssueEventType::CommentDeleted),
+        b"deleted" => Ok(IssueEventType::Deleted),
+        _ => Ok(IssueEventType::Title),
+    }
+}
+
+impl FromSql<IssueEventTypeType, Pg> for IssueEventType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueEventType> {
+        issue_event_type_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for IssueEventType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueEventType> {
+        issue_event_type_from_sql(bytes)
+    }
+}
+
+impl ToSql<IssueEventTypeType, Pg> for IssueEventType {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            IssueEventType::Title => out.write_all(b"title")?,
+            IssueEventType::Type => out.write_all(b"type")?,
+            IssueEventType::Priority => out.write_all(b"priority")?,
+            IssueEventType::Description => out.write_all(b"description")?,
+            IssueEventType::Status => out.write_all(b"status")?,
+            IssueEventType::Reporter => out.write_all(b"reporter")?,
+            IssueEventType::Assignees => out.write_all(b"assignees")?,
+            IssueEventType::Estimate => out.write_all(b"estimate")?,
+            IssueEventType::TimeSpent => out.write_all(b"time_spent")?,
+            IssueEventType::TimeRemaining => out.write_all(b"time_remaining")?,
+            IssueEventType::Epic => out.write_all(b"epic")?,
+            IssueEventType::Parent => out.write_all(b"parent")?,
+            IssueEventType::Labels => out.write_all(b"labels")?,
+            IssueEventType::Sprint => out.write_all(b"sprint")?,
+            IssueEventType::CommentAdded => out.write_all(b"comment_added")?,
+            IssueEventType::CommentDeleted => out.write_all(b"comment_deleted")?,
+            IssueEventType::Deleted => out.write_all(b"deleted")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "IssueLinkTypeType")]
+pub struct IssueLinkTypeType;
+
+impl diesel::query_builder::QueryId for IssueLinkTypeType {
+    type QueryId = IssueLinkType;
+}
+
+fn issue_link_type_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueLinkType> {
+    match not_none!(bytes) {
+        b"blocks" => Ok(IssueLinkType::Blocks),
+        b"is_blocked_by" => Ok(IssueLinkType::IsBlockedBy),
+        b"duplicates" => Ok(IssueLinkType::Duplicates),
+        b"relates_to" => Ok(IssueLinkType::RelatesTo),
+        _ => Ok(IssueLinkType::RelatesTo),
+    }
+}
+
+impl FromSql<IssueLinkTypeType, Pg> for IssueLinkType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueLinkType> {
+        issue_link_type_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for IssueLinkType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<IssueLinkType> {
+        issue_link_type_from_sql(bytes)
+    }
+}
+
+impl ToSql<IssueLinkTypeType, Pg> for IssueLinkType {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            IssueLinkType::Blocks => out.write_all(b"blocks")?,
+            IssueLinkType::IsBlockedBy => out.write_all(b"is_blocked_by")?,
+            IssueLinkType::Duplicates => out.write_all(b"duplicates")?,
+            IssueLinkType::RelatesTo => out.write_all(b"relates_to")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "SprintStateType")]
+pub struct SprintStateType;
+
+impl diesel::query_builder::QueryId for SprintStateType {
+    type QueryId = SprintState;
+}
+
+fn sprint_state_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<SprintState> {
+    match not_none!(bytes) {
+        b"planned" => Ok(SprintState::Planned),
+        b"active" => Ok(SprintState::Active),
+        b"completed" => Ok(SprintState::Completed),
+        _ => Ok(SprintState::Planned),
+    }
+}
+
+impl FromSql<SprintStateType, Pg> for SprintState {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<SprintState> {
+        sprint_state_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for SprintState {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<SprintState> {
+        sprint_state_from_sql(bytes)
+    }
+}
+
+impl ToSql<SprintStateType, Pg> for SprintState {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            SprintState::Planned => out.write_all(b"planned")?,
+            SprintState::Active => out.write_all(b"active")?,
+            SprintState::Completed => out.write_all(b"completed")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "CustomFieldTypeType")]
+pub struct CustomFieldTypeType;
+
+impl diesel::query_builder::QueryId for CustomFieldTypeType {
+    type QueryId = CustomFieldType;
+}
+
+fn custom_field_type_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<CustomFieldType> {
+    match not_none!(bytes) {
+        b"text" => Ok(CustomFieldType::Text),
+        b"number" => Ok(CustomFieldType::Number),
+        b"date" => Ok(CustomFieldType::Date),
+        b"single_select" => Ok(CustomFieldType::SingleSelect),
+        b"multi_select" => Ok(CustomFieldType::MultiSelect),
+        b"user" => Ok(CustomFieldType::User),
+        _ => Ok(CustomFieldType::Text),
+    }
+}
+
+impl FromSql<CustomFieldTypeType, Pg> for CustomFieldType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<CustomFieldType> {
+        custom_field_type_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for CustomFieldType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<CustomFieldType> {
+        custom_field_type_from_sql(bytes)
+    }
+}
+
+impl ToSql<CustomFieldTypeType, Pg> for CustomFieldType {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            CustomFieldType::Text => out.write_all(b"text")?,
+            CustomFieldType::Number => out.write_all(b"number")?,
+            CustomFieldType::Date => out.write_all(b"date")?,
+            CustomFieldType::SingleSelect => out.write_all(b"single_select")?,
+            CustomFieldType::MultiSelect => out.write_all(b"multi_select")?,
+            CustomFieldType::User => out.write_all(b"user")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "TransitionRequirementType")]
+pub struct TransitionRequirementType;
+
+impl diesel::query_builder::QueryId for TransitionRequirementType {
+    type QueryId = TransitionRequirement;
+}
+
+fn transition_requirement_from_sql(
+    bytes: Option<&[u8]>,
+) -> deserialize::Result<TransitionRequirement> {
+    match not_none!(bytes) {
+        b"assignee" => Ok(TransitionRequirement::Assignee),
+        b"estimate" => Ok(TransitionRequirement::Estimate),
+        b"time_spent" => Ok(TransitionRequirement::TimeSpent),
+        b"description" => Ok(TransitionRequirement::Description),
+        _ => Ok(TransitionRequirement::Assignee),
+    }
+}
+
+impl FromSql<TransitionRequirementType, Pg> for TransitionRequirement {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<TransitionRequirement> {
+        transition_requirement_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for TransitionRequirement {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<TransitionRequirement> {
+        transition_requirement_from_sql(bytes)
+    }
+}
+
+impl ToSql<TransitionRequirementType, Pg> for TransitionRequirement {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            TransitionRequirement::Assignee => out.write_all(b"assignee")?,
+            TransitionRequirement::Estimate => out.write_all(b"estimate")?,
+            TransitionRequirement::TimeSpent => out.write_all(b"time_spent")?,
+            TransitionRequirement::Description => out.write_all(b"description")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(SqlType)]
+#[postgres(type_name = "WipLimitPolicyType")]
+pub struct WipLimitPolicyType;
+
+impl diesel::query_builder::QueryId for WipLimitPolicyType {
+    type QueryId = WipLimitPolicy;
+}
+
+fn wip_limit_policy_from_sql(bytes: Option<&[u8]>) -> deserialize::Result<WipLimitPolicy> {
+    match not_none!(bytes) {
+        b"warn" => Ok(WipLimitPolicy::Warn),
+        b"block" => Ok(WipLimitPolicy::Block),
+        _ => Ok(WipLimitPolicy::Warn),
+    }
+}
+
+impl FromSql<WipLimitPolicyType, Pg> for WipLimitPolicy {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<WipLimitPolicy> {
+        wip_limit_policy_from_sql(bytes)
+    }
+}
+
+impl FromSql<sql_types::Text, Pg> for WipLimitPolicy {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<WipLimitPolicy> {
+        wip_limit_policy_from_sql(bytes)
+    }
+}
+
+impl ToSql<WipLimitPolicyType, Pg> for WipLimitPolicy {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match *self {
+            WipLimitPolicy::Warn => out.write_all(b"warn")?,
+            WipLimitPolicy::Block => out.write_all(b"block")?,
         }
         Ok(IsNull::No)
     }
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issue_events;
+DROP TYPE IF EXISTS "IssueEventTypeType";
@@ -0,0 +1,29 @@
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted'
+);
+
+CREATE TABLE issue_events (
+    id serial primary key not null,
+    issue_id integer not null,
+    project_id integer not null references projects (id),
+    user_id integer not null references users (id),
+    event_type "IssueEventTypeType" not null,
+    old_value text,
+    new_value text,
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+CREATE INDEX issue_events_issue_id_index ON issue_events (issue_id);
@@ -0,0 +1,39 @@
+ALTER TABLE issues DROP COLUMN IF EXISTS epic_id;
+
+DELETE FROM issue_events WHERE event_type = 'epic';
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
+
+UPDATE issues SET issue_type = 'story' WHERE issue_type = 'epic';
+ALTER TYPE "IssueTypeType" RENAME TO "IssueTypeTypeOld";
+CREATE TYPE "IssueTypeType" AS ENUM (
+    'task',
+    'bug',
+    'story'
+);
+ALTER TABLE issues ALTER COLUMN issue_type DROP DEFAULT;
+ALTER TABLE issues
+ALTER COLUMN issue_type
+SET DATA TYPE "IssueTypeType"
+USING issue_type::text::"IssueTypeType";
+ALTER TABLE issues ALTER COLUMN issue_type SET DEFAULT 'task';
+DROP TYPE "IssueTypeTypeOld";
@@ -0,0 +1,39 @@
+ALTER TYPE "IssueTypeType" RENAME TO "IssueTypeTypeOld";
+CREATE TYPE "IssueTypeType" AS ENUM (
+    'task',
+    'bug',
+    'story',
+    'epic'
+);
+ALTER TABLE issues ALTER COLUMN issue_type DROP DEFAULT;
+ALTER TABLE issues
+ALTER COLUMN issue_type
+SET DATA TYPE "IssueTypeType"
+USING issue_type::text::"IssueTypeType";
+ALTER TABLE issues ALTER COLUMN issue_type SET DEFAULT 'task';
+DROP TYPE "IssueTypeTypeOld";
+
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted',
+    'epic'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
+
+ALTER TABLE issues ADD COLUMN epic_id integer references issues (id);
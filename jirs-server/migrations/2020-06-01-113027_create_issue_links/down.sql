@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issue_links;
+DROP TYPE IF EXISTS "IssueLinkTypeType";
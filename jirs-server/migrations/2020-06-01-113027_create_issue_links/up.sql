@@ -0,0 +1,19 @@
+CREATE TYPE "IssueLinkTypeType" AS ENUM (
+    'blocks',
+    'is_blocked_by',
+    'duplicates',
+    'relates_to'
+);
+
+CREATE TABLE issue_links (
+    id serial primary key not null,
+    source_issue_id integer not null references issues (id),
+    target_issue_id integer not null references issues (id),
+    link_type "IssueLinkTypeType" not null,
+    user_id integer not null references users (id),
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (source_issue_id, target_issue_id, link_type)
+);
+
+CREATE INDEX issue_links_target_issue_id_index ON issue_links (target_issue_id);
@@ -0,0 +1,27 @@
+DELETE FROM issue_events WHERE event_type = 'labels';
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted',
+    'epic',
+    'parent'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
+
+DROP TABLE IF EXISTS issue_labels CASCADE;
+DROP TABLE IF EXISTS labels CASCADE;
@@ -0,0 +1,42 @@
+CREATE TABLE labels (
+    id serial primary key not null,
+    project_id integer not null references projects (id),
+    name text not null,
+    color text not null default '#0052cc',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (project_id, name)
+);
+
+CREATE TABLE issue_labels (
+    id serial primary key not null,
+    issue_id integer not null references issues (id),
+    label_id integer not null references labels (id),
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted',
+    'epic',
+    'parent',
+    'labels'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
@@ -0,0 +1,31 @@
+DELETE FROM issue_events WHERE event_type = 'sprint';
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted',
+    'epic',
+    'parent',
+    'labels'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
+
+DROP INDEX IF EXISTS issues_sprint_id_index;
+ALTER TABLE issues DROP COLUMN IF EXISTS sprint_id;
+
+DROP TABLE IF EXISTS sprints;
+DROP TYPE IF EXISTS "SprintStateType";
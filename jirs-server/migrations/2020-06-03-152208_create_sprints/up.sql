@@ -0,0 +1,49 @@
+DROP TYPE IF EXISTS "SprintStateType" CASCADE;
+CREATE TYPE "SprintStateType" AS ENUM (
+    'planned',
+    'active',
+    'completed'
+);
+
+CREATE TABLE sprints (
+    id serial primary key not null,
+    project_id integer not null references projects (id),
+    name text not null,
+    goal text,
+    starts_at date,
+    ends_at date,
+    state "SprintStateType" not null default 'planned',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+CREATE INDEX sprints_project_id_index ON sprints (project_id);
+
+ALTER TABLE issues ADD COLUMN sprint_id integer references sprints (id);
+CREATE INDEX issues_sprint_id_index ON issues (sprint_id);
+
+ALTER TYPE "IssueEventTypeType" RENAME TO "IssueEventTypeTypeOld";
+CREATE TYPE "IssueEventTypeType" AS ENUM (
+    'title',
+    'type',
+    'priority',
+    'description',
+    'status',
+    'reporter',
+    'assignees',
+    'estimate',
+    'time_spent',
+    'time_remaining',
+    'comment_added',
+    'comment_deleted',
+    'deleted',
+    'epic',
+    'parent',
+    'labels',
+    'sprint'
+);
+ALTER TABLE issue_events
+ALTER COLUMN event_type
+SET DATA TYPE "IssueEventTypeType"
+USING event_type::text::"IssueEventTypeType";
+DROP TYPE "IssueEventTypeTypeOld";
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS issue_custom_field_values CASCADE;
+DROP TABLE IF EXISTS custom_fields CASCADE;
+DROP TYPE IF EXISTS "CustomFieldTypeType" CASCADE;
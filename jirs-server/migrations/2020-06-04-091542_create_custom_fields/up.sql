@@ -0,0 +1,30 @@
+DROP TYPE IF EXISTS "CustomFieldTypeType" CASCADE;
+CREATE TYPE "CustomFieldTypeType" AS ENUM (
+    'text',
+    'number',
+    'date',
+    'single_select',
+    'multi_select',
+    'user'
+);
+
+CREATE TABLE custom_fields (
+    id serial primary key not null,
+    project_id integer not null references projects (id),
+    name text not null,
+    field_type "CustomFieldTypeType" not null default 'text',
+    options text[] not null default '{}',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (project_id, name)
+);
+
+CREATE TABLE issue_custom_field_values (
+    id serial primary key not null,
+    issue_id integer not null references issues (id),
+    custom_field_id integer not null references custom_fields (id),
+    field_values text[] not null default '{}',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (issue_id, custom_field_id)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issue_status_transitions CASCADE;
+DROP TYPE IF EXISTS "TransitionRequirementType" CASCADE;
@@ -0,0 +1,18 @@
+DROP TYPE IF EXISTS "TransitionRequirementType" CASCADE;
+CREATE TYPE "TransitionRequirementType" AS ENUM (
+    'assignee',
+    'estimate',
+    'time_spent',
+    'description'
+);
+
+CREATE TABLE issue_status_transitions (
+    id serial primary key not null,
+    project_id integer not null references projects (id),
+    from_status_id integer not null references issue_statuses (id),
+    to_status_id integer not null references issue_statuses (id),
+    required_field "TransitionRequirementType",
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (from_status_id, to_status_id)
+);
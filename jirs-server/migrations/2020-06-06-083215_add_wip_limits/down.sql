@@ -0,0 +1,4 @@
+ALTER TABLE issue_statuses DROP COLUMN wip_limit;
+ALTER TABLE projects DROP COLUMN wip_limit_policy;
+
+DROP TYPE IF EXISTS "WipLimitPolicyType";
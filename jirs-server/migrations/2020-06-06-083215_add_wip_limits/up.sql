@@ -0,0 +1,7 @@
+CREATE TYPE "WipLimitPolicyType" AS ENUM (
+    'warn',
+    'block'
+);
+
+ALTER TABLE projects ADD COLUMN wip_limit_policy "WipLimitPolicyType" NOT NULL DEFAULT 'warn';
+ALTER TABLE issue_statuses ADD COLUMN wip_limit integer;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS issues_project_number;
+ALTER TABLE issues DROP COLUMN number;
+DROP INDEX IF EXISTS projects_key;
+ALTER TABLE projects DROP COLUMN key;
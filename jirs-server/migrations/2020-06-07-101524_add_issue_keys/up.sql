@@ -0,0 +1,14 @@
+ALTER TABLE projects ADD COLUMN key text;
+UPDATE projects SET key = 'P' || id;
+ALTER TABLE projects ALTER COLUMN key SET NOT NULL;
+CREATE UNIQUE INDEX projects_key ON projects (key);
+
+ALTER TABLE issues ADD COLUMN number integer;
+UPDATE issues SET number = numbered.number
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY project_id ORDER BY id) AS number
+    FROM issues
+) AS numbered
+WHERE issues.id = numbered.id;
+ALTER TABLE issues ALTER COLUMN number SET NOT NULL;
+CREATE UNIQUE INDEX issues_project_number ON issues (project_id, number);
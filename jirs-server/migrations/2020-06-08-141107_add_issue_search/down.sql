@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS issues_search_vector;
+DROP TRIGGER IF EXISTS comments_search_vector_update ON comments;
+DROP FUNCTION IF EXISTS comments_search_vector_trigger();
+DROP TRIGGER IF EXISTS issues_search_vector_update ON issues;
+DROP FUNCTION IF EXISTS issues_search_vector_trigger();
+DROP FUNCTION IF EXISTS issue_search_vector(text, text, integer);
+ALTER TABLE comments DROP COLUMN body_text;
+ALTER TABLE issues DROP COLUMN search_vector;
@@ -0,0 +1,49 @@
+ALTER TABLE issues ADD COLUMN search_vector tsvector;
+ALTER TABLE comments ADD COLUMN body_text text;
+
+UPDATE comments SET body_text = regexp_replace(body, '<[^>]*>', ' ', 'g');
+
+CREATE OR REPLACE FUNCTION issue_search_vector(issue_title text, issue_description text, target_issue_id integer)
+RETURNS tsvector AS $$
+    SELECT setweight(to_tsvector('english', coalesce(issue_title, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(issue_description, '')), 'B')
+        || setweight(to_tsvector('english', coalesce(
+            (SELECT string_agg(body_text, ' ') FROM comments WHERE comments.issue_id = target_issue_id),
+            ''
+        )), 'C');
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION issues_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := issue_search_vector(NEW.title, NEW.description_text, NEW.id);
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER issues_search_vector_update
+    BEFORE INSERT OR UPDATE OF title, description_text ON issues
+    FOR EACH ROW EXECUTE PROCEDURE issues_search_vector_trigger();
+
+CREATE OR REPLACE FUNCTION comments_search_vector_trigger() RETURNS trigger AS $$
+DECLARE
+    target_issue_id integer;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target_issue_id := OLD.issue_id;
+    ELSE
+        target_issue_id := NEW.issue_id;
+    END IF;
+    UPDATE issues
+    SET search_vector = issue_search_vector(title, description_text, id)
+    WHERE id = target_issue_id;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER comments_search_vector_update
+    AFTER INSERT OR UPDATE OR DELETE ON comments
+    FOR EACH ROW EXECUTE PROCEDURE comments_search_vector_trigger();
+
+UPDATE issues SET search_vector = issue_search_vector(title, description_text, id);
+
+CREATE INDEX issues_search_vector ON issues USING GIN (search_vector);
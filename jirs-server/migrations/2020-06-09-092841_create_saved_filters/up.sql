@@ -0,0 +1,16 @@
+CREATE TABLE saved_filters (
+    id serial primary key not null,
+    project_id integer not null references projects (id),
+    user_id integer not null references users (id),
+    name text not null,
+    shared boolean not null default false,
+    text_filter text not null default '',
+    user_ids integer[] not null default '{}',
+    label_ids integer[] not null default '{}',
+    only_my boolean not null default false,
+    recently_updated boolean not null default false,
+    jql text not null default '',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (project_id, user_id, name)
+);
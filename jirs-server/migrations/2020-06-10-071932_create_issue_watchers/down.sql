@@ -0,0 +1,14 @@
+DELETE FROM messages WHERE message_type IN ('issue_changed', 'issue_commented');
+ALTER TYPE "MessageTypeType" RENAME TO "MessageTypeTypeOld";
+CREATE TYPE "MessageTypeType" AS ENUM (
+    'received_invitation',
+    'assigned_to_issue',
+    'mention'
+);
+ALTER TABLE messages
+ALTER COLUMN message_type
+SET DATA TYPE "MessageTypeType"
+USING message_type::text::"MessageTypeType";
+DROP TYPE "MessageTypeTypeOld";
+
+DROP TABLE IF EXISTS issue_watchers CASCADE;
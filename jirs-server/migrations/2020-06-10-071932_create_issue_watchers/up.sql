@@ -0,0 +1,29 @@
+CREATE TABLE issue_watchers (
+    id serial primary key not null,
+    issue_id integer not null references issues (id),
+    user_id integer not null references users (id),
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (issue_id, user_id)
+);
+
+INSERT INTO issue_watchers (issue_id, user_id)
+SELECT id, reporter_id FROM issues
+UNION
+SELECT issue_id, user_id FROM issue_assignees
+UNION
+SELECT issue_id, user_id FROM comments;
+
+ALTER TYPE "MessageTypeType" RENAME TO "MessageTypeTypeOld";
+CREATE TYPE "MessageTypeType" AS ENUM (
+    'received_invitation',
+    'assigned_to_issue',
+    'mention',
+    'issue_changed',
+    'issue_commented'
+);
+ALTER TABLE messages
+ALTER COLUMN message_type
+SET DATA TYPE "MessageTypeType"
+USING message_type::text::"MessageTypeType";
+DROP TYPE "MessageTypeTypeOld";
@@ -0,0 +1,15 @@
+CREATE TABLE worklogs (
+    id serial primary key not null,
+    issue_id integer not null references issues (id),
+    user_id integer not null references users (id),
+    time_spent integer not null,
+    started_at date not null default now(),
+    comment text not null default '',
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+INSERT INTO worklogs (issue_id, user_id, time_spent, started_at)
+SELECT id, reporter_id, time_spent, updated_at::date
+FROM issues
+WHERE time_spent > 0;
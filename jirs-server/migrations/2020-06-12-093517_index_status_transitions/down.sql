@@ -0,0 +1 @@
+DROP INDEX IF EXISTS issue_events_status_transitions_index;
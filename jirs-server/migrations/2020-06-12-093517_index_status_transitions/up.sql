@@ -0,0 +1,3 @@
+-- status changes are replayed by cumulative flow and burndown reports
+CREATE INDEX issue_events_status_transitions_index ON issue_events (project_id, created_at)
+    WHERE event_type = 'status';
@@ -2,7 +2,7 @@ BEGIN;
 
 SAVEPOINT seed;
 
-insert into projects (name) values ('initial'), ('second'), ('third');
+insert into projects (name, key) values ('initial', 'INIT'), ('second', 'SECO'), ('third', 'THIR');
 
 insert into issue_statuses (name, project_id, position)
 values ('backlog', 1, 1), ('selected', 1, 2), ('in_progress', 1, 3), ('done', 1, 4);
@@ -86,7 +86,8 @@ insert into issues(
     description_text,
     reporter_id,
     project_id,
-    issue_status_id
+    issue_status_id,
+    number
 ) values (
     'Foo',
     'task',
@@ -96,6 +97,7 @@ insert into issues(
     'foz baz',
     1,
     1,
+    1,
     1
 ), (
     'Foo2',
@@ -106,6 +108,7 @@ insert into issues(
     'foz baz 2',
     1,
     1,
+    2,
     2
 ), (
     'Foo3',
@@ -116,6 +119,7 @@ insert into issues(
     'foz baz 3',
     2,
     1,
+    3,
     3
 );
 insert into comments (user_id, issue_id, body) values (
@@ -0,0 +1,488 @@
+//! Archived rows keep ids of source instance. Restore inserts rows in order of
+//! their dependencies and rewrites every reference using ids of rows which
+//! were already created.
+
+use std::collections::HashMap;
+
+use jirs_data::{
+    Comment, CustomFieldId, CustomFieldType, Invitation, IssueAssignee, IssueCustomFieldValue,
+    IssueEvent, IssueEventType, IssueId, IssueLabel, IssueLink, IssueStatusTransition, ProjectId,
+    UserProject, Worklog,
+};
+
+use crate::backup::{ArchivedWatcher, ProjectArchive};
+use crate::errors::ServiceErrors;
+use crate::models::Issue;
+
+/// Archived id to id in this database
+pub struct IdMap {
+    name: &'static str,
+    ids: HashMap<i32, i32>,
+}
+
+impl IdMap {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ids: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, archived: i32, restored: i32) {
+        self.ids.insert(archived, restored);
+    }
+
+    pub fn get(&self, archived: i32) -> Result<i32, ServiceErrors> {
+        self.ids.get(&archived).copied().ok_or_else(|| {
+            ServiceErrors::RecordNotFound(format!("archived {} {}", self.name, archived))
+        })
+    }
+
+    pub fn get_optional(&self, archived: Option<i32>) -> Result<Option<i32>, ServiceErrors> {
+        match archived {
+            Some(archived) => self.get(archived).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Rewrites comma separated ids, ids of rows which no longer exist are
+    /// dropped
+    fn rewrite(&self, value: &str) -> String {
+        value
+            .split(',')
+            .filter_map(|id| id.trim().parse::<i32>().ok())
+            .filter_map(|id| self.ids.get(&id))
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// Ids of rows restored so far
+pub struct ArchiveIds {
+    pub project_id: ProjectId,
+    pub statuses: IdMap,
+    pub labels: IdMap,
+    pub sprints: IdMap,
+    pub custom_fields: IdMap,
+    pub users: IdMap,
+    pub issues: IdMap,
+    /// Archived ids of fields which store user ids as values
+    user_fields: Vec<CustomFieldId>,
+}
+
+impl ArchiveIds {
+    pub fn new(project_id: ProjectId, archive: &ProjectArchive) -> Self {
+        Self {
+            project_id,
+            statuses: IdMap::new("status"),
+            labels: IdMap::new("label"),
+            sprints: IdMap::new("sprint"),
+            custom_fields: IdMap::new("custom field"),
+            users: IdMap::new("user"),
+            issues: IdMap::new("issue"),
+            user_fields: archive
+                .custom_fields
+                .iter()
+                .filter(|field| field.field_type == CustomFieldType::User)
+                .map(|field| field.id)
+                .collect(),
+        }
+    }
+
+    pub fn transition(
+        &self,
+        transition: &IssueStatusTransition,
+    ) -> Result<IssueStatusTransition, ServiceErrors> {
+        Ok(IssueStatusTransition {
+            project_id: self.project_id,
+            from_status_id: self.statuses.get(transition.from_status_id)?,
+            to_status_id: self.statuses.get(transition.to_status_id)?,
+            ..transition.clone()
+        })
+    }
+
+    pub fn member(&self, member: &UserProject) -> Result<UserProject, ServiceErrors> {
+        Ok(UserProject {
+            user_id: self.users.get(member.user_id)?,
+            project_id: self.project_id,
+            ..member.clone()
+        })
+    }
+
+    /// Epic and parent are set by `issue_relations` once every issue exists,
+    /// time spent is summed from restored worklogs.
+    pub fn issue(&self, issue: &Issue) -> Result<Issue, ServiceErrors> {
+        Ok(Issue {
+            reporter_id: self.users.get(issue.reporter_id)?,
+            project_id: self.project_id,
+            issue_status_id: self.statuses.get(issue.issue_status_id)?,
+            sprint_id: self.sprints.get_optional(issue.sprint_id)?,
+            epic_id: None,
+            parent_id: None,
+            time_spent: None,
+            ..issue.clone()
+        })
+    }
+
+    /// Restored issue id with its epic and parent
+    pub fn issue_relations(
+        &self,
+        issue: &Issue,
+    ) -> Result<(IssueId, Option<IssueId>, Option<IssueId>), ServiceErrors> {
+        Ok((
+            self.issues.get(issue.id)?,
+            self.issues.get_optional(issue.epic_id)?,
+            self.issues.get_optional(issue.parent_id)?,
+        ))
+    }
+
+    pub fn assignee(&self, assignee: &IssueAssignee) -> Result<IssueAssignee, ServiceErrors> {
+        Ok(IssueAssignee {
+            issue_id: self.issues.get(assignee.issue_id)?,
+            user_id: self.users.get(assignee.user_id)?,
+            ..assignee.clone()
+        })
+    }
+
+    pub fn issue_label(&self, issue_label: &IssueLabel) -> Result<IssueLabel, ServiceErrors> {
+        Ok(IssueLabel {
+            issue_id: self.issues.get(issue_label.issue_id)?,
+            label_id: self.labels.get(issue_label.label_id)?,
+            ..issue_label.clone()
+        })
+    }
+
+    pub fn custom_value(
+        &self,
+        value: &IssueCustomFieldValue,
+    ) -> Result<IssueCustomFieldValue, ServiceErrors> {
+        let values = if self.user_fields.contains(&value.custom_field_id) {
+            value
+                .values
+                .iter()
+                .map(|user_id| self.users.rewrite(user_id))
+                .filter(|user_id| !user_id.is_empty())
+                .collect()
+        } else {
+            value.values.clone()
+        };
+        Ok(IssueCustomFieldValue {
+            issue_id: self.issues.get(value.issue_id)?,
+            custom_field_id: self.custom_fields.get(value.custom_field_id)?,
+            values,
+            ..value.clone()
+        })
+    }
+
+    pub fn link(&self, link: &IssueLink) -> Result<IssueLink, ServiceErrors> {
+        Ok(IssueLink {
+            source_issue_id: self.issues.get(link.source_issue_id)?,
+            target_issue_id: self.issues.get(link.target_issue_id)?,
+            user_id: self.users.get(link.user_id)?,
+            ..link.clone()
+        })
+    }
+
+    pub fn watcher(&self, watcher: &ArchivedWatcher) -> Result<ArchivedWatcher, ServiceErrors> {
+        Ok(ArchivedWatcher {
+            issue_id: self.issues.get(watcher.issue_id)?,
+            user_id: self.users.get(watcher.user_id)?,
+        })
+    }
+
+    pub fn comment(&self, comment: &Comment) -> Result<Comment, ServiceErrors> {
+        Ok(Comment {
+            user_id: self.users.get(comment.user_id)?,
+            issue_id: self.issues.get(comment.issue_id)?,
+            ..comment.clone()
+        })
+    }
+
+    pub fn worklog(&self, worklog: &Worklog) -> Result<Worklog, ServiceErrors> {
+        Ok(Worklog {
+            issue_id: self.issues.get(worklog.issue_id)?,
+            user_id: self.users.get(worklog.user_id)?,
+            ..worklog.clone()
+        })
+    }
+
+    /// Values of events which store ids are rewritten too, so history and
+    /// reports built from it point to restored rows
+    pub fn event(&self, event: &IssueEvent) -> Result<IssueEvent, ServiceErrors> {
+        let ids = match event.event_type {
+            IssueEventType::Status => Some(&self.statuses),
+            IssueEventType::Reporter | IssueEventType::Assignees => Some(&self.users),
+            IssueEventType::Labels => Some(&self.labels),
+            IssueEventType::Epic | IssueEventType::Parent => Some(&self.issues),
+            IssueEventType::Sprint => Some(&self.sprints),
+            _ => None,
+        };
+        // empty list is value too, single missing id is not
+        let is_list = matches!(
+            event.event_type,
+            IssueEventType::Assignees | IssueEventType::Labels
+        );
+        let rewrite = |value: &Option<String>| match (ids, value) {
+            (Some(ids), Some(value)) => {
+                Some(ids.rewrite(value.as_str())).filter(|restored| is_list || !restored.is_empty())
+            }
+            _ => value.clone(),
+        };
+        Ok(IssueEvent {
+            issue_id: self.issues.get(event.issue_id)?,
+            project_id: self.project_id,
+            user_id: self.users.get(event.user_id)?,
+            old_value: rewrite(&event.old_value),
+            new_value: rewrite(&event.new_value),
+            ..event.clone()
+        })
+    }
+
+    /// Invitation gets new token, links sent from source instance stop working
+    pub fn invitation(&self, invitation: &Invitation) -> Result<Invitation, ServiceErrors> {
+        Ok(Invitation {
+            project_id: self.project_id,
+            invited_by_id: self.users.get(invitation.invited_by_id)?,
+            bind_token: uuid::Uuid::new_v4(),
+            ..invitation.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::tests::archive;
+
+    use super::*;
+
+    /// Remaps archive the same way restore does, every created row gets
+    /// archived id + 100 and user 7 is matched with existing user 1
+    fn restore(archive: &ProjectArchive) -> (ProjectArchive, ArchiveIds) {
+        let mut ids = ArchiveIds::new(103, archive);
+        for status in archive.statuses.iter() {
+            ids.statuses.insert(status.id, status.id + 100);
+        }
+        for label in archive.labels.iter() {
+            ids.labels.insert(label.id, label.id + 100);
+        }
+        for sprint in archive.sprints.iter() {
+            ids.sprints.insert(sprint.id, sprint.id + 100);
+        }
+        for field in archive.custom_fields.iter() {
+            ids.custom_fields.insert(field.id, field.id + 100);
+        }
+        ids.users.insert(7, 1);
+        ids.users.insert(8, 108);
+        let mut issues = vec![];
+        for issue in archive.issues.iter() {
+            ids.issues.insert(issue.id, issue.id + 100);
+            issues.push(Issue {
+                id: issue.id + 100,
+                ..ids.issue(issue).unwrap()
+            });
+        }
+        for issue in issues.iter_mut() {
+            let archived = archive.issues.iter().find(|a| a.id + 100 == issue.id);
+            let (_, epic_id, parent_id) = ids.issue_relations(archived.unwrap()).unwrap();
+            issue.epic_id = epic_id;
+            issue.parent_id = parent_id;
+        }
+        let restored = ProjectArchive {
+            version: archive.version,
+            exported_at: archive.exported_at,
+            project: archive.project.clone(),
+            statuses: vec![],
+            transitions: archive
+                .transitions
+                .iter()
+                .map(|t| ids.transition(t).unwrap())
+                .collect(),
+            labels: vec![],
+            sprints: vec![],
+            custom_fields: vec![],
+            users: vec![],
+            members: archive
+                .members
+                .iter()
+                .map(|m| ids.member(m).unwrap())
+                .collect(),
+            issues,
+            assignees: archive
+                .assignees
+                .iter()
+                .map(|a| ids.assignee(a).unwrap())
+                .collect(),
+            issue_labels: archive
+                .issue_labels
+                .iter()
+                .map(|l| ids.issue_label(l).unwrap())
+                .collect(),
+            custom_values: archive
+                .custom_values
+                .iter()
+                .map(|v| ids.custom_value(v).unwrap())
+                .collect(),
+            links: archive.links.iter().map(|l| ids.link(l).unwrap()).collect(),
+            watchers: archive
+                .watchers
+                .iter()
+                .map(|w| ids.watcher(w).unwrap())
+                .collect(),
+            comments: archive
+                .comments
+                .iter()
+                .map(|c| ids.comment(c).unwrap())
+                .collect(),
+            worklogs: archive
+                .worklogs
+                .iter()
+                .map(|w| ids.worklog(w).unwrap())
+                .collect(),
+            events: archive
+                .events
+                .iter()
+                .map(|e| ids.event(e).unwrap())
+                .collect(),
+            invitations: archive
+                .invitations
+                .iter()
+                .map(|i| ids.invitation(i).unwrap())
+                .collect(),
+            avatars: vec![],
+        };
+        (restored, ids)
+    }
+
+    #[test]
+    fn restored_rows_point_to_restored_records() {
+        let archive = archive();
+        let (restored, _) = restore(&archive);
+
+        let relations: Vec<_> = restored
+            .issues
+            .iter()
+            .map(|i| (i.id, i.epic_id, i.parent_id, i.sprint_id))
+            .collect();
+        assert_eq!(
+            relations,
+            vec![
+                (111, None, None, None),
+                (112, Some(111), None, Some(150)),
+                (113, None, Some(112), None),
+            ]
+        );
+        assert!(restored
+            .issues
+            .iter()
+            .all(|i| i.project_id == 103 && i.reporter_id == 1 && i.issue_status_id == 120));
+        assert_eq!(
+            (
+                restored.transitions[0].from_status_id,
+                restored.transitions[0].to_status_id
+            ),
+            (120, 121)
+        );
+        assert_eq!(
+            (restored.members[0].user_id, restored.members[0].project_id),
+            (1, 103)
+        );
+        assert_eq!(
+            (
+                restored.assignees[0].issue_id,
+                restored.assignees[0].user_id
+            ),
+            (112, 1)
+        );
+        assert_eq!(
+            (
+                restored.issue_labels[0].issue_id,
+                restored.issue_labels[0].label_id
+            ),
+            (112, 140)
+        );
+        assert_eq!(restored.custom_values[0].custom_field_id, 160);
+        assert_eq!(restored.custom_values[0].values, vec!["108".to_string()]);
+        assert_eq!(
+            (
+                restored.links[0].source_issue_id,
+                restored.links[0].target_issue_id
+            ),
+            (113, 111)
+        );
+        assert_eq!(
+            restored.watchers,
+            vec![ArchivedWatcher {
+                issue_id: 112,
+                user_id: 1
+            }]
+        );
+        assert_eq!(restored.comments[0].body, archive.comments[0].body);
+        assert_eq!(
+            (restored.comments[0].issue_id, restored.comments[0].user_id),
+            (112, 1)
+        );
+    }
+
+    #[test]
+    fn restored_time_spent_is_sum_of_worklogs() {
+        let archive = archive();
+        let (restored, _) = restore(&archive);
+        assert!(restored.issues.iter().all(|i| i.time_spent.is_none()));
+        for issue in archive.issues.iter() {
+            let spent: i32 = restored
+                .worklogs
+                .iter()
+                .filter(|w| w.issue_id == issue.id + 100)
+                .map(|w| w.time_spent)
+                .sum();
+            assert_eq!(Some(spent).filter(|n| *n > 0), issue.time_spent);
+        }
+    }
+
+    #[test]
+    fn rewrites_ids_stored_in_history() {
+        let archive = archive();
+        let (restored, _) = restore(&archive);
+        let values: Vec<(Option<&str>, Option<&str>)> = restored
+            .events
+            .iter()
+            .map(|e| (e.old_value.as_deref(), e.new_value.as_deref()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (Some("120"), Some("121")),
+                (Some(""), Some("1,108")),
+                (Some(""), Some("140")),
+                (None, Some("150")),
+                (Some("Old"), Some("Issue 12")),
+            ]
+        );
+        assert!(restored
+            .events
+            .iter()
+            .all(|e| e.issue_id == 112 && e.project_id == 103 && e.user_id == 1));
+    }
+
+    #[test]
+    fn restored_invitation_gets_new_token() {
+        let archive = archive();
+        let (restored, _) = restore(&archive);
+        let (archived, invitation) = (&archive.invitations[0], &restored.invitations[0]);
+        assert_ne!(invitation.bind_token, archived.bind_token);
+        assert_eq!(invitation.email, archived.email);
+        assert_eq!((invitation.project_id, invitation.invited_by_id), (103, 1));
+    }
+
+    #[test]
+    fn unknown_reference_fails_restore() {
+        let mut archive = archive();
+        let (_, ids) = restore(&archive);
+        archive.issues[1].sprint_id = Some(51);
+        match ids.issue(&archive.issues[1]) {
+            Err(ServiceErrors::RecordNotFound(name)) => assert_eq!(name, "archived sprint 51"),
+            res => panic!("expected missing sprint, got {:?}", res),
+        }
+    }
+}
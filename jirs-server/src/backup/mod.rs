@@ -0,0 +1,547 @@
+//! Project archive used to move project between JIRS instances. Archive is
+//! JSON document with format version, restore refuses archives written by
+//! newer version of server.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    Comment, CustomField, CustomFieldType, Invitation, IssueAssignee, IssueCustomFieldValue,
+    IssueEvent, IssueEventType, IssueId, IssueLabel, IssueLink, IssueStatus, IssueStatusTransition,
+    Label, Project, Sprint, User, UserId, UserProject, Worklog,
+};
+
+use crate::db::project_backup::{export_project, restore_project};
+use crate::models::Issue;
+
+pub mod ids;
+
+/// Incremented whenever archive layout changes
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const EXPORT_USAGE: &str =
+    "Usage: jirs_server export-project <project key> <archive file> [--with-avatars]";
+const RESTORE_USAGE: &str = "Usage: jirs_server restore-project <archive file>";
+
+/// Avatar file from local storage, data is hex encoded
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedAvatar {
+    pub user_id: UserId,
+    pub file_name: String,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedWatcher {
+    pub issue_id: IssueId,
+    pub user_id: UserId,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProjectArchive {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    pub project: Project,
+    pub statuses: Vec<IssueStatus>,
+    pub transitions: Vec<IssueStatusTransition>,
+    pub labels: Vec<Label>,
+    pub sprints: Vec<Sprint>,
+    pub custom_fields: Vec<CustomField>,
+    /// Members and every user referenced by any other archived row
+    pub users: Vec<User>,
+    pub members: Vec<UserProject>,
+    pub issues: Vec<Issue>,
+    pub assignees: Vec<IssueAssignee>,
+    pub issue_labels: Vec<IssueLabel>,
+    pub custom_values: Vec<IssueCustomFieldValue>,
+    pub links: Vec<IssueLink>,
+    pub watchers: Vec<ArchivedWatcher>,
+    pub comments: Vec<Comment>,
+    pub worklogs: Vec<Worklog>,
+    /// History of archived issues, history of deleted issues is skipped
+    pub events: Vec<IssueEvent>,
+    pub invitations: Vec<Invitation>,
+    #[serde(default)]
+    pub avatars: Vec<ArchivedAvatar>,
+}
+
+impl ProjectArchive {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| "File is not project archive".to_string())?;
+        if version > ARCHIVE_VERSION as u64 {
+            return Err(format!(
+                "Archive version {} is newer than supported version {}",
+                version, ARCHIVE_VERSION
+            ));
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    /// Ids of users referenced by archived rows, including users stored in
+    /// history values and in values of user custom fields
+    pub fn referenced_user_ids(&self) -> Vec<UserId> {
+        let user_fields: Vec<i32> = self
+            .custom_fields
+            .iter()
+            .filter(|field| field.field_type == CustomFieldType::User)
+            .map(|field| field.id)
+            .collect();
+        let mut user_ids: Vec<UserId> = self
+            .members
+            .iter()
+            .map(|member| member.user_id)
+            .chain(self.issues.iter().map(|issue| issue.reporter_id))
+            .chain(self.assignees.iter().map(|assignee| assignee.user_id))
+            .chain(self.links.iter().map(|link| link.user_id))
+            .chain(self.watchers.iter().map(|watcher| watcher.user_id))
+            .chain(self.comments.iter().map(|comment| comment.user_id))
+            .chain(self.worklogs.iter().map(|worklog| worklog.user_id))
+            .chain(self.events.iter().map(|event| event.user_id))
+            .chain(
+                self.invitations
+                    .iter()
+                    .map(|invitation| invitation.invited_by_id),
+            )
+            .collect();
+        for value in self.custom_values.iter() {
+            if user_fields.contains(&value.custom_field_id) {
+                user_ids.extend(value.values.iter().filter_map(|v| v.parse::<i32>().ok()));
+            }
+        }
+        for event in self.events.iter() {
+            if let IssueEventType::Reporter | IssueEventType::Assignees = event.event_type {
+                for value in event.old_value.iter().chain(event.new_value.iter()) {
+                    user_ids.extend(value.split(',').filter_map(|id| id.parse::<i32>().ok()));
+                }
+            }
+        }
+        user_ids.sort();
+        user_ids.dedup();
+        user_ids
+    }
+}
+
+/// Admin command which writes project with given key to archive file
+pub fn run_export(args: &[String]) -> Result<(), String> {
+    let (project_key, path, with_avatars) = match args {
+        [project_key, path] => (project_key, path, false),
+        [project_key, path, flag] if flag == "--with-avatars" => (project_key, path, true),
+        _ => return Err(EXPORT_USAGE.to_string()),
+    };
+    let pool = crate::db::build_pool();
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let mut archive = export_project(&conn, project_key.as_str())
+        .map_err(|e| format!("Export failed. {:?}", e))?;
+    if with_avatars {
+        archive.avatars = read_avatars(&archive.users)?;
+    }
+    std::fs::write(path, archive.to_json()?).map_err(|e| format!("{}. {}", path, e))?;
+    println!(
+        "Exported project {} with {} issues and {} avatars to {}",
+        archive.project.key,
+        archive.issues.len(),
+        archive.avatars.len(),
+        path
+    );
+    Ok(())
+}
+
+/// Admin command which creates project from archive file
+pub fn run_restore(args: &[String]) -> Result<(), String> {
+    let path = match args {
+        [path] => path,
+        _ => return Err(RESTORE_USAGE.to_string()),
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}. {}", path, e))?;
+    let archive = ProjectArchive::from_json(text.as_str())?;
+    let pool = crate::db::build_pool();
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let report = restore_project(&conn, &archive, &avatar_link)
+        .map_err(|e| format!("Restore failed, nothing was saved. {:?}", e))?;
+    // files are written once restore is committed so failed restore leaves
+    // nothing in local storage
+    for (user_id, avatar) in report.avatars.iter() {
+        write_avatar(*user_id, avatar).map_err(|e| {
+            format!(
+                "Project was restored but avatar of user {} was not saved. {}",
+                user_id, e
+            )
+        })?;
+    }
+    println!("{}", report);
+    Ok(())
+}
+
+#[cfg(feature = "local-storage")]
+fn read_avatars(users: &[User]) -> Result<Vec<ArchivedAvatar>, String> {
+    let web_config = crate::web::Configuration::read();
+    if web_config.filesystem.is_empty() {
+        return Err("Local storage is not configured".to_string());
+    }
+    let client_path = format!("{}/", web_config.filesystem.client_path);
+    let mut avatars = vec![];
+    for user in users {
+        let file_name = match user.avatar_url.as_ref() {
+            Some(url) if url.contains(client_path.as_str()) => {
+                url.rsplit('/').next().unwrap_or_default()
+            }
+            _ => continue,
+        };
+        let path = format!("{}/{}", web_config.filesystem.store_path, file_name);
+        // avatar could be removed from disk, user keeps old link then
+        if let Ok(data) = std::fs::read(path.as_str()) {
+            avatars.push(ArchivedAvatar {
+                user_id: user.id,
+                file_name: file_name.to_string(),
+                data: to_hex(&data),
+            });
+        }
+    }
+    Ok(avatars)
+}
+
+#[cfg(not(feature = "local-storage"))]
+fn read_avatars(_users: &[User]) -> Result<Vec<ArchivedAvatar>, String> {
+    Err("Server was built without local storage".to_string())
+}
+
+/// Name of restored user avatar in local storage. Archive could be edited so
+/// name which could point outside of storage directory is rejected.
+#[cfg(feature = "local-storage")]
+fn avatar_file_name(user_id: UserId, avatar: &ArchivedAvatar) -> Result<String, String> {
+    let name = avatar.file_name.as_str();
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+        return Err(format!("Invalid avatar file name {}", name));
+    }
+    // stored files are prefixed with id of user from source instance
+    let original_name = name
+        .strip_prefix(format!("{}-", avatar.user_id).as_str())
+        .unwrap_or(name);
+    Ok(format!("{}-{}", user_id, original_name))
+}
+
+/// Public link of restored user avatar, file is written by `write_avatar`
+#[cfg(feature = "local-storage")]
+fn avatar_link(user_id: UserId, avatar: &ArchivedAvatar) -> Result<String, String> {
+    let web_config = crate::web::Configuration::read();
+    if web_config.filesystem.is_empty() {
+        return Err("Local storage is not configured".to_string());
+    }
+    from_hex(avatar.data.as_str())?;
+    let file_name = avatar_file_name(user_id, avatar)?;
+    Ok(web_config.local_storage_url(file_name.as_str()))
+}
+
+#[cfg(not(feature = "local-storage"))]
+fn avatar_link(_user_id: UserId, _avatar: &ArchivedAvatar) -> Result<String, String> {
+    Err("Server was built without local storage".to_string())
+}
+
+/// Saves avatar of restored user in local storage
+#[cfg(feature = "local-storage")]
+fn write_avatar(user_id: UserId, avatar: &ArchivedAvatar) -> Result<(), String> {
+    let web_config = crate::web::Configuration::read();
+    let file_name = avatar_file_name(user_id, avatar)?;
+    let data = from_hex(avatar.data.as_str())?;
+    std::fs::write(
+        format!("{}/{}", web_config.filesystem.store_path, file_name),
+        data,
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "local-storage"))]
+fn write_avatar(_user_id: UserId, _avatar: &ArchivedAvatar) -> Result<(), String> {
+    Err("Server was built without local storage".to_string())
+}
+
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if text.len() % 2 != 0 {
+        return Err("Invalid hex data".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| "Invalid hex data".to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use jirs_data::{
+        InvitationState, IssueLinkType, IssuePriority, IssueType, ProjectCategory, SprintState,
+        TimeTracking, TransitionRequirement, UserRole, WipLimitPolicy,
+    };
+
+    use crate::test_support::{issue_row, timestamp};
+
+    use super::*;
+
+    pub fn archive() -> ProjectArchive {
+        let at = timestamp();
+        let issue =
+            |id: i32, issue_type: IssueType, epic_id: Option<i32>, parent_id: Option<i32>| Issue {
+                issue_type,
+                priority: IssuePriority::High,
+                description: Some("<p>Foo</p>".to_string()),
+                description_text: Some("Foo".to_string()),
+                estimate: Some(3),
+                reporter_id: 7,
+                project_id: 3,
+                issue_status_id: 20,
+                epic_id,
+                parent_id,
+                number: id - 10,
+                ..issue_row(id)
+            };
+        let status = |id: i32, name: &str| IssueStatus {
+            id,
+            name: name.to_string(),
+            position: id - 20,
+            project_id: 3,
+            created_at: at,
+            updated_at: at,
+            wip_limit: Some(4),
+        };
+        let user = |id: i32, email: &str| User {
+            id,
+            name: format!("User {}", id),
+            email: email.to_string(),
+            avatar_url: Some(format!("http://localhost:5000/img/{}-foo.png", id)),
+            created_at: at,
+            updated_at: at,
+        };
+        let event = |id: i32, event_type: IssueEventType, old: Option<&str>, new: Option<&str>| {
+            IssueEvent {
+                id,
+                issue_id: 12,
+                project_id: 3,
+                user_id: 7,
+                event_type,
+                old_value: old.map(|v| v.to_string()),
+                new_value: new.map(|v| v.to_string()),
+                created_at: at,
+                updated_at: at,
+            }
+        };
+        let task = Issue {
+            sprint_id: Some(50),
+            time_spent: Some(5),
+            ..issue(12, IssueType::Task, Some(11), None)
+        };
+        ProjectArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: at,
+            project: Project {
+                id: 3,
+                name: "Backup".to_string(),
+                url: "".to_string(),
+                description: "".to_string(),
+                category: ProjectCategory::Software,
+                created_at: at,
+                updated_at: at,
+                time_tracking: TimeTracking::Hourly,
+                wip_limit_policy: WipLimitPolicy::Warn,
+                key: "BACK".to_string(),
+            },
+            statuses: vec![status(20, "TODO"), status(21, "Done")],
+            transitions: vec![IssueStatusTransition {
+                id: 30,
+                project_id: 3,
+                from_status_id: 20,
+                to_status_id: 21,
+                required_field: Some(TransitionRequirement::TimeSpent),
+                created_at: at,
+                updated_at: at,
+            }],
+            labels: vec![Label {
+                id: 40,
+                project_id: 3,
+                name: "bug".to_string(),
+                color: "#ff5630".to_string(),
+                created_at: at,
+                updated_at: at,
+            }],
+            sprints: vec![Sprint {
+                id: 50,
+                project_id: 3,
+                name: "Sprint 1".to_string(),
+                goal: None,
+                starts_at: Some(at.date()),
+                ends_at: None,
+                state: SprintState::Active,
+                created_at: at,
+                updated_at: at,
+            }],
+            custom_fields: vec![CustomField {
+                id: 60,
+                project_id: 3,
+                name: "Reviewer".to_string(),
+                field_type: CustomFieldType::User,
+                options: vec![],
+                created_at: at,
+                updated_at: at,
+            }],
+            users: vec![user(7, "foo@example.com"), user(8, "baz@example.com")],
+            members: vec![UserProject {
+                id: 1,
+                user_id: 7,
+                project_id: 3,
+                is_default: true,
+                is_current: true,
+                role: UserRole::Owner,
+                created_at: at,
+                updated_at: at,
+            }],
+            issues: vec![
+                issue(11, IssueType::Epic, None, None),
+                task,
+                issue(13, IssueType::Subtask, None, Some(12)),
+            ],
+            assignees: vec![IssueAssignee {
+                id: 1,
+                issue_id: 12,
+                user_id: 7,
+                created_at: at,
+                updated_at: at,
+            }],
+            issue_labels: vec![IssueLabel {
+                id: 1,
+                issue_id: 12,
+                label_id: 40,
+                created_at: at,
+                updated_at: at,
+            }],
+            custom_values: vec![IssueCustomFieldValue {
+                id: 1,
+                issue_id: 12,
+                custom_field_id: 60,
+                values: vec!["8".to_string()],
+                created_at: at,
+                updated_at: at,
+            }],
+            links: vec![IssueLink {
+                id: 1,
+                source_issue_id: 13,
+                target_issue_id: 11,
+                link_type: IssueLinkType::Blocks,
+                user_id: 7,
+                created_at: at,
+                updated_at: at,
+            }],
+            watchers: vec![ArchivedWatcher {
+                issue_id: 12,
+                user_id: 7,
+            }],
+            comments: vec![Comment {
+                id: 1,
+                body: "Looks \"good\"".to_string(),
+                user_id: 7,
+                issue_id: 12,
+                created_at: at,
+                updated_at: at,
+            }],
+            worklogs: vec![Worklog {
+                id: 1,
+                issue_id: 12,
+                user_id: 7,
+                time_spent: 5,
+                started_at: at.date(),
+                comment: "Setup".to_string(),
+                created_at: at,
+                updated_at: at,
+            }],
+            events: vec![
+                event(1, IssueEventType::Status, Some("20"), Some("21")),
+                event(2, IssueEventType::Assignees, Some(""), Some("7,8")),
+                event(3, IssueEventType::Labels, Some("99"), Some("40,99")),
+                event(4, IssueEventType::Sprint, None, Some("50")),
+                event(5, IssueEventType::Title, Some("Old"), Some("Issue 12")),
+            ],
+            invitations: vec![Invitation {
+                id: 1,
+                name: "Bar".to_string(),
+                email: "bar@example.com".to_string(),
+                state: InvitationState::Sent,
+                project_id: 3,
+                invited_by_id: 7,
+                created_at: at,
+                updated_at: at,
+                bind_token: uuid::Uuid::nil(),
+                role: UserRole::User,
+            }],
+            avatars: vec![ArchivedAvatar {
+                user_id: 7,
+                file_name: "7-foo.png".to_string(),
+                data: to_hex(&[0x89, 0x50, 0x4e, 0x47, 0x00, 0xff]),
+            }],
+        }
+    }
+
+    #[test]
+    fn archive_round_trip() {
+        let archive = archive();
+        let json = archive.to_json().unwrap();
+        let restored = ProjectArchive::from_json(json.as_str()).unwrap();
+        assert_eq!(restored, archive);
+        assert_eq!(
+            from_hex(restored.avatars[0].data.as_str()).unwrap(),
+            vec![0x89, 0x50, 0x4e, 0x47, 0x00, 0xff]
+        );
+        assert!(from_hex("0").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn rejects_newer_or_unknown_archive() {
+        let mut archive = archive();
+        archive.version = ARCHIVE_VERSION + 1;
+        let json = archive.to_json().unwrap();
+        assert!(ProjectArchive::from_json(json.as_str()).is_err());
+        assert!(ProjectArchive::from_json("{\"issues\": []}").is_err());
+    }
+
+    #[cfg(feature = "local-storage")]
+    #[test]
+    fn avatar_stays_in_storage_directory() {
+        let mut avatar = archive().avatars.remove(0);
+        assert_eq!(avatar_file_name(3, &avatar), Ok("3-foo.png".to_string()));
+        for name in [
+            "",
+            "../avatar.png",
+            "..",
+            "/etc/passwd",
+            "a\\b.png",
+            "a/b.png",
+        ]
+        .iter()
+        {
+            avatar.file_name = name.to_string();
+            assert!(avatar_file_name(3, &avatar).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn references_users_of_every_table() {
+        let mut archive = archive();
+        assert_eq!(archive.referenced_user_ids(), vec![7, 8]);
+        archive.events.clear();
+        archive.custom_values.clear();
+        assert_eq!(archive.referenced_user_ids(), vec![7]);
+    }
+}
@@ -5,8 +5,10 @@ use serde::{Deserialize, Serialize};
 
 use jirs_data::Comment;
 
+use crate::db::issue_watchers::add_issue_watchers;
 use crate::db::DbExecutor;
 use crate::errors::ServiceErrors;
+use crate::html::{sanitize, to_text};
 
 #[derive(Serialize, Deserialize)]
 pub struct LoadIssueComments {
@@ -31,7 +33,8 @@ impl Handler<LoadIssueComments> for DbExecutor {
         let comments_query = comments.distinct_on(id).filter(issue_id.eq(msg.issue_id));
         debug!("{}", diesel::debug_query::<Pg, _>(&comments_query));
         comments_query
-            .load(conn)
+            .load::<crate::models::Comment>(conn)
+            .map(|rows| rows.into_iter().map(|comment| comment.into()).collect())
             .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))
     }
 }
@@ -54,8 +57,10 @@ impl Handler<CreateComment> for DbExecutor {
         use crate::models::CommentForm;
         use crate::schema::comments::dsl::*;
 
+        let comment_body = sanitize(msg.body.as_str());
         let form = CommentForm {
-            body: msg.body,
+            body_text: Some(to_text(comment_body.as_str())),
+            body: comment_body,
             user_id: msg.user_id,
             issue_id: msg.issue_id,
         };
@@ -67,9 +72,12 @@ impl Handler<CreateComment> for DbExecutor {
 
         let comment_query = diesel::insert_into(comments).values(form);
         debug!("{}", diesel::debug_query::<Pg, _>(&comment_query));
-        comment_query
-            .get_result::<Comment>(conn)
-            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))
+        let comment: Comment = comment_query
+            .get_result::<crate::models::Comment>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))?
+            .into();
+        add_issue_watchers(conn, comment.issue_id, &[comment.user_id])?;
+        Ok(comment)
     }
 }
 
@@ -94,16 +102,21 @@ impl Handler<UpdateComment> for DbExecutor {
             .pool
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let comment_body = sanitize(msg.body.as_str());
         let query = diesel::update(
             comments
                 .filter(user_id.eq(msg.user_id))
                 .find(msg.comment_id),
         )
-        .set(body.eq(msg.body));
+        .set((
+            body_text.eq(to_text(comment_body.as_str())),
+            body.eq(comment_body),
+        ));
         info!("{}", diesel::debug_query::<Pg, _>(&query));
         let row: Comment = query
-            .get_result::<Comment>(conn)
-            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))?;
+            .get_result::<crate::models::Comment>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))?
+            .into();
         Ok(row)
     }
 }
@@ -115,11 +128,11 @@ pub struct DeleteComment {
 }
 
 impl Message for DeleteComment {
-    type Result = Result<(), ServiceErrors>;
+    type Result = Result<Comment, ServiceErrors>;
 }
 
 impl Handler<DeleteComment> for DbExecutor {
-    type Result = Result<(), ServiceErrors>;
+    type Result = Result<Comment, ServiceErrors>;
 
     fn handle(&mut self, msg: DeleteComment, _ctx: &mut Self::Context) -> Self::Result {
         use crate::schema::comments::dsl::*;
@@ -136,8 +149,8 @@ impl Handler<DeleteComment> for DbExecutor {
         );
         debug!("{}", diesel::debug_query::<Pg, _>(&comment_query));
         comment_query
-            .execute(conn)
-            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))?;
-        Ok(())
+            .get_result::<crate::models::Comment>(conn)
+            .map(|comment| comment.into())
+            .map_err(|_| ServiceErrors::RecordNotFound("issue comments".to_string()))
     }
 }
@@ -0,0 +1,259 @@
+use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{CustomField, CustomFieldId, CustomFieldType, FieldError, ProjectId, UserId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+pub fn custom_field_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Checks values of single issue field and returns them without blank entries.
+/// `members` are ids of project users and are used only by user fields.
+pub fn validate_values(
+    field: &CustomField,
+    values: &[String],
+    members: &[UserId],
+) -> Result<Vec<String>, String> {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.len() > 1 && !field.field_type.is_multi() {
+        return Err(format!("{} accepts only single value", field.name));
+    }
+    for value in values.iter() {
+        let valid = match field.field_type {
+            CustomFieldType::Text => true,
+            CustomFieldType::Number => value.parse::<f64>().map_or(false, |n| n.is_finite()),
+            CustomFieldType::Date => {
+                chrono::NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").is_ok()
+            }
+            CustomFieldType::SingleSelect | CustomFieldType::MultiSelect => {
+                field.options.contains(value)
+            }
+            CustomFieldType::User => value
+                .parse::<UserId>()
+                .map_or(false, |id| members.contains(&id)),
+        };
+        if !valid {
+            return Err(format!("{} is not valid value of {}", value, field.name));
+        }
+    }
+    Ok(values)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadCustomFields {
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadCustomFields {
+    type Result = Result<Vec<CustomField>, ServiceErrors>;
+}
+
+impl Handler<LoadCustomFields> for DbExecutor {
+    type Result = Result<Vec<CustomField>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadCustomFields, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::custom_fields::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let custom_fields_query = custom_fields
+            .filter(project_id.eq(msg.project_id))
+            .order_by(id.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&custom_fields_query));
+        custom_fields_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project custom fields".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateCustomField {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub options: Vec<String>,
+}
+
+impl Message for CreateCustomField {
+    type Result = Result<CustomField, ServiceErrors>;
+}
+
+impl Handler<CreateCustomField> for DbExecutor {
+    type Result = Result<CustomField, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateCustomField, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::CustomFieldForm;
+        use crate::schema::custom_fields::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let field_name = msg.name.trim().to_string();
+        if field_name.is_empty() {
+            return Err(custom_field_error("name", "Field name can't be blank"));
+        }
+        let mut field_options: Vec<String> = vec![];
+        if msg.field_type.has_options() {
+            for option in msg.options.iter().map(|o| o.trim()) {
+                if !option.is_empty() && !field_options.iter().any(|o| o == option) {
+                    field_options.push(option.to_string());
+                }
+            }
+            if field_options.is_empty() {
+                return Err(custom_field_error(
+                    "options",
+                    "Select field needs at least one option",
+                ));
+            }
+        }
+
+        let form = CustomFieldForm {
+            project_id: msg.project_id,
+            name: field_name,
+            field_type: msg.field_type,
+            options: field_options,
+        };
+        let custom_field_query = diesel::insert_into(custom_fields).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&custom_field_query));
+        custom_field_query
+            .get_result::<CustomField>(conn)
+            .map_err(|_| custom_field_error("name", "Field with this name already exists"))
+    }
+}
+
+/// Removes field values from all issues and then the field itself.
+#[derive(Serialize, Deserialize)]
+pub struct DeleteCustomField {
+    pub custom_field_id: CustomFieldId,
+    pub project_id: ProjectId,
+}
+
+impl Message for DeleteCustomField {
+    type Result = Result<CustomFieldId, ServiceErrors>;
+}
+
+impl Handler<DeleteCustomField> for DbExecutor {
+    type Result = Result<CustomFieldId, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteCustomField, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::custom_fields::dsl::{custom_fields, id, project_id};
+        use crate::schema::issue_custom_field_values::dsl::{
+            custom_field_id, issue_custom_field_values,
+        };
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let count = custom_fields
+            .filter(id.eq(msg.custom_field_id))
+            .filter(project_id.eq(msg.project_id))
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        if count == 0 {
+            return Err(ServiceErrors::RecordNotFound("custom field".to_string()));
+        }
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!(
+                "custom field {}. {}",
+                msg.custom_field_id, e
+            )),
+        };
+
+        diesel::delete(issue_custom_field_values.filter(custom_field_id.eq(msg.custom_field_id)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(custom_fields.find(msg.custom_field_id))
+            .execute(conn)
+            .map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(msg.custom_field_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(field_type: CustomFieldType) -> CustomField {
+        let now = chrono::Utc::now().naive_utc();
+        CustomField {
+            id: 1,
+            project_id: 1,
+            name: "Environment".to_string(),
+            field_type,
+            options: vec!["staging".to_string(), "production".to_string()],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn skips_blank_values() {
+        let text = field(CustomFieldType::Text);
+        assert_eq!(
+            validate_values(&text, &values(&[" ACME ", ""]), &[]),
+            Ok(values(&["ACME"]))
+        );
+        assert_eq!(validate_values(&text, &values(&["  "]), &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn checks_numbers_and_dates() {
+        let number = field(CustomFieldType::Number);
+        assert!(validate_values(&number, &values(&["1.5"]), &[]).is_ok());
+        assert!(validate_values(&number, &values(&["one"]), &[]).is_err());
+        let date = field(CustomFieldType::Date);
+        assert!(validate_values(&date, &values(&["2020-06-04"]), &[]).is_ok());
+        assert!(validate_values(&date, &values(&["04.06.2020"]), &[]).is_err());
+    }
+
+    #[test]
+    fn checks_options() {
+        let single = field(CustomFieldType::SingleSelect);
+        assert!(validate_values(&single, &values(&["staging"]), &[]).is_ok());
+        assert!(validate_values(&single, &values(&["qa"]), &[]).is_err());
+        assert!(validate_values(&single, &values(&["staging", "production"]), &[]).is_err());
+        let multi = field(CustomFieldType::MultiSelect);
+        assert!(validate_values(&multi, &values(&["staging", "production"]), &[]).is_ok());
+    }
+
+    #[test]
+    fn checks_project_members() {
+        let user = field(CustomFieldType::User);
+        assert!(validate_values(&user, &values(&["2"]), &[1, 2]).is_ok());
+        assert!(validate_values(&user, &values(&["3"]), &[1, 2]).is_err());
+        assert!(validate_values(&user, &values(&["admin"]), &[1, 2]).is_err());
+    }
+}
@@ -1,17 +1,19 @@
-use actix::{Handler, Message};
+use actix::{Addr, Handler, Message};
 use diesel::connection::TransactionManager;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 
 use jirs_data::{
-    EmailString, Invitation, InvitationId, InvitationState, InvitationToken, ProjectId, Token,
-    User, UserId, UserRole, UsernameString,
+    EmailString, Invitation, InvitationId, InvitationState, InvitationToken, MessageType,
+    ProjectId, Token, User, UserId, UserRole, UsernameString, WsMsg,
 };
 
+use crate::db::messages::{CreateMessage, CreateMessageReceiver};
 use crate::db::tokens::CreateBindToken;
 use crate::db::users::{LookupUser, Register};
 use crate::db::DbExecutor;
 use crate::errors::ServiceErrors;
+use crate::ws::{InnerMsg, WsServer};
 
 pub struct ListInvitation {
     pub user_id: UserId,
@@ -84,6 +86,7 @@ impl Handler<CreateInvitation> for DbExecutor {
 
 pub struct DeleteInvitation {
     pub id: InvitationId,
+    pub project_id: ProjectId,
 }
 
 impl Message for DeleteInvitation {
@@ -100,17 +103,23 @@ impl Handler<DeleteInvitation> for DbExecutor {
             .pool
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
-        let query = diesel::delete(invitations).filter(id.eq(msg.id));
+        let query = diesel::delete(invitations)
+            .filter(id.eq(msg.id))
+            .filter(project_id.eq(msg.project_id));
         debug!("{}", diesel::debug_query::<Pg, _>(&query).to_string());
-        query
+        let deleted = query
             .execute(conn)
             .map_err(|e| ServiceErrors::DatabaseQueryFailed(format!("{}", e)))?;
+        if deleted == 0 {
+            return Err(ServiceErrors::RecordNotFound("invitation".to_string()));
+        }
         Ok(())
     }
 }
 
 struct UpdateInvitationState {
     pub id: InvitationId,
+    pub project_id: ProjectId,
     pub state: InvitationState,
 }
 
@@ -134,17 +143,22 @@ impl Handler<UpdateInvitationState> for DbExecutor {
                 state.eq(msg.state),
                 updated_at.eq(chrono::Utc::now().naive_utc()),
             ))
-            .filter(id.eq(msg.id));
+            .filter(id.eq(msg.id))
+            .filter(project_id.eq(msg.project_id));
         debug!("{}", diesel::debug_query::<Pg, _>(&query).to_string());
-        query
+        let updated = query
             .execute(conn)
             .map_err(|e| ServiceErrors::DatabaseQueryFailed(format!("{}", e)))?;
+        if updated == 0 {
+            return Err(ServiceErrors::RecordNotFound("invitation".to_string()));
+        }
         Ok(())
     }
 }
 
 pub struct RevokeInvitation {
     pub id: InvitationId,
+    pub project_id: ProjectId,
 }
 
 impl Message for RevokeInvitation {
@@ -158,6 +172,7 @@ impl Handler<RevokeInvitation> for DbExecutor {
         self.handle(
             UpdateInvitationState {
                 id: msg.id,
+                project_id: msg.project_id,
                 state: InvitationState::Revoked,
             },
             ctx,
@@ -280,3 +295,32 @@ impl Handler<AcceptInvitation> for DbExecutor {
         Ok(token)
     }
 }
+
+/// Sends in-app message about invitation if invited person already has an
+/// account, used by both websocket and HTTP invitations.
+pub async fn notify_invited_user(
+    db: &Addr<DbExecutor>,
+    ws: &Addr<WsServer>,
+    invitation: &Invitation,
+    sender_id: UserId,
+) {
+    let sent = db
+        .send(CreateMessage {
+            receiver: CreateMessageReceiver::Lookup {
+                name: invitation.name.clone(),
+                email: invitation.email.clone(),
+            },
+            sender_id,
+            summary: "You have been invited to project".to_string(),
+            description: "You have been invited to project".to_string(),
+            message_type: MessageType::ReceivedInvitation,
+            hyper_link: format!("#{}", invitation.bind_token),
+        })
+        .await;
+    if let Ok(Ok(message)) = sent {
+        ws.do_send(InnerMsg::SendToUser(
+            message.receiver_id,
+            WsMsg::Message(message),
+        ));
+    }
+}
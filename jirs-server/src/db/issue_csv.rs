@@ -0,0 +1,664 @@
+use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    CreateIssuePayload, CsvRowError, Issue, IssueAssignee, IssueLabel, IssuePriority, IssueStatus,
+    IssueType, IssuesImportReport, Label, Project, ProjectId, Sprint, User, UserId,
+};
+
+use crate::db::issues::{create_issue, CreateIssue};
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::utils::{csv_line, parse_csv};
+
+/// Columns of exported file, import reads the same names and ignores
+/// columns which can't be set on new issue.
+pub const ISSUE_CSV_COLUMNS: [&str; 17] = [
+    "Key",
+    "Title",
+    "Type",
+    "Priority",
+    "Status",
+    "Reporter",
+    "Assignees",
+    "Labels",
+    "Description",
+    "Estimate",
+    "Time spent",
+    "Time remaining",
+    "Epic",
+    "Parent",
+    "Sprint",
+    "Created",
+    "Updated",
+];
+
+fn row_error(row: usize, field: &str, message: String) -> CsvRowError {
+    CsvRowError {
+        row: row as i32,
+        field: field.to_string(),
+        message,
+    }
+}
+
+/// Errors of rejected issue are reported under their fields when possible
+fn create_issue_errors(row: usize, e: ServiceErrors) -> Vec<CsvRowError> {
+    let error = e.into_ws_error();
+    if error.fields.is_empty() {
+        return vec![row_error(row, "issue", error.message)];
+    }
+    error
+        .fields
+        .into_iter()
+        .map(|field| row_error(row, field.field.as_str(), field.message))
+        .collect()
+}
+
+fn user_email(users: &[User], user_id: UserId) -> String {
+    users
+        .iter()
+        .find(|user| user.id == user_id)
+        .map(|user| user.email.clone())
+        .unwrap_or_default()
+}
+
+/// Writes issues as CSV with header, issues must have their assignees and
+/// labels filled. Multiple assignees and labels are separated with comma.
+pub fn issues_csv(
+    project: &Project,
+    issues: &[Issue],
+    statuses: &[IssueStatus],
+    users: &[User],
+    labels: &[Label],
+    sprints: &[Sprint],
+) -> String {
+    let issue_key = |issue_id: Option<i32>| {
+        issue_id
+            .and_then(|id| issues.iter().find(|issue| issue.id == id))
+            .map(|issue| project.issue_key(issue))
+            .unwrap_or_default()
+    };
+    let number = |n: Option<i32>| n.map(|n| n.to_string()).unwrap_or_default();
+
+    let mut lines = vec![csv_line(&ISSUE_CSV_COLUMNS)];
+    for issue in issues {
+        let status = statuses
+            .iter()
+            .find(|status| status.id == issue.issue_status_id)
+            .map(|status| status.name.clone())
+            .unwrap_or_default();
+        let assignees: Vec<String> = issue
+            .user_ids
+            .iter()
+            .map(|user_id| user_email(users, *user_id))
+            .collect();
+        let issue_labels: Vec<String> = labels
+            .iter()
+            .filter(|label| issue.label_ids.contains(&label.id))
+            .map(|label| label.name.clone())
+            .collect();
+        let sprint = issue
+            .sprint_id
+            .and_then(|id| sprints.iter().find(|sprint| sprint.id == id))
+            .map(|sprint| sprint.name.clone())
+            .unwrap_or_default();
+        lines.push(csv_line(&[
+            project.issue_key(issue),
+            issue.title.clone(),
+            issue.issue_type.to_string(),
+            issue.priority.to_string(),
+            status,
+            user_email(users, issue.reporter_id),
+            assignees.join(", "),
+            issue_labels.join(", "),
+            issue.description.clone().unwrap_or_default(),
+            number(issue.estimate),
+            number(issue.time_spent),
+            number(issue.time_remaining),
+            issue_key(issue.epic_id),
+            issue_key(issue.parent_id),
+            sprint,
+            issue.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            issue.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        ]));
+    }
+    lines.push(String::new());
+    lines.join("\r\n")
+}
+
+/// Maps CSV rows onto new issues. Statuses must be ordered by position, first
+/// of them is used when row has no status. Parent is key of existing issue.
+/// Number of data rows is returned together with payloads and errors.
+pub fn parse_issues_csv(
+    csv: &str,
+    project: &Project,
+    reporter_id: UserId,
+    statuses: &[IssueStatus],
+    users: &[User],
+    issues: &[Issue],
+) -> (Vec<(usize, CreateIssuePayload)>, Vec<CsvRowError>, usize) {
+    let mut rows = parse_csv(csv).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let rows_count = rows.len();
+    if !header.iter().any(|name| name == "title") {
+        return (
+            vec![],
+            vec![row_error(0, "title", "Missing Title column".to_string())],
+            rows_count,
+        );
+    }
+
+    let mut payloads = vec![];
+    let mut errors = vec![];
+    for (index, row) in rows.enumerate() {
+        let n = index + 1;
+        let value = |column: &str| -> &str {
+            header
+                .iter()
+                .position(|name| name == column)
+                .and_then(|position| row.get(position))
+                .map(|v| v.trim())
+                .unwrap_or_default()
+        };
+        let find_user = |email: &str| {
+            users
+                .iter()
+                .find(|user| user.email.eq_ignore_ascii_case(email))
+                .map(|user| user.id)
+        };
+        let row_errors = errors.len();
+
+        let title = value("title").to_string();
+        if title.is_empty() {
+            errors.push(row_error(n, "title", "Title is required".to_string()));
+        }
+        let issue_type = match value("type") {
+            "" => IssueType::default(),
+            s => s.parse::<IssueType>().unwrap_or_else(|e| {
+                errors.push(row_error(n, "type", e));
+                IssueType::default()
+            }),
+        };
+        let priority = match value("priority") {
+            "" => IssuePriority::default(),
+            s => s.parse::<IssuePriority>().unwrap_or_else(|e| {
+                errors.push(row_error(n, "priority", e));
+                IssuePriority::default()
+            }),
+        };
+        let status = match value("status") {
+            "" => statuses.first(),
+            s => statuses
+                .iter()
+                .find(|status| status.name.eq_ignore_ascii_case(s)),
+        };
+        let issue_status_id = match status {
+            Some(status) => status.id,
+            None => {
+                errors.push(row_error(
+                    n,
+                    "status",
+                    format!("Unknown status {}", value("status")),
+                ));
+                0
+            }
+        };
+        let reporter_id = match value("reporter") {
+            "" => reporter_id,
+            email => find_user(email).unwrap_or_else(|| {
+                errors.push(row_error(
+                    n,
+                    "reporter",
+                    format!("{} is not a project member", email),
+                ));
+                reporter_id
+            }),
+        };
+        let mut user_ids = vec![];
+        for email in value("assignees").split(',').map(|s| s.trim()) {
+            if email.is_empty() {
+                continue;
+            }
+            match find_user(email) {
+                Some(user_id) => user_ids.push(user_id),
+                None => errors.push(row_error(
+                    n,
+                    "assignees",
+                    format!("{} is not a project member", email),
+                )),
+            }
+        }
+        let mut number = |column: &str| match value(column) {
+            "" => None,
+            s => match s.parse::<i32>() {
+                Ok(v) if v >= 0 => Some(v),
+                _ => {
+                    errors.push(row_error(n, column, format!("{} is not a number", s)));
+                    None
+                }
+            },
+        };
+        let estimate = number("estimate");
+        let time_spent = number("time spent");
+        let time_remaining = number("time remaining");
+        let parent_id = match value("parent") {
+            "" => None,
+            key => match issues
+                .iter()
+                .find(|issue| project.issue_key(issue).eq_ignore_ascii_case(key))
+            {
+                Some(parent) => Some(parent.id),
+                None => {
+                    errors.push(row_error(n, "parent", format!("Unknown issue {}", key)));
+                    None
+                }
+            },
+        };
+        if issue_type == IssueType::Subtask && value("parent").is_empty() {
+            errors.push(row_error(
+                n,
+                "parent",
+                "Sub-task must have a parent issue".to_string(),
+            ));
+        }
+        let description = Some(value("description").to_string()).filter(|s| !s.is_empty());
+
+        if errors.len() == row_errors {
+            payloads.push((
+                n,
+                CreateIssuePayload {
+                    title,
+                    issue_type,
+                    priority,
+                    description: description.clone(),
+                    description_text: description,
+                    estimate,
+                    time_spent,
+                    time_remaining,
+                    project_id: project.id,
+                    user_ids,
+                    reporter_id,
+                    issue_status_id,
+                    parent_id,
+                },
+            ));
+        }
+    }
+    (payloads, errors, rows_count)
+}
+
+fn load_statuses(
+    conn: &DbPooledConn,
+    current_project_id: ProjectId,
+) -> Result<Vec<IssueStatus>, ServiceErrors> {
+    use crate::schema::issue_statuses::dsl::*;
+
+    let statuses_query = issue_statuses
+        .filter(project_id.eq(current_project_id))
+        .order_by((position.asc(), id.asc()));
+    debug!("{}", diesel::debug_query::<Pg, _>(&statuses_query));
+    statuses_query
+        .load(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("issue statuses".to_string()))
+}
+
+fn load_issues(
+    conn: &DbPooledConn,
+    current_project_id: ProjectId,
+) -> Result<Vec<Issue>, ServiceErrors> {
+    use crate::schema::issues::dsl::*;
+
+    let issues_query = issues
+        .filter(project_id.eq(current_project_id))
+        .order_by(number.asc());
+    debug!("{}", diesel::debug_query::<Pg, _>(&issues_query));
+    Ok(issues_query
+        .load::<crate::models::Issue>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("project issues".to_string()))?
+        .into_iter()
+        .map(|issue| issue.into())
+        .collect())
+}
+
+/// Project issues as CSV file
+#[derive(Serialize, Deserialize)]
+pub struct ExportIssuesCsv {
+    pub project_id: ProjectId,
+}
+
+impl Message for ExportIssuesCsv {
+    type Result = Result<String, ServiceErrors>;
+}
+
+impl Handler<ExportIssuesCsv> for DbExecutor {
+    type Result = Result<String, ServiceErrors>;
+
+    fn handle(&mut self, msg: ExportIssuesCsv, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_assignees::dsl::{self as assignees_dsl, issue_assignees};
+        use crate::schema::issue_labels::dsl::{self as issue_labels_dsl, issue_labels};
+        use crate::schema::labels::dsl::{self as labels_dsl, labels};
+        use crate::schema::projects::dsl::projects;
+        use crate::schema::sprints::dsl::{self as sprints_dsl, sprints};
+        use crate::schema::users::dsl::{self as users_dsl, users};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let project: Project = projects
+            .find(msg.project_id)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+        let statuses = load_statuses(conn, msg.project_id)?;
+        let mut project_issues = load_issues(conn, msg.project_id)?;
+        let ids: Vec<i32> = project_issues.iter().map(|issue| issue.id).collect();
+
+        let assignees_query = issue_assignees.filter(assignees_dsl::issue_id.eq_any(ids.clone()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&assignees_query));
+        let assignees: Vec<IssueAssignee> = assignees_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue assignees".to_string()))?;
+        let issue_labels_query = issue_labels.filter(issue_labels_dsl::issue_id.eq_any(ids));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issue_labels_query));
+        let project_issue_labels: Vec<IssueLabel> = issue_labels_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue labels".to_string()))?;
+        for issue in project_issues.iter_mut() {
+            issue.user_ids = assignees
+                .iter()
+                .filter(|assignee| assignee.issue_id == issue.id)
+                .map(|assignee| assignee.user_id)
+                .collect();
+            issue.label_ids = project_issue_labels
+                .iter()
+                .filter(|issue_label| issue_label.issue_id == issue.id)
+                .map(|issue_label| issue_label.label_id)
+                .collect();
+        }
+
+        let mut user_ids: Vec<UserId> = assignees.iter().map(|a| a.user_id).collect();
+        user_ids.extend(project_issues.iter().map(|issue| issue.reporter_id));
+        let users_query = users.filter(users_dsl::id.eq_any(user_ids));
+        debug!("{}", diesel::debug_query::<Pg, _>(&users_query));
+        let issue_users: Vec<User> = users_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue users".to_string()))?;
+
+        let labels_query = labels.filter(labels_dsl::project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&labels_query));
+        let project_labels: Vec<Label> = labels_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project labels".to_string()))?;
+        let sprints_query = sprints.filter(sprints_dsl::project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&sprints_query));
+        let project_sprints: Vec<Sprint> = sprints_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project sprints".to_string()))?;
+
+        Ok(issues_csv(
+            &project,
+            project_issues.as_slice(),
+            statuses.as_slice(),
+            issue_users.as_slice(),
+            project_labels.as_slice(),
+            project_sprints.as_slice(),
+        ))
+    }
+}
+
+/// Creates issues from CSV rows in single transaction. Nothing is saved when
+/// any row is invalid or when `dry_run` is set, created issues are returned
+/// otherwise.
+#[derive(Serialize, Deserialize)]
+pub struct ImportIssuesCsv {
+    pub project_id: ProjectId,
+    pub reporter_id: UserId,
+    pub csv: String,
+    pub dry_run: bool,
+}
+
+impl Message for ImportIssuesCsv {
+    type Result = Result<(IssuesImportReport, Vec<crate::models::Issue>), ServiceErrors>;
+}
+
+impl Handler<ImportIssuesCsv> for DbExecutor {
+    type Result = Result<(IssuesImportReport, Vec<crate::models::Issue>), ServiceErrors>;
+
+    fn handle(&mut self, msg: ImportIssuesCsv, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::projects::dsl::projects;
+        use crate::schema::user_projects::dsl::{project_id, user_id, user_projects};
+        use crate::schema::users::dsl::{self as users_dsl, users};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let project: Project = projects
+            .find(msg.project_id)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+        let statuses = load_statuses(conn, msg.project_id)?;
+        let project_issues = load_issues(conn, msg.project_id)?;
+        let users_query = users
+            .distinct_on(users_dsl::id)
+            .inner_join(user_projects.on(user_id.eq(users_dsl::id)))
+            .filter(project_id.eq(msg.project_id))
+            .select(crate::schema::users::all_columns);
+        debug!("{}", diesel::debug_query::<Pg, _>(&users_query));
+        let project_users: Vec<User> = users_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project users".to_string()))?;
+
+        let (payloads, mut errors, rows) = parse_issues_csv(
+            msg.csv.as_str(),
+            &project,
+            msg.reporter_id,
+            statuses.as_slice(),
+            project_users.as_slice(),
+            project_issues.as_slice(),
+        );
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let mut created = vec![];
+        if errors.is_empty() {
+            for (row, payload) in payloads {
+                let msg = CreateIssue {
+                    title: payload.title,
+                    issue_type: payload.issue_type,
+                    issue_status_id: payload.issue_status_id,
+                    priority: payload.priority,
+                    description: payload.description,
+                    estimate: payload.estimate,
+                    time_spent: payload.time_spent,
+                    time_remaining: payload.time_remaining,
+                    project_id: payload.project_id,
+                    reporter_id: payload.reporter_id,
+                    user_ids: payload.user_ids,
+                    parent_id: payload.parent_id,
+                    // reporter comes from file, work is logged by importing user
+                    user_id: msg.reporter_id,
+                };
+                match create_issue(conn, msg) {
+                    Ok(issue) => created.push(issue),
+                    // failed statement aborts whole transaction
+                    Err(e) => {
+                        errors.append(&mut create_issue_errors(row, e));
+                        break;
+                    }
+                }
+            }
+        }
+
+        if msg.dry_run || !errors.is_empty() {
+            tm.rollback_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            created.clear();
+        } else {
+            tm.commit_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        }
+
+        let report = IssuesImportReport {
+            dry_run: msg.dry_run,
+            rows: rows as i32,
+            imported: created.len() as i32,
+            errors,
+        };
+        Ok((report, created))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jirs_data::{FieldError, ProjectCategory, TimeTracking, WipLimitPolicy};
+
+    use crate::test_support::{self, status, timestamp, user};
+
+    use super::*;
+
+    fn project() -> Project {
+        Project {
+            id: 1,
+            name: "Foo".to_string(),
+            url: "".to_string(),
+            description: "".to_string(),
+            category: ProjectCategory::Software,
+            created_at: timestamp(),
+            updated_at: timestamp(),
+            time_tracking: TimeTracking::Hourly,
+            wip_limit_policy: WipLimitPolicy::Warn,
+            key: "FOO".to_string(),
+        }
+    }
+
+    fn issue(id: i32, title: &str) -> Issue {
+        Issue {
+            title: title.to_string(),
+            issue_type: IssueType::Bug,
+            priority: IssuePriority::High,
+            description: Some("Line, with \"quotes\"\nand break".to_string()),
+            estimate: Some(8),
+            time_remaining: Some(3),
+            reporter_id: 2,
+            issue_status_id: 2,
+            user_ids: vec![1, 2],
+            ..test_support::issue(id)
+        }
+    }
+
+    #[test]
+    fn exported_issues_can_be_imported() {
+        let statuses = vec![status(1, "Backlog"), status(2, "In progress")];
+        let users = vec![user(1, "foo@example.com"), user(2, "bar@example.com")];
+        let csv = issues_csv(
+            &project(),
+            &[issue(1, "First")],
+            statuses.as_slice(),
+            users.as_slice(),
+            &[],
+            &[],
+        );
+        let (payloads, errors, rows) = parse_issues_csv(
+            csv.as_str(),
+            &project(),
+            1,
+            statuses.as_slice(),
+            users.as_slice(),
+            &[],
+        );
+        assert_eq!(errors, vec![]);
+        assert_eq!(rows, 1);
+        assert_eq!(
+            payloads,
+            vec![(
+                1,
+                CreateIssuePayload {
+                    title: "First".to_string(),
+                    issue_type: IssueType::Bug,
+                    priority: IssuePriority::High,
+                    description: Some("Line, with \"quotes\"\nand break".to_string()),
+                    description_text: Some("Line, with \"quotes\"\nand break".to_string()),
+                    estimate: Some(8),
+                    time_spent: None,
+                    time_remaining: Some(3),
+                    project_id: 1,
+                    user_ids: vec![1, 2],
+                    reporter_id: 2,
+                    issue_status_id: 2,
+                    parent_id: None,
+                },
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_rows() {
+        let statuses = vec![status(1, "Backlog")];
+        let users = vec![user(1, "foo@example.com")];
+        let csv = "Title,Type,Status,Assignees,Estimate\n\
+                   Fine,task,backlog,FOO@example.com,2\n\
+                   ,story,Done,nobody@example.com,two\n";
+        let (payloads, errors, rows) = parse_issues_csv(
+            csv,
+            &project(),
+            1,
+            statuses.as_slice(),
+            users.as_slice(),
+            &[],
+        );
+        assert_eq!(rows, 2);
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].1.user_ids, vec![1]);
+        let fields: Vec<(i32, &str)> = errors.iter().map(|e| (e.row, e.field.as_str())).collect();
+        assert_eq!(
+            fields,
+            vec![
+                (2, "title"),
+                (2, "status"),
+                (2, "assignees"),
+                (2, "estimate"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_rejected_issue_without_debug_output() {
+        let errors = create_issue_errors(
+            3,
+            ServiceErrors::InvalidPayload(vec![FieldError {
+                field: "parent_id".to_string(),
+                message: "Epics and sub-tasks can't have sub-tasks".to_string(),
+            }]),
+        );
+        assert_eq!(
+            errors,
+            vec![row_error(
+                3,
+                "parent_id",
+                "Epics and sub-tasks can't have sub-tasks".to_string()
+            )]
+        );
+        let errors =
+            create_issue_errors(4, ServiceErrors::RecordNotFound("parent issue".to_string()));
+        assert_eq!(
+            errors,
+            vec![row_error(
+                4,
+                "issue",
+                "Resource not found parent issue".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{CustomField, CustomFieldValue, IssueCustomFieldValue, IssueId, ProjectId, UserId};
+
+use crate::db::custom_fields::{custom_field_error, validate_values};
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueCustomFieldValues {
+    pub issue_id: IssueId,
+}
+
+impl Message for LoadIssueCustomFieldValues {
+    type Result = Result<Vec<IssueCustomFieldValue>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueCustomFieldValues> for DbExecutor {
+    type Result = Result<Vec<IssueCustomFieldValue>, ServiceErrors>;
+
+    fn handle(
+        &mut self,
+        msg: LoadIssueCustomFieldValues,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        use crate::schema::issue_custom_field_values::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let values_query = issue_custom_field_values
+            .filter(issue_id.eq(msg.issue_id))
+            .order_by(custom_field_id.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&values_query));
+        values_query
+            .load::<IssueCustomFieldValue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue custom field values".to_string()))
+    }
+}
+
+/// Validates all given values against project fields before anything is
+/// written, blank values clear field of issue.
+pub fn set_issue_custom_values(
+    conn: &DbPooledConn,
+    current_issue_id: IssueId,
+    current_project_id: ProjectId,
+    custom_values: &[CustomFieldValue],
+) -> Result<(), ServiceErrors> {
+    use crate::schema::custom_fields::dsl::{custom_fields, id, project_id};
+    use crate::schema::issue_custom_field_values::dsl::{
+        custom_field_id, issue_custom_field_values, issue_id,
+    };
+    use crate::schema::user_projects::dsl::{
+        project_id as member_project_id, user_id, user_projects,
+    };
+
+    let ids: Vec<i32> = custom_values.iter().map(|v| v.custom_field_id).collect();
+    let fields: Vec<CustomField> = custom_fields
+        .filter(id.eq_any(&ids))
+        .filter(project_id.eq(current_project_id))
+        .load::<CustomField>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    let members: Vec<UserId> = user_projects
+        .select(user_id)
+        .filter(member_project_id.eq(current_project_id))
+        .get_results::<UserId>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+    let mut forms = vec![];
+    for value in custom_values.iter() {
+        let field = fields
+            .iter()
+            .find(|field| field.id == value.custom_field_id)
+            .ok_or_else(|| ServiceErrors::RecordNotFound("custom field".to_string()))?;
+        let values = validate_values(field, value.values.as_slice(), members.as_slice())
+            .map_err(|message| custom_field_error(field.name.as_str(), message.as_str()))?;
+        forms.push(crate::models::IssueCustomFieldValueForm {
+            issue_id: current_issue_id,
+            custom_field_id: field.id,
+            field_values: values,
+        });
+    }
+
+    diesel::delete(issue_custom_field_values)
+        .filter(issue_id.eq(current_issue_id))
+        .filter(custom_field_id.eq_any(&ids))
+        .execute(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    let forms: Vec<crate::models::IssueCustomFieldValueForm> = forms
+        .into_iter()
+        .filter(|form| !form.field_values.is_empty())
+        .collect();
+    diesel::insert_into(issue_custom_field_values)
+        .values(forms)
+        .execute(conn)
+        .map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to save custom field values".to_string())
+        })?;
+    Ok(())
+}
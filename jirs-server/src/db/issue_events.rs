@@ -0,0 +1,308 @@
+use actix::{Addr, Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{Issue, IssueEvent, IssueEventType, IssueId, ProjectId, UserId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+/// Single field change which will be stored as issue event
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct IssueChange {
+    pub event_type: IssueEventType,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl IssueChange {
+    pub fn new(
+        event_type: IssueEventType,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        Self {
+            event_type,
+            old_value,
+            new_value,
+        }
+    }
+}
+
+fn join_ids(ids: &[i32]) -> String {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Compare issue before and after update. List position is skipped because
+/// it changes every time any issue is moved on board.
+pub fn issue_changes(old: &Issue, new: &Issue) -> Vec<IssueChange> {
+    let mut changes = vec![];
+    if old.title != new.title {
+        changes.push(IssueChange::new(
+            IssueEventType::Title,
+            Some(old.title.clone()),
+            Some(new.title.clone()),
+        ));
+    }
+    if old.issue_type != new.issue_type {
+        changes.push(IssueChange::new(
+            IssueEventType::Type,
+            Some(old.issue_type.to_string()),
+            Some(new.issue_type.to_string()),
+        ));
+    }
+    if old.priority != new.priority {
+        changes.push(IssueChange::new(
+            IssueEventType::Priority,
+            Some(old.priority.to_string()),
+            Some(new.priority.to_string()),
+        ));
+    }
+    if old.description != new.description {
+        changes.push(IssueChange::new(
+            IssueEventType::Description,
+            old.description.clone(),
+            new.description.clone(),
+        ));
+    }
+    if old.issue_status_id != new.issue_status_id {
+        changes.push(IssueChange::new(
+            IssueEventType::Status,
+            Some(old.issue_status_id.to_string()),
+            Some(new.issue_status_id.to_string()),
+        ));
+    }
+    if old.reporter_id != new.reporter_id {
+        changes.push(IssueChange::new(
+            IssueEventType::Reporter,
+            Some(old.reporter_id.to_string()),
+            Some(new.reporter_id.to_string()),
+        ));
+    }
+    let (old_assignees, new_assignees) = (join_ids(&old.user_ids), join_ids(&new.user_ids));
+    if old_assignees != new_assignees {
+        changes.push(IssueChange::new(
+            IssueEventType::Assignees,
+            Some(old_assignees),
+            Some(new_assignees),
+        ));
+    }
+    let (old_labels, new_labels) = (join_ids(&old.label_ids), join_ids(&new.label_ids));
+    if old_labels != new_labels {
+        changes.push(IssueChange::new(
+            IssueEventType::Labels,
+            Some(old_labels),
+            Some(new_labels),
+        ));
+    }
+    if old.epic_id != new.epic_id {
+        changes.push(IssueChange::new(
+            IssueEventType::Epic,
+            old.epic_id.map(|id| id.to_string()),
+            new.epic_id.map(|id| id.to_string()),
+        ));
+    }
+    if old.parent_id != new.parent_id {
+        changes.push(IssueChange::new(
+            IssueEventType::Parent,
+            old.parent_id.map(|id| id.to_string()),
+            new.parent_id.map(|id| id.to_string()),
+        ));
+    }
+    if old.sprint_id != new.sprint_id {
+        changes.push(IssueChange::new(
+            IssueEventType::Sprint,
+            old.sprint_id.map(|id| id.to_string()),
+            new.sprint_id.map(|id| id.to_string()),
+        ));
+    }
+    if old.estimate != new.estimate {
+        changes.push(IssueChange::new(
+            IssueEventType::Estimate,
+            old.estimate.map(|n| n.to_string()),
+            new.estimate.map(|n| n.to_string()),
+        ));
+    }
+    if old.time_spent != new.time_spent {
+        changes.push(IssueChange::new(
+            IssueEventType::TimeSpent,
+            old.time_spent.map(|n| n.to_string()),
+            new.time_spent.map(|n| n.to_string()),
+        ));
+    }
+    if old.time_remaining != new.time_remaining {
+        changes.push(IssueChange::new(
+            IssueEventType::TimeRemaining,
+            old.time_remaining.map(|n| n.to_string()),
+            new.time_remaining.map(|n| n.to_string()),
+        ));
+    }
+    changes
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueEvents {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadIssueEvents {
+    type Result = Result<Vec<IssueEvent>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueEvents> for DbExecutor {
+    type Result = Result<Vec<IssueEvent>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadIssueEvents, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_events::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let events_query = issue_events
+            .filter(issue_id.eq(msg.issue_id))
+            .filter(project_id.eq(msg.project_id))
+            .order_by((created_at.desc(), id.desc()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&events_query));
+        events_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue events".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateIssueEvents {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub changes: Vec<IssueChange>,
+}
+
+impl Message for CreateIssueEvents {
+    type Result = Result<Vec<IssueEvent>, ServiceErrors>;
+}
+
+impl Handler<CreateIssueEvents> for DbExecutor {
+    type Result = Result<Vec<IssueEvent>, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateIssueEvents, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::IssueEventForm;
+        use crate::schema::issue_events::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let CreateIssueEvents {
+            issue_id: current_issue_id,
+            project_id: current_project_id,
+            user_id: current_user_id,
+            changes,
+        } = msg;
+        let values: Vec<IssueEventForm> = changes
+            .into_iter()
+            .map(|change| IssueEventForm {
+                issue_id: current_issue_id,
+                project_id: current_project_id,
+                user_id: current_user_id,
+                event_type: change.event_type,
+                old_value: change.old_value,
+                new_value: change.new_value,
+            })
+            .collect();
+
+        let events_query = diesel::insert_into(issue_events).values(values);
+        debug!("{}", diesel::debug_query::<Pg, _>(&events_query));
+        events_query.get_results::<IssueEvent>(conn).map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to save issue history".to_string())
+        })
+    }
+}
+
+/// Stores history of issue for both websocket and HTTP changes. Failure is
+/// only logged because change itself was already saved.
+pub async fn record_issue_events(
+    db: &Addr<DbExecutor>,
+    issue_id: IssueId,
+    project_id: ProjectId,
+    user_id: UserId,
+    changes: Vec<IssueChange>,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    match db
+        .send(CreateIssueEvents {
+            issue_id,
+            project_id,
+            user_id,
+            changes,
+        })
+        .await
+    {
+        Ok(Ok(_)) => (),
+        Ok(Err(e)) => error!("{:?}", e),
+        Err(e) => error!("{}", e),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support;
+
+    use super::*;
+
+    fn issue() -> Issue {
+        Issue {
+            user_ids: vec![1, 2],
+            ..test_support::issue(1)
+        }
+    }
+
+    #[test]
+    fn unchanged_issue_has_no_changes() {
+        assert_eq!(issue_changes(&issue(), &issue()), vec![]);
+    }
+
+    #[test]
+    fn moving_issue_only_records_status() {
+        let old = issue();
+        let mut new = issue();
+        new.list_position = 5;
+        new.issue_status_id = 2;
+        assert_eq!(
+            issue_changes(&old, &new),
+            vec![IssueChange::new(
+                IssueEventType::Status,
+                Some("1".to_string()),
+                Some("2".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn assignees_order_is_ignored() {
+        let old = issue();
+        let mut new = issue();
+        new.user_ids = vec![2, 1];
+        assert_eq!(issue_changes(&old, &new), vec![]);
+        new.user_ids = vec![3];
+        assert_eq!(
+            issue_changes(&old, &new),
+            vec![IssueChange::new(
+                IssueEventType::Assignees,
+                Some("1,2".to_string()),
+                Some("3".to_string())
+            )]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, IssueLabel};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueLabels {
+    pub issue_id: IssueId,
+}
+
+impl Message for LoadIssueLabels {
+    type Result = Result<Vec<IssueLabel>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueLabels> for DbExecutor {
+    type Result = Result<Vec<IssueLabel>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadIssueLabels, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_labels::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let issue_labels_query = issue_labels
+            .distinct_on(id)
+            .filter(issue_id.eq(msg.issue_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issue_labels_query));
+        issue_labels_query
+            .load::<IssueLabel>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue labels".to_string()))
+    }
+}
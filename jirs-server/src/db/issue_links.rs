@@ -0,0 +1,160 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{FieldError, IssueId, IssueLink, IssueLinkId, IssueLinkType, ProjectId, UserId};
+
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+
+fn link_error(message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "target_issue_id".to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Links can be read and changed only by members of the project which owns
+/// source issue.
+pub fn issue_in_project(
+    conn: &DbPooledConn,
+    current_issue_id: IssueId,
+    current_project_id: ProjectId,
+) -> Result<bool, ServiceErrors> {
+    use crate::schema::issues::dsl::{id, issues, project_id};
+
+    issues
+        .filter(id.eq(current_issue_id))
+        .filter(project_id.eq(current_project_id))
+        .count()
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueLinks {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadIssueLinks {
+    type Result = Result<Vec<IssueLink>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueLinks> for DbExecutor {
+    type Result = Result<Vec<IssueLink>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadIssueLinks, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_links::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        if !issue_in_project(conn, msg.issue_id, msg.project_id)? {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()));
+        }
+
+        let links_query = issue_links
+            .filter(
+                source_issue_id
+                    .eq(msg.issue_id)
+                    .or(target_issue_id.eq(msg.issue_id)),
+            )
+            .order_by((created_at.asc(), id.asc()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&links_query));
+        links_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue links".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateIssueLink {
+    pub source_issue_id: IssueId,
+    pub target_issue_id: IssueId,
+    pub link_type: IssueLinkType,
+    pub user_id: UserId,
+    pub project_id: ProjectId,
+}
+
+impl Message for CreateIssueLink {
+    type Result = Result<IssueLink, ServiceErrors>;
+}
+
+impl Handler<CreateIssueLink> for DbExecutor {
+    type Result = Result<IssueLink, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateIssueLink, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::IssueLinkForm;
+        use crate::schema::issue_links::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        if msg.source_issue_id == msg.target_issue_id {
+            return Err(link_error("Issue can't be linked to itself"));
+        }
+        if !issue_in_project(conn, msg.source_issue_id, msg.project_id)? {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()));
+        }
+        if !issue_in_project(conn, msg.target_issue_id, msg.project_id)? {
+            return Err(link_error("Linked issue must belong to the same project"));
+        }
+
+        let form = IssueLinkForm {
+            source_issue_id: msg.source_issue_id,
+            target_issue_id: msg.target_issue_id,
+            link_type: msg.link_type,
+            user_id: msg.user_id,
+        };
+        let link_query = diesel::insert_into(issue_links).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&link_query));
+        link_query
+            .get_result::<IssueLink>(conn)
+            .map_err(|_| link_error("Issues are already linked"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteIssueLink {
+    pub link_id: IssueLinkId,
+    pub project_id: ProjectId,
+}
+
+impl Message for DeleteIssueLink {
+    type Result = Result<IssueLink, ServiceErrors>;
+}
+
+impl Handler<DeleteIssueLink> for DbExecutor {
+    type Result = Result<IssueLink, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteIssueLink, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_links::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let link: IssueLink = issue_links
+            .find(msg.link_id)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue link".to_string()))?;
+        if !issue_in_project(conn, link.source_issue_id, msg.project_id)? {
+            return Err(ServiceErrors::RecordNotFound("issue link".to_string()));
+        }
+
+        let link_query = diesel::delete(issue_links.find(msg.link_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&link_query));
+        link_query.execute(conn).map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to delete issue link".to_string())
+        })?;
+        Ok(link)
+    }
+}
@@ -0,0 +1,398 @@
+use actix::{Handler, Message};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use diesel::dsl::not;
+use diesel::expression::BoxableExpression;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Nullable, Text};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::jql::{
+    parse_jql, JqlCondition, JqlDate, JqlDirection, JqlError, JqlExpr, JqlField, JqlOperator,
+    JqlOrder, JqlQuery, JqlValue, MAX_DAYS_AGO,
+};
+use jirs_data::{IssueId, IssuePriority, IssueStatus, ProjectId, ToVec, User, UserId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+use crate::schema::{issue_assignees, issues};
+
+sql_function!(fn coalesce(x: Nullable<Text>, y: Text) -> Text);
+
+type IssueFilter = Box<dyn BoxableExpression<issues::table, Pg, SqlType = Bool>>;
+
+/// Everything needed to resolve names used in query. Users and statuses
+/// are loaded up front so unknown names are reported with their position.
+pub struct JqlContext<'a> {
+    pub user_id: UserId,
+    pub users: &'a [User],
+    pub statuses: &'a [IssueStatus],
+    pub now: NaiveDateTime,
+}
+
+fn unknown_value(condition: &JqlCondition, value: &str) -> JqlError {
+    JqlError::new(
+        format!("Unknown {} {}", condition.field, value),
+        condition.span,
+    )
+}
+
+fn is_negated(operator: JqlOperator) -> bool {
+    matches!(
+        operator,
+        JqlOperator::NotEq | JqlOperator::NotIn | JqlOperator::NotContains | JqlOperator::IsEmpty
+    )
+}
+
+fn user_ids(condition: &JqlCondition, context: &JqlContext) -> Result<Vec<UserId>, JqlError> {
+    condition
+        .values
+        .iter()
+        .map(|value| match value {
+            JqlValue::CurrentUser => Ok(context.user_id),
+            JqlValue::Text(name) => context
+                .users
+                .iter()
+                .find(|user| user.name.eq_ignore_ascii_case(name.as_str()))
+                .map(|user| user.id)
+                .ok_or_else(|| unknown_value(condition, name.as_str())),
+            _ => Err(unknown_value(condition, "value")),
+        })
+        .collect()
+}
+
+fn status_ids(condition: &JqlCondition, context: &JqlContext) -> Result<Vec<i32>, JqlError> {
+    condition
+        .values
+        .iter()
+        .map(|value| match value {
+            JqlValue::Text(name) => context
+                .statuses
+                .iter()
+                .find(|status| status.name.eq_ignore_ascii_case(name.as_str()))
+                .map(|status| status.id)
+                .ok_or_else(|| unknown_value(condition, name.as_str())),
+            _ => Err(unknown_value(condition, "value")),
+        })
+        .collect()
+}
+
+/// Priorities matching condition. Comparison uses priority rank so
+/// `priority >= High` means `High` or `Highest`.
+fn priorities(condition: &JqlCondition) -> Result<Vec<IssuePriority>, JqlError> {
+    let values = condition
+        .values
+        .iter()
+        .map(|value| match value {
+            JqlValue::Priority(priority) => Ok(*priority),
+            _ => Err(unknown_value(condition, "value")),
+        })
+        .collect::<Result<Vec<IssuePriority>, JqlError>>()?;
+    let rank = |priority: IssuePriority| -> u32 { priority.into() };
+    let limit = values.first().map(|p| rank(*p)).unwrap_or_default();
+    Ok(IssuePriority::ordered()
+        .into_iter()
+        .filter(|priority| {
+            let r = rank(*priority);
+            match condition.operator {
+                JqlOperator::Eq | JqlOperator::In => values.contains(priority),
+                JqlOperator::NotEq | JqlOperator::NotIn => !values.contains(priority),
+                JqlOperator::Greater => r > limit,
+                JqlOperator::GreaterEq => r >= limit,
+                JqlOperator::Less => r < limit,
+                JqlOperator::LessEq => r <= limit,
+                _ => false,
+            }
+        })
+        .collect())
+}
+
+fn day(condition: &JqlCondition, context: &JqlContext) -> Result<NaiveDate, JqlError> {
+    match condition.values.first() {
+        Some(JqlValue::Date(JqlDate::Day(day))) => Ok(*day),
+        Some(JqlValue::Date(JqlDate::DaysAgo(days))) if (0..=MAX_DAYS_AGO).contains(days) => {
+            Ok((context.now - Duration::days(*days)).date())
+        }
+        _ => Err(unknown_value(condition, "date")),
+    }
+}
+
+fn text_pattern(condition: &JqlCondition) -> Result<String, JqlError> {
+    match condition.values.first() {
+        Some(JqlValue::Text(text)) => Ok(format!(
+            "%{}%",
+            text.replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        )),
+        _ => Err(unknown_value(condition, "text")),
+    }
+}
+
+fn compile_condition(
+    condition: &JqlCondition,
+    context: &JqlContext,
+) -> Result<IssueFilter, JqlError> {
+    let negated = is_negated(condition.operator);
+    let filter: IssueFilter = match condition.field {
+        JqlField::Assignee => {
+            let assigned = issue_assignees::table.select(issue_assignees::issue_id);
+            let matching: IssueFilter = match condition.operator {
+                JqlOperator::IsEmpty | JqlOperator::IsNotEmpty => {
+                    Box::new(issues::id.eq_any(assigned))
+                }
+                _ => Box::new(issues::id.eq_any(
+                    assigned.filter(issue_assignees::user_id.eq_any(user_ids(condition, context)?)),
+                )),
+            };
+            if negated {
+                Box::new(not(matching))
+            } else {
+                matching
+            }
+        }
+        JqlField::Reporter => {
+            let ids = user_ids(condition, context)?;
+            if negated {
+                Box::new(issues::reporter_id.ne_all(ids))
+            } else {
+                Box::new(issues::reporter_id.eq_any(ids))
+            }
+        }
+        JqlField::Status => {
+            let ids = status_ids(condition, context)?;
+            if negated {
+                Box::new(issues::issue_status_id.ne_all(ids))
+            } else {
+                Box::new(issues::issue_status_id.eq_any(ids))
+            }
+        }
+        JqlField::Type => {
+            let types = condition
+                .values
+                .iter()
+                .map(|value| match value {
+                    JqlValue::Type(issue_type) => Ok(*issue_type),
+                    _ => Err(unknown_value(condition, "value")),
+                })
+                .collect::<Result<Vec<_>, JqlError>>()?;
+            if negated {
+                Box::new(issues::issue_type.ne_all(types))
+            } else {
+                Box::new(issues::issue_type.eq_any(types))
+            }
+        }
+        JqlField::Priority => Box::new(issues::priority.eq_any(priorities(condition)?)),
+        JqlField::Summary => {
+            let pattern = text_pattern(condition)?;
+            if negated {
+                Box::new(issues::title.not_ilike(pattern))
+            } else {
+                Box::new(issues::title.ilike(pattern))
+            }
+        }
+        JqlField::Description => {
+            let description = coalesce(issues::description_text, "");
+            match condition.operator {
+                JqlOperator::IsEmpty => Box::new(description.eq("")),
+                JqlOperator::IsNotEmpty => Box::new(description.ne("")),
+                _ if negated => Box::new(description.not_ilike(text_pattern(condition)?)),
+                _ => Box::new(description.ilike(text_pattern(condition)?)),
+            }
+        }
+        JqlField::Created | JqlField::Updated => {
+            let start = day(condition, context)?.and_hms(0, 0, 0);
+            let end = start + Duration::days(1);
+            match (condition.field, condition.operator) {
+                (JqlField::Created, JqlOperator::Eq) => {
+                    Box::new(issues::created_at.ge(start).and(issues::created_at.lt(end)))
+                }
+                (JqlField::Created, JqlOperator::Greater) => Box::new(issues::created_at.ge(end)),
+                (JqlField::Created, JqlOperator::GreaterEq) => {
+                    Box::new(issues::created_at.ge(start))
+                }
+                (JqlField::Created, JqlOperator::Less) => Box::new(issues::created_at.lt(start)),
+                (JqlField::Created, JqlOperator::LessEq) => Box::new(issues::created_at.lt(end)),
+                (JqlField::Updated, JqlOperator::Eq) => {
+                    Box::new(issues::updated_at.ge(start).and(issues::updated_at.lt(end)))
+                }
+                (JqlField::Updated, JqlOperator::Greater) => Box::new(issues::updated_at.ge(end)),
+                (JqlField::Updated, JqlOperator::GreaterEq) => {
+                    Box::new(issues::updated_at.ge(start))
+                }
+                (JqlField::Updated, JqlOperator::Less) => Box::new(issues::updated_at.lt(start)),
+                _ => Box::new(issues::updated_at.lt(end)),
+            }
+        }
+    };
+    Ok(filter)
+}
+
+pub fn compile_filter(expr: &JqlExpr, context: &JqlContext) -> Result<IssueFilter, JqlError> {
+    Ok(match expr {
+        JqlExpr::And(a, b) => {
+            Box::new(compile_filter(a, context)?.and(compile_filter(b, context)?))
+        }
+        JqlExpr::Or(a, b) => Box::new(compile_filter(a, context)?.or(compile_filter(b, context)?)),
+        JqlExpr::Not(a) => Box::new(not(compile_filter(a, context)?)),
+        JqlExpr::Condition(condition) => compile_condition(condition, context)?,
+    })
+}
+
+/// Builds query selecting ids of project issues matching JQL query
+pub fn compile_query<'a>(
+    query: &JqlQuery,
+    project_id: ProjectId,
+    context: &JqlContext,
+) -> Result<issues::BoxedQuery<'a, Pg, diesel::sql_types::Integer>, JqlError> {
+    let mut issues_query = issues::table
+        .select(issues::id)
+        .filter(issues::project_id.eq(project_id))
+        .into_boxed();
+    if let Some(filter) = query.filter.as_ref() {
+        issues_query = issues_query.filter(compile_filter(filter, context)?);
+    }
+    for JqlOrder { field, direction } in query.order_by.iter() {
+        // priorities are stored from highest to lowest
+        issues_query = match (field, direction) {
+            (JqlField::Priority, JqlDirection::Asc) => {
+                issues_query.then_order_by(issues::priority.desc())
+            }
+            (JqlField::Priority, JqlDirection::Desc) => {
+                issues_query.then_order_by(issues::priority.asc())
+            }
+            (JqlField::Type, JqlDirection::Asc) => {
+                issues_query.then_order_by(issues::issue_type.asc())
+            }
+            (JqlField::Type, JqlDirection::Desc) => {
+                issues_query.then_order_by(issues::issue_type.desc())
+            }
+            (JqlField::Summary, JqlDirection::Asc) => {
+                issues_query.then_order_by(issues::title.asc())
+            }
+            (JqlField::Summary, JqlDirection::Desc) => {
+                issues_query.then_order_by(issues::title.desc())
+            }
+            (JqlField::Created, JqlDirection::Asc) => {
+                issues_query.then_order_by(issues::created_at.asc())
+            }
+            (JqlField::Created, JqlDirection::Desc) => {
+                issues_query.then_order_by(issues::created_at.desc())
+            }
+            (JqlField::Updated, JqlDirection::Asc) => {
+                issues_query.then_order_by(issues::updated_at.asc())
+            }
+            (JqlField::Updated, JqlDirection::Desc) => {
+                issues_query.then_order_by(issues::updated_at.desc())
+            }
+            _ => issues_query,
+        };
+    }
+    Ok(issues_query.then_order_by(issues::id.asc()))
+}
+
+/// Ids of current project issues matching JQL query in requested order
+#[derive(Serialize, Deserialize)]
+pub struct QueryIssues {
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub query: String,
+}
+
+impl Message for QueryIssues {
+    type Result = Result<Vec<IssueId>, ServiceErrors>;
+}
+
+impl Handler<QueryIssues> for DbExecutor {
+    type Result = Result<Vec<IssueId>, ServiceErrors>;
+
+    fn handle(&mut self, msg: QueryIssues, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_statuses::dsl as statuses;
+        use crate::schema::user_projects::dsl as user_projects;
+        use crate::schema::users::dsl as users;
+
+        let query = parse_jql(msg.query.as_str()).map_err(ServiceErrors::InvalidQuery)?;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let project_users: Vec<User> = users::users
+            .inner_join(user_projects::user_projects.on(user_projects::user_id.eq(users::id)))
+            .filter(user_projects::project_id.eq(msg.project_id))
+            .select(crate::schema::users::all_columns)
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project users".to_string()))?;
+        let project_statuses: Vec<IssueStatus> = statuses::issue_statuses
+            .filter(statuses::project_id.eq(msg.project_id))
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue statuses".to_string()))?;
+        let context = JqlContext {
+            user_id: msg.user_id,
+            users: project_users.as_slice(),
+            statuses: project_statuses.as_slice(),
+            now: chrono::Utc::now().naive_utc(),
+        };
+
+        let issues_query =
+            compile_query(&query, msg.project_id, &context).map_err(ServiceErrors::InvalidQuery)?;
+        debug!("{}", diesel::debug_query::<Pg, _>(&issues_query));
+        issues_query.load::<IssueId>(conn).map_err(|e| {
+            error!("{}", e);
+            ServiceErrors::DatabaseQueryFailed("Failed to filter issues".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::status;
+
+    use super::*;
+
+    fn condition(text: &str) -> JqlCondition {
+        match parse_jql(text).unwrap().filter {
+            Some(JqlExpr::Condition(condition)) => condition,
+            _ => panic!("{} is not single condition", text),
+        }
+    }
+
+    #[test]
+    fn compares_priorities_by_rank() {
+        assert_eq!(
+            priorities(&condition("priority >= High")),
+            Ok(vec![IssuePriority::Highest, IssuePriority::High])
+        );
+        assert_eq!(
+            priorities(&condition("priority < Low")),
+            Ok(vec![IssuePriority::Lowest])
+        );
+        assert_eq!(
+            priorities(&condition("priority NOT IN (Highest, Lowest)")),
+            Ok(vec![
+                IssuePriority::High,
+                IssuePriority::Medium,
+                IssuePriority::Low
+            ])
+        );
+    }
+
+    #[test]
+    fn resolves_status_names() {
+        let statuses = vec![status(1, "Backlog"), status(2, "In Progress")];
+        let context = JqlContext {
+            user_id: 1,
+            users: &[],
+            statuses: statuses.as_slice(),
+            now: chrono::Utc::now().naive_utc(),
+        };
+        assert_eq!(
+            status_ids(&condition("status IN (backlog, \"in progress\")"), &context),
+            Ok(vec![1, 2])
+        );
+        let error = status_ids(&condition("status = Done"), &context).unwrap_err();
+        assert_eq!(error.span.start, 0);
+        assert_eq!(error.span.end, 13);
+    }
+}
@@ -0,0 +1,182 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Float, Integer, Nullable, Text};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, IssueSearchHit, ProjectId, SearchSnippetPart, UserId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+/// Markers placed by `ts_headline` around matched words. Private use
+/// characters can't collide with anything users write.
+const HIGHLIGHT_START: char = '\u{E000}';
+const HIGHLIGHT_STOP: char = '\u{E001}';
+
+const SEARCH_QUERY: &str = "
+SELECT issues.id AS issue_id,
+       issues.project_id,
+       projects.key AS project_key,
+       issues.number,
+       issues.title,
+       ts_rank(issues.search_vector, search.query) AS rank,
+       ts_headline(
+           'english',
+           concat_ws(
+               ' ',
+               issues.description_text,
+               (SELECT string_agg(comments.body_text, ' ') FROM comments WHERE comments.issue_id = issues.id)
+           ),
+           search.query,
+           $2
+       ) AS headline
+FROM issues
+INNER JOIN projects ON projects.id = issues.project_id
+INNER JOIN user_projects ON user_projects.project_id = issues.project_id AND user_projects.user_id = $3,
+     plainto_tsquery('english', $1) AS search(query)
+WHERE issues.search_vector @@ search.query
+  AND ($4 IS NULL OR issues.project_id = $4)
+ORDER BY rank DESC, issues.id DESC
+LIMIT $5
+";
+
+#[derive(QueryableByName)]
+struct SearchRow {
+    #[sql_type = "Integer"]
+    issue_id: IssueId,
+    #[sql_type = "Integer"]
+    project_id: ProjectId,
+    #[sql_type = "Text"]
+    project_key: String,
+    #[sql_type = "Integer"]
+    number: i32,
+    #[sql_type = "Text"]
+    title: String,
+    #[sql_type = "Float"]
+    rank: f32,
+    #[sql_type = "Text"]
+    headline: String,
+}
+
+/// Splits `ts_headline` output into plain and highlighted fragments.
+pub fn snippet_parts(headline: &str) -> Vec<SearchSnippetPart> {
+    let mut parts = vec![];
+    let mut text = String::new();
+    for c in headline.chars() {
+        match c {
+            HIGHLIGHT_START | HIGHLIGHT_STOP => {
+                if !text.is_empty() {
+                    parts.push(SearchSnippetPart {
+                        text: std::mem::take(&mut text),
+                        highlighted: c == HIGHLIGHT_STOP,
+                    });
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(SearchSnippetPart {
+            text,
+            highlighted: false,
+        });
+    }
+    parts
+}
+
+/// Search issues of all projects user belongs to, `project_id` narrows
+/// results to single project.
+#[derive(Serialize, Deserialize)]
+pub struct SearchIssues {
+    pub user_id: UserId,
+    pub project_id: Option<ProjectId>,
+    pub text: String,
+    pub limit: i64,
+}
+
+impl Message for SearchIssues {
+    type Result = Result<Vec<IssueSearchHit>, ServiceErrors>;
+}
+
+impl Handler<SearchIssues> for DbExecutor {
+    type Result = Result<Vec<IssueSearchHit>, ServiceErrors>;
+
+    fn handle(&mut self, msg: SearchIssues, _ctx: &mut Self::Context) -> Self::Result {
+        let text = msg.text.trim();
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let options = format!(
+            "StartSel=\"{}\", StopSel=\"{}\", MaxFragments=2, MaxWords=20, MinWords=5",
+            HIGHLIGHT_START, HIGHLIGHT_STOP
+        );
+        let search_query = diesel::sql_query(SEARCH_QUERY)
+            .bind::<Text, _>(text)
+            .bind::<Text, _>(options)
+            .bind::<Integer, _>(msg.user_id)
+            .bind::<Nullable<Integer>, _>(msg.project_id)
+            .bind::<BigInt, _>(msg.limit);
+        debug!("{}", diesel::debug_query::<Pg, _>(&search_query));
+        let rows = search_query.load::<SearchRow>(conn).map_err(|e| {
+            error!("{}", e);
+            ServiceErrors::DatabaseQueryFailed("Failed to search issues".to_string())
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| IssueSearchHit {
+                issue_id: row.issue_id,
+                project_id: row.project_id,
+                key: format!("{}-{}", row.project_key, row.number),
+                title: row.title,
+                rank: row.rank,
+                snippet: snippet_parts(row.headline.as_str()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(text: &str, highlighted: bool) -> SearchSnippetPart {
+        SearchSnippetPart {
+            text: text.to_string(),
+            highlighted,
+        }
+    }
+
+    #[test]
+    fn splits_highlighted_words() {
+        let headline = format!(
+            "login {}fails{} after {}reset{}",
+            HIGHLIGHT_START, HIGHLIGHT_STOP, HIGHLIGHT_START, HIGHLIGHT_STOP
+        );
+        assert_eq!(
+            snippet_parts(headline.as_str()),
+            vec![
+                part("login ", false),
+                part("fails", true),
+                part(" after ", false),
+                part("reset", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_headline_is_single_part() {
+        assert_eq!(
+            snippet_parts("nothing matched"),
+            vec![part("nothing matched", false)]
+        );
+        assert!(snippet_parts("").is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    FieldError, IssueStatusId, IssueStatusTransition, IssueStatusTransitionId, ProjectId,
+    TransitionRequirement,
+};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+pub fn transition_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueStatusTransitions {
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadIssueStatusTransitions {
+    type Result = Result<Vec<IssueStatusTransition>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueStatusTransitions> for DbExecutor {
+    type Result = Result<Vec<IssueStatusTransition>, ServiceErrors>;
+
+    fn handle(
+        &mut self,
+        msg: LoadIssueStatusTransitions,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        use crate::schema::issue_status_transitions::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let transitions_query = issue_status_transitions
+            .filter(project_id.eq(msg.project_id))
+            .order_by(id.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&transitions_query));
+        transitions_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue status transitions".to_string()))
+    }
+}
+
+/// Both statuses must belong to given project
+#[derive(Serialize, Deserialize)]
+pub struct CreateIssueStatusTransition {
+    pub project_id: ProjectId,
+    pub from_status_id: IssueStatusId,
+    pub to_status_id: IssueStatusId,
+    pub required_field: Option<TransitionRequirement>,
+}
+
+impl Message for CreateIssueStatusTransition {
+    type Result = Result<IssueStatusTransition, ServiceErrors>;
+}
+
+impl Handler<CreateIssueStatusTransition> for DbExecutor {
+    type Result = Result<IssueStatusTransition, ServiceErrors>;
+
+    fn handle(
+        &mut self,
+        msg: CreateIssueStatusTransition,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        use crate::models::IssueStatusTransitionForm;
+        use crate::schema::issue_status_transitions::dsl::issue_status_transitions;
+        use crate::schema::issue_statuses::dsl::{id, issue_statuses, project_id};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        if msg.from_status_id == msg.to_status_id {
+            return Err(transition_error(
+                "to_status_id",
+                "Transition must lead to other status",
+            ));
+        }
+        let count = issue_statuses
+            .filter(id.eq_any(vec![msg.from_status_id, msg.to_status_id]))
+            .filter(project_id.eq(msg.project_id))
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        if count != 2 {
+            return Err(ServiceErrors::RecordNotFound("issue status".to_string()));
+        }
+
+        let form = IssueStatusTransitionForm {
+            project_id: msg.project_id,
+            from_status_id: msg.from_status_id,
+            to_status_id: msg.to_status_id,
+            required_field: msg.required_field,
+        };
+        let transition_query = diesel::insert_into(issue_status_transitions).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&transition_query));
+        transition_query
+            .get_result::<IssueStatusTransition>(conn)
+            .map_err(|_| transition_error("to_status_id", "Transition already exists"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteIssueStatusTransition {
+    pub transition_id: IssueStatusTransitionId,
+    pub project_id: ProjectId,
+}
+
+impl Message for DeleteIssueStatusTransition {
+    type Result = Result<IssueStatusTransitionId, ServiceErrors>;
+}
+
+impl Handler<DeleteIssueStatusTransition> for DbExecutor {
+    type Result = Result<IssueStatusTransitionId, ServiceErrors>;
+
+    fn handle(
+        &mut self,
+        msg: DeleteIssueStatusTransition,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        use crate::schema::issue_status_transitions::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let transition_query = diesel::delete(issue_status_transitions)
+            .filter(id.eq(msg.transition_id))
+            .filter(project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&transition_query));
+        let deleted = transition_query
+            .execute(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        if deleted == 0 {
+            return Err(ServiceErrors::RecordNotFound(
+                "issue status transition".to_string(),
+            ));
+        }
+        Ok(msg.transition_id)
+    }
+}
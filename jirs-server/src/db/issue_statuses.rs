@@ -1,12 +1,23 @@
 use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 
-use jirs_data::{IssueStatus, IssueStatusId, Position, ProjectId, TitleString};
+use jirs_data::{
+    check_wip_limit, FieldError, IssueStatus, IssueStatusId, Position, ProjectId, TitleString,
+    WipLimitPolicy,
+};
 
 use crate::db::DbExecutor;
 use crate::errors::ServiceErrors;
 
+pub fn wip_limit_error() -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "issue_status_id".to_string(),
+        message: "Column reached its WIP limit".to_string(),
+    }])
+}
+
 pub struct LoadIssueStatuses {
     pub project_id: ProjectId,
 }
@@ -82,6 +93,9 @@ impl Handler<DeleteIssueStatus> for DbExecutor {
     type Result = Result<IssueStatusId, ServiceErrors>;
 
     fn handle(&mut self, msg: DeleteIssueStatus, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_status_transitions::dsl::{
+            from_status_id, issue_status_transitions, to_status_id,
+        };
         use crate::schema::issue_statuses::dsl::{id, issue_statuses, project_id};
 
         let conn = &self
@@ -89,22 +103,49 @@ impl Handler<DeleteIssueStatus> for DbExecutor {
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
-        let issue_assignees_query = diesel::delete(issue_statuses)
+        let status_query = issue_statuses
             .filter(id.eq(msg.issue_status_id))
             .filter(project_id.eq(msg.project_id));
-        debug!("{}", diesel::debug_query::<Pg, _>(&issue_assignees_query));
-        issue_assignees_query
-            .execute(conn)
-            .map_err(|_| ServiceErrors::RecordNotFound("issue users".to_string()))?;
-        Ok(msg.issue_status_id)
+        debug!("{}", diesel::debug_query::<Pg, _>(&status_query));
+        let status = status_query
+            .first::<IssueStatus>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue status".to_string()))?;
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| {
+            error!("{}", e);
+            match tm.rollback_transaction(conn) {
+                Err(_) => ServiceErrors::DatabaseConnectionLost,
+                _ => {
+                    ServiceErrors::DatabaseQueryFailed("Failed to delete issue status".to_string())
+                }
+            }
+        };
+
+        let transitions_query = diesel::delete(issue_status_transitions)
+            .filter(from_status_id.eq(status.id).or(to_status_id.eq(status.id)));
+        debug!("{}", diesel::debug_query::<Pg, _>(&transitions_query));
+        transitions_query.execute(conn).map_err(rollback)?;
+
+        let status_query = diesel::delete(issue_statuses.find(status.id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&status_query));
+        status_query.execute(conn).map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(status.id)
     }
 }
 
+/// WIP limit lower than 1 removes limit from column
 pub struct UpdateIssueStatus {
     pub issue_status_id: IssueStatusId,
     pub project_id: ProjectId,
     pub position: Position,
     pub name: TitleString,
+    pub wip_limit: Option<i32>,
 }
 
 impl Message for UpdateIssueStatus {
@@ -116,7 +157,7 @@ impl Handler<UpdateIssueStatus> for DbExecutor {
 
     fn handle(&mut self, msg: UpdateIssueStatus, _ctx: &mut Self::Context) -> Self::Result {
         use crate::schema::issue_statuses::dsl::{
-            id, issue_statuses, name, position, project_id, updated_at,
+            id, issue_statuses, name, position, project_id, updated_at, wip_limit,
         };
 
         let conn = &self
@@ -128,6 +169,7 @@ impl Handler<UpdateIssueStatus> for DbExecutor {
             .set((
                 name.eq(msg.name),
                 position.eq(msg.position),
+                wip_limit.eq(msg.wip_limit.filter(|limit| *limit > 0)),
                 updated_at.eq(chrono::Utc::now().naive_utc()),
             ))
             .filter(id.eq(msg.issue_status_id))
@@ -138,3 +180,61 @@ impl Handler<UpdateIssueStatus> for DbExecutor {
             .map_err(|_| ServiceErrors::RecordNotFound("issue users".to_string()))
     }
 }
+
+/// Returns project policy when issue moved from `from_status_id` can't be
+/// put into column without exceeding its WIP limit, `None` when there is
+/// still room.
+pub struct CheckWipLimit {
+    pub project_id: ProjectId,
+    pub from_status_id: IssueStatusId,
+    pub issue_status_id: IssueStatusId,
+}
+
+impl Message for CheckWipLimit {
+    type Result = Result<Option<WipLimitPolicy>, ServiceErrors>;
+}
+
+impl Handler<CheckWipLimit> for DbExecutor {
+    type Result = Result<Option<WipLimitPolicy>, ServiceErrors>;
+
+    fn handle(&mut self, msg: CheckWipLimit, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_statuses::dsl::{id, issue_statuses, project_id};
+        use crate::schema::issues::dsl::{issue_status_id, issues};
+        use crate::schema::projects::dsl::{projects, wip_limit_policy};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let status_query = issue_statuses
+            .filter(id.eq(msg.issue_status_id))
+            .filter(project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&status_query));
+        let status = status_query
+            .first::<IssueStatus>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue status".to_string()))?;
+        if status.wip_limit.is_none() {
+            return Ok(None);
+        }
+
+        let count_query = issues
+            .filter(issue_status_id.eq(msg.issue_status_id))
+            .count();
+        debug!("{}", diesel::debug_query::<Pg, _>(&count_query));
+        let count = count_query
+            .get_result::<i64>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let policy = projects
+            .find(msg.project_id)
+            .select(wip_limit_policy)
+            .first::<WipLimitPolicy>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("Project".to_string()))?;
+        Ok(check_wip_limit(
+            policy,
+            msg.from_status_id,
+            &status,
+            count as usize,
+        ))
+    }
+}
@@ -0,0 +1,277 @@
+use actix::{Addr, Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, MessageType, ProjectId, UserId, WsMsg};
+
+use crate::db::issue_events::IssueChange;
+use crate::db::issue_links::issue_in_project;
+use crate::db::user_projects::check_project_members;
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::ws::{InnerMsg, WsServer};
+
+/// Adds watchers to issue, users already watching it are skipped. Only
+/// members of issue project can watch it.
+pub fn add_issue_watchers(
+    conn: &DbPooledConn,
+    current_issue_id: IssueId,
+    user_ids: &[UserId],
+) -> Result<(), ServiceErrors> {
+    use crate::models::IssueWatcherForm;
+    use crate::schema::issue_watchers::dsl::issue_watchers;
+    use crate::schema::issues::dsl::{issues, project_id};
+
+    let project_query = issues.find(current_issue_id).select(project_id);
+    debug!("{}", diesel::debug_query::<Pg, _>(&project_query));
+    let issue_project_id: ProjectId = project_query
+        .first(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+    check_project_members(conn, issue_project_id, "user_id", user_ids)?;
+
+    let forms: Vec<IssueWatcherForm> = user_ids
+        .iter()
+        .map(|user_id| IssueWatcherForm {
+            issue_id: current_issue_id,
+            user_id: *user_id,
+        })
+        .collect();
+    if forms.is_empty() {
+        return Ok(());
+    }
+    let watchers_query = diesel::insert_into(issue_watchers)
+        .values(forms)
+        .on_conflict_do_nothing();
+    debug!("{}", diesel::debug_query::<Pg, _>(&watchers_query));
+    watchers_query
+        .execute(conn)
+        .map_err(|_| ServiceErrors::DatabaseQueryFailed("Failed to watch issue".to_string()))?;
+    Ok(())
+}
+
+fn load_issue_watchers(
+    conn: &DbPooledConn,
+    current_issue_id: IssueId,
+) -> Result<Vec<UserId>, ServiceErrors> {
+    use crate::schema::issue_watchers::dsl::*;
+
+    let watchers_query = issue_watchers
+        .select(user_id)
+        .filter(issue_id.eq(current_issue_id))
+        .order_by(user_id.asc());
+    debug!("{}", diesel::debug_query::<Pg, _>(&watchers_query));
+    watchers_query
+        .load::<UserId>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("issue watchers".to_string()))
+}
+
+/// Names of changed fields used as message description
+pub fn changes_description(changes: &[IssueChange]) -> String {
+    let mut fields: Vec<String> = vec![];
+    for change in changes {
+        let field = change.event_type.to_string();
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    format!("Changed: {}", fields.join(", "))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueWatchers {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadIssueWatchers {
+    type Result = Result<Vec<UserId>, ServiceErrors>;
+}
+
+impl Handler<LoadIssueWatchers> for DbExecutor {
+    type Result = Result<Vec<UserId>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadIssueWatchers, _ctx: &mut Self::Context) -> Self::Result {
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        if !issue_in_project(conn, msg.issue_id, msg.project_id)? {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()));
+        }
+        load_issue_watchers(conn, msg.issue_id)
+    }
+}
+
+/// Starts or stops watching issue and returns all its watchers
+#[derive(Serialize, Deserialize)]
+pub struct WatchIssue {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub watch: bool,
+}
+
+impl Message for WatchIssue {
+    type Result = Result<Vec<UserId>, ServiceErrors>;
+}
+
+impl Handler<WatchIssue> for DbExecutor {
+    type Result = Result<Vec<UserId>, ServiceErrors>;
+
+    fn handle(&mut self, msg: WatchIssue, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_watchers::dsl::{issue_id, issue_watchers, user_id};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        if !issue_in_project(conn, msg.issue_id, msg.project_id)? {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()));
+        }
+        if msg.watch {
+            add_issue_watchers(conn, msg.issue_id, &[msg.user_id])?;
+        } else {
+            let watcher_query = diesel::delete(
+                issue_watchers
+                    .filter(issue_id.eq(msg.issue_id))
+                    .filter(user_id.eq(msg.user_id)),
+            );
+            debug!("{}", diesel::debug_query::<Pg, _>(&watcher_query));
+            watcher_query.execute(conn).map_err(|_| {
+                ServiceErrors::DatabaseQueryFailed("Failed to stop watching issue".to_string())
+            })?;
+        }
+        load_issue_watchers(conn, msg.issue_id)
+    }
+}
+
+/// Creates message for every watcher of issue except user who made the change
+#[derive(Serialize, Deserialize)]
+pub struct NotifyIssueWatchers {
+    pub issue_id: IssueId,
+    pub sender_id: UserId,
+    pub message_type: MessageType,
+    pub description: String,
+}
+
+impl Message for NotifyIssueWatchers {
+    type Result = Result<Vec<jirs_data::Message>, ServiceErrors>;
+}
+
+impl Handler<NotifyIssueWatchers> for DbExecutor {
+    type Result = Result<Vec<jirs_data::Message>, ServiceErrors>;
+
+    fn handle(&mut self, msg: NotifyIssueWatchers, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{issues, title};
+        use crate::schema::messages::dsl::*;
+        use crate::schema::users::dsl::{name, users};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let receivers: Vec<UserId> = load_issue_watchers(conn, msg.issue_id)?
+            .into_iter()
+            .filter(|watcher_id| *watcher_id != msg.sender_id)
+            .collect();
+        if receivers.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let issue_title: String = issues
+            .find(msg.issue_id)
+            .select(title)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+        let sender_name: String = users
+            .find(msg.sender_id)
+            .select(name)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("user".to_string()))?;
+        let message_summary = match msg.message_type {
+            MessageType::IssueCommented => format!("{} commented on {}", sender_name, issue_title),
+            _ => format!("{} updated {}", sender_name, issue_title),
+        };
+
+        let values: Vec<_> = receivers
+            .into_iter()
+            .map(|receiver| {
+                (
+                    receiver_id.eq(receiver),
+                    sender_id.eq(msg.sender_id),
+                    summary.eq(message_summary.clone()),
+                    description.eq(msg.description.clone()),
+                    message_type.eq(msg.message_type),
+                    hyper_link.eq(format!("/issues/{}", msg.issue_id)),
+                )
+            })
+            .collect();
+        let messages_query = diesel::insert_into(messages).values(values);
+        debug!("{}", diesel::debug_query::<Pg, _>(&messages_query));
+        messages_query
+            .get_results(conn)
+            .map_err(|_| ServiceErrors::DatabaseQueryFailed("create message failed".to_string()))
+    }
+}
+
+/// Sends message to every watcher of issue except sender, for both websocket
+/// and HTTP changes. Failure is only logged because change itself was already
+/// saved.
+pub async fn notify_issue_watchers(
+    db: &Addr<DbExecutor>,
+    ws: &Addr<WsServer>,
+    issue_id: IssueId,
+    sender_id: UserId,
+    message_type: MessageType,
+    description: String,
+) {
+    let messages = match db
+        .send(NotifyIssueWatchers {
+            issue_id,
+            sender_id,
+            message_type,
+            description,
+        })
+        .await
+    {
+        Ok(Ok(messages)) => messages,
+        Ok(Err(e)) => {
+            error!("{:?}", e);
+            return;
+        }
+        Err(e) => {
+            error!("{}", e);
+            return;
+        }
+    };
+    for message in messages {
+        ws.do_send(InnerMsg::SendToUser(
+            message.receiver_id,
+            WsMsg::Message(message),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jirs_data::IssueEventType;
+
+    use super::*;
+
+    #[test]
+    fn lists_each_changed_field_once() {
+        let changes = vec![
+            IssueChange::new(IssueEventType::Status, None, None),
+            IssueChange::new(IssueEventType::Assignees, None, None),
+            IssueChange::new(IssueEventType::Status, None, None),
+        ];
+        assert_eq!(
+            changes_description(changes.as_slice()),
+            "Changed: Status, Assignees".to_string()
+        );
+    }
+}
@@ -1,16 +1,26 @@
 use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
 use diesel::expression::dsl::not;
 use diesel::expression::sql_literal::sql;
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use jirs_data::{IssuePriority, IssueStatusId, IssueType};
+use jirs_data::{
+    CustomFieldValue, FieldError, IssueId, IssuePriority, IssueStatusId, IssueType, LabelId,
+    ProjectId, UserId,
+};
 
-use crate::db::DbExecutor;
+use crate::db::issue_custom_field_values::set_issue_custom_values;
+use crate::db::issue_watchers::add_issue_watchers;
+use crate::db::user_projects::check_project_members;
+use crate::db::{DbExecutor, DbPooledConn};
 use crate::errors::ServiceErrors;
+use crate::html::{sanitize, to_text};
 use crate::models::Issue;
 
 const FAILED_CONNECT_USER_AND_ISSUE: &str = "Failed to create connection between user and issue";
+const FAILED_CONNECT_LABEL_AND_ISSUE: &str = "Failed to create connection between label and issue";
 
 #[derive(Serialize, Deserialize)]
 pub struct LoadIssue {
@@ -42,6 +52,61 @@ impl Handler<LoadIssue> for DbExecutor {
     }
 }
 
+/// Finds issue by its key like `JIRS-12` in any project of given user
+#[derive(Serialize, Deserialize)]
+pub struct LoadIssueByKey {
+    pub user_id: UserId,
+    pub key: String,
+}
+
+impl Message for LoadIssueByKey {
+    type Result = Result<Issue, ServiceErrors>;
+}
+
+impl Handler<LoadIssueByKey> for DbExecutor {
+    type Result = Result<Issue, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadIssueByKey, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{issues, number, project_id};
+        use crate::schema::projects::dsl::{id, key, projects};
+        use crate::schema::user_projects::dsl::{
+            project_id as member_project_id, user_id, user_projects,
+        };
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let not_found = || ServiceErrors::RecordNotFound(format!("issue {}", msg.key));
+        let (project_key, issue_number) =
+            jirs_data::parse_issue_key(msg.key.as_str()).ok_or_else(not_found)?;
+
+        let project_query = projects.select(id).filter(key.eq(project_key));
+        debug!("{}", diesel::debug_query::<Pg, _>(&project_query));
+        let current_project_id = project_query
+            .first::<ProjectId>(conn)
+            .map_err(|_| not_found())?;
+
+        let member_query = user_projects
+            .filter(user_id.eq(msg.user_id))
+            .filter(member_project_id.eq(current_project_id))
+            .count();
+        debug!("{}", diesel::debug_query::<Pg, _>(&member_query));
+        let members = member_query
+            .get_result::<i64>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        if members == 0 {
+            return Err(not_found());
+        }
+
+        let issue_query = issues
+            .filter(project_id.eq(current_project_id))
+            .filter(number.eq(issue_number));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issue_query));
+        issue_query.first::<Issue>(conn).map_err(|_| not_found())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LoadProjectIssues {
     pub project_id: i32,
@@ -72,7 +137,10 @@ impl Handler<LoadProjectIssues> for DbExecutor {
     }
 }
 
+/// Time spent is not updated directly, it's sum of issue worklogs.
+/// Description is sanitized and its plain text is derived from it.
 #[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct UpdateIssue {
     pub issue_id: i32,
     pub title: Option<String>,
@@ -80,12 +148,12 @@ pub struct UpdateIssue {
     pub priority: Option<IssuePriority>,
     pub list_position: Option<i32>,
     pub description: Option<String>,
-    pub description_text: Option<String>,
     pub estimate: Option<i32>,
-    pub time_spent: Option<i32>,
     pub time_remaining: Option<i32>,
     pub project_id: Option<i32>,
     pub user_ids: Option<Vec<i32>>,
+    pub label_ids: Option<Vec<LabelId>>,
+    pub custom_values: Option<Vec<CustomFieldValue>>,
     pub reporter_id: Option<i32>,
     pub issue_status_id: Option<i32>,
 }
@@ -98,111 +166,299 @@ impl Handler<UpdateIssue> for DbExecutor {
     type Result = Result<Issue, ServiceErrors>;
 
     fn handle(&mut self, msg: UpdateIssue, _ctx: &mut Self::Context) -> Self::Result {
-        use crate::schema::issues::dsl::{self, issues};
         let conn = &self
             .pool
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
-        let current_issue_id = msg.issue_id;
-
-        let chain = diesel::update(issues.find(current_issue_id)).set((
-            msg.title.map(|title| dsl::title.eq(title)),
-            msg.issue_type
-                .map(|issue_type| dsl::issue_type.eq(issue_type)),
-            msg.issue_status_id.map(|id| dsl::issue_status_id.eq(id)),
-            msg.priority.map(|priority| dsl::priority.eq(priority)),
-            msg.list_position
-                .map(|list_position| dsl::list_position.eq(list_position)),
-            msg.description
-                .map(|description| dsl::description.eq(description)),
-            msg.description_text
-                .map(|description_text| dsl::description_text.eq(description_text)),
-            msg.estimate.map(|estimate| dsl::estimate.eq(estimate)),
-            msg.time_spent
-                .map(|time_spent| dsl::time_spent.eq(time_spent)),
-            msg.time_remaining
-                .map(|time_remaining| dsl::time_remaining.eq(time_remaining)),
-            msg.project_id
-                .map(|project_id| dsl::project_id.eq(project_id)),
-            msg.reporter_id
-                .map(|reporter_id| dsl::reporter_id.eq(reporter_id)),
-            dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
-        ));
-        debug!(
-            "{}",
-            diesel::debug_query::<diesel::pg::Pg, _>(&chain).to_string()
-        );
-        chain.get_result::<Issue>(conn).map_err(|_| {
-            ServiceErrors::DatabaseQueryFailed("Failed to update issue".to_string())
-        })?;
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        match update_issue(conn, msg) {
+            Ok(issue) => {
+                tm.commit_transaction(conn)
+                    .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+                Ok(issue)
+            }
+            Err(e) => {
+                tm.rollback_transaction(conn)
+                    .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Every change of `UpdateIssue` is saved or none when any of them fails
+fn update_issue(conn: &DbPooledConn, msg: UpdateIssue) -> Result<Issue, ServiceErrors> {
+    use crate::schema::issues::dsl::{self, issues};
+
+    let current_issue_id = msg.issue_id;
+
+    let current_project_id = issues
+        .find(current_issue_id)
+        .select(dsl::project_id)
+        .first::<i32>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+
+    // invalid custom values, assignees or reporter reject whole update
+    if let Some(custom_values) = msg.custom_values.as_ref() {
+        set_issue_custom_values(conn, current_issue_id, current_project_id, custom_values)?;
+    }
+    let members_project_id = msg.project_id.unwrap_or(current_project_id);
+    if let Some(user_ids) = msg.user_ids.as_ref() {
+        check_project_members(conn, members_project_id, "user_ids", user_ids.as_slice())?;
+    }
+    if let Some(reporter_id) = msg.reporter_id {
+        check_project_members(conn, members_project_id, "reporter_id", &[reporter_id])?;
+    }
+
+    // sub-task type is set only together with parent, see `SetIssueParent`
+    if let Some(new_type) = msg.issue_type {
+        let current = issues
+            .find(current_issue_id)
+            .first::<Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+        if new_type == IssueType::Subtask && current.issue_type != IssueType::Subtask {
+            return Err(parent_error(
+                "Issue becomes a sub-task when it's moved under a parent issue",
+            ));
+        }
+        if new_type == IssueType::Epic && count_subtasks(conn, current_issue_id)? > 0 {
+            return Err(parent_error("Issue with sub-tasks can't become an epic"));
+        }
+    }
 
-        if let Some(user_ids) = msg.user_ids.as_ref() {
-            use crate::schema::issue_assignees::dsl;
-            diesel::delete(dsl::issue_assignees)
-                .filter(not(dsl::user_id.eq_any(user_ids)).and(dsl::issue_id.eq(current_issue_id)))
+    let description = msg.description.map(|html| sanitize(html.as_str()));
+    let description_text = description.as_ref().map(|html| to_text(html.as_str()));
+    let chain = diesel::update(issues.find(current_issue_id)).set((
+        msg.title.map(|title| dsl::title.eq(title)),
+        msg.issue_type
+            .map(|issue_type| dsl::issue_type.eq(issue_type)),
+        msg.issue_status_id.map(|id| dsl::issue_status_id.eq(id)),
+        msg.priority.map(|priority| dsl::priority.eq(priority)),
+        msg.list_position
+            .map(|list_position| dsl::list_position.eq(list_position)),
+        description.map(|description| dsl::description.eq(description)),
+        description_text.map(|description_text| dsl::description_text.eq(description_text)),
+        msg.estimate.map(|estimate| dsl::estimate.eq(estimate)),
+        msg.time_remaining
+            .map(|time_remaining| dsl::time_remaining.eq(time_remaining)),
+        msg.project_id
+            .map(|project_id| dsl::project_id.eq(project_id)),
+        msg.reporter_id
+            .map(|reporter_id| dsl::reporter_id.eq(reporter_id)),
+        dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+    ));
+    debug!(
+        "{}",
+        diesel::debug_query::<diesel::pg::Pg, _>(&chain).to_string()
+    );
+    let issue = chain
+        .get_result::<Issue>(conn)
+        .map_err(|_| ServiceErrors::DatabaseQueryFailed("Failed to update issue".to_string()))?;
+
+    // epic can't belong to epic and only epic can have issues
+    match msg.issue_type {
+        Some(IssueType::Epic) => {
+            diesel::update(issues.find(issue.id))
+                .set(dsl::epic_id.eq(None::<IssueId>))
                 .execute(conn)
                 .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
-            let existing: Vec<i32> = dsl::issue_assignees
-                .select(dsl::user_id)
-                .filter(dsl::issue_id.eq(current_issue_id))
-                .get_results::<i32>(conn)
+        }
+        Some(_) => {
+            diesel::update(issues.filter(dsl::epic_id.eq(issue.id)))
+                .set(dsl::epic_id.eq(None::<IssueId>))
+                .execute(conn)
                 .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
-            let mut values = vec![];
-            for user_id in user_ids.iter() {
-                if !existing.contains(user_id) {
-                    values.push(crate::models::CreateIssueAssigneeForm {
-                        issue_id: current_issue_id,
-                        user_id: *user_id,
-                    })
-                }
+        }
+        None => (),
+    };
+    if msg.issue_type.map_or(false, |t| t != IssueType::Subtask) {
+        diesel::update(issues.find(issue.id))
+            .set(dsl::parent_id.eq(None::<IssueId>))
+            .execute(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    }
+
+    if let Some(user_ids) = msg.user_ids.as_ref() {
+        use crate::schema::issue_assignees::dsl;
+        diesel::delete(dsl::issue_assignees)
+            .filter(not(dsl::user_id.eq_any(user_ids)).and(dsl::issue_id.eq(current_issue_id)))
+            .execute(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let existing: Vec<i32> = dsl::issue_assignees
+            .select(dsl::user_id)
+            .filter(dsl::issue_id.eq(current_issue_id))
+            .get_results::<i32>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let mut values = vec![];
+        for user_id in user_ids.iter() {
+            if !existing.contains(user_id) {
+                values.push(crate::models::CreateIssueAssigneeForm {
+                    issue_id: current_issue_id,
+                    user_id: *user_id,
+                })
             }
-            diesel::insert_into(dsl::issue_assignees)
-                .values(values)
-                .execute(conn)
-                .map_err(|_| {
-                    ServiceErrors::DatabaseQueryFailed(FAILED_CONNECT_USER_AND_ISSUE.to_string())
-                })?;
         }
+        diesel::insert_into(dsl::issue_assignees)
+            .values(values)
+            .execute(conn)
+            .map_err(|_| {
+                ServiceErrors::DatabaseQueryFailed(FAILED_CONNECT_USER_AND_ISSUE.to_string())
+            })?;
+        add_issue_watchers(conn, current_issue_id, user_ids.as_slice())?;
+    }
+    if let Some(reporter_id) = msg.reporter_id {
+        add_issue_watchers(conn, current_issue_id, &[reporter_id])?;
+    }
 
-        issues
-            .find(msg.issue_id)
-            .first::<Issue>(conn)
-            .map_err(|_| ServiceErrors::DatabaseConnectionLost)
+    // labels from other projects are silently skipped
+    if let Some(label_ids) = msg.label_ids.as_ref() {
+        use crate::schema::issue_labels::dsl;
+        use crate::schema::labels::dsl::{id as label_id, labels, project_id as label_project};
+        diesel::delete(dsl::issue_labels)
+            .filter(not(dsl::label_id.eq_any(label_ids)).and(dsl::issue_id.eq(current_issue_id)))
+            .execute(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let existing: Vec<LabelId> = dsl::issue_labels
+            .select(dsl::label_id)
+            .filter(dsl::issue_id.eq(current_issue_id))
+            .get_results::<LabelId>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let allowed: Vec<LabelId> = labels
+            .select(label_id)
+            .filter(label_id.eq_any(label_ids))
+            .filter(label_project.eq(issue.project_id))
+            .get_results::<LabelId>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let values: Vec<crate::models::CreateIssueLabelForm> = allowed
+            .into_iter()
+            .filter(|id| !existing.contains(id))
+            .map(|id| crate::models::CreateIssueLabelForm {
+                issue_id: current_issue_id,
+                label_id: id,
+            })
+            .collect();
+        diesel::insert_into(dsl::issue_labels)
+            .values(values)
+            .execute(conn)
+            .map_err(|_| {
+                ServiceErrors::DatabaseQueryFailed(FAILED_CONNECT_LABEL_AND_ISSUE.to_string())
+            })?;
     }
+
+    issues
+        .find(msg.issue_id)
+        .first::<Issue>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)
 }
 
+/// Deletes issue together with its sub-tasks and links in single transaction,
+/// deleted sub-tasks are returned.
 #[derive(Serialize, Deserialize)]
 pub struct DeleteIssue {
     pub issue_id: i32,
 }
 
 impl Message for DeleteIssue {
-    type Result = Result<(), ServiceErrors>;
+    type Result = Result<Vec<Issue>, ServiceErrors>;
 }
 
 impl Handler<DeleteIssue> for DbExecutor {
-    type Result = Result<(), ServiceErrors>;
+    type Result = Result<Vec<Issue>, ServiceErrors>;
 
     fn handle(&mut self, msg: DeleteIssue, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::comments::dsl::{self as comments_dsl, comments};
         use crate::schema::issue_assignees::dsl::{issue_assignees, issue_id};
-        use crate::schema::issues::dsl::issues;
+        use crate::schema::issue_custom_field_values::dsl::{
+            self as custom_values_dsl, issue_custom_field_values,
+        };
+        use crate::schema::issue_labels::dsl::{self as issue_labels_dsl, issue_labels};
+        use crate::schema::issue_links::dsl::{issue_links, source_issue_id, target_issue_id};
+        use crate::schema::issue_watchers::dsl::{self as issue_watchers_dsl, issue_watchers};
+        use crate::schema::issues::dsl::{epic_id, issues, parent_id};
+        use crate::schema::worklogs::dsl::{self as worklogs_dsl, worklogs};
 
         let conn = &self
             .pool
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
-        diesel::delete(issue_assignees.filter(issue_id.eq(msg.issue_id)))
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!("issue {}. {}", msg.issue_id, e)),
+        };
+
+        let subtasks = issues
+            .filter(parent_id.eq(msg.issue_id))
+            .load::<Issue>(conn)
+            .map_err(rollback)?;
+        let mut ids: Vec<IssueId> = subtasks.iter().map(|subtask| subtask.id).collect();
+        ids.push(msg.issue_id);
+
+        diesel::update(issues.filter(epic_id.eq(msg.issue_id)))
+            .set(epic_id.eq(None::<IssueId>))
             .execute(conn)
-            .map_err(|e| ServiceErrors::RecordNotFound(format!("issue {}. {}", msg.issue_id, e)))?;
+            .map_err(rollback)?;
+        diesel::delete(
+            issue_links.filter(
+                source_issue_id
+                    .eq_any(&ids)
+                    .or(target_issue_id.eq_any(&ids)),
+            ),
+        )
+        .execute(conn)
+        .map_err(rollback)?;
+        diesel::delete(comments.filter(comments_dsl::issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(issue_assignees.filter(issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(issue_labels.filter(issue_labels_dsl::issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(issue_custom_field_values.filter(custom_values_dsl::issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(issue_watchers.filter(issue_watchers_dsl::issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(worklogs.filter(worklogs_dsl::issue_id.eq_any(&ids)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(issues.filter(parent_id.eq(msg.issue_id)))
+            .execute(conn)
+            .map_err(rollback)?;
         diesel::delete(issues.find(msg.issue_id))
             .execute(conn)
-            .map_err(|e| ServiceErrors::RecordNotFound(format!("issue {}. {}", msg.issue_id, e)))?;
-        Ok(())
+            .map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(subtasks)
     }
 }
 
+/// Ids and titles of issues removed by `DeleteIssue`, sub-tasks come before
+/// their parent
+pub fn deleted_issues(
+    issue_id: IssueId,
+    title: String,
+    subtasks: Vec<Issue>,
+) -> Vec<(IssueId, String)> {
+    subtasks
+        .into_iter()
+        .map(|subtask| (subtask.id, subtask.title))
+        .chain(std::iter::once((issue_id, title)))
+        .collect()
+}
+
+/// Description is sanitized and its plain text is derived from it
 #[derive(Serialize, Deserialize)]
 pub struct CreateIssue {
     pub title: String,
@@ -210,13 +466,16 @@ pub struct CreateIssue {
     pub issue_status_id: IssueStatusId,
     pub priority: IssuePriority,
     pub description: Option<String>,
-    pub description_text: Option<String>,
     pub estimate: Option<i32>,
     pub time_spent: Option<i32>,
     pub time_remaining: Option<i32>,
     pub project_id: i32,
     pub reporter_id: i32,
     pub user_ids: Vec<i32>,
+    pub parent_id: Option<IssueId>,
+    /// User who creates issue, time spent given on create is logged as work
+    /// of this user
+    pub user_id: UserId,
 }
 
 impl Message for CreateIssue {
@@ -227,63 +486,409 @@ impl Handler<CreateIssue> for DbExecutor {
     type Result = Result<Issue, ServiceErrors>;
 
     fn handle(&mut self, msg: CreateIssue, _ctx: &mut Self::Context) -> Self::Result {
-        use crate::schema::issue_assignees::dsl;
-        use crate::schema::issues::dsl::issues;
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        create_issue(conn, msg)
+    }
+}
+
+/// Inserts issue with its assignees, watchers and creator worklog in one
+/// transaction. Caller can run it inside of its own transaction.
+pub fn create_issue(conn: &DbPooledConn, msg: CreateIssue) -> Result<Issue, ServiceErrors> {
+    let tm = conn.transaction_manager();
+    tm.begin_transaction(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    match insert_issue(conn, msg) {
+        Ok(issue) => {
+            tm.commit_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Ok(issue)
+        }
+        Err(e) => {
+            tm.rollback_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Err(e)
+        }
+    }
+}
+
+fn insert_issue(conn: &DbPooledConn, msg: CreateIssue) -> Result<Issue, ServiceErrors> {
+    use crate::schema::issue_assignees::dsl;
+    use crate::schema::issues::dsl::{issues, project_id};
+    use crate::schema::projects::dsl::{id as projects_id, projects};
+
+    // issue created under parent is always a sub-task
+    let issue_type = match msg.parent_id {
+        Some(parent_id) => {
+            load_parent(conn, parent_id, msg.project_id)?;
+            IssueType::Subtask
+        }
+        None if msg.issue_type == IssueType::Subtask => {
+            return Err(parent_error("Sub-task must have a parent issue"));
+        }
+        None => msg.issue_type,
+    };
+
+    let list_position = issues
+        // .filter(issue_status_id.eq(IssueStatus::Backlog))
+        .select(sql("max(list_position) + 1"))
+        .get_result::<i32>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+    // project row lock serializes concurrent creates until transaction ends
+    let lock_query = projects
+        .find(msg.project_id)
+        .select(projects_id)
+        .for_update();
+    debug!("{}", diesel::debug_query::<Pg, _>(&lock_query));
+    lock_query
+        .get_result::<ProjectId>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+
+    // issue key is built from project key and this per-project sequence
+    let number = issues
+        .filter(project_id.eq(msg.project_id))
+        .select(sql("COALESCE(max(number), 0) + 1"))
+        .get_result::<i32>(conn)
+        .map_err(|e| {
+            error!("{}", e);
+            ServiceErrors::DatabaseQueryFailed("Failed to number new issue".to_string())
+        })?;
+
+    check_project_members(conn, msg.project_id, "reporter_id", &[msg.reporter_id])?;
+    check_project_members(conn, msg.project_id, "user_ids", msg.user_ids.as_slice())?;
+
+    let description = msg.description.map(|html| sanitize(html.as_str()));
+    let description_text = description.as_ref().map(|html| to_text(html.as_str()));
+    let form = crate::models::CreateIssueForm {
+        title: msg.title,
+        issue_type,
+        issue_status_id: msg.issue_status_id,
+        priority: msg.priority,
+        list_position,
+        description,
+        description_text,
+        estimate: msg.estimate,
+        time_spent: msg.time_spent.filter(|n| *n > 0),
+        time_remaining: msg.time_remaining,
+        reporter_id: msg.reporter_id,
+        project_id: msg.project_id,
+        parent_id: msg.parent_id,
+        number,
+    };
+
+    let issue = diesel::insert_into(issues)
+        .values(form)
+        .get_result::<Issue>(conn)
+        .map_err(|e| {
+            error!("{}", e);
+            ServiceErrors::DatabaseQueryFailed("Failed to create issue".to_string())
+        })?;
+
+    let mut values = vec![];
+    for user_id in msg.user_ids.iter() {
+        values.push(crate::models::CreateIssueAssigneeForm {
+            issue_id: issue.id,
+            user_id: *user_id,
+        });
+    }
+    if !msg.user_ids.contains(&msg.reporter_id) {
+        values.push(crate::models::CreateIssueAssigneeForm {
+            issue_id: issue.id,
+            user_id: msg.reporter_id,
+        });
+    }
+
+    diesel::insert_into(dsl::issue_assignees)
+        .values(values)
+        .execute(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+    let mut watchers = msg.user_ids.clone();
+    watchers.push(msg.reporter_id);
+    add_issue_watchers(conn, issue.id, watchers.as_slice())?;
+
+    if let Some(spent) = issue.time_spent {
+        let worklog_query = diesel::insert_into(crate::schema::worklogs::table).values(
+            crate::models::WorklogForm {
+                issue_id: issue.id,
+                user_id: msg.user_id,
+                time_spent: spent,
+                started_at: chrono::Utc::now().naive_utc().date(),
+                comment: String::new(),
+            },
+        );
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklog_query));
+        worklog_query
+            .execute(conn)
+            .map_err(|_| ServiceErrors::DatabaseQueryFailed("Failed to log work".to_string()))?;
+    }
+
+    Ok(issue)
+}
+
+fn epic_error(message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "epic_id".to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Epic can't belong to another epic and issue can be attached only to epic
+/// from its own project
+fn check_epic(issue: &Issue, epic: &Issue) -> Result<(), ServiceErrors> {
+    if issue.issue_type == IssueType::Epic {
+        return Err(epic_error("Epic can't be attached to another epic"));
+    }
+    if epic.issue_type != IssueType::Epic || epic.project_id != issue.project_id {
+        return Err(epic_error(
+            "Issue can be attached only to epic from the same project",
+        ));
+    }
+    Ok(())
+}
+
+/// Attach issue to epic or detach it when `epic_id` is `None`
+#[derive(Serialize, Deserialize)]
+pub struct SetIssueEpic {
+    pub issue_id: IssueId,
+    pub epic_id: Option<IssueId>,
+}
+
+impl Message for SetIssueEpic {
+    type Result = Result<Issue, ServiceErrors>;
+}
+
+impl Handler<SetIssueEpic> for DbExecutor {
+    type Result = Result<Issue, ServiceErrors>;
+
+    fn handle(&mut self, msg: SetIssueEpic, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{self, issues};
 
         let conn = &self
             .pool
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
-        let list_position = issues
-            // .filter(issue_status_id.eq(IssueStatus::Backlog))
-            .select(sql("max(list_position) + 1"))
-            .get_result::<i32>(conn)
+        let issue = issues
+            .find(msg.issue_id)
+            .first::<Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+        if let Some(epic_id) = msg.epic_id {
+            let epic = issues
+                .find(epic_id)
+                .first::<Issue>(conn)
+                .map_err(|_| ServiceErrors::RecordNotFound("epic".to_string()))?;
+            check_epic(&issue, &epic)?;
+        }
+
+        let query = diesel::update(issues.find(msg.issue_id)).set((
+            dsl::epic_id.eq(msg.epic_id),
+            dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ));
+        debug!(
+            "{}",
+            diesel::debug_query::<diesel::pg::Pg, _>(&query).to_string()
+        );
+        query.get_result::<Issue>(conn).map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to update issue epic".to_string())
+        })
+    }
+}
+
+fn parent_error(message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "parent_id".to_string(),
+        message: message.to_string(),
+    }])
+}
+
+fn count_subtasks(conn: &DbPooledConn, issue_id: IssueId) -> Result<i64, ServiceErrors> {
+    use crate::schema::issues::dsl::{issues, parent_id};
+
+    issues
+        .filter(parent_id.eq(issue_id))
+        .count()
+        .get_result(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)
+}
+
+/// Only tasks, bugs and stories from the same project can have sub-tasks
+fn load_parent(
+    conn: &DbPooledConn,
+    parent_id: IssueId,
+    project_id: i32,
+) -> Result<Issue, ServiceErrors> {
+    use crate::schema::issues::dsl::issues;
+
+    let parent = issues
+        .find(parent_id)
+        .first::<Issue>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("parent issue".to_string()))?;
+    if parent.project_id != project_id {
+        return Err(parent_error("Parent issue must belong to the same project"));
+    }
+    match parent.issue_type {
+        IssueType::Epic | IssueType::Subtask => {
+            Err(parent_error("Epics and sub-tasks can't have sub-tasks"))
+        }
+        _ => Ok(parent),
+    }
+}
+
+/// Move issue under another parent, it becomes a sub-task. When `parent_id`
+/// is `None` sub-task is converted to a regular task.
+#[derive(Serialize, Deserialize)]
+pub struct SetIssueParent {
+    pub issue_id: IssueId,
+    pub parent_id: Option<IssueId>,
+}
+
+impl Message for SetIssueParent {
+    type Result = Result<Issue, ServiceErrors>;
+}
+
+impl Handler<SetIssueParent> for DbExecutor {
+    type Result = Result<Issue, ServiceErrors>;
+
+    fn handle(&mut self, msg: SetIssueParent, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{self, issues};
+
+        let conn = &self
+            .pool
+            .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
-        let form = crate::models::CreateIssueForm {
-            title: msg.title,
-            issue_type: msg.issue_type,
-            issue_status_id: msg.issue_status_id,
-            priority: msg.priority,
-            list_position,
-            description: msg.description,
-            description_text: msg.description_text,
-            estimate: msg.estimate,
-            time_spent: msg.time_spent,
-            time_remaining: msg.time_remaining,
-            reporter_id: msg.reporter_id,
-            project_id: msg.project_id,
+        let issue = issues
+            .find(msg.issue_id)
+            .first::<Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+        let issue_type = match msg.parent_id {
+            Some(parent_id) => {
+                if parent_id == issue.id {
+                    return Err(parent_error("Issue can't be its own parent"));
+                }
+                if issue.issue_type == IssueType::Epic {
+                    return Err(parent_error("Epic can't become a sub-task"));
+                }
+                if count_subtasks(conn, issue.id)? > 0 {
+                    return Err(parent_error("Issue with sub-tasks can't become a sub-task"));
+                }
+                load_parent(conn, parent_id, issue.project_id)?;
+                IssueType::Subtask
+            }
+            None if issue.issue_type == IssueType::Subtask => IssueType::Task,
+            None => issue.issue_type,
         };
 
-        let issue = diesel::insert_into(issues)
-            .values(form)
-            .on_conflict_do_nothing()
-            .get_result::<Issue>(conn)
-            .map_err(|e| {
-                error!("{}", e);
-                ServiceErrors::DatabaseConnectionLost
-            })?;
+        let query = diesel::update(issues.find(msg.issue_id)).set((
+            dsl::parent_id.eq(msg.parent_id),
+            dsl::issue_type.eq(issue_type),
+            dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ));
+        debug!(
+            "{}",
+            diesel::debug_query::<diesel::pg::Pg, _>(&query).to_string()
+        );
+        query.get_result::<Issue>(conn).map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to update issue parent".to_string())
+        })
+    }
+}
 
-        let mut values = vec![];
-        for user_id in msg.user_ids.iter() {
-            values.push(crate::models::CreateIssueAssigneeForm {
-                issue_id: issue.id,
-                user_id: *user_id,
-            });
+#[cfg(test)]
+mod tests {
+    use crate::test_support::issue_row;
+
+    use super::*;
+
+    fn subtask(id: IssueId, parent_id: IssueId) -> Issue {
+        Issue {
+            title: format!("Sub-task {}", id),
+            issue_type: IssueType::Subtask,
+            parent_id: Some(parent_id),
+            ..issue_row(id)
         }
-        if !msg.user_ids.contains(&msg.reporter_id) {
-            values.push(crate::models::CreateIssueAssigneeForm {
-                issue_id: issue.id,
-                user_id: msg.reporter_id,
-            });
+    }
+
+    fn issue(id: IssueId, issue_type: IssueType, project_id: i32) -> Issue {
+        Issue {
+            issue_type,
+            project_id,
+            ..issue_row(id)
         }
+    }
 
-        diesel::insert_into(dsl::issue_assignees)
-            .values(values)
-            .execute(conn)
-            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    fn assert_epic_error(res: Result<(), ServiceErrors>, message: &str) {
+        match res {
+            Err(ServiceErrors::InvalidPayload(fields)) => assert_eq!(
+                fields,
+                vec![FieldError {
+                    field: "epic_id".to_string(),
+                    message: message.to_string(),
+                }]
+            ),
+            res => panic!("expected invalid epic, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn issue_can_be_attached_to_epic_from_its_project() {
+        let epic = issue(1, IssueType::Epic, 1);
+        for issue_type in [
+            IssueType::Task,
+            IssueType::Bug,
+            IssueType::Story,
+            IssueType::Subtask,
+        ]
+        .iter()
+        {
+            assert!(check_epic(&issue(2, *issue_type, 1), &epic).is_ok());
+        }
+    }
+
+    #[test]
+    fn epic_can_not_be_attached_to_epic() {
+        assert_epic_error(
+            check_epic(&issue(2, IssueType::Epic, 1), &issue(1, IssueType::Epic, 1)),
+            "Epic can't be attached to another epic",
+        );
+    }
 
-        Ok(issue)
+    #[test]
+    fn issue_can_be_attached_only_to_epic_from_same_project() {
+        let task = issue(2, IssueType::Task, 1);
+        assert_epic_error(
+            check_epic(&task, &issue(1, IssueType::Story, 1)),
+            "Issue can be attached only to epic from the same project",
+        );
+        assert_epic_error(
+            check_epic(&task, &issue(1, IssueType::Epic, 2)),
+            "Issue can be attached only to epic from the same project",
+        );
+    }
+
+    #[test]
+    fn delete_cascades_to_subtasks() {
+        let deleted = deleted_issues(
+            10,
+            "Parent".to_string(),
+            vec![subtask(11, 10), subtask(12, 10)],
+        );
+        assert_eq!(
+            deleted,
+            vec![
+                (11, "Sub-task 11".to_string()),
+                (12, "Sub-task 12".to_string()),
+                (10, "Parent".to_string()),
+            ]
+        );
+        assert_eq!(
+            deleted_issues(10, "Parent".to_string(), vec![]),
+            vec![(10, "Parent".to_string())]
+        );
     }
 }
@@ -0,0 +1,319 @@
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+
+use jirs_data::{IssueId, IssuePriority, IssueStatus, IssueType, Project, User, UserId, UserRole};
+
+use crate::db::issue_watchers::add_issue_watchers;
+use crate::db::issues::{create_issue, CreateIssue};
+use crate::db::user_projects::add_project_member;
+use crate::db::{lower, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::html::{sanitize, to_text};
+use crate::jira::{map_issue_type, map_priority, parents_first, JiraIssue, JiraUser};
+
+/// What was imported from Jira and what had to be skipped or changed
+#[derive(Debug, Default)]
+pub struct JiraImportReport {
+    pub issues: usize,
+    pub comments: usize,
+    pub statuses_created: usize,
+    pub users_created: usize,
+    pub users_matched: usize,
+    pub skipped: Vec<String>,
+}
+
+impl std::fmt::Display for JiraImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Imported issues:  {}", self.issues)?;
+        writeln!(f, "Imported comments: {}", self.comments)?;
+        writeln!(f, "Created statuses: {}", self.statuses_created)?;
+        writeln!(f, "Created users:    {}", self.users_created)?;
+        writeln!(f, "Matched users:    {}", self.users_matched)?;
+        writeln!(f, "Skipped:          {}", self.skipped.len())?;
+        for line in self.skipped.iter() {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+fn query_error(e: diesel::result::Error) -> ServiceErrors {
+    ServiceErrors::DatabaseQueryFailed(format!("Failed to import Jira issues. {}", e))
+}
+
+/// Imports issues into project with given key in single transaction,
+/// nothing is saved when any query fails.
+pub fn import_jira_issues(
+    conn: &DbPooledConn,
+    project_key: &str,
+    jira_issues: Vec<JiraIssue>,
+) -> Result<JiraImportReport, ServiceErrors> {
+    use crate::schema::projects::dsl::{key, projects};
+
+    let project: Project = projects
+        .filter(key.eq(project_key.to_uppercase()))
+        .first(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+
+    let tm = conn.transaction_manager();
+    tm.begin_transaction(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    match JiraImport::new(conn, project).and_then(|import| import.run(jira_issues)) {
+        Ok(report) => {
+            tm.commit_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Ok(report)
+        }
+        Err(e) => {
+            tm.rollback_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Err(e)
+        }
+    }
+}
+
+struct JiraImport<'a> {
+    conn: &'a DbPooledConn,
+    project: Project,
+    /// Oldest project member, used when Jira user has no email
+    fallback_user_id: UserId,
+    statuses: Vec<IssueStatus>,
+    users: Vec<User>,
+    issues: Vec<(String, IssueId, IssueType)>,
+    report: JiraImportReport,
+}
+
+impl<'a> JiraImport<'a> {
+    fn new(conn: &'a DbPooledConn, project: Project) -> Result<Self, ServiceErrors> {
+        use crate::schema::issue_statuses::dsl::{self as statuses_dsl, issue_statuses, position};
+        use crate::schema::user_projects::dsl::{
+            self as user_projects_dsl, user_id, user_projects,
+        };
+
+        let fallback_user_id: UserId = user_projects
+            .select(user_id)
+            .filter(user_projects_dsl::project_id.eq(project.id))
+            .order_by(user_projects_dsl::id.asc())
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project users".to_string()))?;
+        let statuses_query = issue_statuses
+            .filter(statuses_dsl::project_id.eq(project.id))
+            .order_by((position.asc(), statuses_dsl::id.asc()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&statuses_query));
+        let statuses = statuses_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue statuses".to_string()))?;
+
+        Ok(Self {
+            conn,
+            project,
+            fallback_user_id,
+            statuses,
+            users: vec![],
+            issues: vec![],
+            report: JiraImportReport::default(),
+        })
+    }
+
+    fn run(mut self, jira_issues: Vec<JiraIssue>) -> Result<JiraImportReport, ServiceErrors> {
+        // parents have to exist before their children
+        for issue in parents_first(jira_issues) {
+            self.import_issue(issue)?;
+        }
+        Ok(self.report)
+    }
+
+    fn skip(&mut self, key: &str, message: String) {
+        self.report.skipped.push(format!("{}: {}", key, message));
+    }
+
+    /// Finds user by email or creates new one, both are added to project
+    fn find_user(&mut self, user: Option<&JiraUser>) -> Result<Option<UserId>, ServiceErrors> {
+        use crate::schema::users::dsl::{email, name, users};
+
+        let address = match user.and_then(|user| user.email.as_ref()) {
+            Some(address) => address.trim().to_lowercase(),
+            None => return Ok(None),
+        };
+        if let Some(user) = self.users.iter().find(|user| user.email == address) {
+            return Ok(Some(user.id));
+        }
+
+        let user_query = users.filter(lower(email).eq(address.clone()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&user_query));
+        let existing: Option<User> = user_query
+            .first(self.conn)
+            .optional()
+            .map_err(query_error)?;
+        let found = match existing {
+            Some(found) => {
+                self.report.users_matched += 1;
+                found
+            }
+            None => {
+                let display_name = user.map(|u| u.name.clone()).unwrap_or_default();
+                let insert_query = diesel::insert_into(users)
+                    .values((name.eq(display_name), email.eq(address.clone())));
+                debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+                self.report.users_created += 1;
+                insert_query.get_result(self.conn).map_err(query_error)?
+            }
+        };
+        add_project_member(self.conn, found.id, self.project.id, UserRole::User)?;
+        let user_id = found.id;
+        self.users.push(User {
+            email: address,
+            ..found
+        });
+        Ok(Some(user_id))
+    }
+
+    fn find_status(&mut self, status_name: &str) -> Result<i32, ServiceErrors> {
+        use crate::schema::issue_statuses::dsl::{issue_statuses, name, position, project_id};
+
+        if let Some(status) = self
+            .statuses
+            .iter()
+            .find(|status| status.name.eq_ignore_ascii_case(status_name.trim()))
+        {
+            return Ok(status.id);
+        }
+        let next_position = self
+            .statuses
+            .iter()
+            .map(|status| status.position + 1)
+            .max()
+            .unwrap_or_default();
+        let insert_query = diesel::insert_into(issue_statuses).values((
+            project_id.eq(self.project.id),
+            name.eq(status_name.trim()),
+            position.eq(next_position),
+        ));
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let status: IssueStatus = insert_query.get_result(self.conn).map_err(query_error)?;
+        self.report.statuses_created += 1;
+        let status_id = status.id;
+        self.statuses.push(status);
+        Ok(status_id)
+    }
+
+    fn import_issue(&mut self, jira_issue: JiraIssue) -> Result<(), ServiceErrors> {
+        use crate::models::CommentForm;
+        use crate::schema::comments::dsl::comments;
+        use crate::schema::issues::dsl::{epic_id, issues};
+
+        let key = jira_issue.key.clone();
+        if jira_issue.summary.trim().is_empty() {
+            self.skip(&key, "issue without summary".to_string());
+            return Ok(());
+        }
+
+        let parent = jira_issue
+            .parent_key
+            .as_ref()
+            .and_then(|parent_key| self.issues.iter().find(|(k, _, _)| k == parent_key))
+            .map(|(_, id, issue_type)| (*id, *issue_type));
+        let mut issue_type = map_issue_type(jira_issue.issue_type.as_str()).unwrap_or_else(|| {
+            self.skip(
+                &key,
+                format!("type {} imported as task", jira_issue.issue_type),
+            );
+            IssueType::Task
+        });
+        let (parent_id, epic) = match parent {
+            Some((id, IssueType::Epic)) if issue_type != IssueType::Subtask => (None, Some(id)),
+            Some((id, _)) if issue_type == IssueType::Subtask => (Some(id), None),
+            _ => {
+                if let Some(parent_key) = jira_issue.parent_key.as_ref() {
+                    let reason = if parent.is_some() {
+                        "ignored"
+                    } else {
+                        "not imported"
+                    };
+                    self.skip(&key, format!("parent {} {}", parent_key, reason));
+                }
+                if issue_type == IssueType::Subtask {
+                    issue_type = IssueType::Task;
+                }
+                (None, None)
+            }
+        };
+        let priority = match jira_issue.priority.as_ref() {
+            Some(priority) => map_priority(priority.as_str()).unwrap_or_else(|| {
+                self.skip(&key, format!("priority {} imported as medium", priority));
+                IssuePriority::Medium
+            }),
+            None => IssuePriority::Medium,
+        };
+        let issue_status_id = self.find_status(jira_issue.status.as_str())?;
+
+        let reporter_id = match self.find_user(jira_issue.reporter.as_ref())? {
+            Some(reporter_id) => reporter_id,
+            None => {
+                self.skip(&key, "reporter without email".to_string());
+                self.fallback_user_id
+            }
+        };
+        let mut user_ids = vec![];
+        match self.find_user(jira_issue.assignee.as_ref())? {
+            Some(assignee_id) => user_ids.push(assignee_id),
+            None if jira_issue.assignee.is_some() => {
+                self.skip(&key, "assignee without email".to_string())
+            }
+            None => (),
+        }
+
+        let issue = create_issue(
+            self.conn,
+            CreateIssue {
+                title: jira_issue.summary.trim().to_string(),
+                issue_type,
+                issue_status_id,
+                priority,
+                description: jira_issue.description,
+                estimate: None,
+                time_spent: None,
+                time_remaining: None,
+                project_id: self.project.id,
+                reporter_id,
+                user_ids,
+                parent_id,
+                user_id: reporter_id,
+            },
+        )?;
+        if epic.is_some() {
+            diesel::update(issues.find(issue.id))
+                .set(epic_id.eq(epic))
+                .execute(self.conn)
+                .map_err(query_error)?;
+        }
+        self.issues.push((key.clone(), issue.id, issue.issue_type));
+        self.report.issues += 1;
+
+        for comment in jira_issue.comments {
+            let author_id = match self.find_user(comment.author.as_ref())? {
+                Some(author_id) => author_id,
+                None => {
+                    self.skip(&key, "comment author without email".to_string());
+                    self.fallback_user_id
+                }
+            };
+            let comment_body = sanitize(comment.body.as_str());
+            let comment_query = diesel::insert_into(comments).values(CommentForm {
+                body_text: Some(to_text(comment_body.as_str())),
+                body: comment_body,
+                user_id: author_id,
+                issue_id: issue.id,
+            });
+            debug!("{}", diesel::debug_query::<Pg, _>(&comment_query));
+            comment_query.execute(self.conn).map_err(query_error)?;
+            add_issue_watchers(self.conn, issue.id, &[author_id])?;
+            self.report.comments += 1;
+        }
+        for field in jira_issue.ignored_fields {
+            self.skip(&key, format!("{} not imported", field));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{FieldError, Label, LabelId, ProjectId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+fn label_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Accepts only full hex notation, ex. `#0052cc`
+pub fn is_valid_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color.chars().skip(1).all(|c| c.is_ascii_hexdigit())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadLabels {
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadLabels {
+    type Result = Result<Vec<Label>, ServiceErrors>;
+}
+
+impl Handler<LoadLabels> for DbExecutor {
+    type Result = Result<Vec<Label>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadLabels, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::labels::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let labels_query = labels
+            .filter(project_id.eq(msg.project_id))
+            .order_by(name.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&labels_query));
+        labels_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project labels".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateLabel {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub color: String,
+}
+
+impl Message for CreateLabel {
+    type Result = Result<Label, ServiceErrors>;
+}
+
+impl Handler<CreateLabel> for DbExecutor {
+    type Result = Result<Label, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateLabel, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::LabelForm;
+        use crate::schema::labels::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let label_name = msg.name.trim().to_string();
+        if label_name.is_empty() {
+            return Err(label_error("name", "Label name can't be blank"));
+        }
+        if !is_valid_color(msg.color.as_str()) {
+            return Err(label_error("color", "Color must be in #rrggbb format"));
+        }
+
+        let form = LabelForm {
+            project_id: msg.project_id,
+            name: label_name,
+            color: msg.color.to_lowercase(),
+        };
+        let label_query = diesel::insert_into(labels).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&label_query));
+        label_query
+            .get_result::<Label>(conn)
+            .map_err(|_| label_error("name", "Label with this name already exists"))
+    }
+}
+
+/// Removes label from all issues and then the label itself.
+#[derive(Serialize, Deserialize)]
+pub struct DeleteLabel {
+    pub label_id: LabelId,
+    pub project_id: ProjectId,
+}
+
+impl Message for DeleteLabel {
+    type Result = Result<LabelId, ServiceErrors>;
+}
+
+impl Handler<DeleteLabel> for DbExecutor {
+    type Result = Result<LabelId, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteLabel, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_labels::dsl::{issue_labels, label_id};
+        use crate::schema::labels::dsl::{id, labels, project_id};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let count = labels
+            .filter(id.eq(msg.label_id))
+            .filter(project_id.eq(msg.project_id))
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        if count == 0 {
+            return Err(ServiceErrors::RecordNotFound("label".to_string()));
+        }
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!("label {}. {}", msg.label_id, e)),
+        };
+
+        diesel::delete(issue_labels.filter(label_id.eq(msg.label_id)))
+            .execute(conn)
+            .map_err(rollback)?;
+        diesel::delete(labels.find(msg.label_id))
+            .execute(conn)
+            .map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(msg.label_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_hex_colors() {
+        assert!(is_valid_color("#0052cc"));
+        assert!(is_valid_color("#FFAB00"));
+    }
+
+    #[test]
+    fn rejects_other_colors() {
+        assert!(!is_valid_color("0052cc"));
+        assert!(!is_valid_color("#05c"));
+        assert!(!is_valid_color("#0052cg"));
+        assert!(!is_valid_color("red"));
+    }
+}
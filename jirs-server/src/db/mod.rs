@@ -3,19 +3,39 @@ use std::fs::*;
 use actix::{Actor, SyncContext};
 use diesel::pg::PgConnection;
 use diesel::r2d2::{self, ConnectionManager};
+use diesel::sql_types::Text;
 use serde::{Deserialize, Serialize};
 
 pub mod authorize_user;
 pub mod comments;
+pub mod custom_fields;
 pub mod invitations;
 pub mod issue_assignees;
+pub mod issue_csv;
+pub mod issue_custom_field_values;
+pub mod issue_events;
+pub mod issue_labels;
+pub mod issue_links;
+pub mod issue_query;
+pub mod issue_search;
+pub mod issue_status_transitions;
 pub mod issue_statuses;
+pub mod issue_watchers;
 pub mod issues;
+pub mod jira_import;
+pub mod labels;
 pub mod messages;
+pub mod project_backup;
 pub mod projects;
+pub mod reports;
+pub mod saved_filters;
+pub mod sprints;
 pub mod tokens;
 pub mod user_projects;
 pub mod users;
+pub mod worklogs;
+
+sql_function!(fn lower(x: Text) -> Text);
 
 pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type DbPooledConn = r2d2::PooledConnection<ConnectionManager<PgConnection>>;
@@ -0,0 +1,739 @@
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+
+use jirs_data::{CustomField, IssueStatus, Label, Project, ProjectId, Sprint, User, UserId};
+
+use crate::backup::ids::ArchiveIds;
+use crate::backup::{ArchivedAvatar, ArchivedWatcher, ProjectArchive, ARCHIVE_VERSION};
+use crate::db::user_projects::add_project_member;
+use crate::db::worklogs::sync_time_spent;
+use crate::db::{lower, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::html::{sanitize, to_text};
+use crate::models::Issue;
+
+/// What was created while restoring project archive
+#[derive(Debug, Default)]
+pub struct ProjectRestoreReport {
+    pub project_id: ProjectId,
+    pub statuses: usize,
+    pub transitions: usize,
+    pub labels: usize,
+    pub sprints: usize,
+    pub custom_fields: usize,
+    pub issues: usize,
+    pub assignees: usize,
+    pub issue_labels: usize,
+    pub custom_values: usize,
+    pub links: usize,
+    pub watchers: usize,
+    pub comments: usize,
+    pub worklogs: usize,
+    pub events: usize,
+    pub invitations: usize,
+    pub members: usize,
+    pub users_created: usize,
+    pub users_matched: usize,
+    /// Avatars of created users, files are written once restore is committed
+    pub avatars: Vec<(UserId, ArchivedAvatar)>,
+}
+
+impl std::fmt::Display for ProjectRestoreReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Restored project: {}", self.project_id)?;
+        writeln!(f, "Statuses:         {}", self.statuses)?;
+        writeln!(f, "Transitions:      {}", self.transitions)?;
+        writeln!(f, "Labels:           {}", self.labels)?;
+        writeln!(f, "Sprints:          {}", self.sprints)?;
+        writeln!(f, "Custom fields:    {}", self.custom_fields)?;
+        writeln!(f, "Issues:           {}", self.issues)?;
+        writeln!(f, "Assignees:        {}", self.assignees)?;
+        writeln!(f, "Issue labels:     {}", self.issue_labels)?;
+        writeln!(f, "Field values:     {}", self.custom_values)?;
+        writeln!(f, "Links:            {}", self.links)?;
+        writeln!(f, "Watchers:         {}", self.watchers)?;
+        writeln!(f, "Comments:         {}", self.comments)?;
+        writeln!(f, "Worklogs:         {}", self.worklogs)?;
+        writeln!(f, "Events:           {}", self.events)?;
+        writeln!(f, "Invitations:      {}", self.invitations)?;
+        writeln!(f, "Members:          {}", self.members)?;
+        writeln!(f, "Created users:    {}", self.users_created)?;
+        writeln!(f, "Matched users:    {}", self.users_matched)?;
+        writeln!(f, "Avatars:          {}", self.avatars.len())
+    }
+}
+
+fn export_error(e: diesel::result::Error) -> ServiceErrors {
+    ServiceErrors::DatabaseQueryFailed(format!("Failed to export project. {}", e))
+}
+
+fn restore_error(e: diesel::result::Error) -> ServiceErrors {
+    ServiceErrors::DatabaseQueryFailed(format!("Failed to restore project. {}", e))
+}
+
+/// Loads project with given key and everything which belongs to it. Avatars
+/// are not read from storage here, history of deleted issues is skipped.
+pub fn export_project(
+    conn: &DbPooledConn,
+    project_key: &str,
+) -> Result<ProjectArchive, ServiceErrors> {
+    use crate::schema::comments::dsl as comments_dsl;
+    use crate::schema::custom_fields::dsl as fields_dsl;
+    use crate::schema::invitations::dsl as invitations_dsl;
+    use crate::schema::issue_assignees::dsl as assignees_dsl;
+    use crate::schema::issue_custom_field_values::dsl as values_dsl;
+    use crate::schema::issue_events::dsl as events_dsl;
+    use crate::schema::issue_labels::dsl as issue_labels_dsl;
+    use crate::schema::issue_links::dsl as links_dsl;
+    use crate::schema::issue_status_transitions::dsl as transitions_dsl;
+    use crate::schema::issue_statuses::dsl as statuses_dsl;
+    use crate::schema::issue_watchers::dsl as watchers_dsl;
+    use crate::schema::issues::dsl as issues_dsl;
+    use crate::schema::labels::dsl as labels_dsl;
+    use crate::schema::projects::dsl as projects_dsl;
+    use crate::schema::sprints::dsl as sprints_dsl;
+    use crate::schema::user_projects::dsl as members_dsl;
+    use crate::schema::users::dsl as users_dsl;
+    use crate::schema::worklogs::dsl as worklogs_dsl;
+
+    let project: Project = projects_dsl::projects
+        .filter(projects_dsl::key.eq(project_key.to_uppercase()))
+        .first(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+
+    let statuses = statuses_dsl::issue_statuses
+        .filter(statuses_dsl::project_id.eq(project.id))
+        .order_by(statuses_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let transitions = transitions_dsl::issue_status_transitions
+        .filter(transitions_dsl::project_id.eq(project.id))
+        .order_by(transitions_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let labels = labels_dsl::labels
+        .filter(labels_dsl::project_id.eq(project.id))
+        .order_by(labels_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let sprints = sprints_dsl::sprints
+        .filter(sprints_dsl::project_id.eq(project.id))
+        .order_by(sprints_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let custom_fields = fields_dsl::custom_fields
+        .filter(fields_dsl::project_id.eq(project.id))
+        .order_by(fields_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let members: Vec<jirs_data::UserProject> = members_dsl::user_projects
+        .filter(members_dsl::project_id.eq(project.id))
+        .order_by(members_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let issues: Vec<Issue> = issues_dsl::issues
+        .filter(issues_dsl::project_id.eq(project.id))
+        .order_by(issues_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let issue_ids: Vec<i32> = issues.iter().map(|issue| issue.id).collect();
+    let assignees: Vec<jirs_data::IssueAssignee> = assignees_dsl::issue_assignees
+        .filter(assignees_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(assignees_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let issue_labels = issue_labels_dsl::issue_labels
+        .filter(issue_labels_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(issue_labels_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let custom_values = values_dsl::issue_custom_field_values
+        .filter(values_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(values_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    // links to issues of other projects can't be restored
+    let links = links_dsl::issue_links
+        .filter(links_dsl::source_issue_id.eq_any(issue_ids.clone()))
+        .filter(links_dsl::target_issue_id.eq_any(issue_ids.clone()))
+        .order_by(links_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let watchers = watchers_dsl::issue_watchers
+        .select((watchers_dsl::issue_id, watchers_dsl::user_id))
+        .filter(watchers_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(watchers_dsl::id.asc())
+        .load::<(i32, i32)>(conn)
+        .map_err(export_error)?
+        .into_iter()
+        .map(|(issue_id, user_id)| ArchivedWatcher { issue_id, user_id })
+        .collect();
+    let comments: Vec<jirs_data::Comment> = comments_dsl::comments
+        .filter(comments_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(comments_dsl::id.asc())
+        .load::<crate::models::Comment>(conn)
+        .map_err(export_error)?
+        .into_iter()
+        .map(|comment| comment.into())
+        .collect();
+    let worklogs = worklogs_dsl::worklogs
+        .filter(worklogs_dsl::issue_id.eq_any(issue_ids.clone()))
+        .order_by(worklogs_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let events = events_dsl::issue_events
+        .filter(events_dsl::issue_id.eq_any(issue_ids))
+        .order_by(events_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+    let invitations: Vec<jirs_data::Invitation> = invitations_dsl::invitations
+        .filter(invitations_dsl::project_id.eq(project.id))
+        .order_by(invitations_dsl::id.asc())
+        .load(conn)
+        .map_err(export_error)?;
+
+    let mut archive = ProjectArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().naive_utc(),
+        project,
+        statuses,
+        transitions,
+        labels,
+        sprints,
+        custom_fields,
+        users: vec![],
+        members,
+        issues,
+        assignees,
+        issue_labels,
+        custom_values,
+        links,
+        watchers,
+        comments,
+        worklogs,
+        events,
+        invitations,
+        avatars: vec![],
+    };
+    let users_query = users_dsl::users
+        .filter(users_dsl::id.eq_any(archive.referenced_user_ids()))
+        .order_by(users_dsl::id.asc());
+    debug!("{}", diesel::debug_query::<Pg, _>(&users_query));
+    archive.users = users_query.load(conn).map_err(export_error)?;
+    Ok(archive)
+}
+
+/// Creates project from archive in single transaction. Every row gets new id,
+/// users are matched by email and only new users receive archived avatar.
+/// Avatar files are not written, caller saves reported avatars after restore.
+/// Project key must not be used in this database.
+pub fn restore_project(
+    conn: &DbPooledConn,
+    archive: &ProjectArchive,
+    avatar_link: &dyn Fn(UserId, &ArchivedAvatar) -> Result<String, String>,
+) -> Result<ProjectRestoreReport, ServiceErrors> {
+    use crate::schema::projects::dsl::{key, projects};
+
+    let taken: i64 = projects
+        .filter(key.eq(archive.project.key.as_str()))
+        .count()
+        .get_result(conn)
+        .map_err(restore_error)?;
+    if taken > 0 {
+        return Err(ServiceErrors::DatabaseQueryFailed(format!(
+            "Project with key {} already exists",
+            archive.project.key
+        )));
+    }
+
+    let tm = conn.transaction_manager();
+    tm.begin_transaction(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    let restored = insert_project(conn, &archive.project).and_then(|project_id| {
+        let restore = ProjectRestore {
+            conn,
+            archive,
+            avatar_link,
+            ids: ArchiveIds::new(project_id, archive),
+            report: ProjectRestoreReport {
+                project_id,
+                ..Default::default()
+            },
+        };
+        restore.run()
+    });
+    match restored {
+        Ok(report) => {
+            tm.commit_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Ok(report)
+        }
+        Err(e) => {
+            tm.rollback_transaction(conn)
+                .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+            Err(e)
+        }
+    }
+}
+
+fn insert_project(conn: &DbPooledConn, project: &Project) -> Result<ProjectId, ServiceErrors> {
+    use crate::schema::projects::dsl::*;
+
+    let insert_query = diesel::insert_into(projects)
+        .values((
+            name.eq(project.name.as_str()),
+            url.eq(project.url.as_str()),
+            description.eq(project.description.as_str()),
+            category.eq(project.category),
+            time_tracking.eq(project.time_tracking),
+            wip_limit_policy.eq(project.wip_limit_policy),
+            key.eq(project.key.as_str()),
+            created_at.eq(project.created_at),
+            updated_at.eq(project.updated_at),
+        ))
+        .returning(id);
+    debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+    insert_query.get_result(conn).map_err(restore_error)
+}
+
+struct ProjectRestore<'a> {
+    conn: &'a DbPooledConn,
+    archive: &'a ProjectArchive,
+    avatar_link: &'a dyn Fn(UserId, &ArchivedAvatar) -> Result<String, String>,
+    ids: ArchiveIds,
+    report: ProjectRestoreReport,
+}
+
+impl<'a> ProjectRestore<'a> {
+    fn run(mut self) -> Result<ProjectRestoreReport, ServiceErrors> {
+        for status in self.archive.statuses.iter() {
+            self.restore_status(status)?;
+        }
+        self.restore_transitions()?;
+        for label in self.archive.labels.iter() {
+            self.restore_label(label)?;
+        }
+        for sprint in self.archive.sprints.iter() {
+            self.restore_sprint(sprint)?;
+        }
+        for field in self.archive.custom_fields.iter() {
+            self.restore_custom_field(field)?;
+        }
+        for user in self.archive.users.iter() {
+            self.restore_user(user)?;
+        }
+        for member in self.archive.members.iter() {
+            let member = self.ids.member(member)?;
+            add_project_member(self.conn, member.user_id, member.project_id, member.role)?;
+            self.report.members += 1;
+        }
+        // epics and parents are linked once every issue exists
+        for issue in self.archive.issues.iter() {
+            self.restore_issue(issue)?;
+        }
+        for issue in self.archive.issues.iter() {
+            self.link_issue(issue)?;
+        }
+        self.restore_assignees()?;
+        self.restore_issue_labels()?;
+        self.restore_custom_values()?;
+        self.restore_links()?;
+        self.restore_watchers()?;
+        self.restore_comments()?;
+        self.restore_worklogs()?;
+        self.restore_events()?;
+        self.restore_invitations()?;
+        Ok(self.report)
+    }
+
+    fn restore_status(&mut self, status: &IssueStatus) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_statuses::dsl::*;
+
+        let insert_query = diesel::insert_into(issue_statuses)
+            .values((
+                name.eq(status.name.as_str()),
+                position.eq(status.position),
+                project_id.eq(self.ids.project_id),
+                wip_limit.eq(status.wip_limit),
+                created_at.eq(status.created_at),
+                updated_at.eq(status.updated_at),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.statuses.insert(status.id, restored_id);
+        self.report.statuses += 1;
+        Ok(())
+    }
+
+    fn restore_transitions(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_status_transitions::dsl::*;
+
+        for transition in self.archive.transitions.iter() {
+            let transition = self.ids.transition(transition)?;
+            let insert_query = diesel::insert_into(issue_status_transitions).values((
+                project_id.eq(transition.project_id),
+                from_status_id.eq(transition.from_status_id),
+                to_status_id.eq(transition.to_status_id),
+                required_field.eq(transition.required_field),
+                created_at.eq(transition.created_at),
+                updated_at.eq(transition.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.transitions += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_label(&mut self, label: &Label) -> Result<(), ServiceErrors> {
+        use crate::schema::labels::dsl::*;
+
+        let insert_query = diesel::insert_into(labels)
+            .values((
+                project_id.eq(self.ids.project_id),
+                name.eq(label.name.as_str()),
+                color.eq(label.color.as_str()),
+                created_at.eq(label.created_at),
+                updated_at.eq(label.updated_at),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.labels.insert(label.id, restored_id);
+        self.report.labels += 1;
+        Ok(())
+    }
+
+    fn restore_sprint(&mut self, sprint: &Sprint) -> Result<(), ServiceErrors> {
+        use crate::schema::sprints::dsl::*;
+
+        let insert_query = diesel::insert_into(sprints)
+            .values((
+                project_id.eq(self.ids.project_id),
+                name.eq(sprint.name.as_str()),
+                goal.eq(sprint.goal.as_ref()),
+                starts_at.eq(sprint.starts_at),
+                ends_at.eq(sprint.ends_at),
+                state.eq(sprint.state),
+                created_at.eq(sprint.created_at),
+                updated_at.eq(sprint.updated_at),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.sprints.insert(sprint.id, restored_id);
+        self.report.sprints += 1;
+        Ok(())
+    }
+
+    fn restore_custom_field(&mut self, field: &CustomField) -> Result<(), ServiceErrors> {
+        use crate::schema::custom_fields::dsl::*;
+
+        let insert_query = diesel::insert_into(custom_fields)
+            .values((
+                project_id.eq(self.ids.project_id),
+                name.eq(field.name.as_str()),
+                field_type.eq(field.field_type),
+                options.eq(&field.options),
+                created_at.eq(field.created_at),
+                updated_at.eq(field.updated_at),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.custom_fields.insert(field.id, restored_id);
+        self.report.custom_fields += 1;
+        Ok(())
+    }
+
+    fn restore_user(&mut self, user: &User) -> Result<(), ServiceErrors> {
+        use crate::schema::users::dsl::*;
+
+        let user_query = users
+            .select(id)
+            .filter(lower(email).eq(user.email.trim().to_lowercase()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&user_query));
+        let existing: Option<UserId> = user_query
+            .first(self.conn)
+            .optional()
+            .map_err(restore_error)?;
+        if let Some(existing) = existing {
+            self.ids.users.insert(user.id, existing);
+            self.report.users_matched += 1;
+            return Ok(());
+        }
+
+        let insert_query = diesel::insert_into(users)
+            .values((
+                name.eq(user.name.as_str()),
+                email.eq(user.email.as_str()),
+                avatar_url.eq(user.avatar_url.as_ref()),
+                created_at.eq(user.created_at),
+                updated_at.eq(user.updated_at),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id: UserId = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.users.insert(user.id, restored_id);
+        self.report.users_created += 1;
+
+        let avatar = self
+            .archive
+            .avatars
+            .iter()
+            .find(|avatar| avatar.user_id == user.id);
+        if let Some(avatar) = avatar {
+            let link = (self.avatar_link)(restored_id, avatar).map_err(|e| {
+                ServiceErrors::DatabaseQueryFailed(format!("Failed to restore avatar. {}", e))
+            })?;
+            diesel::update(users.find(restored_id))
+                .set(avatar_url.eq(link))
+                .execute(self.conn)
+                .map_err(restore_error)?;
+            self.report.avatars.push((restored_id, avatar.clone()));
+        }
+        Ok(())
+    }
+
+    /// Time spent is set by `restore_worklogs` from restored worklogs
+    fn restore_issue(&mut self, issue: &Issue) -> Result<(), ServiceErrors> {
+        use crate::schema::issues::dsl::*;
+
+        let restored = self.ids.issue(issue)?;
+        // archive could come from older server which didn't sanitize rich text
+        let restored_description = restored
+            .description
+            .as_ref()
+            .map(|html| sanitize(html.as_str()));
+        let restored_description_text = restored_description
+            .as_ref()
+            .map(|html| to_text(html.as_str()));
+        let insert_query = diesel::insert_into(issues)
+            .values((
+                title.eq(restored.title.as_str()),
+                issue_type.eq(restored.issue_type),
+                priority.eq(restored.priority),
+                list_position.eq(restored.list_position),
+                description.eq(restored_description.as_ref()),
+                description_text.eq(restored_description_text),
+                estimate.eq(restored.estimate),
+                time_spent.eq(restored.time_spent),
+                time_remaining.eq(restored.time_remaining),
+                reporter_id.eq(restored.reporter_id),
+                project_id.eq(restored.project_id),
+                created_at.eq(restored.created_at),
+                updated_at.eq(restored.updated_at),
+                issue_status_id.eq(restored.issue_status_id),
+                sprint_id.eq(restored.sprint_id),
+                number.eq(restored.number),
+            ))
+            .returning(id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+        let restored_id = insert_query.get_result(self.conn).map_err(restore_error)?;
+        self.ids.issues.insert(issue.id, restored_id);
+        self.report.issues += 1;
+        Ok(())
+    }
+
+    fn link_issue(&self, issue: &Issue) -> Result<(), ServiceErrors> {
+        use crate::schema::issues::dsl::*;
+
+        if issue.epic_id.is_none() && issue.parent_id.is_none() {
+            return Ok(());
+        }
+        let (restored_id, restored_epic_id, restored_parent_id) =
+            self.ids.issue_relations(issue)?;
+        let update_query = diesel::update(issues.find(restored_id)).set((
+            epic_id.eq(restored_epic_id),
+            parent_id.eq(restored_parent_id),
+        ));
+        debug!("{}", diesel::debug_query::<Pg, _>(&update_query));
+        update_query
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(restore_error)
+    }
+
+    fn restore_assignees(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_assignees::dsl::*;
+
+        for assignee in self.archive.assignees.iter() {
+            let assignee = self.ids.assignee(assignee)?;
+            let insert_query = diesel::insert_into(issue_assignees).values((
+                issue_id.eq(assignee.issue_id),
+                user_id.eq(assignee.user_id),
+                created_at.eq(assignee.created_at),
+                updated_at.eq(assignee.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.assignees += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_issue_labels(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_labels::dsl::*;
+
+        for issue_label in self.archive.issue_labels.iter() {
+            let issue_label = self.ids.issue_label(issue_label)?;
+            let insert_query = diesel::insert_into(issue_labels).values((
+                issue_id.eq(issue_label.issue_id),
+                label_id.eq(issue_label.label_id),
+                created_at.eq(issue_label.created_at),
+                updated_at.eq(issue_label.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.issue_labels += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_custom_values(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_custom_field_values::dsl::*;
+
+        for value in self.archive.custom_values.iter() {
+            let value = self.ids.custom_value(value)?;
+            let insert_query = diesel::insert_into(issue_custom_field_values).values((
+                issue_id.eq(value.issue_id),
+                custom_field_id.eq(value.custom_field_id),
+                field_values.eq(&value.values),
+                created_at.eq(value.created_at),
+                updated_at.eq(value.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.custom_values += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_links(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_links::dsl::*;
+
+        for link in self.archive.links.iter() {
+            let link = self.ids.link(link)?;
+            let insert_query = diesel::insert_into(issue_links).values((
+                source_issue_id.eq(link.source_issue_id),
+                target_issue_id.eq(link.target_issue_id),
+                link_type.eq(link.link_type),
+                user_id.eq(link.user_id),
+                created_at.eq(link.created_at),
+                updated_at.eq(link.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.links += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_watchers(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_watchers::dsl::*;
+
+        for watcher in self.archive.watchers.iter() {
+            let watcher = self.ids.watcher(watcher)?;
+            let insert_query = diesel::insert_into(issue_watchers)
+                .values((issue_id.eq(watcher.issue_id), user_id.eq(watcher.user_id)));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.watchers += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_comments(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::comments::dsl::*;
+
+        for comment in self.archive.comments.iter() {
+            let comment = self.ids.comment(comment)?;
+            let restored_body = sanitize(comment.body.as_str());
+            let insert_query = diesel::insert_into(comments).values((
+                body_text.eq(to_text(restored_body.as_str())),
+                body.eq(restored_body),
+                user_id.eq(comment.user_id),
+                issue_id.eq(comment.issue_id),
+                created_at.eq(comment.created_at),
+                updated_at.eq(comment.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.comments += 1;
+        }
+        Ok(())
+    }
+
+    /// Time spent of every restored issue is summed from its worklogs, so
+    /// both always agree no matter what archive stored
+    fn restore_worklogs(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::worklogs::dsl::*;
+
+        for worklog in self.archive.worklogs.iter() {
+            let worklog = self.ids.worklog(worklog)?;
+            let insert_query = diesel::insert_into(worklogs).values((
+                issue_id.eq(worklog.issue_id),
+                user_id.eq(worklog.user_id),
+                time_spent.eq(worklog.time_spent),
+                started_at.eq(worklog.started_at),
+                comment.eq(worklog.comment.as_str()),
+                created_at.eq(worklog.created_at),
+                updated_at.eq(worklog.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.worklogs += 1;
+        }
+        for issue in self.archive.issues.iter() {
+            sync_time_spent(self.conn, self.ids.issues.get(issue.id)?).map_err(restore_error)?;
+        }
+        Ok(())
+    }
+
+    fn restore_events(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::issue_events::dsl::*;
+
+        for event in self.archive.events.iter() {
+            let event = self.ids.event(event)?;
+            let insert_query = diesel::insert_into(issue_events).values((
+                issue_id.eq(event.issue_id),
+                project_id.eq(event.project_id),
+                user_id.eq(event.user_id),
+                event_type.eq(event.event_type),
+                old_value.eq(event.old_value.as_ref()),
+                new_value.eq(event.new_value.as_ref()),
+                created_at.eq(event.created_at),
+                updated_at.eq(event.updated_at),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.events += 1;
+        }
+        Ok(())
+    }
+
+    fn restore_invitations(&mut self) -> Result<(), ServiceErrors> {
+        use crate::schema::invitations::dsl::*;
+
+        for invitation in self.archive.invitations.iter() {
+            let invitation = self.ids.invitation(invitation)?;
+            let insert_query = diesel::insert_into(invitations).values((
+                name.eq(invitation.name.as_str()),
+                email.eq(invitation.email.as_str()),
+                state.eq(invitation.state.clone()),
+                project_id.eq(invitation.project_id),
+                invited_by_id.eq(invitation.invited_by_id),
+                created_at.eq(invitation.created_at),
+                updated_at.eq(invitation.updated_at),
+                bind_token.eq(invitation.bind_token),
+                role.eq(invitation.role),
+            ));
+            debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+            insert_query.execute(self.conn).map_err(restore_error)?;
+            self.report.invitations += 1;
+        }
+        Ok(())
+    }
+}
@@ -3,12 +3,43 @@ use diesel::pg::Pg;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use jirs_data::{Project, ProjectCategory, TimeTracking, UserId};
+use jirs_data::{FieldError, Project, ProjectCategory, TimeTracking, UserId, WipLimitPolicy};
 
-use crate::db::DbExecutor;
+use crate::db::{DbExecutor, DbPooledConn};
 use crate::errors::ServiceErrors;
 use crate::schema::projects::all_columns;
 
+fn project_key_error(message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "key".to_string(),
+        message: message.to_string(),
+    }])
+}
+
+fn is_key_taken(conn: &DbPooledConn, project_key: &str) -> Result<bool, ServiceErrors> {
+    use crate::schema::projects::dsl::{key, projects};
+
+    projects
+        .filter(key.eq(project_key))
+        .count()
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)
+}
+
+/// New project key is built from its name, numbered suffix is added when
+/// other project already uses it.
+fn free_project_key(conn: &DbPooledConn, project_name: &str) -> Result<String, ServiceErrors> {
+    let base = jirs_data::project_key_from_name(project_name);
+    let mut candidate = base.clone();
+    let mut suffix = 1;
+    while is_key_taken(conn, candidate.as_str())? {
+        suffix += 1;
+        candidate = format!("{}{}", base, suffix);
+    }
+    Ok(candidate)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LoadCurrentProject {
     pub project_id: i32,
@@ -64,9 +95,11 @@ impl Handler<CreateProject> for DbExecutor {
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
+        let project_key = free_project_key(conn, msg.name.as_str())?;
         let query = diesel::insert_into(projects)
             .values((
                 name.eq(msg.name),
+                key.eq(project_key),
                 msg.url.map(|v| url.eq(v)),
                 msg.description.map(|v| description.eq(v)),
                 msg.category.map(|v| category.eq(v)),
@@ -88,6 +121,8 @@ pub struct UpdateProject {
     pub description: Option<String>,
     pub category: Option<ProjectCategory>,
     pub time_tracking: Option<TimeTracking>,
+    pub wip_limit_policy: Option<WipLimitPolicy>,
+    pub key: Option<String>,
 }
 
 impl Message for UpdateProject {
@@ -104,12 +139,34 @@ impl Handler<UpdateProject> for DbExecutor {
             .get()
             .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
 
+        let new_key = match msg.key.map(|v| v.trim().to_ascii_uppercase()) {
+            Some(v) if !jirs_data::is_valid_project_key(v.as_str()) => {
+                return Err(project_key_error(
+                    "Key must have 2 to 10 letters or digits and start with letter",
+                ));
+            }
+            Some(v) => {
+                let current = projects
+                    .find(msg.project_id)
+                    .select(key)
+                    .first::<String>(conn)
+                    .map_err(|_| ServiceErrors::RecordNotFound("Project".to_string()))?;
+                if current != v && is_key_taken(conn, v.as_str())? {
+                    return Err(project_key_error("Key is already used by other project"));
+                }
+                Some(v)
+            }
+            None => None,
+        };
+
         let update_query = diesel::update(projects.find(msg.project_id)).set((
             msg.name.map(|v| name.eq(v)),
             msg.url.map(|v| url.eq(v)),
             msg.description.map(|v| description.eq(v)),
             msg.category.map(|v| category.eq(v)),
             msg.time_tracking.map(|v| time_tracking.eq(v)),
+            msg.wip_limit_policy.map(|v| wip_limit_policy.eq(v)),
+            new_key.map(|v| key.eq(v)),
         ));
         debug!("{}", diesel::debug_query::<Pg, _>(&update_query));
         update_query
@@ -0,0 +1,673 @@
+use actix::{Handler, Message};
+use chrono::{Duration, NaiveDate};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    BurndownPoint, BurndownReport, CumulativeFlowPoint, CumulativeFlowReport, FieldError,
+    FlowTimeStats, Issue, IssueEvent, IssueEventType, IssueId, IssueStatus, IssueStatusId,
+    IssueType, Project, ProjectId, TimeTracking, TimeTrackingReport, TimeTrackingSummary, ToVec,
+    Worklog,
+};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+/// Longest range of day by day reports
+const MAX_REPORT_DAYS: i64 = 366;
+
+fn range_error() -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: "from".to_string(),
+        message: "Start of range must be before its end".to_string(),
+    }])
+}
+
+/// Day by day reports build point for every day so their range is limited
+fn check_report_range(from: NaiveDate, to: NaiveDate) -> Result<(), ServiceErrors> {
+    if from > to {
+        return Err(range_error());
+    }
+    if (to - from).num_days() >= MAX_REPORT_DAYS {
+        return Err(ServiceErrors::InvalidPayload(vec![FieldError {
+            field: "to".to_string(),
+            message: format!("Range can't be longer than {} days", MAX_REPORT_DAYS),
+        }]));
+    }
+    Ok(())
+}
+
+fn add_issue(summary: &mut TimeTrackingSummary, issue: &Issue, time_spent: i32) {
+    summary.issues += 1;
+    summary.estimate += issue.estimate.unwrap_or_default();
+    summary.time_spent += time_spent;
+    summary.time_remaining += issue.time_remaining.unwrap_or_default();
+}
+
+fn group_entry<'a, K: PartialEq, V: Default>(groups: &'a mut Vec<(K, V)>, key: K) -> &'a mut V {
+    let position = match groups.iter().position(|(k, _)| *k == key) {
+        Some(position) => position,
+        None => {
+            groups.push((key, V::default()));
+            groups.len() - 1
+        }
+    };
+    &mut groups[position].1
+}
+
+/// Sums work logged within range together with estimates and time remaining
+/// of issues it was logged on. Issues without such work are skipped.
+pub fn time_tracking_report(
+    time_tracking: TimeTracking,
+    from: NaiveDate,
+    to: NaiveDate,
+    issues: &[Issue],
+    worklogs: &[Worklog],
+) -> TimeTrackingReport {
+    let mut total = TimeTrackingSummary::default();
+    let mut by_user = vec![];
+    let mut by_issue_type = vec![];
+    let mut by_status = vec![];
+
+    for issue in issues {
+        let mut issue_spent = 0;
+        let mut user_spent = vec![];
+        for worklog in worklogs.iter().filter(|worklog| {
+            worklog.issue_id == issue.id && worklog.started_at >= from && worklog.started_at <= to
+        }) {
+            issue_spent += worklog.time_spent;
+            *group_entry(&mut user_spent, worklog.user_id) += worklog.time_spent;
+        }
+        if user_spent.is_empty() {
+            continue;
+        }
+        add_issue(&mut total, issue, issue_spent);
+        for (user_id, spent) in user_spent {
+            add_issue(group_entry(&mut by_user, user_id), issue, spent);
+        }
+        add_issue(
+            group_entry(&mut by_issue_type, issue.issue_type),
+            issue,
+            issue_spent,
+        );
+        add_issue(
+            group_entry(&mut by_status, issue.issue_status_id),
+            issue,
+            issue_spent,
+        );
+    }
+    by_user.sort_by_key(|(user_id, _)| *user_id);
+    by_issue_type.sort_by_key(|(issue_type, _)| {
+        IssueType::ordered()
+            .iter()
+            .position(|t| t == issue_type)
+            .unwrap_or_default()
+    });
+    by_status.sort_by_key(|(issue_status_id, _)| *issue_status_id);
+
+    TimeTrackingReport {
+        time_tracking,
+        from,
+        to,
+        total,
+        by_user,
+        by_issue_type,
+        by_status,
+    }
+}
+
+/// Work logged on project issues between `from` and `to` inclusive
+#[derive(Serialize, Deserialize)]
+pub struct LoadTimeTrackingReport {
+    pub project_id: ProjectId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl Message for LoadTimeTrackingReport {
+    type Result = Result<TimeTrackingReport, ServiceErrors>;
+}
+
+impl Handler<LoadTimeTrackingReport> for DbExecutor {
+    type Result = Result<TimeTrackingReport, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadTimeTrackingReport, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{self as issues_dsl, issues, project_id};
+        use crate::schema::projects::dsl::projects;
+        use crate::schema::worklogs::dsl::{started_at, worklogs};
+
+        if msg.from > msg.to {
+            return Err(range_error());
+        }
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let project: Project = projects
+            .find(msg.project_id)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+
+        let worklogs_query = worklogs
+            .inner_join(issues)
+            .filter(project_id.eq(msg.project_id))
+            .filter(started_at.ge(msg.from))
+            .filter(started_at.le(msg.to))
+            .select(crate::schema::worklogs::all_columns);
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklogs_query));
+        let report_worklogs: Vec<Worklog> = worklogs_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("worklogs".to_string()))?;
+
+        let mut ids: Vec<IssueId> = report_worklogs.iter().map(|w| w.issue_id).collect();
+        ids.sort();
+        ids.dedup();
+        let issues_query = issues.filter(issues_dsl::id.eq_any(ids));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issues_query));
+        let report_issues: Vec<Issue> = issues_query
+            .load::<crate::models::Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project issues".to_string()))?
+            .into_iter()
+            .map(|issue| issue.into())
+            .collect();
+
+        Ok(time_tracking_report(
+            project.time_tracking,
+            msg.from,
+            msg.to,
+            report_issues.as_slice(),
+            report_worklogs.as_slice(),
+        ))
+    }
+}
+
+/// Replays status, estimate and time remaining changes backwards from
+/// current issue values. Issues deleted before `to` are not counted.
+pub fn burndown_points(
+    from: NaiveDate,
+    to: NaiveDate,
+    done_status_id: Option<IssueStatusId>,
+    issues: &[Issue],
+    events: &[IssueEvent],
+) -> Vec<BurndownPoint> {
+    let mut states: Vec<(IssueStatusId, Option<i32>, Option<i32>)> = issues
+        .iter()
+        .map(|issue| (issue.issue_status_id, issue.estimate, issue.time_remaining))
+        .collect();
+    let mut events: Vec<&IssueEvent> = events.iter().collect();
+    events.sort_by_key(|event| std::cmp::Reverse((event.created_at, event.id)));
+
+    let mut points = vec![];
+    let mut next_event = 0;
+    let mut day = to;
+    while day >= from {
+        let end = (day + Duration::days(1)).and_hms(0, 0, 0);
+        // undo changes made after this day
+        while let Some(event) = events.get(next_event).filter(|e| e.created_at >= end) {
+            if let Some(position) = issues.iter().position(|i| i.id == event.issue_id) {
+                let state = &mut states[position];
+                let old_value = event.old_value.as_ref().and_then(|v| v.parse::<i32>().ok());
+                match event.event_type {
+                    IssueEventType::Status => state.0 = old_value.unwrap_or(state.0),
+                    IssueEventType::Estimate => state.1 = old_value,
+                    IssueEventType::TimeRemaining => state.2 = old_value,
+                    _ => (),
+                }
+            }
+            next_event += 1;
+        }
+
+        let mut point = BurndownPoint {
+            day,
+            scope: 0,
+            completed: 0,
+            time_remaining: 0,
+        };
+        for (issue, (status_id, estimate, time_remaining)) in issues.iter().zip(states.iter()) {
+            if issue.created_at >= end {
+                continue;
+            }
+            point.scope += estimate.unwrap_or_default();
+            if Some(*status_id) == done_status_id {
+                point.completed += estimate.unwrap_or_default();
+            } else {
+                point.time_remaining += time_remaining.unwrap_or_default();
+            }
+        }
+        points.push(point);
+        day = day.pred();
+    }
+    points.reverse();
+    points
+}
+
+/// Burndown of project issues between `from` and `to` inclusive
+#[derive(Serialize, Deserialize)]
+pub struct LoadBurndown {
+    pub project_id: ProjectId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl Message for LoadBurndown {
+    type Result = Result<BurndownReport, ServiceErrors>;
+}
+
+impl Handler<LoadBurndown> for DbExecutor {
+    type Result = Result<BurndownReport, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadBurndown, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_events::dsl::{self as events_dsl, event_type, issue_events};
+        use crate::schema::issue_statuses::dsl::{self as statuses_dsl, issue_statuses, position};
+        use crate::schema::issues::dsl::{issues, project_id};
+        use crate::schema::projects::dsl::projects;
+
+        check_report_range(msg.from, msg.to)?;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let project: Project = projects
+            .find(msg.project_id)
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project".to_string()))?;
+
+        let done_status_query = issue_statuses
+            .select(statuses_dsl::id)
+            .filter(statuses_dsl::project_id.eq(msg.project_id))
+            .order_by(position.desc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&done_status_query));
+        let done_status_id: Option<IssueStatusId> = done_status_query
+            .first(conn)
+            .optional()
+            .map_err(|_| ServiceErrors::RecordNotFound("issue statuses".to_string()))?;
+
+        let issues_query = issues.filter(project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issues_query));
+        let report_issues: Vec<Issue> = issues_query
+            .load::<crate::models::Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project issues".to_string()))?
+            .into_iter()
+            .map(|issue| issue.into())
+            .collect();
+
+        // only changes made after first day have to be reverted
+        let events_query = issue_events
+            .filter(events_dsl::project_id.eq(msg.project_id))
+            .filter(events_dsl::created_at.ge(msg.from.and_hms(0, 0, 0)))
+            .filter(event_type.eq_any(vec![
+                IssueEventType::Status,
+                IssueEventType::Estimate,
+                IssueEventType::TimeRemaining,
+            ]));
+        debug!("{}", diesel::debug_query::<Pg, _>(&events_query));
+        let events: Vec<IssueEvent> = events_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue history".to_string()))?;
+
+        Ok(BurndownReport {
+            time_tracking: project.time_tracking,
+            from: msg.from,
+            to: msg.to,
+            points: burndown_points(
+                msg.from,
+                msg.to,
+                done_status_id,
+                report_issues.as_slice(),
+                events.as_slice(),
+            ),
+        })
+    }
+}
+
+fn flow_time_stats(mut days: Vec<f64>) -> FlowTimeStats {
+    if days.is_empty() {
+        return FlowTimeStats::default();
+    }
+    days.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let middle = days.len() / 2;
+    let median = if days.len() % 2 == 0 {
+        (days[middle - 1] + days[middle]) / 2f64
+    } else {
+        days[middle]
+    };
+    FlowTimeStats {
+        issues: days.len() as i32,
+        average: days.iter().sum::<f64>() / days.len() as f64,
+        median,
+        max: days[days.len() - 1],
+    }
+}
+
+/// Replays status changes backwards from current issue statuses. Statuses
+/// must be ordered by position, issues moved to last of them within range
+/// are counted in cycle and lead time.
+pub fn cumulative_flow(
+    from: NaiveDate,
+    to: NaiveDate,
+    statuses: &[IssueStatus],
+    issues: &[Issue],
+    events: &[IssueEvent],
+) -> CumulativeFlowReport {
+    let mut events: Vec<&IssueEvent> = events
+        .iter()
+        .filter(|event| event.event_type == IssueEventType::Status)
+        .collect();
+    events.sort_by_key(|event| std::cmp::Reverse((event.created_at, event.id)));
+    let status_of = |value: &Option<String>| value.as_ref().and_then(|v| v.parse::<i32>().ok());
+
+    let mut states: Vec<IssueStatusId> = issues.iter().map(|i| i.issue_status_id).collect();
+    let mut points = vec![];
+    let mut next_event = 0;
+    let mut day = to;
+    while day >= from {
+        let end = (day + Duration::days(1)).and_hms(0, 0, 0);
+        // undo changes made after this day
+        while let Some(event) = events.get(next_event).filter(|e| e.created_at >= end) {
+            if let Some(position) = issues.iter().position(|i| i.id == event.issue_id) {
+                states[position] = status_of(&event.old_value).unwrap_or(states[position]);
+            }
+            next_event += 1;
+        }
+
+        let counts = statuses
+            .iter()
+            .map(|status| {
+                let count = issues
+                    .iter()
+                    .zip(states.iter())
+                    .filter(|(issue, status_id)| issue.created_at < end && **status_id == status.id)
+                    .count();
+                (status.id, count as i32)
+            })
+            .collect();
+        points.push(CumulativeFlowPoint { day, counts });
+        day = day.pred();
+    }
+    points.reverse();
+
+    let done_status_id = statuses.last().map(|status| status.id);
+    let (start, end) = (
+        from.and_hms(0, 0, 0),
+        (to + Duration::days(1)).and_hms(0, 0, 0),
+    );
+    let mut cycle_times = vec![];
+    let mut lead_times = vec![];
+    for issue in issues {
+        // events are sorted from newest
+        let issue_events: Vec<&&IssueEvent> =
+            events.iter().filter(|e| e.issue_id == issue.id).collect();
+        let done_at = match issue_events.iter().find(|e| {
+            status_of(&e.new_value) == done_status_id && e.created_at >= start && e.created_at < end
+        }) {
+            Some(event) => event.created_at,
+            None => continue,
+        };
+        let started_at = issue_events
+            .last()
+            .map(|e| e.created_at)
+            .unwrap_or(issue.created_at);
+        let days = |since: chrono::NaiveDateTime| (done_at - since).num_minutes() as f64 / 1440f64;
+        cycle_times.push(days(started_at));
+        lead_times.push(days(issue.created_at));
+    }
+
+    CumulativeFlowReport {
+        from,
+        to,
+        points,
+        cycle_time: flow_time_stats(cycle_times),
+        lead_time: flow_time_stats(lead_times),
+    }
+}
+
+/// Cumulative flow of project issues between `from` and `to` inclusive
+#[derive(Serialize, Deserialize)]
+pub struct LoadCumulativeFlow {
+    pub project_id: ProjectId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl Message for LoadCumulativeFlow {
+    type Result = Result<CumulativeFlowReport, ServiceErrors>;
+}
+
+impl Handler<LoadCumulativeFlow> for DbExecutor {
+    type Result = Result<CumulativeFlowReport, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadCumulativeFlow, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issue_events::dsl::{self as events_dsl, event_type, issue_events};
+        use crate::schema::issue_statuses::dsl::{self as statuses_dsl, issue_statuses, position};
+        use crate::schema::issues::dsl::{issues, project_id};
+
+        check_report_range(msg.from, msg.to)?;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let statuses_query = issue_statuses
+            .filter(statuses_dsl::project_id.eq(msg.project_id))
+            .order_by((position.asc(), statuses_dsl::id.asc()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&statuses_query));
+        let statuses: Vec<IssueStatus> = statuses_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue statuses".to_string()))?;
+
+        let issues_query = issues.filter(project_id.eq(msg.project_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&issues_query));
+        let report_issues: Vec<Issue> = issues_query
+            .load::<crate::models::Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project issues".to_string()))?
+            .into_iter()
+            .map(|issue| issue.into())
+            .collect();
+
+        // whole history is needed to find when finished issues were started
+        let events_query = issue_events
+            .filter(events_dsl::project_id.eq(msg.project_id))
+            .filter(event_type.eq(IssueEventType::Status));
+        debug!("{}", diesel::debug_query::<Pg, _>(&events_query));
+        let events: Vec<IssueEvent> = events_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue history".to_string()))?;
+
+        Ok(cumulative_flow(
+            msg.from,
+            msg.to,
+            statuses.as_slice(),
+            report_issues.as_slice(),
+            events.as_slice(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{self, status};
+
+    use super::*;
+
+    fn issue(id: i32, issue_type: IssueType) -> Issue {
+        Issue {
+            issue_type,
+            estimate: Some(10),
+            time_spent: Some(4),
+            ..test_support::issue(id)
+        }
+    }
+
+    fn worklog(issue_id: IssueId, user_id: i32, time_spent: i32, started_at: NaiveDate) -> Worklog {
+        Worklog {
+            id: 1,
+            issue_id,
+            user_id,
+            time_spent,
+            started_at,
+            comment: String::new(),
+            created_at: test_support::timestamp(),
+            updated_at: test_support::timestamp(),
+        }
+    }
+
+    #[test]
+    fn sums_work_logged_within_range_by_user_and_type() {
+        let day = NaiveDate::from_ymd(2020, 6, 1);
+        let issues = vec![
+            issue(1, IssueType::Bug),
+            issue(2, IssueType::Task),
+            issue(3, IssueType::Task),
+        ];
+        let worklogs = vec![
+            worklog(1, 1, 2, day),
+            worklog(1, 2, 3, day),
+            worklog(1, 1, 1, day),
+            worklog(2, 1, 5, day),
+            worklog(2, 2, 7, day.pred()),
+            worklog(3, 2, 7, day.succ()),
+        ];
+        let report = time_tracking_report(
+            TimeTracking::Hourly,
+            day,
+            day,
+            issues.as_slice(),
+            worklogs.as_slice(),
+        );
+        let summary = |issues, estimate, time_spent| TimeTrackingSummary {
+            issues,
+            estimate,
+            time_spent,
+            time_remaining: 0,
+        };
+        assert_eq!(report.total, summary(2, 20, 11));
+        assert_eq!(
+            report.by_user,
+            vec![(1, summary(2, 20, 8)), (2, summary(1, 10, 3))]
+        );
+        assert_eq!(
+            report.by_issue_type,
+            vec![
+                (IssueType::Task, summary(1, 10, 5)),
+                (IssueType::Bug, summary(1, 10, 6)),
+            ]
+        );
+        assert_eq!(report.by_status, vec![(1, summary(2, 20, 11))]);
+    }
+
+    #[test]
+    fn burndown_reverts_later_changes() {
+        let now = chrono::Utc::now().naive_utc();
+        let today = now.date();
+        let yesterday = today.pred();
+        let mut done = issue(1, IssueType::Task);
+        done.issue_status_id = 2;
+        done.time_remaining = Some(0);
+        done.created_at = yesterday.and_hms(8, 0, 0);
+        let event = |event_type, old_value: &str, new_value: &str| IssueEvent {
+            id: 1,
+            issue_id: 1,
+            project_id: 1,
+            user_id: 1,
+            event_type,
+            old_value: Some(old_value.to_string()),
+            new_value: Some(new_value.to_string()),
+            created_at: now,
+            updated_at: now,
+        };
+        let events = vec![
+            event(IssueEventType::Status, "1", "2"),
+            event(IssueEventType::TimeRemaining, "6", "0"),
+        ];
+        let points = burndown_points(yesterday, today, Some(2), &[done], events.as_slice());
+        assert_eq!(
+            points,
+            vec![
+                BurndownPoint {
+                    day: yesterday,
+                    scope: 10,
+                    completed: 0,
+                    time_remaining: 6,
+                },
+                BurndownPoint {
+                    day: today,
+                    scope: 10,
+                    completed: 10,
+                    time_remaining: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn day_by_day_range_is_limited() {
+        let from = NaiveDate::from_ymd(2020, 1, 1);
+        assert!(check_report_range(from, from).is_ok());
+        assert!(check_report_range(from, NaiveDate::from_ymd(2020, 12, 31)).is_ok());
+        assert!(check_report_range(from, NaiveDate::from_ymd(2021, 1, 1)).is_err());
+        assert!(check_report_range(from, from.pred()).is_err());
+    }
+
+    #[test]
+    fn cumulative_flow_replays_status_changes() {
+        let today = chrono::Utc::now().naive_utc().date();
+        let yesterday = today.pred();
+        let statuses = vec![status(1, "1"), status(2, "2"), status(3, "3")];
+        let mut done = issue(1, IssueType::Task);
+        done.issue_status_id = 3;
+        done.created_at = yesterday.and_hms(0, 0, 0);
+        let mut todo = issue(2, IssueType::Task);
+        todo.created_at = today.and_hms(1, 0, 0);
+        let event = |id, old_value: &str, new_value: &str, created_at| IssueEvent {
+            id,
+            issue_id: 1,
+            project_id: 1,
+            user_id: 1,
+            event_type: IssueEventType::Status,
+            old_value: Some(old_value.to_string()),
+            new_value: Some(new_value.to_string()),
+            created_at,
+            updated_at: created_at,
+        };
+        let events = vec![
+            event(1, "1", "2", yesterday.and_hms(12, 0, 0)),
+            event(2, "2", "3", today.and_hms(12, 0, 0)),
+        ];
+        let report = cumulative_flow(
+            yesterday,
+            today,
+            statuses.as_slice(),
+            &[done, todo],
+            events.as_slice(),
+        );
+        assert_eq!(
+            report.points,
+            vec![
+                CumulativeFlowPoint {
+                    day: yesterday,
+                    counts: vec![(1, 0), (2, 1), (3, 0)],
+                },
+                CumulativeFlowPoint {
+                    day: today,
+                    counts: vec![(1, 1), (2, 0), (3, 1)],
+                },
+            ]
+        );
+        assert_eq!(
+            report.cycle_time,
+            FlowTimeStats {
+                issues: 1,
+                average: 1f64,
+                median: 1f64,
+                max: 1f64,
+            }
+        );
+        assert_eq!(report.lead_time.median, 1.5f64);
+    }
+}
@@ -0,0 +1,142 @@
+use actix::{Handler, Message};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{BoardFilter, FieldError, ProjectId, SavedFilter, SavedFilterId, UserId};
+
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+
+fn saved_filter_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Loads filters created by user and filters shared by other project members.
+#[derive(Serialize, Deserialize)]
+pub struct LoadSavedFilters {
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+}
+
+impl Message for LoadSavedFilters {
+    type Result = Result<Vec<SavedFilter>, ServiceErrors>;
+}
+
+impl Handler<LoadSavedFilters> for DbExecutor {
+    type Result = Result<Vec<SavedFilter>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadSavedFilters, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::saved_filters::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let saved_filters_query = saved_filters
+            .filter(project_id.eq(msg.project_id))
+            .filter(user_id.eq(msg.user_id).or(shared.eq(true)))
+            .order_by(name.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&saved_filters_query));
+        saved_filters_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("saved filters".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateSavedFilter {
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub shared: bool,
+    pub filter: BoardFilter,
+}
+
+impl Message for CreateSavedFilter {
+    type Result = Result<SavedFilter, ServiceErrors>;
+}
+
+impl Handler<CreateSavedFilter> for DbExecutor {
+    type Result = Result<SavedFilter, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateSavedFilter, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::SavedFilterForm;
+        use crate::schema::saved_filters::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let filter_name = msg.name.trim().to_string();
+        if filter_name.is_empty() {
+            return Err(saved_filter_error("name", "Filter name can't be blank"));
+        }
+
+        let BoardFilter {
+            text,
+            user_ids: filter_user_ids,
+            label_ids: filter_label_ids,
+            only_my: filter_only_my,
+            recently_updated: filter_recently_updated,
+            jql: filter_jql,
+        } = msg.filter;
+        let form = SavedFilterForm {
+            project_id: msg.project_id,
+            user_id: msg.user_id,
+            name: filter_name,
+            shared: msg.shared,
+            text_filter: text,
+            user_ids: filter_user_ids,
+            label_ids: filter_label_ids,
+            only_my: filter_only_my,
+            recently_updated: filter_recently_updated,
+            jql: filter_jql.trim().to_string(),
+        };
+        let saved_filter_query = diesel::insert_into(saved_filters).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&saved_filter_query));
+        saved_filter_query
+            .get_result::<SavedFilter>(conn)
+            .map_err(|_| saved_filter_error("name", "Filter with this name already exists"))
+    }
+}
+
+/// Only owner can remove filter, removed filter is returned so caller knows
+/// whether it was shared.
+#[derive(Serialize, Deserialize)]
+pub struct DeleteSavedFilter {
+    pub saved_filter_id: SavedFilterId,
+    pub user_id: UserId,
+}
+
+impl Message for DeleteSavedFilter {
+    type Result = Result<SavedFilter, ServiceErrors>;
+}
+
+impl Handler<DeleteSavedFilter> for DbExecutor {
+    type Result = Result<SavedFilter, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteSavedFilter, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::saved_filters::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let saved_filter_query = diesel::delete(
+            saved_filters
+                .filter(id.eq(msg.saved_filter_id))
+                .filter(user_id.eq(msg.user_id)),
+        );
+        debug!("{}", diesel::debug_query::<Pg, _>(&saved_filter_query));
+        saved_filter_query
+            .get_result::<SavedFilter>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("saved filter".to_string()))
+    }
+}
@@ -0,0 +1,519 @@
+use actix::{Handler, Message};
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    FieldError, IssueId, IssueStatus, IssueStatusId, ProjectId, Sprint, SprintId, SprintState,
+    UpdateSprintPayload,
+};
+
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::models::Issue;
+
+fn sprint_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+fn load_sprint(
+    conn: &DbPooledConn,
+    sprint_id: SprintId,
+    current_project_id: ProjectId,
+) -> Result<Sprint, ServiceErrors> {
+    use crate::schema::sprints::dsl::{project_id, sprints};
+
+    sprints
+        .find(sprint_id)
+        .filter(project_id.eq(current_project_id))
+        .first::<Sprint>(conn)
+        .map_err(|_| ServiceErrors::RecordNotFound("sprint".to_string()))
+}
+
+/// Issue is finished when it's in last column of project board, every issue
+/// is unfinished when project has no columns.
+fn unfinished(
+    statuses: &[IssueStatus],
+    sprint_issues: &[(IssueId, IssueStatusId)],
+) -> Vec<IssueId> {
+    let done_id = statuses
+        .iter()
+        .max_by_key(|status| (status.position, status.id))
+        .map(|status| status.id);
+    sprint_issues
+        .iter()
+        .filter(|(_, status_id)| Some(*status_id) != done_id)
+        .map(|(issue_id, _)| *issue_id)
+        .collect()
+}
+
+/// Ids of sprint issues which are not finished yet.
+fn unfinished_issue_ids(
+    conn: &DbPooledConn,
+    sprint: &Sprint,
+) -> Result<Vec<IssueId>, ServiceErrors> {
+    use crate::schema::issue_statuses::dsl::{issue_statuses, project_id};
+    use crate::schema::issues::dsl::{id, issue_status_id, issues, sprint_id};
+
+    let statuses = issue_statuses
+        .filter(project_id.eq(sprint.project_id))
+        .load::<IssueStatus>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    let sprint_issues = issues
+        .select((id, issue_status_id))
+        .filter(sprint_id.eq(sprint.id))
+        .order_by(id.asc())
+        .load::<(IssueId, IssueStatusId)>(conn)
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+    Ok(unfinished(&statuses, &sprint_issues))
+}
+
+/// First planned sprint created after given one.
+fn next_planned_sprint(
+    conn: &DbPooledConn,
+    sprint: &Sprint,
+) -> Result<Option<Sprint>, ServiceErrors> {
+    use crate::schema::sprints::dsl::{id, project_id, sprints, state};
+
+    sprints
+        .filter(project_id.eq(sprint.project_id))
+        .filter(state.eq(SprintState::Planned))
+        .filter(id.ne(sprint.id))
+        .order_by(id.asc())
+        .first::<Sprint>(conn)
+        .optional()
+        .map_err(|_| ServiceErrors::DatabaseConnectionLost)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadSprints {
+    pub project_id: ProjectId,
+}
+
+impl Message for LoadSprints {
+    type Result = Result<Vec<Sprint>, ServiceErrors>;
+}
+
+impl Handler<LoadSprints> for DbExecutor {
+    type Result = Result<Vec<Sprint>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadSprints, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::sprints::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let sprints_query = sprints
+            .filter(project_id.eq(msg.project_id))
+            .order_by(id.asc());
+        debug!("{}", diesel::debug_query::<Pg, _>(&sprints_query));
+        sprints_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project sprints".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateSprint {
+    pub project_id: ProjectId,
+    pub name: String,
+}
+
+impl Message for CreateSprint {
+    type Result = Result<Sprint, ServiceErrors>;
+}
+
+impl Handler<CreateSprint> for DbExecutor {
+    type Result = Result<Sprint, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateSprint, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::models::SprintForm;
+        use crate::schema::sprints::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let sprint_name = msg.name.trim().to_string();
+        if sprint_name.is_empty() {
+            return Err(sprint_error("name", "Sprint name can't be blank"));
+        }
+
+        let form = SprintForm {
+            project_id: msg.project_id,
+            name: sprint_name,
+        };
+        let sprint_query = diesel::insert_into(sprints).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&sprint_query));
+        sprint_query
+            .get_result::<Sprint>(conn)
+            .map_err(|_| ServiceErrors::DatabaseQueryFailed("Failed to create sprint".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateSprint {
+    pub payload: UpdateSprintPayload,
+    pub project_id: ProjectId,
+}
+
+impl Message for UpdateSprint {
+    type Result = Result<Sprint, ServiceErrors>;
+}
+
+impl Handler<UpdateSprint> for DbExecutor {
+    type Result = Result<Sprint, ServiceErrors>;
+
+    fn handle(&mut self, msg: UpdateSprint, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::sprints::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let UpdateSprintPayload {
+            id: sprint_id,
+            name: sprint_name,
+            goal: sprint_goal,
+            starts_at: sprint_starts_at,
+            ends_at: sprint_ends_at,
+        } = msg.payload;
+        load_sprint(conn, sprint_id, msg.project_id)?;
+
+        let sprint_name = sprint_name.trim().to_string();
+        if sprint_name.is_empty() {
+            return Err(sprint_error("name", "Sprint name can't be blank"));
+        }
+        if let (Some(start), Some(end)) = (sprint_starts_at, sprint_ends_at) {
+            if end < start {
+                return Err(sprint_error("ends_at", "Sprint can't end before it starts"));
+            }
+        }
+        let sprint_goal = sprint_goal
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        let sprint_query = diesel::update(sprints.find(sprint_id)).set((
+            name.eq(sprint_name),
+            goal.eq(sprint_goal),
+            starts_at.eq(sprint_starts_at),
+            ends_at.eq(sprint_ends_at),
+            updated_at.eq(chrono::Utc::now().naive_utc()),
+        ));
+        debug!("{}", diesel::debug_query::<Pg, _>(&sprint_query));
+        sprint_query
+            .get_result::<Sprint>(conn)
+            .map_err(|_| ServiceErrors::DatabaseQueryFailed("Failed to update sprint".to_string()))
+    }
+}
+
+/// Moves issues of sprint back to backlog and removes the sprint.
+#[derive(Serialize, Deserialize)]
+pub struct DeleteSprint {
+    pub sprint_id: SprintId,
+    pub project_id: ProjectId,
+}
+
+impl Message for DeleteSprint {
+    type Result = Result<Vec<IssueId>, ServiceErrors>;
+}
+
+impl Handler<DeleteSprint> for DbExecutor {
+    type Result = Result<Vec<IssueId>, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteSprint, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{self as issues_dsl, issues};
+        use crate::schema::sprints::dsl::sprints;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        load_sprint(conn, msg.sprint_id, msg.project_id)?;
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!("sprint {}. {}", msg.sprint_id, e)),
+        };
+
+        let moved = diesel::update(issues.filter(issues_dsl::sprint_id.eq(msg.sprint_id)))
+            .set(issues_dsl::sprint_id.eq(None::<SprintId>))
+            .returning(issues_dsl::id)
+            .get_results::<IssueId>(conn)
+            .map_err(rollback)?;
+        diesel::delete(sprints.find(msg.sprint_id))
+            .execute(conn)
+            .map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(moved)
+    }
+}
+
+/// Plan issue into sprint, `None` moves it back to backlog. Sub-tasks always
+/// follow their parent.
+#[derive(Serialize, Deserialize)]
+pub struct SetIssueSprint {
+    pub issue_id: IssueId,
+    pub sprint_id: Option<SprintId>,
+    pub project_id: ProjectId,
+}
+
+impl Message for SetIssueSprint {
+    type Result = Result<Vec<Issue>, ServiceErrors>;
+}
+
+impl Handler<SetIssueSprint> for DbExecutor {
+    type Result = Result<Vec<Issue>, ServiceErrors>;
+
+    fn handle(&mut self, msg: SetIssueSprint, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{self, issues};
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let issue = issues
+            .find(msg.issue_id)
+            .filter(dsl::project_id.eq(msg.project_id))
+            .first::<Issue>(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue".to_string()))?;
+        if issue.parent_id.is_some() {
+            return Err(sprint_error(
+                "sprint_id",
+                "Sub-task is planned together with its parent",
+            ));
+        }
+        if let Some(sprint_id) = msg.sprint_id {
+            let sprint = load_sprint(conn, sprint_id, msg.project_id)?;
+            if sprint.state == SprintState::Completed {
+                return Err(sprint_error(
+                    "sprint_id",
+                    "Issue can't be moved to completed sprint",
+                ));
+            }
+        }
+
+        let mut ids = issues
+            .select(dsl::id)
+            .filter(dsl::parent_id.eq(issue.id))
+            .load::<IssueId>(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        ids.push(issue.id);
+
+        let query = diesel::update(issues.filter(dsl::id.eq_any(ids))).set((
+            dsl::sprint_id.eq(msg.sprint_id),
+            dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ));
+        debug!("{}", diesel::debug_query::<Pg, _>(&query));
+        query.get_results::<Issue>(conn).map_err(|_| {
+            ServiceErrors::DatabaseQueryFailed("Failed to update issue sprint".to_string())
+        })
+    }
+}
+
+/// Result of starting or completing sprint. Unfinished issues are moved
+/// to `target` sprint or to backlog when there's no such sprint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SprintTransition {
+    pub sprints: Vec<Sprint>,
+    pub moved_issue_ids: Vec<IssueId>,
+    pub from: SprintId,
+    pub target: Option<SprintId>,
+}
+
+fn complete_sprint(
+    conn: &DbPooledConn,
+    sprint: &Sprint,
+    unfinished: &[IssueId],
+    target: Option<SprintId>,
+) -> Result<Sprint, diesel::result::Error> {
+    use crate::schema::issues::dsl::{self as issues_dsl, issues};
+    use crate::schema::sprints::dsl::{self as sprints_dsl, sprints};
+
+    diesel::update(issues.filter(issues_dsl::id.eq_any(unfinished)))
+        .set(issues_dsl::sprint_id.eq(target))
+        .execute(conn)?;
+    let today = chrono::Utc::now().naive_utc().date();
+    diesel::update(sprints.find(sprint.id))
+        .set((
+            sprints_dsl::state.eq(SprintState::Completed),
+            sprints_dsl::ends_at.eq(sprint.ends_at.or(Some(today))),
+            sprints_dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .get_result::<Sprint>(conn)
+}
+
+/// Only one sprint of project can be active, currently active sprint is
+/// completed and its unfinished issues are moved to started sprint.
+#[derive(Serialize, Deserialize)]
+pub struct StartSprint {
+    pub sprint_id: SprintId,
+    pub project_id: ProjectId,
+}
+
+impl Message for StartSprint {
+    type Result = Result<SprintTransition, ServiceErrors>;
+}
+
+impl Handler<StartSprint> for DbExecutor {
+    type Result = Result<SprintTransition, ServiceErrors>;
+
+    fn handle(&mut self, msg: StartSprint, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::sprints::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let sprint = load_sprint(conn, msg.sprint_id, msg.project_id)?;
+        if sprint.state != SprintState::Planned {
+            return Err(sprint_error("state", "Only planned sprint can be started"));
+        }
+        let active = sprints
+            .filter(project_id.eq(msg.project_id))
+            .filter(state.eq(SprintState::Active))
+            .first::<Sprint>(conn)
+            .optional()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let moved_issue_ids = match active.as_ref() {
+            Some(active) => unfinished_issue_ids(conn, active)?,
+            _ => vec![],
+        };
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!("sprint {}. {}", msg.sprint_id, e)),
+        };
+
+        let mut changed = vec![];
+        let mut from = sprint.id;
+        if let Some(active) = active.as_ref() {
+            let completed = complete_sprint(conn, active, &moved_issue_ids, Some(sprint.id))
+                .map_err(rollback)?;
+            from = completed.id;
+            changed.push(completed);
+        }
+        let today = chrono::Utc::now().naive_utc().date();
+        let started = diesel::update(sprints.find(sprint.id))
+            .set((
+                state.eq(SprintState::Active),
+                starts_at.eq(Some(today)),
+                updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .get_result::<Sprint>(conn)
+            .map_err(rollback)?;
+        changed.push(started);
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(SprintTransition {
+            sprints: changed,
+            moved_issue_ids,
+            from,
+            target: Some(sprint.id),
+        })
+    }
+}
+
+/// Unfinished issues of completed sprint go to the next planned sprint or
+/// back to backlog.
+#[derive(Serialize, Deserialize)]
+pub struct CompleteSprint {
+    pub sprint_id: SprintId,
+    pub project_id: ProjectId,
+}
+
+impl Message for CompleteSprint {
+    type Result = Result<SprintTransition, ServiceErrors>;
+}
+
+impl Handler<CompleteSprint> for DbExecutor {
+    type Result = Result<SprintTransition, ServiceErrors>;
+
+    fn handle(&mut self, msg: CompleteSprint, _ctx: &mut Self::Context) -> Self::Result {
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let sprint = load_sprint(conn, msg.sprint_id, msg.project_id)?;
+        if sprint.state != SprintState::Active {
+            return Err(sprint_error("state", "Only active sprint can be completed"));
+        }
+        let target = next_planned_sprint(conn, &sprint)?.map(|next| next.id);
+        let moved_issue_ids = unfinished_issue_ids(conn, &sprint)?;
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| match tm.rollback_transaction(conn) {
+            Err(_) => ServiceErrors::DatabaseConnectionLost,
+            _ => ServiceErrors::RecordNotFound(format!("sprint {}. {}", msg.sprint_id, e)),
+        };
+
+        let completed =
+            complete_sprint(conn, &sprint, &moved_issue_ids, target).map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(SprintTransition {
+            sprints: vec![completed],
+            moved_issue_ids,
+            from: sprint.id,
+            target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::status;
+
+    use super::*;
+
+    fn column(id: IssueStatusId, position: i32) -> IssueStatus {
+        IssueStatus {
+            position,
+            ..status(id, &format!("Status {}", id))
+        }
+    }
+
+    #[test]
+    fn issues_outside_last_column_are_unfinished() {
+        let statuses = vec![column(1, 0), column(3, 2), column(2, 1)];
+        let sprint_issues = vec![(10, 1), (11, 3), (12, 2), (13, 3)];
+        assert_eq!(unfinished(&statuses, &sprint_issues), vec![10, 12]);
+    }
+
+    #[test]
+    fn columns_with_same_position_are_ordered_by_id() {
+        let statuses = vec![column(1, 0), column(2, 1), column(3, 1)];
+        let sprint_issues = vec![(10, 2), (11, 3)];
+        assert_eq!(unfinished(&statuses, &sprint_issues), vec![10]);
+    }
+
+    #[test]
+    fn every_issue_is_unfinished_without_columns() {
+        assert_eq!(unfinished(&[], &[(10, 1), (11, 2)]), vec![10, 11]);
+        assert!(unfinished(&[column(1, 0)], &[]).is_empty());
+    }
+}
@@ -3,9 +3,9 @@ use diesel::connection::TransactionManager;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 
-use jirs_data::{ProjectId, UserId, UserProject, UserProjectId, UserRole};
+use jirs_data::{FieldError, ProjectId, UserId, UserProject, UserProjectId, UserRole};
 
-use crate::db::DbExecutor;
+use crate::db::{DbExecutor, DbPooledConn};
 use crate::errors::ServiceErrors;
 
 pub struct CurrentUserProject {
@@ -192,3 +192,73 @@ impl Handler<RemoveInvitedUser> for DbExecutor {
         Ok(())
     }
 }
+
+/// Rejects users which are not members of project, `field` names payload
+/// field which holds them.
+pub fn check_project_members(
+    conn: &DbPooledConn,
+    member_project_id: ProjectId,
+    field: &str,
+    member_ids: &[UserId],
+) -> Result<(), ServiceErrors> {
+    use crate::schema::user_projects::dsl::*;
+
+    if member_ids.is_empty() {
+        return Ok(());
+    }
+    let members_query = user_projects
+        .select(user_id)
+        .filter(project_id.eq(member_project_id))
+        .filter(user_id.eq_any(member_ids));
+    debug!("{}", diesel::debug_query::<Pg, _>(&members_query));
+    let members: Vec<UserId> = members_query.load(conn).map_err(|e| {
+        error!("{}", e);
+        ServiceErrors::DatabaseQueryFailed("Failed to load project members".to_string())
+    })?;
+    if member_ids.iter().all(|member| members.contains(member)) {
+        return Ok(());
+    }
+    Err(ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: "User is not a project member".to_string(),
+    }]))
+}
+
+/// Adds user to project unless user is already a member. Project of user
+/// without any other project becomes the current and default one.
+pub fn add_project_member(
+    conn: &DbPooledConn,
+    member_id: UserId,
+    member_project_id: ProjectId,
+    member_role: UserRole,
+) -> Result<(), ServiceErrors> {
+    use crate::schema::user_projects::dsl::*;
+
+    let failed = |e: diesel::result::Error| {
+        error!("{}", e);
+        ServiceErrors::DatabaseQueryFailed("Failed to add project member".to_string())
+    };
+    let projects_count: i64 = user_projects
+        .filter(user_id.eq(member_id))
+        .count()
+        .get_result(conn)
+        .map_err(failed)?;
+    let member: i64 = user_projects
+        .filter(user_id.eq(member_id))
+        .filter(project_id.eq(member_project_id))
+        .count()
+        .get_result(conn)
+        .map_err(failed)?;
+    if member > 0 {
+        return Ok(());
+    }
+    let insert_query = diesel::insert_into(user_projects).values((
+        user_id.eq(member_id),
+        project_id.eq(member_project_id),
+        role.eq(member_role),
+        is_current.eq(projects_count == 0),
+        is_default.eq(projects_count == 0),
+    ));
+    debug!("{}", diesel::debug_query::<Pg, _>(&insert_query));
+    insert_query.execute(conn).map(|_| ()).map_err(failed)
+}
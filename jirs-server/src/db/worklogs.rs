@@ -0,0 +1,251 @@
+use actix::{Handler, Message};
+use chrono::NaiveDate;
+use diesel::connection::TransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{FieldError, IssueId, ProjectId, UserId, Worklog, WorklogId};
+
+use crate::db::{DbExecutor, DbPooledConn};
+use crate::errors::ServiceErrors;
+use crate::models::{Issue, WorklogForm};
+
+fn worklog_error(field: &str, message: &str) -> ServiceErrors {
+    ServiceErrors::InvalidPayload(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
+
+/// Stores sum of issue worklogs as issue time spent, issue without
+/// worklogs has no time spent.
+pub fn sync_time_spent(conn: &DbPooledConn, current_issue_id: IssueId) -> QueryResult<Issue> {
+    use crate::schema::issues::dsl::{issues, time_spent as issue_time_spent};
+    use crate::schema::worklogs::dsl::*;
+
+    let sum_query = worklogs
+        .select(diesel::dsl::sum(time_spent))
+        .filter(issue_id.eq(current_issue_id));
+    debug!("{}", diesel::debug_query::<Pg, _>(&sum_query));
+    let total: Option<i64> = sum_query.first(conn)?;
+
+    let issue_query = diesel::update(issues.find(current_issue_id))
+        .set(issue_time_spent.eq(total.map(|n| n as i32)));
+    debug!("{}", diesel::debug_query::<Pg, _>(&issue_query));
+    issue_query.get_result::<Issue>(conn)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadWorklogs {
+    pub issue_id: IssueId,
+}
+
+impl Message for LoadWorklogs {
+    type Result = Result<Vec<Worklog>, ServiceErrors>;
+}
+
+impl Handler<LoadWorklogs> for DbExecutor {
+    type Result = Result<Vec<Worklog>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadWorklogs, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::worklogs::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let worklogs_query = worklogs
+            .filter(issue_id.eq(msg.issue_id))
+            .order_by((started_at.desc(), id.desc()));
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklogs_query));
+        worklogs_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("issue worklogs".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadWorklog {
+    pub worklog_id: WorklogId,
+}
+
+impl Message for LoadWorklog {
+    type Result = Result<Worklog, ServiceErrors>;
+}
+
+impl Handler<LoadWorklog> for DbExecutor {
+    type Result = Result<Worklog, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadWorklog, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::worklogs::dsl::worklogs;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let worklog_query = worklogs.find(msg.worklog_id);
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklog_query));
+        worklog_query
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("worklog".to_string()))
+    }
+}
+
+/// Worklogs of project issues started between `from` and `to` inclusive,
+/// used by timesheet.
+#[derive(Serialize, Deserialize)]
+pub struct LoadProjectWorklogs {
+    pub project_id: ProjectId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub user_id: Option<UserId>,
+}
+
+impl Message for LoadProjectWorklogs {
+    type Result = Result<Vec<Worklog>, ServiceErrors>;
+}
+
+impl Handler<LoadProjectWorklogs> for DbExecutor {
+    type Result = Result<Vec<Worklog>, ServiceErrors>;
+
+    fn handle(&mut self, msg: LoadProjectWorklogs, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::issues::dsl::{issues, project_id};
+        use crate::schema::worklogs::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let mut worklogs_query = worklogs
+            .inner_join(issues)
+            .select(crate::schema::worklogs::all_columns)
+            .filter(project_id.eq(msg.project_id))
+            .filter(started_at.between(msg.from, msg.to))
+            .order_by((started_at.asc(), id.asc()))
+            .into_boxed();
+        if let Some(worker_id) = msg.user_id {
+            worklogs_query = worklogs_query.filter(user_id.eq(worker_id));
+        }
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklogs_query));
+        worklogs_query
+            .load(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("project worklogs".to_string()))
+    }
+}
+
+/// Logs work on issue and returns issue with updated time spent
+#[derive(Serialize, Deserialize)]
+pub struct CreateWorklog {
+    pub issue_id: IssueId,
+    pub user_id: UserId,
+    pub time_spent: i32,
+    pub started_at: NaiveDate,
+    pub comment: String,
+}
+
+impl Message for CreateWorklog {
+    type Result = Result<Issue, ServiceErrors>;
+}
+
+impl Handler<CreateWorklog> for DbExecutor {
+    type Result = Result<Issue, ServiceErrors>;
+
+    fn handle(&mut self, msg: CreateWorklog, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::worklogs::dsl::worklogs;
+
+        if msg.time_spent <= 0 {
+            return Err(worklog_error(
+                "time_spent",
+                "Time spent must be greater than zero",
+            ));
+        }
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| {
+            error!("{}", e);
+            match tm.rollback_transaction(conn) {
+                Err(_) => ServiceErrors::DatabaseConnectionLost,
+                _ => ServiceErrors::DatabaseQueryFailed("Failed to log work".to_string()),
+            }
+        };
+
+        let form = WorklogForm {
+            issue_id: msg.issue_id,
+            user_id: msg.user_id,
+            time_spent: msg.time_spent,
+            started_at: msg.started_at,
+            comment: msg.comment.trim().to_string(),
+        };
+        let worklog_query = diesel::insert_into(worklogs).values(form);
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklog_query));
+        worklog_query.execute(conn).map_err(rollback)?;
+        let issue = sync_time_spent(conn, msg.issue_id).map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(issue)
+    }
+}
+
+/// Removes worklog of given user and returns issue with updated time spent
+#[derive(Serialize, Deserialize)]
+pub struct DeleteWorklog {
+    pub worklog_id: WorklogId,
+    pub user_id: UserId,
+}
+
+impl Message for DeleteWorklog {
+    type Result = Result<Issue, ServiceErrors>;
+}
+
+impl Handler<DeleteWorklog> for DbExecutor {
+    type Result = Result<Issue, ServiceErrors>;
+
+    fn handle(&mut self, msg: DeleteWorklog, _ctx: &mut Self::Context) -> Self::Result {
+        use crate::schema::worklogs::dsl::*;
+
+        let conn = &self
+            .pool
+            .get()
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+
+        let worklog_query = worklogs
+            .filter(id.eq(msg.worklog_id))
+            .filter(user_id.eq(msg.user_id));
+        debug!("{}", diesel::debug_query::<Pg, _>(&worklog_query));
+        let worklog: Worklog = worklog_query
+            .first(conn)
+            .map_err(|_| ServiceErrors::RecordNotFound("worklog".to_string()))?;
+
+        let tm = conn.transaction_manager();
+        tm.begin_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        let rollback = |e: diesel::result::Error| {
+            error!("{}", e);
+            match tm.rollback_transaction(conn) {
+                Err(_) => ServiceErrors::DatabaseConnectionLost,
+                _ => ServiceErrors::DatabaseQueryFailed("Failed to delete worklog".to_string()),
+            }
+        };
+
+        diesel::delete(worklogs.find(worklog.id))
+            .execute(conn)
+            .map_err(rollback)?;
+        let issue = sync_time_spent(conn, worklog.issue_id).map_err(rollback)?;
+
+        tm.commit_transaction(conn)
+            .map_err(|_| ServiceErrors::DatabaseConnectionLost)?;
+        Ok(issue)
+    }
+}
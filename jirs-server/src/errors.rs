@@ -1,23 +1,78 @@
 use actix_web::HttpResponse;
 
-use jirs_data::ErrorResponse;
+use jirs_data::jql::JqlError;
+use jirs_data::{ErrorResponse, FieldError, WsError, WsErrorKind, WsMsg};
 
 const TOKEN_NOT_FOUND: &str = "Token not found";
 const DATABASE_CONNECTION_FAILED: &str = "Database connection failed";
+const INVALID_PAYLOAD: &str = "Invalid payload";
+const FORBIDDEN: &str = "You are not allowed to do this";
 
 #[derive(Debug)]
 pub enum ServiceErrors {
     Unauthorized,
+    Forbidden,
     DatabaseConnectionLost,
     DatabaseQueryFailed(String),
     RecordNotFound(String),
     RegisterCollision,
+    InvalidPayload(Vec<FieldError>),
+    InvalidQuery(JqlError),
 }
 
 impl ServiceErrors {
     pub fn into_http_response(self) -> HttpResponse {
         self.into()
     }
+
+    pub fn into_ws_error(self) -> WsError {
+        let (kind, message, fields) = match self {
+            ServiceErrors::Unauthorized => (
+                WsErrorKind::Unauthorized,
+                TOKEN_NOT_FOUND.to_owned(),
+                vec![],
+            ),
+            ServiceErrors::Forbidden => (WsErrorKind::Forbidden, FORBIDDEN.to_owned(), vec![]),
+            ServiceErrors::DatabaseConnectionLost => (
+                WsErrorKind::DatabaseConnectionLost,
+                DATABASE_CONNECTION_FAILED.to_owned(),
+                vec![],
+            ),
+            ServiceErrors::DatabaseQueryFailed(error) => {
+                (WsErrorKind::DatabaseQueryFailed, error, vec![])
+            }
+            ServiceErrors::RecordNotFound(resource_name) => (
+                WsErrorKind::RecordNotFound,
+                format!("Resource not found {}", resource_name),
+                vec![],
+            ),
+            ServiceErrors::RegisterCollision => (
+                WsErrorKind::RegisterCollision,
+                "Register collision".to_string(),
+                vec![],
+            ),
+            ServiceErrors::InvalidPayload(fields) => (
+                WsErrorKind::InvalidPayload,
+                INVALID_PAYLOAD.to_owned(),
+                fields,
+            ),
+            ServiceErrors::InvalidQuery(error) => {
+                (WsErrorKind::InvalidPayload, error.to_string(), vec![])
+            }
+        };
+        WsError {
+            request_id: None,
+            kind,
+            message,
+            fields,
+        }
+    }
+}
+
+impl Into<WsMsg> for ServiceErrors {
+    fn into(self) -> WsMsg {
+        WsMsg::Error(self.into_ws_error())
+    }
 }
 
 impl Into<HttpResponse> for ServiceErrors {
@@ -26,6 +81,9 @@ impl Into<HttpResponse> for ServiceErrors {
             ServiceErrors::Unauthorized => HttpResponse::Unauthorized().json(ErrorResponse {
                 errors: vec![TOKEN_NOT_FOUND.to_owned()],
             }),
+            ServiceErrors::Forbidden => HttpResponse::Forbidden().json(ErrorResponse {
+                errors: vec![FORBIDDEN.to_owned()],
+            }),
             ServiceErrors::DatabaseConnectionLost => {
                 HttpResponse::InternalServerError().json(ErrorResponse {
                     errors: vec![DATABASE_CONNECTION_FAILED.to_owned()],
@@ -44,6 +102,106 @@ impl Into<HttpResponse> for ServiceErrors {
             ServiceErrors::RegisterCollision => HttpResponse::Unauthorized().json(ErrorResponse {
                 errors: vec!["Register collision".to_string()],
             }),
+            ServiceErrors::InvalidPayload(fields) => {
+                HttpResponse::BadRequest().json(ErrorResponse {
+                    errors: fields
+                        .into_iter()
+                        .map(|f| format!("{}: {}", f.field, f.message))
+                        .collect(),
+                })
+            }
+            ServiceErrors::InvalidQuery(error) => HttpResponse::BadRequest().json(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+
+    use super::*;
+
+    fn invalid_title() -> FieldError {
+        FieldError {
+            field: "title".to_string(),
+            message: "Title is required".to_string(),
         }
     }
+
+    #[test]
+    fn maps_every_error_to_ws_error_kind() {
+        let kinds: Vec<WsErrorKind> = vec![
+            ServiceErrors::Unauthorized,
+            ServiceErrors::Forbidden,
+            ServiceErrors::DatabaseConnectionLost,
+            ServiceErrors::DatabaseQueryFailed("Failed".to_string()),
+            ServiceErrors::RecordNotFound("issue".to_string()),
+            ServiceErrors::RegisterCollision,
+            ServiceErrors::InvalidPayload(vec![]),
+        ]
+        .into_iter()
+        .map(|e| e.into_ws_error().kind)
+        .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                WsErrorKind::Unauthorized,
+                WsErrorKind::Forbidden,
+                WsErrorKind::DatabaseConnectionLost,
+                WsErrorKind::DatabaseQueryFailed,
+                WsErrorKind::RecordNotFound,
+                WsErrorKind::RegisterCollision,
+                WsErrorKind::InvalidPayload,
+            ]
+        );
+    }
+
+    #[test]
+    fn ws_error_keeps_message_and_fields() {
+        let error = ServiceErrors::RecordNotFound("issue".to_string()).into_ws_error();
+        assert_eq!(error.message, "Resource not found issue");
+        assert!(error.fields.is_empty());
+
+        let error = ServiceErrors::InvalidPayload(vec![invalid_title()]).into_ws_error();
+        assert_eq!(error.message, INVALID_PAYLOAD);
+        assert_eq!(error.fields, vec![invalid_title()]);
+    }
+
+    #[test]
+    fn ws_error_is_sent_without_request_id() {
+        let msg: WsMsg = ServiceErrors::Forbidden.into();
+        assert_eq!(
+            msg,
+            WsMsg::Error(WsError {
+                request_id: None,
+                kind: WsErrorKind::Forbidden,
+                message: FORBIDDEN.to_string(),
+                fields: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn maps_errors_to_http_status() {
+        let statuses: Vec<StatusCode> = vec![
+            ServiceErrors::Unauthorized,
+            ServiceErrors::Forbidden,
+            ServiceErrors::DatabaseConnectionLost,
+            ServiceErrors::RecordNotFound("issue".to_string()),
+            ServiceErrors::InvalidPayload(vec![invalid_title()]),
+        ]
+        .into_iter()
+        .map(|e| e.into_http_response().status())
+        .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                StatusCode::UNAUTHORIZED,
+                StatusCode::FORBIDDEN,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_REQUEST,
+                StatusCode::BAD_REQUEST,
+            ]
+        );
+    }
 }
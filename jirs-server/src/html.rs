@@ -0,0 +1,487 @@
+//! Allowlist sanitizer for rich text written by users (issue descriptions and
+//! comments). Unknown tags are removed but their text is kept, script-like
+//! elements are removed together with their content and only listed
+//! attributes survive. Output is written from parsed values, so browser reads
+//! it the same way as sanitizer did.
+
+use crate::utils::unescape_entities;
+
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// Elements without content and closing tag
+const VOID_TAGS: &[&str] = &["br", "hr", "img"];
+
+/// Elements closed by next sibling of the same kind, like `<li>a<li>b`
+const SIBLING_CLOSED_TAGS: &[&str] = &["li", "p", "td", "th", "tr"];
+
+/// Elements removed together with everything inside of them
+const DROPPED_TAGS: &[&str] = &[
+    "applet",
+    "frameset",
+    "iframe",
+    "math",
+    "noembed",
+    "noframes",
+    "noscript",
+    "object",
+    "plaintext",
+    "script",
+    "select",
+    "style",
+    "svg",
+    "template",
+    "textarea",
+    "title",
+    "xmp",
+];
+
+/// Elements which start new line in plain text
+const BLOCK_TAGS: &[&str] = &[
+    "blockquote",
+    "br",
+    "div",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "li",
+    "p",
+    "pre",
+    "tr",
+];
+
+const SAFE_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+const TEXT_ALIGNS: &[&str] = &["left", "right", "center", "justify"];
+
+#[derive(Debug)]
+struct Tag {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+fn is_space(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\n' || b == b'\r' || b == 0x0c
+}
+
+/// Reads tag at the beginning of text and returns it with text after it.
+/// Unterminated tag returns nothing, browser would ignore it as well.
+fn read_tag(s: &str) -> Option<(Tag, &str)> {
+    let bytes = s.as_bytes();
+    let len = bytes.len();
+    let closing = bytes.get(1) == Some(&b'/');
+    let mut i = if closing { 2 } else { 1 };
+
+    let name_start = i;
+    while i < len && !is_space(bytes[i]) && bytes[i] != b'/' && bytes[i] != b'>' {
+        i += 1;
+    }
+    let mut tag = Tag {
+        name: s[name_start..i].to_ascii_lowercase(),
+        closing,
+        attributes: vec![],
+    };
+
+    loop {
+        while i < len && (is_space(bytes[i]) || bytes[i] == b'/') {
+            i += 1;
+        }
+        if i >= len {
+            return None;
+        }
+        if bytes[i] == b'>' {
+            return Some((tag, &s[i + 1..]));
+        }
+
+        let attr_start = i;
+        i += 1;
+        while i < len
+            && !is_space(bytes[i])
+            && bytes[i] != b'/'
+            && bytes[i] != b'>'
+            && bytes[i] != b'='
+        {
+            i += 1;
+        }
+        let attr_name = s[attr_start..i].to_ascii_lowercase();
+        while i < len && is_space(bytes[i]) {
+            i += 1;
+        }
+        let mut value = "";
+        if i < len && bytes[i] == b'=' {
+            i += 1;
+            while i < len && is_space(bytes[i]) {
+                i += 1;
+            }
+            if i < len && (bytes[i] == b'"' || bytes[i] == b'\'') {
+                let quote = bytes[i] as char;
+                let value_start = i + 1;
+                let end = s[value_start..].find(quote)?;
+                value = &s[value_start..value_start + end];
+                i = value_start + end + 1;
+            } else {
+                let value_start = i;
+                while i < len && !is_space(bytes[i]) && bytes[i] != b'>' {
+                    i += 1;
+                }
+                value = &s[value_start..i];
+            }
+        }
+        tag.attributes.push((attr_name, unescape_entities(value)));
+    }
+}
+
+/// Relative links and links with allowed scheme are safe. Browsers ignore
+/// whitespace and control characters inside of scheme so they are skipped.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    match url.find(|c| c == ':' || c == '/' || c == '?' || c == '#') {
+        Some(idx) if url[idx..].starts_with(':') => SAFE_URL_SCHEMES.contains(&&url[..idx]),
+        _ => true,
+    }
+}
+
+/// Only text alignment set by editor is kept from inline styles
+fn text_align(style: &str) -> Option<String> {
+    style
+        .split(';')
+        .filter_map(|declaration| {
+            let mut parts = declaration.splitn(2, ':');
+            let property = parts.next()?.trim().to_lowercase();
+            let value = parts.next()?.trim().to_lowercase();
+            if property == "text-align" && TEXT_ALIGNS.contains(&value.as_str()) {
+                Some(format!("text-align: {}", value))
+            } else {
+                None
+            }
+        })
+        .last()
+}
+
+fn allowed_attribute(tag: &str, name: &str, value: &str) -> Option<String> {
+    match (tag, name) {
+        (_, "title") => Some(value.to_string()),
+        (_, "style") => text_align(value),
+        ("a", "href") | ("img", "src") if is_safe_url(value) => Some(value.to_string()),
+        ("img", "alt") | ("img", "width") | ("img", "height") => Some(value.to_string()),
+        ("td", "colspan") | ("td", "rowspan") | ("th", "colspan") | ("th", "rowspan") => {
+            value.parse::<u32>().ok().map(|n| n.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn close_until(out: &mut String, open: &mut Vec<String>, name: &str) {
+    while let Some(tag) = open.pop() {
+        out.push_str(format!("</{}>", tag).as_str());
+        if tag == name {
+            break;
+        }
+    }
+}
+
+/// Returns HTML with allowed tags only, every opened tag is closed
+pub fn sanitize(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        match rest[1..].chars().next() {
+            Some(c) if c == '/' || c == '!' || c == '?' || c.is_ascii_alphabetic() => (),
+            _ => {
+                out.push_str("&lt;");
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        let (tag, remaining) = match read_tag(rest) {
+            Some(read) => read,
+            None => break,
+        };
+        rest = remaining;
+        let name = tag.name.as_str();
+
+        if tag.closing {
+            if open.iter().any(|open_tag| open_tag == name) {
+                close_until(&mut out, &mut open, name);
+            }
+            continue;
+        }
+        if DROPPED_TAGS.contains(&name) {
+            let end_tag = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(end_tag.as_str()) {
+                Some(end) => read_tag(&rest[end..]).map_or("", |(_, remaining)| remaining),
+                None => "",
+            };
+            continue;
+        }
+        if !ALLOWED_TAGS.contains(&name) {
+            continue;
+        }
+
+        if SIBLING_CLOSED_TAGS.contains(&name) && open.last().map(|t| t.as_str()) == Some(name) {
+            close_until(&mut out, &mut open, name);
+        }
+        out.push('<');
+        out.push_str(name);
+        for (attr_name, value) in tag.attributes.iter() {
+            if let Some(value) = allowed_attribute(name, attr_name, value) {
+                out.push_str(format!(" {}=\"{}\"", attr_name, escape_attribute(&value)).as_str());
+            }
+        }
+        out.push('>');
+        if !VOID_TAGS.contains(&name) {
+            open.push(tag.name);
+        }
+    }
+    if !rest.starts_with('<') {
+        out.push_str(rest);
+    }
+    while let Some(tag) = open.pop() {
+        out.push_str(format!("</{}>", tag).as_str());
+    }
+    out
+}
+
+/// Plain text of sanitized HTML, block elements start new line and
+/// whitespace is collapsed
+pub fn to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(unescape_entities(&rest[..start]).as_str());
+        match read_tag(&rest[start..]) {
+            Some((tag, remaining)) => {
+                if BLOCK_TAGS.contains(&tag.name.as_str()) {
+                    text.push('\n');
+                } else if tag.name == "td" || tag.name == "th" {
+                    text.push(' ');
+                }
+                rest = remaining;
+            }
+            None => rest = "",
+        }
+    }
+    text.push_str(unescape_entities(rest).as_str());
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XSS_PAYLOADS: &[&str] = &[
+        "<script>alert(1)</script>",
+        "<SCRIPT SRC=http://xss.rocks/xss.js></SCRIPT>",
+        "<<SCRIPT>alert(\"XSS\");//<</SCRIPT>",
+        "<scr<script>ipt>alert(1)</scr</script>ipt>",
+        "<script>alert(1)</script >",
+        "<IMG SRC=\"javascript:alert('XSS');\">",
+        "<IMG SRC=JaVaScRiPt:alert('XSS')>",
+        "<IMG SRC=`javascript:alert(\"RSnake says, 'XSS'\")`>",
+        "<IMG SRC=&#106;&#97;&#118;&#97;&#115;&#99;&#114;&#105;&#112;&#116;&#58;&#97;&#108;&#101;&#114;&#116;&#40;&#39;&#88;&#83;&#83;&#39;&#41;>",
+        "<IMG SRC=&#0000106&#0000097&#0000118&#0000097&#0000115&#0000099&#0000114&#0000105&#0000112&#0000116&#0000058&#0000097&#0000108&#0000101&#0000114&#0000116&#0000040&#0000039&#0000088&#0000083&#0000083&#0000039&#0000041>",
+        "<IMG SRC=&#x6A&#x61&#x76&#x61&#x73&#x63&#x72&#x69&#x70&#x74&#x3A&#x61&#x6C&#x65&#x72&#x74&#x28&#x27&#x58&#x53&#x53&#x27&#x29>",
+        "<IMG SRC=\"jav\tascript:alert('XSS');\">",
+        "<IMG SRC=\"jav&#x09;ascript:alert('XSS');\">",
+        "<IMG SRC=\"jav&#x0A;ascript:alert('XSS');\">",
+        "<IMG SRC=\" &#14;  javascript:alert('XSS');\">",
+        "<a href=\"&#1;javascript:alert(1)\">x</a>",
+        "<a href=\"java&Tab;script&colon;alert(1)\">x</a>",
+        "<a href=javascript&colon;alert(1)>x</a>",
+        "<a href=\"&#X6A;avascript:alert(1)\">x</a>",
+        "<a href=\"vbscript:msgbox(1)\">x</a>",
+        "<a href=\"data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==\">x</a>",
+        "<img src=x onerror=alert(1)>",
+        "<img src=x onerror=\"alert(1)\"//",
+        "<img src=\"x` `<script>alert(1)</script>\"` `>",
+        "<img/src=x/onerror=alert(1)>",
+        "<svg/onload=alert(1)>",
+        "<svg><script>alert(1)</script></svg>",
+        "<body onload=alert('XSS')>",
+        "<p title=\"x\" onmouseover=\"alert(1)\">hover</p>",
+        "<div style=\"background:url(javascript:alert(1))\">x</div>",
+        "<div style=\"width: expression(alert(1))\">x</div>",
+        "<span style=\"text-align: center; behavior: url(xss.htc)\">x</span>",
+        "<iframe src=\"javascript:alert(1)\"></iframe>",
+        "<iframe srcdoc=\"&lt;script&gt;alert(1)&lt;/script&gt;\"></iframe>",
+        "<object data=\"javascript:alert(1)\"></object>",
+        "<embed src=\"javascript:alert(1)\">",
+        "<form action=\"javascript:alert(1)\"><button>x</button></form>",
+        "<input onfocus=alert(1) autofocus>",
+        "<details open ontoggle=alert(1)>",
+        "<meta http-equiv=\"refresh\" content=\"0;url=javascript:alert(1)\">",
+        "<link rel=stylesheet href=javascript:alert(1)>",
+        "<base href=\"javascript:alert(1)//\">",
+        "<style>@import 'javascript:alert(1)';</style>",
+        "<template><script>alert(1)</script></template>",
+        "<xmp><script>alert(1)</script></xmp>",
+        "<textarea><script>alert(1)</script></textarea>",
+        "<noscript><p title=\"</noscript><img src=x onerror=alert(1)>\">",
+        "<math><mtext><table><mglyph><style><img src=x onerror=alert(1)>",
+        "<!--<img src=\"--><img src=x onerror=alert(1)//\">",
+        "<!--><script>alert(1)</script>-->",
+        "<![CDATA[<script>alert(1)</script>]]>",
+        "<?xml version=\"1.0\"?><script>alert(1)</script>",
+        "<a href=\"https://example.com\" onclick=\"alert(1)\" target=\"_blank\">x</a>",
+        "<table background=\"javascript:alert(1)\"><tr><td onmouseover=alert(1)>x</td></tr></table>",
+    ];
+
+    /// Event handlers and other attributes are checked by `assert_allowed_markup`
+    const FORBIDDEN: &[&str] = &[
+        "<script",
+        "javascript:",
+        "vbscript:",
+        "data:",
+        "expression(",
+        "url(",
+    ];
+
+    /// Every tag in output is allowed and has only allowed attributes
+    fn assert_allowed_markup(html: &str) {
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            let (tag, remaining) = read_tag(&rest[start..])
+                .unwrap_or_else(|| panic!("unterminated tag in {:?}", html));
+            assert!(
+                ALLOWED_TAGS.contains(&tag.name.as_str()),
+                "tag {} in {:?}",
+                tag.name,
+                html
+            );
+            for (name, value) in tag.attributes.iter() {
+                assert_eq!(
+                    allowed_attribute(tag.name.as_str(), name, value).as_ref(),
+                    Some(value),
+                    "attribute {} in {:?}",
+                    name,
+                    html
+                );
+            }
+            rest = remaining;
+        }
+    }
+
+    #[test]
+    fn removes_xss_payloads() {
+        for payload in XSS_PAYLOADS {
+            let html = sanitize(payload);
+            assert_allowed_markup(html.as_str());
+            let lower = html.to_lowercase();
+            for forbidden in FORBIDDEN {
+                assert!(
+                    !lower.contains(forbidden),
+                    "{:?} sanitized to {:?}",
+                    payload,
+                    html
+                );
+            }
+            assert_eq!(sanitize(html.as_str()), html, "not stable {:?}", payload);
+        }
+    }
+
+    #[test]
+    fn keeps_editor_markup() {
+        assert_eq!(
+            sanitize("<h1>Title</h1><p style=\"text-align: center;\">Some <b>bold</b>, <i>italic</i> and <u>under</u> text&nbsp;</p>"),
+            "<h1>Title</h1><p style=\"text-align: center\">Some <b>bold</b>, <i>italic</i> and <u>under</u> text&nbsp;</p>"
+        );
+        assert_eq!(
+            sanitize("<table><tbody><tr><td>&nbsp;</td><td colspan=2>x</td></tr></tbody></table>"),
+            "<table><tbody><tr><td>&nbsp;</td><td colspan=\"2\">x</td></tr></tbody></table>"
+        );
+        assert_eq!(
+            sanitize("<ul><li>one<li>two</ul><a href=\"https://example.com/?a=1&amp;b=2\" title='Say \"hi\"'>link</a><br/>"),
+            "<ul><li>one</li><li>two</li></ul><a href=\"https://example.com/?a=1&amp;b=2\" title=\"Say &quot;hi&quot;\">link</a><br>"
+        );
+    }
+
+    #[test]
+    fn closes_tags_and_escapes_text() {
+        assert_eq!(
+            sanitize("<b><i>nested</b> 1 < 2 > 0 <p>open"),
+            "<b><i>nested</i></b> 1 &lt; 2 > 0 <p>open</p>"
+        );
+        assert_eq!(sanitize("x <unknown>kept</unknown> </p>"), "x kept ");
+        assert_eq!(
+            sanitize("Markdown **text**\n> quote"),
+            "Markdown **text**\n> quote"
+        );
+    }
+
+    #[test]
+    fn reads_plain_text() {
+        assert_eq!(
+            to_text("<h1>Title</h1><p>First&nbsp;line<br>second   <b>line</b></p><table><tr><td>a</td><td>b &amp; c</td></tr></table>"),
+            "Title\nFirst line\nsecond line\na b & c"
+        );
+        assert_eq!(to_text("1 &lt; 2"), "1 < 2");
+    }
+}
@@ -0,0 +1,455 @@
+//! Reading of Jira project exports. Supported are JSON responses of Jira
+//! search API (`/rest/api/2/search?jql=project=KEY`) and XML (RSS) export of
+//! issue navigator.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use jirs_data::{IssuePriority, IssueType};
+
+use crate::db::jira_import::import_jira_issues;
+
+pub mod xml;
+
+const USAGE: &str = "Usage: jirs_server import-jira <project key> <export file>";
+
+/// Admin command which imports export file into existing project and prints
+/// summary of imported and skipped data
+pub fn run_import(args: &[String]) -> Result<(), String> {
+    let (project_key, path) = match args {
+        [project_key, path] => (project_key, path),
+        _ => return Err(USAGE.to_string()),
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}. {}", path, e))?;
+    let jira_issues = parse_export(text.as_str())?;
+    let pool = crate::db::build_pool();
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let report = import_jira_issues(&conn, project_key.as_str(), jira_issues)
+        .map_err(|e| format!("Import failed, nothing was saved. {:?}", e))?;
+    println!("{}", report);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JiraUser {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JiraComment {
+    pub author: Option<JiraUser>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JiraIssue {
+    pub key: String,
+    pub summary: String,
+    pub issue_type: String,
+    pub priority: Option<String>,
+    pub status: String,
+    pub description: Option<String>,
+    pub reporter: Option<JiraUser>,
+    pub assignee: Option<JiraUser>,
+    pub parent_key: Option<String>,
+    pub comments: Vec<JiraComment>,
+    /// Names of fields which have value but can't be imported
+    pub ignored_fields: Vec<String>,
+}
+
+/// Jira fields which are reported as skipped when they have any value
+const IGNORED_FIELDS: [&str; 7] = [
+    "attachment",
+    "labels",
+    "fixVersions",
+    "components",
+    "issuelinks",
+    "worklog",
+    "timeoriginalestimate",
+];
+
+/// Jira issue type or `None` when type has no equivalent
+pub fn map_issue_type(name: &str) -> Option<IssueType> {
+    match name.to_lowercase().trim() {
+        "task" => Some(IssueType::Task),
+        "bug" => Some(IssueType::Bug),
+        "story" => Some(IssueType::Story),
+        "epic" => Some(IssueType::Epic),
+        "sub-task" | "subtask" => Some(IssueType::Subtask),
+        _ => None,
+    }
+}
+
+/// Maps also priorities of older Jira versions, `None` for unknown ones
+pub fn map_priority(name: &str) -> Option<IssuePriority> {
+    match name.to_lowercase().trim() {
+        "highest" | "blocker" | "critical" => Some(IssuePriority::Highest),
+        "high" | "major" => Some(IssuePriority::High),
+        "medium" => Some(IssuePriority::Medium),
+        "low" | "minor" => Some(IssuePriority::Low),
+        "lowest" | "trivial" => Some(IssuePriority::Lowest),
+        _ => None,
+    }
+}
+
+/// Orders issues so every parent comes before its children at any depth.
+/// Issues with parent missing in export are kept in place, issues in parent
+/// cycle are moved to the end.
+pub fn parents_first(issues: Vec<JiraIssue>) -> Vec<JiraIssue> {
+    let keys: HashSet<String> = issues.iter().map(|issue| issue.key.clone()).collect();
+    let mut placed: HashSet<String> = HashSet::new();
+    let mut sorted = Vec::with_capacity(issues.len());
+    let mut pending = issues;
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<JiraIssue>, Vec<JiraIssue>) =
+            pending
+                .into_iter()
+                .partition(|issue| match issue.parent_key.as_ref() {
+                    Some(parent_key) => !keys.contains(parent_key) || placed.contains(parent_key),
+                    None => true,
+                });
+        pending = waiting;
+        if ready.is_empty() {
+            break;
+        }
+        for issue in ready {
+            placed.insert(issue.key.clone());
+            sorted.push(issue);
+        }
+    }
+    sorted.append(&mut pending);
+    sorted
+}
+
+/// Reads export and picks format by its first character
+pub fn parse_export(text: &str) -> Result<Vec<JiraIssue>, String> {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('<') {
+        parse_xml(text)
+    } else {
+        parse_json(text)
+    }
+}
+
+fn json_user(value: &Value) -> Option<JiraUser> {
+    let name = value
+        .get("displayName")
+        .or_else(|| value.get("name"))
+        .and_then(Value::as_str)?;
+    Some(JiraUser {
+        name: name.to_string(),
+        email: value
+            .get("emailAddress")
+            .and_then(Value::as_str)
+            .map(|s| s.to_string()),
+    })
+}
+
+/// Plain text of string or Atlassian document format value
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(o) => {
+            let text = o.get("text").and_then(Value::as_str).unwrap_or_default();
+            let children: Vec<String> = o
+                .get("content")
+                .and_then(Value::as_array)
+                .map(|content| content.iter().filter_map(json_text).collect())
+                .unwrap_or_default();
+            let separator = if o.get("type").and_then(Value::as_str) == Some("doc") {
+                "\n"
+            } else {
+                ""
+            };
+            Some(format!("{}{}", text, children.join(separator)))
+        }
+        _ => None,
+    }
+}
+
+fn json_name(fields: &Value, field: &str) -> Option<String> {
+    fields
+        .get(field)
+        .and_then(|v| v.get("name"))
+        .and_then(Value::as_str)
+        .map(|s| s.to_string())
+}
+
+pub fn parse_json(text: &str) -> Result<Vec<JiraIssue>, String> {
+    let root: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let issues = root
+        .get("issues")
+        .unwrap_or(&root)
+        .as_array()
+        .ok_or_else(|| "Export has no issues".to_string())?;
+
+    let mut result = vec![];
+    for issue in issues {
+        let key = issue
+            .get("key")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Issue without key".to_string())?;
+        let fields = issue
+            .get("fields")
+            .ok_or_else(|| format!("{} has no fields", key))?;
+        let comments = fields
+            .get("comment")
+            .and_then(|c| c.get("comments"))
+            .and_then(Value::as_array)
+            .map(|comments| {
+                comments
+                    .iter()
+                    .map(|comment| JiraComment {
+                        author: comment.get("author").and_then(json_user),
+                        body: comment.get("body").and_then(json_text).unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let ignored_fields = IGNORED_FIELDS
+            .iter()
+            .filter(|field| match fields.get(**field) {
+                Some(Value::Array(a)) => !a.is_empty(),
+                Some(Value::Object(o)) => o
+                    .get("total")
+                    .and_then(Value::as_u64)
+                    .map(|total| total > 0)
+                    .unwrap_or(true),
+                Some(Value::Null) | None => false,
+                Some(_) => true,
+            })
+            .map(|field| field.to_string())
+            .collect();
+
+        result.push(JiraIssue {
+            key: key.to_string(),
+            summary: fields
+                .get("summary")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            issue_type: json_name(fields, "issuetype").unwrap_or_default(),
+            priority: json_name(fields, "priority"),
+            status: json_name(fields, "status").unwrap_or_default(),
+            description: fields
+                .get("description")
+                .and_then(json_text)
+                .filter(|s| !s.is_empty()),
+            reporter: fields.get("reporter").and_then(json_user),
+            assignee: fields.get("assignee").and_then(json_user),
+            parent_key: fields
+                .get("parent")
+                .and_then(|p| p.get("key"))
+                .and_then(Value::as_str)
+                .map(|s| s.to_string()),
+            comments,
+            ignored_fields,
+        });
+    }
+    Ok(result)
+}
+
+/// RSS export has no emails, unless username itself is an email
+fn xml_user(element: &xml::Element) -> Option<JiraUser> {
+    let name = element.text().trim().to_string();
+    if name.is_empty() || name == "Unassigned" {
+        return None;
+    }
+    let email = element
+        .attribute("email")
+        .or_else(|| element.attribute("username").filter(|s| s.contains('@')))
+        .map(|s| s.to_string());
+    Some(JiraUser { name, email })
+}
+
+pub fn parse_xml(text: &str) -> Result<Vec<JiraIssue>, String> {
+    let root = xml::parse(text)?;
+    let channel = root
+        .child("channel")
+        .ok_or_else(|| "Export has no channel".to_string())?;
+
+    let mut result = vec![];
+    for item in channel.children_named("item") {
+        let key = item
+            .child_text("key")
+            .ok_or_else(|| "Issue without key".to_string())?;
+        let comments = item
+            .child("comments")
+            .map(|comments| {
+                comments
+                    .children_named("comment")
+                    .map(|comment| JiraComment {
+                        author: comment.attribute("author").map(|author| JiraUser {
+                            name: author.to_string(),
+                            email: Some(author.to_string()).filter(|s| s.contains('@')),
+                        }),
+                        body: comment.text().trim().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut ignored_fields = vec![];
+        for (element, field) in [
+            ("attachments", "attachment"),
+            ("labels", "labels"),
+            ("fixVersion", "fixVersions"),
+            ("component", "components"),
+            ("issuelinks", "issuelinks"),
+            ("timeoriginalestimate", "timeoriginalestimate"),
+        ]
+        .iter()
+        {
+            let has_value = item
+                .child(element)
+                .map(|e| !e.children.is_empty())
+                .unwrap_or_default();
+            if has_value {
+                ignored_fields.push(field.to_string());
+            }
+        }
+
+        result.push(JiraIssue {
+            summary: item.child_text("summary").unwrap_or_default(),
+            issue_type: item.child_text("type").unwrap_or_default(),
+            priority: item.child_text("priority"),
+            status: item.child_text("status").unwrap_or_default(),
+            description: item.child_text("description"),
+            reporter: item.child("reporter").and_then(xml_user),
+            assignee: item.child("assignee").and_then(xml_user),
+            parent_key: item.child_text("parent"),
+            key,
+            comments,
+            ignored_fields,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_search_api_json() {
+        let json = r#"{"issues": [{
+            "key": "FOO-2",
+            "fields": {
+                "summary": "Broken login",
+                "issuetype": {"name": "Sub-task"},
+                "priority": {"name": "Major"},
+                "status": {"name": "In Progress"},
+                "description": "It *fails*",
+                "reporter": {"displayName": "Jane", "emailAddress": "jane@example.com"},
+                "assignee": null,
+                "parent": {"key": "FOO-1"},
+                "labels": ["login"],
+                "attachment": [],
+                "comment": {"comments": [
+                    {"author": {"displayName": "Bob"}, "body": "Confirmed"}
+                ]}
+            }
+        }]}"#;
+        assert_eq!(
+            parse_export(json),
+            Ok(vec![JiraIssue {
+                key: "FOO-2".to_string(),
+                summary: "Broken login".to_string(),
+                issue_type: "Sub-task".to_string(),
+                priority: Some("Major".to_string()),
+                status: "In Progress".to_string(),
+                description: Some("It *fails*".to_string()),
+                reporter: Some(JiraUser {
+                    name: "Jane".to_string(),
+                    email: Some("jane@example.com".to_string()),
+                }),
+                assignee: None,
+                parent_key: Some("FOO-1".to_string()),
+                comments: vec![JiraComment {
+                    author: Some(JiraUser {
+                        name: "Bob".to_string(),
+                        email: None,
+                    }),
+                    body: "Confirmed".to_string(),
+                }],
+                ignored_fields: vec!["labels".to_string()],
+            }])
+        );
+        assert_eq!(map_issue_type("Sub-task"), Some(IssueType::Subtask));
+        assert_eq!(map_priority("Major"), Some(IssuePriority::High));
+    }
+
+    #[test]
+    fn reads_rss_xml() {
+        let rss = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- RSS generated by JIRA -->
+            <rss version="0.92"><channel><title>Jira</title>
+            <item>
+                <title>[FOO-1] Login &amp; logout</title>
+                <key id="10001">FOO-1</key>
+                <summary>Login &amp; logout</summary>
+                <type id="1">Bug</type>
+                <priority id="2">Blocker</priority>
+                <status id="3">To Do</status>
+                <description><![CDATA[<p>Steps</p>]]></description>
+                <assignee username="bob@example.com">Bob</assignee>
+                <reporter username="jane">Jane</reporter>
+                <comments>
+                    <comment id="1" author="bob@example.com">&lt;p&gt;Fixed&lt;/p&gt;</comment>
+                </comments>
+                <attachments/>
+            </item>
+            </channel></rss>"#;
+        let issues = parse_export(rss).unwrap();
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert_eq!(issue.key, "FOO-1");
+        assert_eq!(issue.summary, "Login & logout");
+        assert_eq!(issue.description, Some("<p>Steps</p>".to_string()));
+        assert_eq!(
+            map_priority(issue.priority.as_ref().unwrap()),
+            Some(IssuePriority::Highest)
+        );
+        assert_eq!(
+            issue.assignee.as_ref().and_then(|u| u.email.clone()),
+            Some("bob@example.com".to_string())
+        );
+        assert_eq!(issue.reporter.as_ref().and_then(|u| u.email.clone()), None);
+        assert_eq!(issue.comments[0].body, "<p>Fixed</p>");
+        assert!(issue.ignored_fields.is_empty());
+    }
+
+    #[test]
+    fn orders_parents_before_children_at_any_depth() {
+        let issue = |key: &str, issue_type: &str, parent_key: Option<&str>| JiraIssue {
+            key: key.to_string(),
+            summary: key.to_string(),
+            issue_type: issue_type.to_string(),
+            priority: None,
+            status: "To Do".to_string(),
+            description: None,
+            reporter: None,
+            assignee: None,
+            parent_key: parent_key.map(|k| k.to_string()),
+            comments: vec![],
+            ignored_fields: vec![],
+        };
+        let issues = vec![
+            issue("FOO-3", "Sub-task", Some("FOO-2")),
+            issue("FOO-2", "Story", Some("FOO-1")),
+            issue("FOO-4", "Task", Some("BAR-1")),
+            issue("FOO-1", "Epic", None),
+            issue("FOO-5", "Task", Some("FOO-6")),
+            issue("FOO-6", "Task", Some("FOO-5")),
+        ];
+        let keys: Vec<String> = parents_first(issues)
+            .into_iter()
+            .map(|issue| issue.key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["FOO-4", "FOO-1", "FOO-2", "FOO-3", "FOO-5", "FOO-6"]
+        );
+    }
+}
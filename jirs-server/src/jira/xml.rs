@@ -0,0 +1,180 @@
+//! Small XML reader for Jira RSS exports. It understands elements,
+//! attributes, text, CDATA sections and predefined or numeric entities,
+//! declarations, comments and doctype are skipped.
+
+use crate::utils::unescape_entities;
+
+#[derive(Debug, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find_map(|node| match node {
+            Node::Element(e) if e.name == name => Some(e),
+            _ => None,
+        })
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter_map(move |node| match node {
+            Node::Element(e) if e.name == name => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Concatenated text of element and its descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in self.children.iter() {
+            match node {
+                Node::Text(s) => text.push_str(s),
+                Node::Element(e) => text.push_str(e.text().as_str()),
+            }
+        }
+        text
+    }
+
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(|e| e.text().trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+fn parse_attributes(s: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = vec![];
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| format!("Invalid attribute {}", rest))?;
+        let name = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("Unquoted attribute {}", name))?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or_else(|| format!("Unterminated attribute {}", name))?;
+        attributes.push((name, unescape_entities(&value[1..=end])));
+        rest = value[end + 2..].trim_start();
+    }
+    Ok(attributes)
+}
+
+/// Parses document and returns its root element
+pub fn parse(text: &str) -> Result<Element, String> {
+    let mut stack = vec![Element::default()];
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let start = match rest.find('<') {
+            Some(start) => start,
+            None => {
+                push_text(&mut stack, unescape_entities(rest));
+                break;
+            }
+        };
+        if start > 0 {
+            push_text(&mut stack, unescape_entities(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        let (skip_to, terminator) = if rest.starts_with("<![CDATA[") {
+            ("<![CDATA[", "]]>")
+        } else if rest.starts_with("<!--") {
+            ("<!--", "-->")
+        } else if rest.starts_with("<?") {
+            ("<?", "?>")
+        } else if rest.starts_with("<!") {
+            ("<!", ">")
+        } else {
+            ("", "")
+        };
+        if !skip_to.is_empty() {
+            let end = rest
+                .find(terminator)
+                .ok_or_else(|| format!("Unterminated {}", skip_to))?;
+            if skip_to == "<![CDATA[" {
+                push_text(&mut stack, rest[skip_to.len()..end].to_string());
+            }
+            rest = &rest[end + terminator.len()..];
+            continue;
+        }
+
+        let end = rest
+            .find('>')
+            .ok_or_else(|| "Unterminated tag".to_string())?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('/') {
+            let element = stack.pop().filter(|_| !stack.is_empty());
+            match element {
+                Some(element) if element.name == tag[1..].trim() => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(Node::Element(element));
+                    }
+                }
+                _ => return Err(format!("Unexpected closing tag {}", tag)),
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or_else(|| tag.len());
+        let element = Element {
+            name: tag[..name_end].to_string(),
+            attributes: parse_attributes(&tag[name_end..])?,
+            children: vec![],
+        };
+        if self_closing {
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Element(element));
+            }
+        } else {
+            stack.push(element);
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err("Unclosed element".to_string());
+    }
+    stack
+        .pop()
+        .and_then(|document| {
+            document.children.into_iter().find_map(|node| match node {
+                Node::Element(e) => Some(e),
+                _ => None,
+            })
+        })
+        .ok_or_else(|| "Document has no root element".to_string())
+}
+
+fn push_text(stack: &mut Vec<Element>, text: String) {
+    if let Some(element) = stack.last_mut() {
+        match element.children.last_mut() {
+            Some(Node::Text(s)) => s.push_str(text.as_str()),
+            _ => element.children.push(Node::Text(text)),
+        }
+    }
+}
@@ -14,8 +14,11 @@ use actix_web::{App, HttpServer};
 
 use crate::ws::WsServer;
 
+pub mod backup;
 pub mod db;
 pub mod errors;
+pub mod html;
+pub mod jira;
 pub mod mail;
 pub mod middleware;
 pub mod models;
@@ -24,11 +27,22 @@ pub mod utils;
 pub mod web;
 pub mod ws;
 
+#[cfg(test)]
+mod test_support;
+
 #[actix_rt::main]
 async fn main() -> Result<(), String> {
     dotenv::dotenv().ok();
     pretty_env_logger::init();
 
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(|s| s.as_str()) {
+        Some("import-jira") => return crate::jira::run_import(&args[2..]),
+        Some("export-project") => return crate::backup::run_export(&args[2..]),
+        Some("restore-project") => return crate::backup::run_restore(&args[2..]),
+        _ => (),
+    }
+
     let web_config = web::Configuration::read();
 
     std::fs::create_dir_all(web_config.tmp_dir.as_str()).map_err(|e| e.to_string())?;
@@ -58,7 +72,8 @@ async fn main() -> Result<(), String> {
             .data(mail_addr.clone())
             .data(crate::db::build_pool())
             .service(crate::ws::index)
-            .service(actix_web::web::scope("/avatar").service(crate::web::avatar::upload));
+            .service(actix_web::web::scope("/avatar").service(crate::web::avatar::upload))
+            .service(crate::web::api::scope());
 
         #[cfg(feature = "local-storage")]
         let web_config = web::Configuration::read();
@@ -6,7 +6,7 @@ use actix_web::http::HeaderMap;
 use actix_web::{dev::ServiceRequest, dev::ServiceResponse, Error};
 use futures::future::{ok, FutureExt, LocalBoxFuture, Ready};
 
-use jirs_data::User;
+use jirs_data::{ProjectId, User, UserProject, UserRole};
 
 use crate::db::SyncQuery;
 
@@ -102,6 +102,33 @@ fn check_token(
     })
 }
 
+/// Succeeds only if given role is at least as privileged as required one
+pub fn check_role(
+    role: UserRole,
+    min: UserRole,
+) -> std::result::Result<(), crate::errors::ServiceErrors> {
+    if role >= min {
+        Ok(())
+    } else {
+        Err(crate::errors::ServiceErrors::Forbidden)
+    }
+}
+
+/// Succeeds only if record belongs to project of given membership and member
+/// role is at least as privileged as required one
+pub fn check_project_role(
+    user_project: &UserProject,
+    project_id: ProjectId,
+    min: UserRole,
+) -> std::result::Result<(), crate::errors::ServiceErrors> {
+    check_role(user_project.role, min)?;
+    if user_project.project_id == project_id {
+        Ok(())
+    } else {
+        Err(crate::errors::ServiceErrors::Forbidden)
+    }
+}
+
 fn parse_bearer(header: &str) -> Result<uuid::Uuid, crate::errors::ServiceErrors> {
     if !header.starts_with("Bearer ") {
         return Err(crate::errors::ServiceErrors::Unauthorized);
@@ -109,3 +136,100 @@ fn parse_bearer(header: &str) -> Result<uuid::Uuid, crate::errors::ServiceErrors
     let (_bearer, token) = header.split_at(7);
     uuid::Uuid::parse_str(token).map_err(|_e| crate::errors::ServiceErrors::Unauthorized)
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::HeaderValue;
+
+    use crate::test_support::user_project;
+
+    use super::*;
+
+    const TOKEN: &str = "3a2f4b3c-7f1d-4c0e-9a56-8e9ad2c1b0f7";
+
+    fn headers(authorization: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static(authorization),
+        );
+        headers
+    }
+
+    #[test]
+    fn reads_bearer_token() {
+        let token = token_from_headers(&headers("Bearer 3a2f4b3c-7f1d-4c0e-9a56-8e9ad2c1b0f7"));
+        assert_eq!(token.ok(), uuid::Uuid::parse_str(TOKEN).ok());
+    }
+
+    #[test]
+    fn request_without_bearer_token_is_unauthorized() {
+        for headers in vec![
+            HeaderMap::new(),
+            headers("3a2f4b3c-7f1d-4c0e-9a56-8e9ad2c1b0f7"),
+            headers("Basic 3a2f4b3c-7f1d-4c0e-9a56-8e9ad2c1b0f7"),
+            headers("Bearer foo"),
+            headers("Bearer "),
+        ] {
+            match token_from_headers(&headers) {
+                Err(crate::errors::ServiceErrors::Unauthorized) => (),
+                res => panic!("expected unauthorized, got {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn user_role_permissions() {
+        assert!(check_role(UserRole::User, UserRole::User).is_ok());
+        assert!(check_role(UserRole::User, UserRole::Manager).is_err());
+        assert!(check_role(UserRole::User, UserRole::Owner).is_err());
+    }
+
+    #[test]
+    fn manager_role_permissions() {
+        assert!(check_role(UserRole::Manager, UserRole::User).is_ok());
+        assert!(check_role(UserRole::Manager, UserRole::Manager).is_ok());
+        assert!(check_role(UserRole::Manager, UserRole::Owner).is_err());
+    }
+
+    #[test]
+    fn owner_role_permissions() {
+        assert!(check_role(UserRole::Owner, UserRole::User).is_ok());
+        assert!(check_role(UserRole::Owner, UserRole::Manager).is_ok());
+        assert!(check_role(UserRole::Owner, UserRole::Owner).is_ok());
+    }
+
+    #[test]
+    fn denied_role_is_forbidden() {
+        match check_role(UserRole::User, UserRole::Owner) {
+            Err(crate::errors::ServiceErrors::Forbidden) => (),
+            res => panic!("expected forbidden, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn project_member_can_change_project_records() {
+        assert!(check_project_role(&user_project(1, UserRole::User), 1, UserRole::User).is_ok());
+        assert!(
+            check_project_role(&user_project(1, UserRole::Owner), 1, UserRole::Manager).is_ok()
+        );
+    }
+
+    #[test]
+    fn other_project_is_forbidden() {
+        for role in vec![UserRole::User, UserRole::Manager, UserRole::Owner] {
+            match check_project_role(&user_project(1, role), 2, UserRole::User) {
+                Err(crate::errors::ServiceErrors::Forbidden) => (),
+                res => panic!("expected forbidden, got {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn lower_role_in_project_is_forbidden() {
+        match check_project_role(&user_project(1, UserRole::User), 1, UserRole::Manager) {
+            Err(crate::errors::ServiceErrors::Forbidden) => (),
+            res => panic!("expected forbidden, got {:?}", res),
+        }
+    }
+}
@@ -1,23 +1,49 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use jirs_data::{
-    InvitationState, IssuePriority, IssueStatusId, IssueType, ProjectCategory, ProjectId,
-    TimeTracking, UserId,
+    CustomFieldId, CustomFieldType, InvitationState, IssueEventType, IssueId, IssueLinkType,
+    IssuePriority, IssueStatusId, IssueType, LabelId, ProjectCategory, ProjectId, SprintId,
+    TimeTracking, TransitionRequirement, UserId,
 };
 
 use crate::schema::*;
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Queryable)]
+pub struct Comment {
+    pub id: i32,
+    pub body: String,
+    pub user_id: i32,
+    pub issue_id: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub body_text: Option<String>,
+}
+
+impl Into<jirs_data::Comment> for Comment {
+    fn into(self) -> jirs_data::Comment {
+        jirs_data::Comment {
+            id: self.id,
+            body: self.body,
+            user_id: self.user_id,
+            issue_id: self.issue_id,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Insertable)]
 #[table_name = "comments"]
 pub struct CommentForm {
     pub body: String,
+    pub body_text: Option<String>,
     pub user_id: i32,
     pub issue_id: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Queryable)]
 pub struct Issue {
     pub id: i32,
     pub title: String,
@@ -34,6 +60,10 @@ pub struct Issue {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub issue_status_id: IssueStatusId,
+    pub epic_id: Option<IssueId>,
+    pub parent_id: Option<IssueId>,
+    pub sprint_id: Option<SprintId>,
+    pub number: i32,
 }
 
 impl Into<jirs_data::Issue> for Issue {
@@ -54,8 +84,14 @@ impl Into<jirs_data::Issue> for Issue {
             created_at: self.created_at,
             updated_at: self.updated_at,
             issue_status_id: self.issue_status_id,
+            epic_id: self.epic_id,
+            parent_id: self.parent_id,
+            sprint_id: self.sprint_id,
+            number: self.number,
 
             user_ids: vec![],
+            label_ids: vec![],
+            custom_values: vec![],
         }
     }
 }
@@ -75,6 +111,8 @@ pub struct CreateIssueForm {
     pub reporter_id: UserId,
     pub project_id: ProjectId,
     pub issue_status_id: IssueStatusId,
+    pub parent_id: Option<IssueId>,
+    pub number: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -84,6 +122,81 @@ pub struct CreateIssueAssigneeForm {
     pub user_id: i32,
 }
 
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_watchers"]
+pub struct IssueWatcherForm {
+    pub issue_id: IssueId,
+    pub user_id: UserId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_labels"]
+pub struct CreateIssueLabelForm {
+    pub issue_id: IssueId,
+    pub label_id: LabelId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "labels"]
+pub struct LabelForm {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "sprints"]
+pub struct SprintForm {
+    pub project_id: ProjectId,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "custom_fields"]
+pub struct CustomFieldForm {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_custom_field_values"]
+pub struct IssueCustomFieldValueForm {
+    pub issue_id: IssueId,
+    pub custom_field_id: CustomFieldId,
+    pub field_values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_status_transitions"]
+pub struct IssueStatusTransitionForm {
+    pub project_id: ProjectId,
+    pub from_status_id: IssueStatusId,
+    pub to_status_id: IssueStatusId,
+    pub required_field: Option<TransitionRequirement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_events"]
+pub struct IssueEventForm {
+    pub issue_id: IssueId,
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub event_type: IssueEventType,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "issue_links"]
+pub struct IssueLinkForm {
+    pub source_issue_id: IssueId,
+    pub target_issue_id: IssueId,
+    pub link_type: IssueLinkType,
+    pub user_id: UserId,
+}
+
 #[derive(Debug, Serialize, Deserialize, Insertable)]
 #[table_name = "projects"]
 pub struct UpdateProjectForm {
@@ -129,3 +242,28 @@ pub struct InvitationForm {
     pub project_id: i32,
     pub invited_by_id: i32,
 }
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "saved_filters"]
+pub struct SavedFilterForm {
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub shared: bool,
+    pub text_filter: String,
+    pub user_ids: Vec<UserId>,
+    pub label_ids: Vec<LabelId>,
+    pub only_my: bool,
+    pub recently_updated: bool,
+    pub jql: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[table_name = "worklogs"]
+pub struct WorklogForm {
+    pub issue_id: IssueId,
+    pub user_id: UserId,
+    pub time_spent: i32,
+    pub started_at: NaiveDate,
+    pub comment: String,
+}
@@ -44,6 +44,65 @@ table! {
         ///
         /// (Automatically generated by Diesel.)
         updated_at -> Timestamp,
+        /// The `body_text` column of the `comments` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body_text -> Nullable<Text>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `custom_fields` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    custom_fields (id) {
+        /// The `id` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `project_id` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `name` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `field_type` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `CustomFieldTypeType`.
+        ///
+        /// (Automatically generated by Diesel.)
+        field_type -> CustomFieldTypeType,
+        /// The `options` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        options -> Array<Text>,
+        /// The `created_at` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `custom_fields` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
     }
 }
 
@@ -257,6 +316,289 @@ table! {
         ///
         /// (Automatically generated by Diesel.)
         issue_status_id -> Int4,
+        /// The `epic_id` column of the `issues` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        epic_id -> Nullable<Int4>,
+        /// The `parent_id` column of the `issues` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        parent_id -> Nullable<Int4>,
+        /// The `sprint_id` column of the `issues` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        sprint_id -> Nullable<Int4>,
+        /// The `number` column of the `issues` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        number -> Int4,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_custom_field_values` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_custom_field_values (id) {
+        /// The `id` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `issue_id` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        issue_id -> Int4,
+        /// The `custom_field_id` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        custom_field_id -> Int4,
+        /// The `field_values` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        field_values -> Array<Text>,
+        /// The `created_at` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_custom_field_values` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_events` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_events (id) {
+        /// The `id` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `issue_id` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        issue_id -> Int4,
+        /// The `project_id` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `user_id` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `event_type` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `IssueEventTypeType`.
+        ///
+        /// (Automatically generated by Diesel.)
+        event_type -> IssueEventTypeType,
+        /// The `old_value` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        old_value -> Nullable<Text>,
+        /// The `new_value` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        new_value -> Nullable<Text>,
+        /// The `created_at` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_events` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_labels` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_labels (id) {
+        /// The `id` column of the `issue_labels` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `issue_id` column of the `issue_labels` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        issue_id -> Int4,
+        /// The `label_id` column of the `issue_labels` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        label_id -> Int4,
+        /// The `created_at` column of the `issue_labels` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_labels` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_links` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_links (id) {
+        /// The `id` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `source_issue_id` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_issue_id -> Int4,
+        /// The `target_issue_id` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        target_issue_id -> Int4,
+        /// The `link_type` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `IssueLinkTypeType`.
+        ///
+        /// (Automatically generated by Diesel.)
+        link_type -> IssueLinkTypeType,
+        /// The `user_id` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `created_at` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_links` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_status_transitions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_status_transitions (id) {
+        /// The `id` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `project_id` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `from_status_id` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        from_status_id -> Int4,
+        /// The `to_status_id` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        to_status_id -> Int4,
+        /// The `required_field` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Nullable<TransitionRequirementType>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        required_field -> Nullable<TransitionRequirementType>,
+        /// The `created_at` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_status_transitions` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
     }
 }
 
@@ -304,6 +646,100 @@ table! {
         ///
         /// (Automatically generated by Diesel.)
         updated_at -> Timestamp,
+        /// The `wip_limit` column of the `issue_statuses` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        wip_limit -> Nullable<Int4>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `issue_watchers` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    issue_watchers (id) {
+        /// The `id` column of the `issue_watchers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `issue_id` column of the `issue_watchers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        issue_id -> Int4,
+        /// The `user_id` column of the `issue_watchers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `created_at` column of the `issue_watchers` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `issue_watchers` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `labels` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    labels (id) {
+        /// The `id` column of the `labels` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `project_id` column of the `labels` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `name` column of the `labels` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `color` column of the `labels` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        color -> Text,
+        /// The `created_at` column of the `labels` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `labels` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
     }
 }
 
@@ -428,6 +864,172 @@ table! {
         ///
         /// (Automatically generated by Diesel.)
         time_tracking -> TimeTrackingType,
+        /// The `wip_limit_policy` column of the `projects` table.
+        ///
+        /// Its SQL type is `WipLimitPolicyType`.
+        ///
+        /// (Automatically generated by Diesel.)
+        wip_limit_policy -> WipLimitPolicyType,
+        /// The `key` column of the `projects` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Text,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `saved_filters` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    saved_filters (id) {
+        /// The `id` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `project_id` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `user_id` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `name` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `shared` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        shared -> Bool,
+        /// The `text_filter` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        text_filter -> Text,
+        /// The `user_ids` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Array<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_ids -> Array<Int4>,
+        /// The `label_ids` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Array<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        label_ids -> Array<Int4>,
+        /// The `only_my` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        only_my -> Bool,
+        /// The `recently_updated` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        recently_updated -> Bool,
+        /// The `jql` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        jql -> Text,
+        /// The `created_at` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `saved_filters` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `sprints` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sprints (id) {
+        /// The `id` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `project_id` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        project_id -> Int4,
+        /// The `name` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `goal` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        goal -> Nullable<Text>,
+        /// The `starts_at` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Nullable<Date>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        starts_at -> Nullable<Date>,
+        /// The `ends_at` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Nullable<Date>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ends_at -> Nullable<Date>,
+        /// The `state` column of the `sprints` table.
+        ///
+        /// Its SQL type is `SprintStateType`.
+        ///
+        /// (Automatically generated by Diesel.)
+        state -> SprintStateType,
+        /// The `created_at` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `sprints` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
     }
 }
 
@@ -590,29 +1192,117 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use jirs_data::sql::*;
+
+    /// Representation of the `worklogs` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    worklogs (id) {
+        /// The `id` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `issue_id` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        issue_id -> Int4,
+        /// The `user_id` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `time_spent` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        time_spent -> Int4,
+        /// The `started_at` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Date`.
+        ///
+        /// (Automatically generated by Diesel.)
+        started_at -> Date,
+        /// The `comment` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        comment -> Text,
+        /// The `created_at` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `worklogs` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
 joinable!(comments -> issues (issue_id));
 joinable!(comments -> users (user_id));
+joinable!(custom_fields -> projects (project_id));
 joinable!(invitations -> projects (project_id));
 joinable!(invitations -> users (invited_by_id));
 joinable!(issue_assignees -> issues (issue_id));
 joinable!(issue_assignees -> users (user_id));
+joinable!(issue_custom_field_values -> custom_fields (custom_field_id));
+joinable!(issue_custom_field_values -> issues (issue_id));
+joinable!(issue_events -> projects (project_id));
+joinable!(issue_events -> users (user_id));
+joinable!(issue_labels -> issues (issue_id));
+joinable!(issue_labels -> labels (label_id));
+joinable!(issue_links -> users (user_id));
+joinable!(issue_status_transitions -> projects (project_id));
 joinable!(issue_statuses -> projects (project_id));
+joinable!(issue_watchers -> issues (issue_id));
+joinable!(issue_watchers -> users (user_id));
 joinable!(issues -> issue_statuses (issue_status_id));
 joinable!(issues -> projects (project_id));
+joinable!(issues -> sprints (sprint_id));
 joinable!(issues -> users (reporter_id));
+joinable!(labels -> projects (project_id));
+joinable!(saved_filters -> projects (project_id));
+joinable!(saved_filters -> users (user_id));
+joinable!(sprints -> projects (project_id));
 joinable!(tokens -> users (user_id));
 joinable!(user_projects -> projects (project_id));
 joinable!(user_projects -> users (user_id));
+joinable!(worklogs -> issues (issue_id));
+joinable!(worklogs -> users (user_id));
 
 allow_tables_to_appear_in_same_query!(
     comments,
+    custom_fields,
     invitations,
     issue_assignees,
+    issue_custom_field_values,
+    issue_events,
+    issue_labels,
+    issue_links,
     issues,
+    issue_status_transitions,
     issue_statuses,
+    issue_watchers,
+    labels,
     messages,
     projects,
+    saved_filters,
+    sprints,
     tokens,
     user_projects,
     users,
+    worklogs,
 );
@@ -0,0 +1,81 @@
+//! Fixtures shared by unit tests. Every record is created at the same moment
+//! so tests comparing rows or history don't depend on the clock.
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use jirs_data::{
+    IssueId, IssuePriority, IssueStatus, IssueStatusId, IssueType, ProjectId, User, UserId,
+    UserProject, UserRole,
+};
+
+use crate::models;
+
+pub fn timestamp() -> NaiveDateTime {
+    NaiveDate::from_ymd(2020, 6, 14).and_hms(10, 0, 0)
+}
+
+pub fn user(id: UserId, email: &str) -> User {
+    User {
+        id,
+        name: email.to_string(),
+        email: email.to_string(),
+        avatar_url: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+pub fn user_project(project_id: ProjectId, role: UserRole) -> UserProject {
+    UserProject {
+        id: 1,
+        user_id: 1,
+        project_id,
+        is_default: true,
+        is_current: true,
+        role,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+/// Column of project 1, columns are ordered by id
+pub fn status(id: IssueStatusId, name: &str) -> IssueStatus {
+    IssueStatus {
+        id,
+        name: name.to_string(),
+        position: id,
+        project_id: 1,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        wip_limit: None,
+    }
+}
+
+/// Unassigned task of project 1 reported by user 1, as it's stored in database
+pub fn issue_row(id: IssueId) -> models::Issue {
+    models::Issue {
+        id,
+        title: format!("Issue {}", id),
+        issue_type: IssueType::Task,
+        priority: IssuePriority::Medium,
+        list_position: id,
+        description: None,
+        description_text: None,
+        estimate: None,
+        time_spent: None,
+        time_remaining: None,
+        reporter_id: 1,
+        project_id: 1,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        issue_status_id: 1,
+        epic_id: None,
+        parent_id: None,
+        sprint_id: None,
+        number: id,
+    }
+}
+
+pub fn issue(id: IssueId) -> jirs_data::Issue {
+    issue_row(id).into()
+}
@@ -1 +1,157 @@
+/// Spreadsheets evaluate cells starting with these characters as formulas
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
 
+/// Field which would be evaluated by spreadsheet, or which already is escaped
+/// formula, so escaped fields are read back unchanged
+fn is_formula(field: &str) -> bool {
+    field
+        .trim_start_matches('\'')
+        .starts_with(&FORMULA_PREFIXES[..])
+}
+
+/// Joins fields into single CSV line, fields with separator, quote or line
+/// break are quoted. Formulas are prefixed with apostrophe so spreadsheet
+/// shows them as text.
+pub fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            let field = if is_formula(field) {
+                format!("'{}", field)
+            } else {
+                field.to_string()
+            };
+            if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Reads CSV as described in RFC 4180, quoted fields can contain separators
+/// and line breaks. Empty lines are skipped and formulas escaped by
+/// `csv_line` lose their apostrophe.
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => (),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|f| !f.is_empty()) {
+        rows.push(row);
+    }
+    for field in rows.iter_mut().flat_map(|row| row.iter_mut()) {
+        if field.starts_with('\'') && is_formula(field.as_str()) {
+            field.remove(0);
+        }
+    }
+    rows
+}
+
+/// Replaces predefined and numeric character references, unknown entities
+/// are left as they are.
+pub fn unescape_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..]
+                .parse::<u32>()
+                .ok()
+                .and_then(std::char::from_u32),
+            _ => None,
+        };
+        match c {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trip() {
+        let fields = vec!["Foo", "Bar, baz", "Say \"hi\"", "Multi\nline", ""];
+        let text = format!("{}\r\n{}\n\n", csv_line(&fields), csv_line(&["a", "b"]));
+        assert_eq!(
+            parse_csv(text.as_str()),
+            vec![
+                fields
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<String>>(),
+                vec!["a".to_string(), "b".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_formulas_are_escaped() {
+        let fields = vec!["=1+2", "-1", "@SUM(A1)", "\tx", "'=x", "'quoted", "a=b"];
+        let line = csv_line(&fields);
+        assert_eq!(line, "'=1+2,'-1,'@SUM(A1),'\tx,''=x,'quoted,a=b");
+        assert_eq!(
+            parse_csv(line.as_str()),
+            vec![fields
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<String>>()]
+        );
+        assert_eq!(csv_line(&["\r=x"]), "\"'\r=x\"");
+    }
+}
@@ -0,0 +1,149 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, put, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{CommentId, IssueEventType, IssueId, MessageType, UserRole, WsMsg};
+
+use crate::db::comments::{CreateComment, DeleteComment, LoadIssueComments, UpdateComment};
+use crate::db::issue_events::IssueChange;
+use crate::db::DbExecutor;
+use crate::web::api::issues::find_project_issue;
+use crate::web::api::{authorize, broadcast, notify_issue_watchers, query, record_issue_events};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct CommentPayload {
+    pub body: String,
+}
+
+#[get("/issues/{id}/comments")]
+pub async fn list(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue_id = find_project_issue(&db, session.user_project.project_id, *path)
+        .await?
+        .id;
+    let comments = query(&db, LoadIssueComments { issue_id }).await?;
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+#[post("/issues/{id}/comments")]
+pub async fn create(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<CommentPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let issue_id = find_project_issue(&db, project_id, *path).await?.id;
+    let comment = query(
+        &db,
+        CreateComment {
+            user_id: session.user.id,
+            issue_id,
+            body: payload.into_inner().body,
+        },
+    )
+    .await?;
+    notify_issue_watchers(
+        &db,
+        &ws,
+        &session,
+        issue_id,
+        MessageType::IssueCommented,
+        comment.body.clone(),
+    )
+    .await;
+    record_issue_events(
+        &db,
+        &session,
+        issue_id,
+        vec![IssueChange::new(
+            IssueEventType::CommentAdded,
+            None,
+            Some(comment.body.clone()),
+        )],
+    )
+    .await;
+    let comments = query(&db, LoadIssueComments { issue_id }).await?;
+    broadcast(&ws, project_id, WsMsg::IssueCommentsLoaded(comments));
+    Ok(HttpResponse::Created().json(comment))
+}
+
+#[put("/comments/{id}")]
+pub async fn update(
+    req: HttpRequest,
+    path: Path<CommentId>,
+    payload: Json<CommentPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let comment = query(
+        &db,
+        UpdateComment {
+            comment_id: *path,
+            user_id: session.user.id,
+            body: payload.into_inner().body,
+        },
+    )
+    .await?;
+    let comments = query(
+        &db,
+        LoadIssueComments {
+            issue_id: comment.issue_id,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        session.user_project.project_id,
+        WsMsg::IssueCommentsLoaded(comments),
+    );
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[delete("/comments/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<CommentId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let comment_id = *path;
+    let comment = query(
+        &db,
+        DeleteComment {
+            comment_id,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    record_issue_events(
+        &db,
+        &session,
+        comment.issue_id,
+        vec![IssueChange::new(
+            IssueEventType::CommentDeleted,
+            Some(comment.body),
+            None,
+        )],
+    )
+    .await;
+    broadcast(
+        &ws,
+        session.user_project.project_id,
+        WsMsg::CommentDeleted(comment_id),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,79 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{CreateCustomFieldPayload, CustomFieldId, UserRole, WsMsg};
+
+use crate::db::custom_fields::{CreateCustomField, DeleteCustomField, LoadCustomFields};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+#[get("/custom_fields")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let custom_fields = query(
+        &db,
+        LoadCustomFields {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(custom_fields))
+}
+
+#[post("/custom_fields")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<CreateCustomFieldPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let CreateCustomFieldPayload {
+        name,
+        field_type,
+        options,
+    } = payload.into_inner();
+    let custom_field = query(
+        &db,
+        CreateCustomField {
+            project_id,
+            name,
+            field_type,
+            options,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::CustomFieldCreated(custom_field.clone()),
+    );
+    Ok(HttpResponse::Created().json(custom_field))
+}
+
+/// Field values are also removed from all issues
+#[delete("/custom_fields/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<CustomFieldId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let custom_field_id = query(
+        &db,
+        DeleteCustomField {
+            custom_field_id: *path,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::CustomFieldDeleted(custom_field_id));
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,109 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{post, put, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, IssuePriority, IssueType, MessageType, TitleString, UserRole, WsMsg};
+
+use crate::db::issue_events::issue_changes;
+use crate::db::issue_statuses::LoadIssueStatuses;
+use crate::db::issue_watchers::changes_description;
+use crate::db::issues::{CreateIssue, SetIssueEpic};
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+use crate::web::api::issues::find_project_issue;
+use crate::web::api::{
+    authorize, broadcast, load_assignees, notify_issue_watchers, query, record_issue_events,
+};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct EpicPayload {
+    pub name: TitleString,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueEpicPayload {
+    pub epic_id: Option<IssueId>,
+}
+
+#[post("/epics")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<EpicPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let issue_status_id = query(&db, LoadIssueStatuses { project_id })
+        .await?
+        .iter()
+        .min_by_key(|is| is.position)
+        .map(|is| is.id)
+        .ok_or_else(|| {
+            ServiceErrors::RecordNotFound("issue status".to_string()).into_http_response()
+        })?;
+    let issue = query(
+        &db,
+        CreateIssue {
+            title: payload.into_inner().name,
+            issue_type: IssueType::Epic,
+            issue_status_id,
+            priority: IssuePriority::default(),
+            description: None,
+            estimate: None,
+            time_spent: None,
+            time_remaining: None,
+            project_id,
+            reporter_id: session.user.id,
+            user_ids: vec![],
+            parent_id: None,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    let issue = load_assignees(&db, issue).await?;
+    broadcast(&ws, project_id, WsMsg::IssueCreated(issue.clone()));
+    Ok(HttpResponse::Created().json(issue))
+}
+
+/// Attach issue to epic, `null` epic id detaches issue.
+#[put("/issues/{id}/epic")]
+pub async fn set_epic(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<IssueEpicPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let old_issue = load_assignees(&db, find_project_issue(&db, project_id, *path).await?).await?;
+    let issue = query(
+        &db,
+        SetIssueEpic {
+            issue_id: old_issue.id,
+            epic_id: payload.into_inner().epic_id,
+        },
+    )
+    .await?;
+    let issue = load_assignees(&db, issue).await?;
+    let changes = issue_changes(&old_issue, &issue);
+    if !changes.is_empty() {
+        notify_issue_watchers(
+            &db,
+            &ws,
+            &session,
+            issue.id,
+            MessageType::IssueChanged,
+            changes_description(changes.as_slice()),
+        )
+        .await;
+    }
+    record_issue_events(&db, &session, issue.id, changes).await;
+    broadcast(&ws, project_id, WsMsg::IssueUpdated(issue.clone()));
+    Ok(HttpResponse::Ok().json(issue))
+}
@@ -0,0 +1,103 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{EmailString, ErrorResponse, InvitationId, UserRole, UsernameString};
+
+use crate::db::invitations::{
+    notify_invited_user, CreateInvitation, ListInvitation, RevokeInvitation,
+};
+use crate::db::DbExecutor;
+use crate::mail::invite::Invite;
+use crate::mail::MailExecutor;
+use crate::web::api::{authorize, query};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct InvitationPayload {
+    pub name: UsernameString,
+    pub email: EmailString,
+    pub role: UserRole,
+}
+
+#[get("/invitations")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let invitations = query(
+        &db,
+        ListInvitation {
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(invitations))
+}
+
+#[post("/invitations")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<InvitationPayload>,
+    db: Data<Addr<DbExecutor>>,
+    mail: Data<Addr<MailExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let InvitationPayload { name, email, role } = payload.into_inner();
+    // nobody can invite with role higher than own
+    session.require_role(role)?;
+    let invitation = query(
+        &db,
+        CreateInvitation {
+            user_id: session.user.id,
+            project_id: session.user_project.project_id,
+            email,
+            name,
+            role,
+        },
+    )
+    .await?;
+    let sent = mail
+        .send(Invite {
+            bind_token: invitation.bind_token,
+            email: invitation.email.clone(),
+            inviter_name: session.user.name.clone(),
+        })
+        .await;
+    match sent {
+        Ok(Ok(_)) => {
+            notify_invited_user(&db, &ws, &invitation, session.user.id).await;
+            Ok(HttpResponse::Created().json(invitation))
+        }
+        Ok(Err(e)) => {
+            error!("{}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse { errors: vec![e] }))
+        }
+        Err(e) => {
+            error!("{}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                errors: vec![e.to_string()],
+            }))
+        }
+    }
+}
+
+#[delete("/invitations/{id}")]
+pub async fn revoke(
+    req: HttpRequest,
+    path: Path<InvitationId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    query(
+        &db,
+        RevokeInvitation {
+            id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
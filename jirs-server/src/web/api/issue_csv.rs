@@ -0,0 +1,72 @@
+use actix::Addr;
+use actix_web::web::{Data, Query};
+use actix_web::{get, post, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{UserRole, WsMsg};
+
+use crate::db::issue_csv::{ExportIssuesCsv, ImportIssuesCsv};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, load_assignees, query};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct ImportParams {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// All issues of current project with assignees, status name and reporter
+/// email
+#[get("/issues/csv")]
+pub async fn export(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let csv = query(
+        &db,
+        ExportIssuesCsv {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .header("Content-Disposition", "attachment; filename=\"issues.csv\"")
+        .body(csv))
+}
+
+/// Body is CSV file with header. Dry run only validates rows, otherwise
+/// issues are created when every row is valid.
+#[post("/issues/csv")]
+pub async fn import(
+    req: HttpRequest,
+    params: Query<ImportParams>,
+    body: String,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let (report, issues) = query(
+        &db,
+        ImportIssuesCsv {
+            project_id,
+            reporter_id: session.user.id,
+            csv: body,
+            dry_run: params.dry_run,
+        },
+    )
+    .await?;
+    if !report.errors.is_empty() {
+        return Ok(HttpResponse::UnprocessableEntity().json(report));
+    }
+    for issue in issues {
+        let issue = load_assignees(&db, issue).await?;
+        broadcast(&ws, project_id, WsMsg::IssueCreated(issue));
+    }
+    if report.dry_run {
+        Ok(HttpResponse::Ok().json(report))
+    } else {
+        Ok(HttpResponse::Created().json(report))
+    }
+}
@@ -0,0 +1,88 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, IssueLinkId, IssueLinkType, UserRole, WsMsg};
+
+use crate::db::issue_links::{CreateIssueLink, DeleteIssueLink, LoadIssueLinks};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueLinkPayload {
+    pub target_issue_id: IssueId,
+    pub link_type: IssueLinkType,
+}
+
+/// Links where issue is either source or target.
+#[get("/issues/{id}/links")]
+pub async fn list(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let links = query(
+        &db,
+        LoadIssueLinks {
+            issue_id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(links))
+}
+
+#[post("/issues/{id}/links")]
+pub async fn create(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<IssueLinkPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let IssueLinkPayload {
+        target_issue_id,
+        link_type,
+    } = payload.into_inner();
+    let link = query(
+        &db,
+        CreateIssueLink {
+            source_issue_id: *path,
+            target_issue_id,
+            link_type,
+            user_id: session.user.id,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::IssueLinkCreated(link.clone()));
+    Ok(HttpResponse::Created().json(link))
+}
+
+#[delete("/issue_links/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<IssueLinkId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let link = query(
+        &db,
+        DeleteIssueLink {
+            link_id: *path,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::IssueLinkDeleted(link.id));
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,34 @@
+use actix::Addr;
+use actix_web::web::{Data, Query};
+use actix_web::{get, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::db::issue_query::QueryIssues;
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, query};
+
+#[derive(Serialize, Deserialize)]
+pub struct JqlParams {
+    pub query: String,
+}
+
+/// Ids of current project issues matching JQL query. Invalid query responds
+/// with message and position of error.
+#[get("/jql")]
+pub async fn list(
+    req: HttpRequest,
+    params: Query<JqlParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue_ids = query(
+        &db,
+        QueryIssues {
+            project_id: session.user_project.project_id,
+            user_id: session.user.id,
+            query: params.into_inner().query,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(issue_ids))
+}
@@ -0,0 +1,44 @@
+use actix::Addr;
+use actix_web::web::{Data, Query};
+use actix_web::{get, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::db::issue_search::SearchIssues;
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, query};
+use crate::ws::issue_search::SEARCH_LIMIT;
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchParams {
+    pub text: String,
+    #[serde(default)]
+    pub all_projects: bool,
+}
+
+/// Ranked issues matching text, `all_projects=true` searches every project
+/// of current user.
+#[get("/search")]
+pub async fn search(
+    req: HttpRequest,
+    params: Query<SearchParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let params = params.into_inner();
+    let project_id = if params.all_projects {
+        None
+    } else {
+        Some(session.user_project.project_id)
+    };
+    let hits = query(
+        &db,
+        SearchIssues {
+            user_id: session.user.id,
+            project_id,
+            text: params.text,
+            limit: SEARCH_LIMIT,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(hits))
+}
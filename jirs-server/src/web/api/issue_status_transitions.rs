@@ -0,0 +1,84 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{CreateIssueStatusTransitionPayload, IssueStatusTransitionId, UserRole, WsMsg};
+
+use crate::db::issue_status_transitions::{
+    CreateIssueStatusTransition, DeleteIssueStatusTransition, LoadIssueStatusTransitions,
+};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+#[get("/issue_status_transitions")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let transitions = query(
+        &db,
+        LoadIssueStatusTransitions {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(transitions))
+}
+
+#[post("/issue_status_transitions")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<CreateIssueStatusTransitionPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let CreateIssueStatusTransitionPayload {
+        from_status_id,
+        to_status_id,
+        required_field,
+    } = payload.into_inner();
+    let transition = query(
+        &db,
+        CreateIssueStatusTransition {
+            project_id,
+            from_status_id,
+            to_status_id,
+            required_field,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::IssueStatusTransitionCreated(transition.clone()),
+    );
+    Ok(HttpResponse::Created().json(transition))
+}
+
+#[delete("/issue_status_transitions/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<IssueStatusTransitionId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let transition_id = query(
+        &db,
+        DeleteIssueStatusTransition {
+            transition_id: *path,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::IssueStatusTransitionDeleted(transition_id),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,119 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, put, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueStatusId, Position, TitleString, UserRole, WsMsg};
+
+use crate::db::issue_statuses::{
+    CreateIssueStatus, DeleteIssueStatus, LoadIssueStatuses, UpdateIssueStatus,
+};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueStatusPayload {
+    pub name: TitleString,
+    pub position: Position,
+    #[serde(default)]
+    pub wip_limit: Option<i32>,
+}
+
+#[get("/issue_statuses")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue_statuses = query(
+        &db,
+        LoadIssueStatuses {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(issue_statuses))
+}
+
+#[post("/issue_statuses")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<IssueStatusPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let IssueStatusPayload { name, position, .. } = payload.into_inner();
+    let issue_status = query(
+        &db,
+        CreateIssueStatus {
+            project_id,
+            position,
+            name,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::IssueStatusCreated(issue_status.clone()),
+    );
+    Ok(HttpResponse::Created().json(issue_status))
+}
+
+#[put("/issue_statuses/{id}")]
+pub async fn update(
+    req: HttpRequest,
+    path: Path<IssueStatusId>,
+    payload: Json<IssueStatusPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let IssueStatusPayload {
+        name,
+        position,
+        wip_limit,
+    } = payload.into_inner();
+    let issue_status = query(
+        &db,
+        UpdateIssueStatus {
+            issue_status_id: *path,
+            project_id,
+            position,
+            name,
+            wip_limit,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::IssueStatusUpdated(issue_status.clone()),
+    );
+    Ok(HttpResponse::Ok().json(issue_status))
+}
+
+#[delete("/issue_statuses/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<IssueStatusId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let issue_status_id = query(
+        &db,
+        DeleteIssueStatus {
+            project_id,
+            issue_status_id: *path,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::IssueStatusDeleted(issue_status_id));
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,79 @@
+use actix::Addr;
+use actix_web::web::{Data, Path};
+use actix_web::{delete, get, put, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{IssueId, UserRole, WsMsg};
+
+use crate::db::issue_watchers::{LoadIssueWatchers, WatchIssue};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+/// Ids of users watching issue
+#[get("/issues/{id}/watchers")]
+pub async fn list(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let watchers = query(
+        &db,
+        LoadIssueWatchers {
+            issue_id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(watchers))
+}
+
+async fn watch(
+    req: HttpRequest,
+    issue_id: IssueId,
+    watch: bool,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let watchers = query(
+        &db,
+        WatchIssue {
+            issue_id,
+            project_id,
+            user_id: session.user.id,
+            watch,
+        },
+    )
+    .await?;
+    broadcast(
+        &ws,
+        project_id,
+        WsMsg::IssueWatchersLoaded(issue_id, watchers.clone()),
+    );
+    Ok(HttpResponse::Ok().json(watchers))
+}
+
+/// Current user starts watching issue
+#[put("/issues/{id}/watch")]
+pub async fn create(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    watch(req, *path, true, db, ws).await
+}
+
+/// Current user stops watching issue
+#[delete("/issues/{id}/watch")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    watch(req, *path, false, db, ws).await
+}
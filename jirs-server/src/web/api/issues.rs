@@ -0,0 +1,256 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, put, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{
+    CreateIssuePayload, IssueEventType, IssueId, MessageType, ProjectId, UserRole, WipLimitPolicy,
+    WsMsg,
+};
+
+use crate::db::issue_events::{issue_changes, IssueChange, LoadIssueEvents};
+use crate::db::issue_status_transitions::{transition_error, LoadIssueStatusTransitions};
+use crate::db::issue_statuses::{wip_limit_error, CheckWipLimit};
+use crate::db::issue_watchers::changes_description;
+use crate::db::issues::{
+    deleted_issues, CreateIssue, DeleteIssue, LoadIssue, LoadIssueByKey, LoadProjectIssues,
+    SetIssueParent, UpdateIssue,
+};
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+use crate::web::api::{
+    authorize, broadcast, load_assignees, notify_issue_watchers, query, record_issue_events,
+};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueParentPayload {
+    pub parent_id: Option<IssueId>,
+}
+
+/// Load issue and make sure it belongs to given project
+pub async fn find_project_issue(
+    db: &Data<Addr<DbExecutor>>,
+    project_id: ProjectId,
+    issue_id: IssueId,
+) -> Result<crate::models::Issue, HttpResponse> {
+    let issue = query(db, LoadIssue { issue_id }).await?;
+    if issue.project_id != project_id {
+        return Err(ServiceErrors::RecordNotFound("issue".to_string()).into_http_response());
+    }
+    Ok(issue)
+}
+
+#[get("/issues")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let project_id = session.user_project.project_id;
+    let mut issues = vec![];
+    for issue in query(&db, LoadProjectIssues { project_id }).await? {
+        issues.push(load_assignees(&db, issue).await?);
+    }
+    Ok(HttpResponse::Ok().json(issues))
+}
+
+#[get("/issues/{id}")]
+pub async fn show(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue = find_project_issue(&db, session.user_project.project_id, *path).await?;
+    Ok(HttpResponse::Ok().json(load_assignees(&db, issue).await?))
+}
+
+/// Issue key like `JIRS-12` can point to any project of current user
+#[get("/browse/{key}")]
+pub async fn browse(
+    req: HttpRequest,
+    path: Path<String>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue = query(
+        &db,
+        LoadIssueByKey {
+            user_id: session.user.id,
+            key: path.into_inner(),
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(load_assignees(&db, issue).await?))
+}
+
+#[post("/issues")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<CreateIssuePayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let payload = payload.into_inner();
+    let issue = query(
+        &db,
+        CreateIssue {
+            title: payload.title,
+            issue_type: payload.issue_type,
+            issue_status_id: payload.issue_status_id,
+            priority: payload.priority,
+            description: payload.description,
+            estimate: payload.estimate,
+            time_spent: payload.time_spent,
+            time_remaining: payload.time_remaining,
+            project_id,
+            reporter_id: payload.reporter_id,
+            user_ids: payload.user_ids,
+            parent_id: payload.parent_id,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    let issue = load_assignees(&db, issue).await?;
+    broadcast(&ws, project_id, WsMsg::IssueCreated(issue.clone()));
+    Ok(HttpResponse::Created().json(issue))
+}
+
+/// Accepts any subset of `UpdateIssue` fields, issue id is taken from path.
+#[put("/issues/{id}")]
+pub async fn update(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<UpdateIssue>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let old_issue = load_assignees(&db, find_project_issue(&db, project_id, *path).await?).await?;
+    let mut msg = payload.into_inner();
+    msg.issue_id = old_issue.id;
+    msg.project_id = None;
+    if let Some(issue_status_id) = msg.issue_status_id {
+        let transitions = query(&db, LoadIssueStatusTransitions { project_id }).await?;
+        jirs_data::check_status_transition(transitions.as_slice(), &old_issue, issue_status_id)
+            .map_err(|message| {
+                transition_error("issue_status_id", message.as_str()).into_http_response()
+            })?;
+        let policy = query(
+            &db,
+            CheckWipLimit {
+                project_id,
+                from_status_id: old_issue.issue_status_id,
+                issue_status_id,
+            },
+        )
+        .await?;
+        if policy == Some(WipLimitPolicy::Block) {
+            return Err(wip_limit_error().into_http_response().into());
+        }
+    }
+    let issue = query(&db, msg).await?;
+    let issue = load_assignees(&db, issue).await?;
+    let changes = issue_changes(&old_issue, &issue);
+    if !changes.is_empty() {
+        notify_issue_watchers(
+            &db,
+            &ws,
+            &session,
+            issue.id,
+            MessageType::IssueChanged,
+            changes_description(changes.as_slice()),
+        )
+        .await;
+    }
+    record_issue_events(&db, &session, issue.id, changes).await;
+    broadcast(&ws, project_id, WsMsg::IssueUpdated(issue.clone()));
+    Ok(HttpResponse::Ok().json(issue))
+}
+
+#[delete("/issues/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let issue = find_project_issue(&db, project_id, *path).await?;
+    let issue_id = issue.id;
+    let subtasks = query(&db, DeleteIssue { issue_id }).await?;
+    for (id, title) in deleted_issues(issue_id, issue.title, subtasks) {
+        record_issue_events(
+            &db,
+            &session,
+            id,
+            vec![IssueChange::new(IssueEventType::Deleted, Some(title), None)],
+        )
+        .await;
+        broadcast(&ws, project_id, WsMsg::IssueDeleted(id));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Move issue under parent issue, `null` parent id converts sub-task to task.
+#[put("/issues/{id}/parent")]
+pub async fn set_parent(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<IssueParentPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let old_issue = load_assignees(&db, find_project_issue(&db, project_id, *path).await?).await?;
+    let issue = query(
+        &db,
+        SetIssueParent {
+            issue_id: old_issue.id,
+            parent_id: payload.into_inner().parent_id,
+        },
+    )
+    .await?;
+    let issue = load_assignees(&db, issue).await?;
+    let changes = issue_changes(&old_issue, &issue);
+    if !changes.is_empty() {
+        notify_issue_watchers(
+            &db,
+            &ws,
+            &session,
+            issue.id,
+            MessageType::IssueChanged,
+            changes_description(changes.as_slice()),
+        )
+        .await;
+    }
+    record_issue_events(&db, &session, issue.id, changes).await;
+    broadcast(&ws, project_id, WsMsg::IssueUpdated(issue.clone()));
+    Ok(HttpResponse::Ok().json(issue))
+}
+
+/// History of issue, also available after issue was deleted.
+#[get("/issues/{id}/events")]
+pub async fn events(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let events = query(
+        &db,
+        LoadIssueEvents {
+            issue_id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(events))
+}
@@ -0,0 +1,77 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{LabelId, UserRole, WsMsg};
+
+use crate::db::labels::{CreateLabel, DeleteLabel, LoadLabels};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct LabelPayload {
+    pub name: String,
+    pub color: String,
+}
+
+#[get("/labels")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let labels = query(
+        &db,
+        LoadLabels {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(labels))
+}
+
+#[post("/labels")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<LabelPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let LabelPayload { name, color } = payload.into_inner();
+    let label = query(
+        &db,
+        CreateLabel {
+            project_id,
+            name,
+            color,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::LabelCreated(label.clone()));
+    Ok(HttpResponse::Created().json(label))
+}
+
+/// Label is also removed from all issues
+#[delete("/labels/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<LabelId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let label_id = query(
+        &db,
+        DeleteLabel {
+            label_id: *path,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::LabelDeleted(label_id));
+    Ok(HttpResponse::NoContent().finish())
+}
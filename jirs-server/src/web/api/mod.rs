@@ -0,0 +1,255 @@
+use actix::dev::ToEnvelope;
+use actix::{Actor, Addr, Handler, Message};
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse, Scope};
+
+use jirs_data::{Issue, IssueId, MessageType, ProjectId, User, UserProject, UserRole, WsMsg};
+
+use crate::db::issue_assignees::LoadAssignees;
+use crate::db::issue_custom_field_values::LoadIssueCustomFieldValues;
+use crate::db::issue_events::{self, IssueChange};
+use crate::db::issue_labels::LoadIssueLabels;
+use crate::db::issue_watchers;
+use crate::db::user_projects::CurrentUserProject;
+use crate::db::DbExecutor;
+use crate::errors::ServiceErrors;
+use crate::middleware::authorize::check_role;
+use crate::web::user_from_request;
+use crate::ws::{InnerMsg, WsServer};
+
+pub mod comments;
+pub mod custom_fields;
+pub mod epics;
+pub mod invitations;
+pub mod issue_csv;
+pub mod issue_links;
+pub mod issue_query;
+pub mod issue_search;
+pub mod issue_status_transitions;
+pub mod issue_statuses;
+pub mod issue_watchers;
+pub mod issues;
+pub mod labels;
+pub mod projects;
+pub mod reports;
+pub mod saved_filters;
+pub mod sprints;
+pub mod users;
+pub mod worklogs;
+
+pub fn scope() -> Scope {
+    web::scope("/api/v1")
+        // projects
+        .service(projects::list)
+        .service(projects::current)
+        .service(projects::update)
+        // issues
+        .service(issues::list)
+        // csv paths must be registered before issue id path
+        .service(issue_csv::export)
+        .service(issue_csv::import)
+        .service(issues::show)
+        .service(issues::browse)
+        .service(issues::create)
+        .service(issues::update)
+        .service(issues::delete)
+        .service(issues::set_parent)
+        .service(issues::events)
+        // search
+        .service(issue_search::search)
+        .service(issue_query::list)
+        // saved filters
+        .service(saved_filters::list)
+        .service(saved_filters::create)
+        .service(saved_filters::delete)
+        // epics
+        .service(epics::create)
+        .service(epics::set_epic)
+        // issue watchers
+        .service(issue_watchers::list)
+        .service(issue_watchers::create)
+        .service(issue_watchers::delete)
+        // worklogs
+        .service(worklogs::timesheet)
+        .service(worklogs::list)
+        .service(worklogs::create)
+        .service(worklogs::delete)
+        // reports
+        .service(reports::time_tracking)
+        .service(reports::burndown)
+        .service(reports::cumulative_flow)
+        // issue links
+        .service(issue_links::list)
+        .service(issue_links::create)
+        .service(issue_links::delete)
+        // labels
+        .service(labels::list)
+        .service(labels::create)
+        .service(labels::delete)
+        // sprints
+        .service(sprints::list)
+        .service(sprints::create)
+        .service(sprints::update)
+        .service(sprints::delete)
+        .service(sprints::start)
+        .service(sprints::complete)
+        .service(sprints::set_issue_sprint)
+        // custom fields
+        .service(custom_fields::list)
+        .service(custom_fields::create)
+        .service(custom_fields::delete)
+        // issue statuses
+        .service(issue_statuses::list)
+        .service(issue_statuses::create)
+        .service(issue_statuses::update)
+        .service(issue_statuses::delete)
+        // workflow
+        .service(issue_status_transitions::list)
+        .service(issue_status_transitions::create)
+        .service(issue_status_transitions::delete)
+        // comments
+        .service(comments::list)
+        .service(comments::create)
+        .service(comments::update)
+        .service(comments::delete)
+        // users
+        .service(users::list)
+        .service(users::invited)
+        .service(users::remove_invited)
+        // invitations
+        .service(invitations::list)
+        .service(invitations::create)
+        .service(invitations::revoke)
+}
+
+/// Authorized user together with currently selected project.
+pub struct Session {
+    pub user: User,
+    pub user_project: UserProject,
+}
+
+impl Session {
+    pub fn require_role(&self, min: UserRole) -> Result<(), HttpResponse> {
+        check_role(self.user_project.role, min).map_err(|e| e.into_http_response())
+    }
+}
+
+pub async fn authorize(
+    req: &HttpRequest,
+    db: &Data<Addr<DbExecutor>>,
+) -> Result<Session, HttpResponse> {
+    let user = user_from_request(req.clone(), db).await?;
+    let user_project = query(db, CurrentUserProject { user_id: user.id }).await?;
+    Ok(Session { user, user_project })
+}
+
+pub async fn query<M, T>(db: &Data<Addr<DbExecutor>>, msg: M) -> Result<T, HttpResponse>
+where
+    M: Message<Result = Result<T, ServiceErrors>> + Send + 'static,
+    T: Send + 'static,
+    DbExecutor: Handler<M>,
+    <DbExecutor as Actor>::Context: ToEnvelope<DbExecutor, M>,
+{
+    match db.send(msg).await {
+        Ok(Ok(v)) => Ok(v),
+        Ok(Err(e)) => {
+            error!("{:?}", e);
+            Err(e.into_http_response())
+        }
+        Err(e) => {
+            error!("{}", e);
+            Err(ServiceErrors::DatabaseConnectionLost.into_http_response())
+        }
+    }
+}
+
+pub fn broadcast(ws: &Data<Addr<WsServer>>, project_id: ProjectId, msg: WsMsg) {
+    ws.do_send(InnerMsg::BroadcastToChannel(project_id, msg));
+}
+
+/// Converts database issue and fills its assignees, labels and custom values
+pub async fn load_assignees(
+    db: &Data<Addr<DbExecutor>>,
+    issue: crate::models::Issue,
+) -> Result<Issue, HttpResponse> {
+    let mut issue: Issue = issue.into();
+    for assignee in query(db, LoadAssignees { issue_id: issue.id }).await? {
+        issue.user_ids.push(assignee.user_id);
+    }
+    for issue_label in query(db, LoadIssueLabels { issue_id: issue.id }).await? {
+        issue.label_ids.push(issue_label.label_id);
+    }
+    for value in query(db, LoadIssueCustomFieldValues { issue_id: issue.id }).await? {
+        issue.custom_values.push(value.into());
+    }
+    Ok(issue)
+}
+
+/// Store history of issue changed by session user
+pub async fn record_issue_events(
+    db: &Data<Addr<DbExecutor>>,
+    session: &Session,
+    issue_id: IssueId,
+    changes: Vec<IssueChange>,
+) {
+    issue_events::record_issue_events(
+        db,
+        issue_id,
+        session.user_project.project_id,
+        session.user.id,
+        changes,
+    )
+    .await
+}
+
+/// Sends message to every watcher of issue except session user
+pub async fn notify_issue_watchers(
+    db: &Data<Addr<DbExecutor>>,
+    ws: &Data<Addr<WsServer>>,
+    session: &Session,
+    issue_id: IssueId,
+    message_type: MessageType,
+    description: String,
+) {
+    issue_watchers::notify_issue_watchers(
+        db,
+        ws,
+        issue_id,
+        session.user.id,
+        message_type,
+        description,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+
+    use crate::test_support::{user, user_project};
+
+    use super::*;
+
+    fn session(role: UserRole) -> Session {
+        Session {
+            user: user(1, "foo@example.com"),
+            user_project: user_project(1, role),
+        }
+    }
+
+    #[test]
+    fn session_with_required_role_is_allowed() {
+        assert!(session(UserRole::User).require_role(UserRole::User).is_ok());
+        assert!(session(UserRole::Owner)
+            .require_role(UserRole::Manager)
+            .is_ok());
+    }
+
+    #[test]
+    fn session_with_lower_role_gets_forbidden_response() {
+        match session(UserRole::User).require_role(UserRole::Manager) {
+            Err(res) => assert_eq!(res.status(), StatusCode::FORBIDDEN),
+            Ok(()) => panic!("expected forbidden response"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use actix::Addr;
+use actix_web::web::{Data, Json};
+use actix_web::{get, put, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{UpdateProjectPayload, UserRole, WsMsg};
+
+use crate::db::projects::{LoadCurrentProject, LoadProjects, UpdateProject};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, query};
+use crate::ws::{InnerMsg, WsServer};
+
+#[get("/projects")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let projects = query(
+        &db,
+        LoadProjects {
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+#[get("/project")]
+pub async fn current(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let project = query(
+        &db,
+        LoadCurrentProject {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(project))
+}
+
+#[put("/project")]
+pub async fn update(
+    req: HttpRequest,
+    payload: Json<UpdateProjectPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Owner)?;
+    let payload = payload.into_inner();
+    let project = query(
+        &db,
+        UpdateProject {
+            project_id: session.user_project.project_id,
+            name: payload.name,
+            url: payload.url,
+            description: payload.description,
+            category: payload.category,
+            time_tracking: payload.time_tracking,
+            wip_limit_policy: payload.wip_limit_policy,
+            key: payload.key,
+        },
+    )
+    .await?;
+    let projects = query(
+        &db,
+        LoadProjects {
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    ws.do_send(InnerMsg::SendToUser(
+        session.user.id,
+        WsMsg::ProjectsLoaded(projects),
+    ));
+    Ok(HttpResponse::Ok().json(project))
+}
@@ -0,0 +1,80 @@
+use actix::Addr;
+use actix_web::web::{Data, Query};
+use actix_web::{get, Error, HttpRequest, HttpResponse};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::db::reports::{LoadBurndown, LoadCumulativeFlow, LoadTimeTrackingReport};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, query};
+
+#[derive(Serialize, Deserialize)]
+pub struct ReportParams {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Estimate, time spent and time remaining of issues updated between `from`
+/// and `to` grouped by assignee, issue type and status
+#[get("/reports/time_tracking")]
+pub async fn time_tracking(
+    req: HttpRequest,
+    params: Query<ReportParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let params = params.into_inner();
+    let report = query(
+        &db,
+        LoadTimeTrackingReport {
+            project_id: session.user_project.project_id,
+            from: params.from,
+            to: params.to,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Daily scope, completed estimate and time remaining between `from` and `to`
+#[get("/reports/burndown")]
+pub async fn burndown(
+    req: HttpRequest,
+    params: Query<ReportParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let params = params.into_inner();
+    let report = query(
+        &db,
+        LoadBurndown {
+            project_id: session.user_project.project_id,
+            from: params.from,
+            to: params.to,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Daily issue count of every status between `from` and `to` with cycle and
+/// lead time of issues finished in that range
+#[get("/reports/cumulative_flow")]
+pub async fn cumulative_flow(
+    req: HttpRequest,
+    params: Query<ReportParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let params = params.into_inner();
+    let report = query(
+        &db,
+        LoadCumulativeFlow {
+            project_id: session.user_project.project_id,
+            from: params.from,
+            to: params.to,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(report))
+}
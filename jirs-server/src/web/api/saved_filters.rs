@@ -0,0 +1,87 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{CreateSavedFilterPayload, SavedFilterId, WsMsg};
+
+use crate::db::saved_filters::{CreateSavedFilter, DeleteSavedFilter, LoadSavedFilters};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, broadcast, query};
+use crate::ws::WsServer;
+
+/// Own filters of current user and filters shared with project
+#[get("/saved_filters")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let saved_filters = query(
+        &db,
+        LoadSavedFilters {
+            project_id: session.user_project.project_id,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(saved_filters))
+}
+
+#[post("/saved_filters")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<CreateSavedFilterPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let project_id = session.user_project.project_id;
+    let CreateSavedFilterPayload {
+        name,
+        shared,
+        filter,
+    } = payload.into_inner();
+    let saved_filter = query(
+        &db,
+        CreateSavedFilter {
+            project_id,
+            user_id: session.user.id,
+            name,
+            shared,
+            filter,
+        },
+    )
+    .await?;
+    if saved_filter.shared {
+        broadcast(
+            &ws,
+            project_id,
+            WsMsg::SavedFilterCreated(saved_filter.clone()),
+        );
+    }
+    Ok(HttpResponse::Created().json(saved_filter))
+}
+
+/// Only owner of filter can remove it
+#[delete("/saved_filters/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<SavedFilterId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let saved_filter = query(
+        &db,
+        DeleteSavedFilter {
+            saved_filter_id: *path,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    if saved_filter.shared {
+        broadcast(
+            &ws,
+            session.user_project.project_id,
+            WsMsg::SavedFilterDeleted(saved_filter.id),
+        );
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,247 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, put, Error, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueEventType, IssueId, SprintId, UpdateSprintPayload, UserRole, WsMsg};
+
+use crate::db::issue_events::IssueChange;
+use crate::db::sprints::{
+    CompleteSprint, CreateSprint, DeleteSprint, LoadSprints, SetIssueSprint, SprintTransition,
+    StartSprint, UpdateSprint,
+};
+use crate::db::DbExecutor;
+use crate::web::api::issues::find_project_issue;
+use crate::web::api::{authorize, broadcast, query, record_issue_events, Session};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct SprintPayload {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueSprintPayload {
+    pub sprint_id: Option<SprintId>,
+}
+
+/// Record history of issues moved between sprints and notify project members
+async fn sprint_changed(
+    db: &Data<Addr<DbExecutor>>,
+    ws: &Data<Addr<WsServer>>,
+    session: &Session,
+    issue_ids: Vec<IssueId>,
+    from: Option<SprintId>,
+    target: Option<SprintId>,
+) {
+    if issue_ids.is_empty() {
+        return;
+    }
+    for issue_id in issue_ids.iter() {
+        record_issue_events(
+            db,
+            session,
+            *issue_id,
+            vec![IssueChange::new(
+                IssueEventType::Sprint,
+                from.map(|id| id.to_string()),
+                target.map(|id| id.to_string()),
+            )],
+        )
+        .await;
+    }
+    broadcast(
+        ws,
+        session.user_project.project_id,
+        WsMsg::IssuesSprintChanged(issue_ids, target),
+    );
+}
+
+async fn transition_response(
+    db: &Data<Addr<DbExecutor>>,
+    ws: &Data<Addr<WsServer>>,
+    session: &Session,
+    transition: SprintTransition,
+) -> HttpResponse {
+    let project_id = session.user_project.project_id;
+    for sprint in transition.sprints.iter() {
+        broadcast(ws, project_id, WsMsg::SprintUpdated(sprint.clone()));
+    }
+    sprint_changed(
+        db,
+        ws,
+        session,
+        transition.moved_issue_ids.clone(),
+        Some(transition.from),
+        transition.target,
+    )
+    .await;
+    HttpResponse::Ok().json(transition)
+}
+
+#[get("/sprints")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let sprints = query(
+        &db,
+        LoadSprints {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(sprints))
+}
+
+#[post("/sprints")]
+pub async fn create(
+    req: HttpRequest,
+    payload: Json<SprintPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let sprint = query(
+        &db,
+        CreateSprint {
+            project_id,
+            name: payload.into_inner().name,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::SprintCreated(sprint.clone()));
+    Ok(HttpResponse::Created().json(sprint))
+}
+
+/// Sprint id is taken from path
+#[put("/sprints/{id}")]
+pub async fn update(
+    req: HttpRequest,
+    path: Path<SprintId>,
+    payload: Json<UpdateSprintPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let mut payload = payload.into_inner();
+    payload.id = *path;
+    let sprint = query(
+        &db,
+        UpdateSprint {
+            payload,
+            project_id,
+        },
+    )
+    .await?;
+    broadcast(&ws, project_id, WsMsg::SprintUpdated(sprint.clone()));
+    Ok(HttpResponse::Ok().json(sprint))
+}
+
+/// Issues of sprint are moved back to backlog
+#[delete("/sprints/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<SprintId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let project_id = session.user_project.project_id;
+    let sprint_id = *path;
+    let moved = query(
+        &db,
+        DeleteSprint {
+            sprint_id,
+            project_id,
+        },
+    )
+    .await?;
+    sprint_changed(&db, &ws, &session, moved, Some(sprint_id), None).await;
+    broadcast(&ws, project_id, WsMsg::SprintDeleted(sprint_id));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Completes currently active sprint and moves its unfinished issues to
+/// started one.
+#[post("/sprints/{id}/start")]
+pub async fn start(
+    req: HttpRequest,
+    path: Path<SprintId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let transition = query(
+        &db,
+        StartSprint {
+            sprint_id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(transition_response(&db, &ws, &session, transition).await)
+}
+
+/// Unfinished issues are moved to the next planned sprint or to backlog.
+#[post("/sprints/{id}/complete")]
+pub async fn complete(
+    req: HttpRequest,
+    path: Path<SprintId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Manager)?;
+    let transition = query(
+        &db,
+        CompleteSprint {
+            sprint_id: *path,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(transition_response(&db, &ws, &session, transition).await)
+}
+
+/// Plan issue into sprint, `null` sprint id moves issue back to backlog.
+#[put("/issues/{id}/sprint")]
+pub async fn set_issue_sprint(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<IssueSprintPayload>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let old_issue = find_project_issue(&db, project_id, *path).await?;
+    let sprint_id = payload.into_inner().sprint_id;
+    let issues = query(
+        &db,
+        SetIssueSprint {
+            issue_id: old_issue.id,
+            sprint_id,
+            project_id,
+        },
+    )
+    .await?;
+    let ids: Vec<IssueId> = issues.iter().map(|issue| issue.id).collect();
+    if old_issue.sprint_id != sprint_id {
+        sprint_changed(
+            &db,
+            &ws,
+            &session,
+            ids.clone(),
+            old_issue.sprint_id,
+            sprint_id,
+        )
+        .await;
+    }
+    Ok(HttpResponse::Ok().json(ids))
+}
@@ -0,0 +1,56 @@
+use actix::Addr;
+use actix_web::web::{Data, Path};
+use actix_web::{delete, get, Error, HttpRequest, HttpResponse};
+
+use jirs_data::{UserId, UserRole};
+
+use crate::db::user_projects::RemoveInvitedUser;
+use crate::db::users::{LoadInvitedUsers, LoadProjectUsers};
+use crate::db::DbExecutor;
+use crate::web::api::{authorize, query};
+
+#[get("/users")]
+pub async fn list(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let users = query(
+        &db,
+        LoadProjectUsers {
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[get("/users/invited")]
+pub async fn invited(req: HttpRequest, db: Data<Addr<DbExecutor>>) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let users = query(
+        &db,
+        LoadInvitedUsers {
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[delete("/users/invited/{id}")]
+pub async fn remove_invited(
+    req: HttpRequest,
+    path: Path<UserId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::Owner)?;
+    query(
+        &db,
+        RemoveInvitedUser {
+            invited_id: *path,
+            inviter_id: session.user.id,
+            project_id: session.user_project.project_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,163 @@
+use actix::Addr;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, post, Error, HttpRequest, HttpResponse};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use jirs_data::{IssueId, MessageType, UserId, UserRole, WorklogId, WsMsg};
+
+use crate::db::issue_events::issue_changes;
+use crate::db::issue_watchers::changes_description;
+use crate::db::worklogs::{
+    CreateWorklog, DeleteWorklog, LoadProjectWorklogs, LoadWorklog, LoadWorklogs,
+};
+use crate::db::DbExecutor;
+use crate::web::api::issues::find_project_issue;
+use crate::web::api::{
+    authorize, broadcast, load_assignees, notify_issue_watchers, query, record_issue_events,
+    Session,
+};
+use crate::ws::WsServer;
+
+#[derive(Serialize, Deserialize)]
+pub struct WorklogParams {
+    pub time_spent: i32,
+    pub started_at: NaiveDate,
+    #[serde(default)]
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TimesheetParams {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub user_id: Option<UserId>,
+}
+
+/// Worklogs of project issues started between `from` and `to`, optionally
+/// only of single user.
+#[get("/worklogs")]
+pub async fn timesheet(
+    req: HttpRequest,
+    params: Query<TimesheetParams>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let params = params.into_inner();
+    let worklogs = query(
+        &db,
+        LoadProjectWorklogs {
+            project_id: session.user_project.project_id,
+            from: params.from,
+            to: params.to,
+            user_id: params.user_id,
+        },
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(worklogs))
+}
+
+#[get("/issues/{id}/worklogs")]
+pub async fn list(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    db: Data<Addr<DbExecutor>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    let issue_id = find_project_issue(&db, session.user_project.project_id, *path)
+        .await?
+        .id;
+    let worklogs = query(&db, LoadWorklogs { issue_id }).await?;
+    Ok(HttpResponse::Ok().json(worklogs))
+}
+
+/// Sends issue with recalculated time spent and its worklogs to project
+async fn broadcast_time_spent(
+    db: &Data<Addr<DbExecutor>>,
+    ws: &Data<Addr<WsServer>>,
+    session: &Session,
+    old_issue: jirs_data::Issue,
+    issue: crate::models::Issue,
+) -> Result<jirs_data::Issue, Error> {
+    let project_id = session.user_project.project_id;
+    let issue = load_assignees(db, issue).await?;
+    let worklogs = query(db, LoadWorklogs { issue_id: issue.id }).await?;
+    let changes = issue_changes(&old_issue, &issue);
+    if !changes.is_empty() {
+        notify_issue_watchers(
+            db,
+            ws,
+            session,
+            issue.id,
+            MessageType::IssueChanged,
+            changes_description(changes.as_slice()),
+        )
+        .await;
+    }
+    record_issue_events(db, session, issue.id, changes).await;
+    broadcast(ws, project_id, WsMsg::WorklogsLoaded(issue.id, worklogs));
+    broadcast(ws, project_id, WsMsg::IssueUpdated(issue.clone()));
+    Ok(issue)
+}
+
+/// Logs work of current user, returns issue with new time spent
+#[post("/issues/{id}/worklogs")]
+pub async fn create(
+    req: HttpRequest,
+    path: Path<IssueId>,
+    payload: Json<WorklogParams>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let old_issue = load_assignees(&db, find_project_issue(&db, project_id, *path).await?).await?;
+    let WorklogParams {
+        time_spent,
+        started_at,
+        comment,
+    } = payload.into_inner();
+    let issue = query(
+        &db,
+        CreateWorklog {
+            issue_id: old_issue.id,
+            user_id: session.user.id,
+            time_spent,
+            started_at,
+            comment,
+        },
+    )
+    .await?;
+    let issue = broadcast_time_spent(&db, &ws, &session, old_issue, issue).await?;
+    Ok(HttpResponse::Created().json(issue))
+}
+
+/// Only author can remove worklog, returns issue with new time spent
+#[delete("/worklogs/{id}")]
+pub async fn delete(
+    req: HttpRequest,
+    path: Path<WorklogId>,
+    db: Data<Addr<DbExecutor>>,
+    ws: Data<Addr<WsServer>>,
+) -> Result<HttpResponse, Error> {
+    let session = authorize(&req, &db).await?;
+    session.require_role(UserRole::User)?;
+    let project_id = session.user_project.project_id;
+    let worklog = query(&db, LoadWorklog { worklog_id: *path }).await?;
+    let old_issue = load_assignees(
+        &db,
+        find_project_issue(&db, project_id, worklog.issue_id).await?,
+    )
+    .await?;
+    let issue = query(
+        &db,
+        DeleteWorklog {
+            worklog_id: worklog.id,
+            user_id: session.user.id,
+        },
+    )
+    .await?;
+    let issue = broadcast_time_spent(&db, &ws, &session, old_issue, issue).await?;
+    Ok(HttpResponse::Ok().json(issue))
+}
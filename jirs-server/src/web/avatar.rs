@@ -158,18 +158,7 @@ async fn handle_image(
         )
         .map_err(|_| HttpResponse::InsufficientStorage().finish())?;
 
-        new_link = Some(format!(
-            "{proto}://{bind}{port}{client_path}/{user_id}-{filename}",
-            proto = if web_config.ssl { "https" } else { "http" },
-            bind = web_config.bind,
-            port = match web_config.port.as_str() {
-                "80" | "443" => "".to_string(),
-                p => format!(":{}", p),
-            },
-            client_path = filesystem.client_path,
-            user_id = user_id,
-            filename = filename
-        ));
+        new_link = Some(web_config.local_storage_url(format!("{}-{}", user_id, filename).as_str()));
     }
 
     // Upload to AWS S3
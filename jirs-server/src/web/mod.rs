@@ -14,6 +14,7 @@ use crate::db::DbExecutor;
 use crate::errors::ServiceErrors;
 use crate::middleware::authorize::token_from_headers;
 
+pub mod api;
 pub mod avatar;
 
 pub async fn user_from_request(
@@ -187,6 +188,22 @@ impl Configuration {
         }
     }
 
+    /// Public link of file saved in local storage
+    #[cfg(feature = "local-storage")]
+    pub fn local_storage_url(&self, file_name: &str) -> String {
+        format!(
+            "{proto}://{bind}{port}{client_path}/{file_name}",
+            proto = if self.ssl { "https" } else { "http" },
+            bind = self.bind,
+            port = match self.port.as_str() {
+                "80" | "443" => "".to_string(),
+                p => format!(":{}", p),
+            },
+            client_path = self.filesystem.client_path,
+            file_name = file_name
+        )
+    }
+
     pub fn read() -> Self {
         let contents: String = read_to_string(Self::config_file()).unwrap_or_default();
         match toml::from_str(contents.as_str()) {
@@ -1,11 +1,12 @@
 use actix::AsyncContext;
 use futures::executor::block_on;
 
-use jirs_data::{Token, WsMsg};
+use jirs_data::{Token, WsError, WsErrorKind, WsMsg};
 
 use crate::db::authorize_user::AuthorizeUser;
 use crate::db::tokens::{CreateBindToken, FindBindToken};
 use crate::db::users::LookupUser;
+use crate::errors::ServiceErrors;
 use crate::mail::welcome::Welcome;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
@@ -22,22 +23,22 @@ impl WsHandler<Authenticate> for WebSocketActor {
             Ok(Ok(user)) => user,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         let token = match block_on(self.db.send(CreateBindToken { user_id: user.id })) {
             Ok(Ok(token)) => token,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         if let Some(bind_token) = token.bind_token.as_ref().cloned() {
@@ -48,11 +49,11 @@ impl WsHandler<Authenticate> for WebSocketActor {
                 Ok(Ok(_)) => (),
                 Ok(Err(e)) => {
                     error!("{}", e);
-                    return Ok(None);
+                    return Err(welcome_not_sent());
                 }
                 Err(e) => {
                     error!("{}", e);
-                    return Ok(None);
+                    return Err(welcome_not_sent());
                 }
             }
         }
@@ -60,6 +61,15 @@ impl WsHandler<Authenticate> for WebSocketActor {
     }
 }
 
+fn welcome_not_sent() -> WsMsg {
+    WsMsg::Error(WsError {
+        request_id: None,
+        kind: WsErrorKind::MailNotSent,
+        message: "Failed to send sign in link, please try again later".to_string(),
+        fields: vec![],
+    })
+}
+
 pub struct CheckAuthToken {
     pub token: uuid::Uuid,
 }
@@ -97,7 +107,10 @@ impl WsHandler<CheckBindToken> for WebSocketActor {
         })) {
             Ok(Ok(token)) => token,
             Ok(Err(_)) => return Ok(Some(WsMsg::BindTokenBad)),
-            _ => return Ok(None),
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
         };
         Ok(Some(WsMsg::BindTokenOk(token.access_token)))
     }
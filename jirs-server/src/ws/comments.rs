@@ -1,7 +1,12 @@
 use futures::executor::block_on;
 
-use jirs_data::{CommentId, CreateCommentPayload, IssueId, UpdateCommentPayload, WsMsg};
+use jirs_data::{
+    CommentId, CreateCommentPayload, IssueEventType, IssueId, MessageType, UpdateCommentPayload,
+    UserRole, WsMsg,
+};
 
+use crate::db::issue_events::IssueChange;
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
 pub struct LoadIssueComments {
@@ -18,11 +23,11 @@ impl WsHandler<LoadIssueComments> for WebSocketActor {
             Ok(Ok(comments)) => comments,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
 
@@ -34,7 +39,10 @@ impl WsHandler<CreateCommentPayload> for WebSocketActor {
     fn handle_msg(&mut self, mut msg: CreateCommentPayload, ctx: &mut Self::Context) -> WsResult {
         use crate::db::comments::CreateComment;
 
-        let user_id = self.require_user()?.id;
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
         if msg.user_id.is_none() {
             msg.user_id = Some(user_id);
         }
@@ -44,14 +52,29 @@ impl WsHandler<CreateCommentPayload> for WebSocketActor {
             issue_id,
             body: msg.body,
         })) {
-            Ok(Ok(_)) => (),
+            Ok(Ok(comment)) => {
+                self.notify_issue_watchers(
+                    issue_id,
+                    MessageType::IssueCommented,
+                    comment.body.clone(),
+                );
+                self.record_issue_events(
+                    issue_id,
+                    project_id,
+                    vec![IssueChange::new(
+                        IssueEventType::CommentAdded,
+                        None,
+                        Some(comment.body),
+                    )],
+                )
+            }
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         self.handle_msg(LoadIssueComments { issue_id }, ctx)
@@ -63,7 +86,7 @@ impl WsHandler<UpdateCommentPayload> for WebSocketActor {
         use crate::db::comments::UpdateComment;
 
         info!("{:?}", msg);
-        let user_id = self.require_user()?.id;
+        let user_id = self.require_role(UserRole::User)?.user_id;
 
         let UpdateCommentPayload {
             id: comment_id,
@@ -78,11 +101,11 @@ impl WsHandler<UpdateCommentPayload> for WebSocketActor {
             Ok(Ok(comment)) => comment.issue_id,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         if let Some(v) = self.handle_msg(LoadIssueComments { issue_id }, ctx)? {
@@ -100,21 +123,32 @@ impl WsHandler<DeleteComment> for WebSocketActor {
     fn handle_msg(&mut self, msg: DeleteComment, _ctx: &mut Self::Context) -> WsResult {
         use crate::db::comments::DeleteComment;
 
-        let user_id = self.require_user()?.id;
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
 
         let m = DeleteComment {
             comment_id: msg.comment_id,
             user_id,
         };
         match block_on(self.db.send(m)) {
-            Ok(Ok(_)) => (),
+            Ok(Ok(comment)) => self.record_issue_events(
+                comment.issue_id,
+                project_id,
+                vec![IssueChange::new(
+                    IssueEventType::CommentDeleted,
+                    Some(comment.body),
+                    None,
+                )],
+            ),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
 
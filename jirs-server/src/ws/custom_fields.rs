@@ -0,0 +1,85 @@
+use futures::executor::block_on;
+
+use jirs_data::{CreateCustomFieldPayload, CustomFieldId, UserRole, WsMsg};
+
+use crate::db::custom_fields;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct LoadCustomFields;
+
+impl WsHandler<LoadCustomFields> for WebSocketActor {
+    fn handle_msg(&mut self, _msg: LoadCustomFields, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let msg = match block_on(self.db.send(custom_fields::LoadCustomFields { project_id })) {
+            Ok(Ok(v)) => Some(WsMsg::CustomFieldsLoaded(v)),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(msg)
+    }
+}
+
+impl WsHandler<CreateCustomFieldPayload> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateCustomFieldPayload, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let CreateCustomFieldPayload {
+            name,
+            field_type,
+            options,
+        } = msg;
+        let custom_field = match block_on(self.db.send(custom_fields::CreateCustomField {
+            project_id,
+            name,
+            field_type,
+            options,
+        })) {
+            Ok(Ok(custom_field)) => custom_field,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::CustomFieldCreated(custom_field));
+        Ok(None)
+    }
+}
+
+pub struct DeleteCustomField {
+    pub custom_field_id: CustomFieldId,
+}
+
+impl WsHandler<DeleteCustomField> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteCustomField, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let custom_field_id = match block_on(self.db.send(custom_fields::DeleteCustomField {
+            custom_field_id: msg.custom_field_id,
+            project_id,
+        })) {
+            Ok(Ok(custom_field_id)) => custom_field_id,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::CustomFieldDeleted(custom_field_id));
+        Ok(None)
+    }
+}
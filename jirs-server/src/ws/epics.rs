@@ -0,0 +1,127 @@
+use futures::executor::block_on;
+
+use jirs_data::{IssueId, IssuePriority, IssueType, MessageType, TitleString, UserRole, WsMsg};
+
+use crate::db::issue_events::issue_changes;
+use crate::db::issue_statuses::LoadIssueStatuses;
+use crate::db::issue_watchers::changes_description;
+use crate::db::issues::{CreateIssue, SetIssueEpic};
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct CreateEpic {
+    pub name: TitleString,
+}
+
+impl WsHandler<CreateEpic> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateEpic, _ctx: &mut Self::Context) -> WsResult {
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
+
+        let issue_status_id = match block_on(self.db.send(LoadIssueStatuses { project_id })) {
+            Ok(Ok(statuses)) => match statuses.iter().min_by_key(|is| is.position) {
+                Some(is) => is.id,
+                _ => return Err(ServiceErrors::RecordNotFound("issue status".to_string()).into()),
+            },
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        let issue: jirs_data::Issue = match block_on(self.db.send(CreateIssue {
+            title: msg.name,
+            issue_type: IssueType::Epic,
+            issue_status_id,
+            priority: IssuePriority::default(),
+            description: None,
+            estimate: None,
+            time_spent: None,
+            time_remaining: None,
+            project_id,
+            reporter_id: user_id,
+            user_ids: vec![],
+            parent_id: None,
+            user_id,
+        })) {
+            Ok(Ok(issue)) => issue.into(),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        let issue = self.load_issue(issue.id)?;
+        self.broadcast(&WsMsg::IssueCreated(issue));
+        Ok(None)
+    }
+}
+
+pub struct AttachEpic {
+    pub epic_id: IssueId,
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<AttachEpic> for WebSocketActor {
+    fn handle_msg(&mut self, msg: AttachEpic, _ctx: &mut Self::Context) -> WsResult {
+        self.set_issue_epic(msg.issue_id, Some(msg.epic_id))
+    }
+}
+
+pub struct DetachEpic {
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<DetachEpic> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DetachEpic, _ctx: &mut Self::Context) -> WsResult {
+        self.set_issue_epic(msg.issue_id, None)
+    }
+}
+
+impl WebSocketActor {
+    fn set_issue_epic(&mut self, issue_id: IssueId, epic_id: Option<IssueId>) -> WsResult {
+        let project_id = self.require_role(UserRole::User)?.project_id;
+
+        let old_issue = self.load_issue(issue_id)?;
+        if old_issue.project_id != project_id {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()).into());
+        }
+
+        let mut issue: jirs_data::Issue =
+            match block_on(self.db.send(SetIssueEpic { issue_id, epic_id })) {
+                Ok(Ok(issue)) => issue.into(),
+                Ok(Err(e)) => {
+                    error!("{:?}", e);
+                    return Err(e.into());
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    return Err(ServiceErrors::DatabaseConnectionLost.into());
+                }
+            };
+        issue.user_ids = old_issue.user_ids.clone();
+        issue.label_ids = old_issue.label_ids.clone();
+        issue.custom_values = old_issue.custom_values.clone();
+
+        let changes = issue_changes(&old_issue, &issue);
+        if !changes.is_empty() {
+            self.notify_issue_watchers(
+                issue.id,
+                MessageType::IssueChanged,
+                changes_description(changes.as_slice()),
+            );
+        }
+        self.record_issue_events(issue.id, issue.project_id, changes);
+        self.broadcast(&WsMsg::IssueUpdated(issue));
+        Ok(None)
+    }
+}
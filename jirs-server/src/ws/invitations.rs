@@ -1,30 +1,25 @@
 use futures::executor::block_on;
 
-use jirs_data::{
-    EmailString, InvitationId, InvitationToken, MessageType, UserRole, UsernameString, WsMsg,
-};
+use jirs_data::{EmailString, InvitationId, InvitationToken, UserRole, UsernameString, WsMsg};
 
 use crate::db::invitations;
-use crate::db::messages::CreateMessageReceiver;
-use crate::ws::{InnerMsg, WebSocketActor, WsHandler, WsMessageSender, WsResult};
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsMessageSender, WsResult};
 
 pub struct ListInvitation;
 
 impl WsHandler<ListInvitation> for WebSocketActor {
     fn handle_msg(&mut self, _msg: ListInvitation, _ctx: &mut Self::Context) -> WsResult {
-        let user_id = match self.current_user.as_ref().map(|u| u.id) {
-            Some(id) => id,
-            _ => return Ok(None),
-        };
+        let user_id = self.require_user()?.id;
         let res = match block_on(self.db.send(invitations::ListInvitation { user_id })) {
             Ok(Ok(v)) => Some(WsMsg::InvitationListLoaded(v)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(res)
@@ -39,18 +34,17 @@ pub struct CreateInvitation {
 
 impl WsHandler<CreateInvitation> for WebSocketActor {
     fn handle_msg(&mut self, msg: CreateInvitation, _ctx: &mut Self::Context) -> WsResult {
-        let project_id = match self.current_user_project.as_ref() {
-            Some(up) => up.project_id,
-            _ => return Ok(None),
-        };
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
         let (user_id, inviter_name) = self.require_user().map(|u| (u.id, u.name.clone()))?;
 
         let CreateInvitation { email, name, role } = msg;
+        // nobody can invite with role higher than own
+        self.require_role(role)?;
         let invitation = match block_on(self.db.send(crate::db::invitations::CreateInvitation {
             user_id,
             project_id,
-            email: email.clone(),
-            name: name.clone(),
+            email,
+            name,
             role,
         })) {
             Ok(Ok(invitation)) => invitation,
@@ -65,7 +59,7 @@ impl WsHandler<CreateInvitation> for WebSocketActor {
         };
         match block_on(self.mail.send(crate::mail::invite::Invite {
             bind_token: invitation.bind_token,
-            email: invitation.email,
+            email: invitation.email.clone(),
             inviter_name,
         })) {
             Ok(Ok(_)) => (),
@@ -79,20 +73,12 @@ impl WsHandler<CreateInvitation> for WebSocketActor {
             }
         }
 
-        // If user exists then send message to him
-        if let Ok(Ok(message)) = block_on(self.db.send(crate::db::messages::CreateMessage {
-            receiver: CreateMessageReceiver::Lookup { name, email },
-            sender_id: user_id,
-            summary: "You have been invited to project".to_string(),
-            description: "You have been invited to project".to_string(),
-            message_type: MessageType::ReceivedInvitation,
-            hyper_link: format!("#{}", invitation.bind_token),
-        })) {
-            self.addr.do_send(InnerMsg::SendToUser(
-                message.receiver_id,
-                WsMsg::Message(message),
-            ));
-        }
+        block_on(invitations::notify_invited_user(
+            &self.db,
+            &self.addr,
+            &invitation,
+            user_id,
+        ));
 
         Ok(Some(WsMsg::InvitationSendSuccess))
     }
@@ -104,17 +90,20 @@ pub struct DeleteInvitation {
 
 impl WsHandler<DeleteInvitation> for WebSocketActor {
     fn handle_msg(&mut self, msg: DeleteInvitation, _ctx: &mut Self::Context) -> WsResult {
-        self.require_user()?;
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
         let DeleteInvitation { id } = msg;
-        let res = match block_on(self.db.send(invitations::DeleteInvitation { id })) {
+        let res = match block_on(
+            self.db
+                .send(invitations::DeleteInvitation { id, project_id }),
+        ) {
             Ok(Ok(_)) => None,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(res)
@@ -127,17 +116,20 @@ pub struct RevokeInvitation {
 
 impl WsHandler<RevokeInvitation> for WebSocketActor {
     fn handle_msg(&mut self, msg: RevokeInvitation, _ctx: &mut Self::Context) -> WsResult {
-        self.require_user()?;
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
         let RevokeInvitation { id } = msg;
-        let res = match block_on(self.db.send(invitations::RevokeInvitation { id })) {
+        let res = match block_on(
+            self.db
+                .send(invitations::RevokeInvitation { id, project_id }),
+        ) {
             Ok(Ok(_)) => Some(WsMsg::InvitationRevokeSuccess(id)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(res)
@@ -0,0 +1,52 @@
+use futures::executor::block_on;
+
+use jirs_data::{IssueId, ProjectId, WsMsg};
+
+use crate::db::issue_events::{self, IssueChange};
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+impl WebSocketActor {
+    /// Store history of issue changed by current user
+    pub fn record_issue_events(
+        &self,
+        issue_id: IssueId,
+        project_id: ProjectId,
+        changes: Vec<IssueChange>,
+    ) {
+        let user_id = match self.require_user() {
+            Ok(user) => user.id,
+            _ => return,
+        };
+        block_on(issue_events::record_issue_events(
+            &self.db, issue_id, project_id, user_id, changes,
+        ));
+    }
+}
+
+pub struct LoadIssueEvents {
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<LoadIssueEvents> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadIssueEvents, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let events = match block_on(self.db.send(crate::db::issue_events::LoadIssueEvents {
+            issue_id: msg.issue_id,
+            project_id,
+        })) {
+            Ok(Ok(events)) => events,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::IssueEventsLoaded(msg.issue_id, events)))
+    }
+}
@@ -0,0 +1,91 @@
+use futures::executor::block_on;
+
+use jirs_data::{CreateIssueLinkPayload, IssueId, IssueLinkId, UserRole, WsMsg};
+
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct LoadIssueLinks {
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<LoadIssueLinks> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadIssueLinks, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let links = match block_on(self.db.send(crate::db::issue_links::LoadIssueLinks {
+            issue_id: msg.issue_id,
+            project_id,
+        })) {
+            Ok(Ok(links)) => links,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::IssueLinksLoaded(msg.issue_id, links)))
+    }
+}
+
+impl WsHandler<CreateIssueLinkPayload> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateIssueLinkPayload, _ctx: &mut Self::Context) -> WsResult {
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
+
+        let link = match block_on(self.db.send(crate::db::issue_links::CreateIssueLink {
+            source_issue_id: msg.source_issue_id,
+            target_issue_id: msg.target_issue_id,
+            link_type: msg.link_type,
+            user_id,
+            project_id,
+        })) {
+            Ok(Ok(link)) => link,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        self.broadcast(&WsMsg::IssueLinkCreated(link));
+        Ok(None)
+    }
+}
+
+pub struct DeleteIssueLink {
+    pub link_id: IssueLinkId,
+}
+
+impl WsHandler<DeleteIssueLink> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteIssueLink, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::User)?.project_id;
+
+        let link = match block_on(self.db.send(crate::db::issue_links::DeleteIssueLink {
+            link_id: msg.link_id,
+            project_id,
+        })) {
+            Ok(Ok(link)) => link,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        self.broadcast(&WsMsg::IssueLinkDeleted(link.id));
+        Ok(None)
+    }
+}
@@ -0,0 +1,38 @@
+use futures::executor::block_on;
+
+use jirs_data::WsMsg;
+
+use crate::db::issue_query;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct QueryIssues {
+    pub query: String,
+}
+
+impl WsHandler<QueryIssues> for WebSocketActor {
+    fn handle_msg(&mut self, msg: QueryIssues, _ctx: &mut Self::Context) -> WsResult {
+        let user_id = self.require_user()?.id;
+        let project_id = self.require_user_project()?.project_id;
+
+        let issue_ids = match block_on(self.db.send(issue_query::QueryIssues {
+            project_id,
+            user_id,
+            query: msg.query.clone(),
+        })) {
+            Ok(Ok(issue_ids)) => issue_ids,
+            Ok(Err(ServiceErrors::InvalidQuery(error))) => {
+                return Ok(Some(WsMsg::IssueQueryFailed(msg.query, error)));
+            }
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(Some(WsMsg::IssueQueryLoaded(msg.query, issue_ids)))
+    }
+}
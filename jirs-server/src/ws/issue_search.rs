@@ -0,0 +1,44 @@
+use futures::executor::block_on;
+
+use jirs_data::WsMsg;
+
+use crate::db::issue_search;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+/// Max number of hits returned for single search
+pub const SEARCH_LIMIT: i64 = 50;
+
+pub struct SearchIssues {
+    pub text: String,
+    pub all_projects: bool,
+}
+
+impl WsHandler<SearchIssues> for WebSocketActor {
+    fn handle_msg(&mut self, msg: SearchIssues, _ctx: &mut Self::Context) -> WsResult {
+        let user_id = self.require_user()?.id;
+        let project_id = if msg.all_projects {
+            None
+        } else {
+            Some(self.require_user_project()?.project_id)
+        };
+
+        let hits = match block_on(self.db.send(issue_search::SearchIssues {
+            user_id,
+            project_id,
+            text: msg.text.clone(),
+            limit: SEARCH_LIMIT,
+        })) {
+            Ok(Ok(hits)) => hits,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(Some(WsMsg::IssueSearchLoaded(msg.text, hits)))
+    }
+}
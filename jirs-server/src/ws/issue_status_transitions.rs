@@ -0,0 +1,138 @@
+use futures::executor::block_on;
+
+use jirs_data::{
+    CreateIssueStatusTransitionPayload, Issue, IssueStatusId, IssueStatusTransitionId, UserRole,
+    WsMsg,
+};
+
+use crate::db::issue_status_transitions;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+impl WebSocketActor {
+    /// Reject status change which is not allowed by project workflow
+    pub fn check_status_transition(
+        &self,
+        issue: &Issue,
+        to_status_id: IssueStatusId,
+    ) -> Result<(), WsMsg> {
+        let transitions = match block_on(self.db.send(
+            issue_status_transitions::LoadIssueStatusTransitions {
+                project_id: issue.project_id,
+            },
+        )) {
+            Ok(Ok(v)) => v,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        jirs_data::check_status_transition(transitions.as_slice(), issue, to_status_id).map_err(
+            |message| {
+                issue_status_transitions::transition_error("issue_status_id", message.as_str())
+                    .into()
+            },
+        )
+    }
+}
+
+pub struct LoadIssueStatusTransitions;
+
+impl WsHandler<LoadIssueStatusTransitions> for WebSocketActor {
+    fn handle_msg(
+        &mut self,
+        _msg: LoadIssueStatusTransitions,
+        _ctx: &mut Self::Context,
+    ) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let msg = match block_on(
+            self.db
+                .send(issue_status_transitions::LoadIssueStatusTransitions { project_id }),
+        ) {
+            Ok(Ok(v)) => Some(WsMsg::IssueStatusTransitionsLoaded(v)),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(msg)
+    }
+}
+
+impl WsHandler<CreateIssueStatusTransitionPayload> for WebSocketActor {
+    fn handle_msg(
+        &mut self,
+        msg: CreateIssueStatusTransitionPayload,
+        _ctx: &mut Self::Context,
+    ) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let CreateIssueStatusTransitionPayload {
+            from_status_id,
+            to_status_id,
+            required_field,
+        } = msg;
+        let transition = match block_on(self.db.send(
+            issue_status_transitions::CreateIssueStatusTransition {
+                project_id,
+                from_status_id,
+                to_status_id,
+                required_field,
+            },
+        )) {
+            Ok(Ok(transition)) => transition,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::IssueStatusTransitionCreated(transition));
+        Ok(None)
+    }
+}
+
+pub struct DeleteIssueStatusTransition {
+    pub transition_id: IssueStatusTransitionId,
+}
+
+impl WsHandler<DeleteIssueStatusTransition> for WebSocketActor {
+    fn handle_msg(
+        &mut self,
+        msg: DeleteIssueStatusTransition,
+        _ctx: &mut Self::Context,
+    ) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let transition_id = match block_on(self.db.send(
+            issue_status_transitions::DeleteIssueStatusTransition {
+                transition_id: msg.transition_id,
+                project_id,
+            },
+        )) {
+            Ok(Ok(transition_id)) => transition_id,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::IssueStatusTransitionDeleted(transition_id));
+        Ok(None)
+    }
+}
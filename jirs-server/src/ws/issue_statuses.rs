@@ -1,10 +1,44 @@
 use futures::executor::block_on;
 
-use jirs_data::{IssueStatusId, Position, TitleString, WsMsg};
+use jirs_data::{Issue, IssueStatusId, Position, TitleString, UserRole, WipLimitPolicy, WsMsg};
 
 use crate::db::issue_statuses;
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
+impl WebSocketActor {
+    /// Moving issue into full column is rejected or only reported back to
+    /// its author, depending on project WIP limit policy.
+    pub fn check_wip_limit(
+        &self,
+        issue: &Issue,
+        to_status_id: IssueStatusId,
+    ) -> Result<Option<WsMsg>, WsMsg> {
+        let policy = match block_on(self.db.send(issue_statuses::CheckWipLimit {
+            project_id: issue.project_id,
+            from_status_id: issue.issue_status_id,
+            issue_status_id: to_status_id,
+        })) {
+            Ok(Ok(policy)) => policy,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        match policy {
+            Some(WipLimitPolicy::Block) => Err(issue_statuses::wip_limit_error().into()),
+            Some(WipLimitPolicy::Warn) => {
+                Ok(Some(WsMsg::IssueStatusWipLimitExceeded(to_status_id)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
 pub struct LoadIssueStatuses;
 
 impl WsHandler<LoadIssueStatuses> for WebSocketActor {
@@ -18,11 +52,11 @@ impl WsHandler<LoadIssueStatuses> for WebSocketActor {
             Ok(Ok(v)) => Some(WsMsg::IssueStatusesResponse(v)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(msg)
@@ -36,7 +70,7 @@ pub struct CreateIssueStatus {
 
 impl WsHandler<CreateIssueStatus> for WebSocketActor {
     fn handle_msg(&mut self, msg: CreateIssueStatus, _ctx: &mut Self::Context) -> WsResult {
-        let project_id = self.require_user_project()?.project_id;
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
 
         let CreateIssueStatus { position, name } = msg;
         let msg = match block_on(self.db.send(issue_statuses::CreateIssueStatus {
@@ -47,11 +81,11 @@ impl WsHandler<CreateIssueStatus> for WebSocketActor {
             Ok(Ok(is)) => Some(WsMsg::IssueStatusCreated(is)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(msg)
@@ -64,7 +98,7 @@ pub struct DeleteIssueStatus {
 
 impl WsHandler<DeleteIssueStatus> for WebSocketActor {
     fn handle_msg(&mut self, msg: DeleteIssueStatus, _ctx: &mut Self::Context) -> WsResult {
-        let project_id = self.require_user_project()?.project_id;
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
 
         let DeleteIssueStatus { issue_status_id } = msg;
         let msg = match block_on(self.db.send(issue_statuses::DeleteIssueStatus {
@@ -74,11 +108,11 @@ impl WsHandler<DeleteIssueStatus> for WebSocketActor {
             Ok(Ok(is)) => Some(WsMsg::IssueStatusDeleted(is)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(msg)
@@ -89,31 +123,34 @@ pub struct UpdateIssueStatus {
     pub issue_status_id: IssueStatusId,
     pub position: Position,
     pub name: TitleString,
+    pub wip_limit: Option<i32>,
 }
 
 impl WsHandler<UpdateIssueStatus> for WebSocketActor {
     fn handle_msg(&mut self, msg: UpdateIssueStatus, _ctx: &mut Self::Context) -> WsResult {
-        let project_id = self.require_user_project()?.project_id;
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
 
         let UpdateIssueStatus {
             issue_status_id,
             position,
             name,
+            wip_limit,
         } = msg;
         let msg = match block_on(self.db.send(issue_statuses::UpdateIssueStatus {
             issue_status_id,
             position,
             name,
+            wip_limit,
             project_id,
         })) {
             Ok(Ok(is)) => Some(WsMsg::IssueStatusUpdated(is)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         if let Some(ws_msg) = msg.as_ref() {
@@ -0,0 +1,90 @@
+use futures::executor::block_on;
+
+use jirs_data::{IssueId, MessageType, UserRole, WsMsg};
+
+use crate::db::issue_watchers;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+impl WebSocketActor {
+    /// Sends message to every watcher of issue except current user
+    pub fn notify_issue_watchers(
+        &self,
+        issue_id: IssueId,
+        message_type: MessageType,
+        description: String,
+    ) {
+        let sender_id = match self.require_user() {
+            Ok(user) => user.id,
+            _ => return,
+        };
+        block_on(issue_watchers::notify_issue_watchers(
+            &self.db,
+            &self.addr,
+            issue_id,
+            sender_id,
+            message_type,
+            description,
+        ));
+    }
+}
+
+pub struct LoadIssueWatchers {
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<LoadIssueWatchers> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadIssueWatchers, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let watchers = match block_on(self.db.send(issue_watchers::LoadIssueWatchers {
+            issue_id: msg.issue_id,
+            project_id,
+        })) {
+            Ok(Ok(watchers)) => watchers,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::IssueWatchersLoaded(msg.issue_id, watchers)))
+    }
+}
+
+pub struct WatchIssue {
+    pub issue_id: IssueId,
+    pub watch: bool,
+}
+
+impl WsHandler<WatchIssue> for WebSocketActor {
+    fn handle_msg(&mut self, msg: WatchIssue, _ctx: &mut Self::Context) -> WsResult {
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
+
+        let watchers = match block_on(self.db.send(issue_watchers::WatchIssue {
+            issue_id: msg.issue_id,
+            project_id,
+            user_id,
+            watch: msg.watch,
+        })) {
+            Ok(Ok(watchers)) => watchers,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::IssueWatchersLoaded(msg.issue_id, watchers));
+        Ok(None)
+    }
+}
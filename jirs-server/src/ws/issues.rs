@@ -2,12 +2,87 @@ use std::collections::HashMap;
 
 use futures::executor::block_on;
 
-use jirs_data::{CreateIssuePayload, IssueAssignee, IssueFieldId, IssueId, PayloadVariant, WsMsg};
+use jirs_data::{
+    CreateIssuePayload, CustomFieldValue, FieldError, IssueAssignee, IssueEventType, IssueFieldId,
+    IssueId, LabelId, MessageType, PayloadVariant, UserRole, WsMsg,
+};
 
 use crate::db::issue_assignees::LoadAssignees;
-use crate::db::issues::{LoadProjectIssues, UpdateIssue};
+use crate::db::issue_custom_field_values::LoadIssueCustomFieldValues;
+use crate::db::issue_events::{issue_changes, IssueChange};
+use crate::db::issue_labels::LoadIssueLabels;
+use crate::db::issue_watchers::changes_description;
+use crate::db::issues::{
+    deleted_issues, LoadIssue, LoadIssueByKey, LoadProjectIssues, SetIssueParent, UpdateIssue,
+};
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
+impl WebSocketActor {
+    pub fn load_issue(&self, issue_id: IssueId) -> Result<jirs_data::Issue, WsMsg> {
+        let mut issue: jirs_data::Issue = match block_on(self.db.send(LoadIssue { issue_id })) {
+            Ok(Ok(issue)) => issue.into(),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        match block_on(self.db.send(LoadAssignees { issue_id })) {
+            Ok(Ok(assignees)) => {
+                for assignee in assignees {
+                    issue.user_ids.push(assignee.user_id);
+                }
+            }
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        issue.label_ids = self.load_issue_label_ids(issue_id)?;
+        issue.custom_values = self.load_issue_custom_values(issue_id)?;
+        Ok(issue)
+    }
+
+    pub fn load_issue_label_ids(&self, issue_id: IssueId) -> Result<Vec<LabelId>, WsMsg> {
+        match block_on(self.db.send(LoadIssueLabels { issue_id })) {
+            Ok(Ok(issue_labels)) => Ok(issue_labels.into_iter().map(|l| l.label_id).collect()),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                Err(e.into())
+            }
+            Err(e) => {
+                error!("{}", e);
+                Err(ServiceErrors::DatabaseConnectionLost.into())
+            }
+        }
+    }
+
+    pub fn load_issue_custom_values(
+        &self,
+        issue_id: IssueId,
+    ) -> Result<Vec<CustomFieldValue>, WsMsg> {
+        match block_on(self.db.send(LoadIssueCustomFieldValues { issue_id })) {
+            Ok(Ok(values)) => Ok(values.into_iter().map(|v| v.into()).collect()),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                Err(e.into())
+            }
+            Err(e) => {
+                error!("{}", e);
+                Err(ServiceErrors::DatabaseConnectionLost.into())
+            }
+        }
+    }
+}
+
 pub struct UpdateIssueHandler {
     pub id: i32,
     pub field_id: IssueFieldId,
@@ -16,16 +91,18 @@ pub struct UpdateIssueHandler {
 
 impl WsHandler<UpdateIssueHandler> for WebSocketActor {
     fn handle_msg(&mut self, msg: UpdateIssueHandler, _ctx: &mut Self::Context) -> WsResult {
-        self.require_user()?;
-
         let UpdateIssueHandler {
             id,
             field_id,
             payload,
         } = msg;
 
+        let old_issue = self.load_issue(id)?;
+        self.require_project_role(UserRole::User, old_issue.project_id)?;
+
         let mut msg = UpdateIssue::default();
         msg.issue_id = id;
+        let mut warning = None;
         match (field_id, payload) {
             (IssueFieldId::Type, PayloadVariant::IssueType(t)) => {
                 msg.issue_type = Some(t);
@@ -37,6 +114,8 @@ impl WsHandler<UpdateIssueHandler> for WebSocketActor {
                 msg.description = Some(s);
             }
             (IssueFieldId::IssueStatusId, PayloadVariant::I32(s)) => {
+                self.check_status_transition(&old_issue, s)?;
+                warning = self.check_wip_limit(&old_issue, s)?;
                 msg.issue_status_id = Some(s);
             }
             (IssueFieldId::ListPosition, PayloadVariant::I32(i)) => {
@@ -45,6 +124,15 @@ impl WsHandler<UpdateIssueHandler> for WebSocketActor {
             (IssueFieldId::Assignees, PayloadVariant::VecI32(v)) => {
                 msg.user_ids = Some(v);
             }
+            (IssueFieldId::Labels, PayloadVariant::VecI32(v)) => {
+                msg.label_ids = Some(v);
+            }
+            (IssueFieldId::CustomField(custom_field_id), PayloadVariant::VecString(values)) => {
+                msg.custom_values = Some(vec![CustomFieldValue {
+                    custom_field_id,
+                    values,
+                }]);
+            }
             (IssueFieldId::Reporter, PayloadVariant::I32(i)) => {
                 msg.reporter_id = Some(i);
             }
@@ -54,18 +142,35 @@ impl WsHandler<UpdateIssueHandler> for WebSocketActor {
             (IssueFieldId::Estimate, PayloadVariant::OptionI32(o)) => {
                 msg.estimate = o;
             }
-            (IssueFieldId::TimeSpent, PayloadVariant::OptionI32(o)) => {
-                msg.time_spent = o;
+            (IssueFieldId::TimeSpent, _) => {
+                return Err(ServiceErrors::InvalidPayload(vec![FieldError {
+                    field: format!("{:?}", IssueFieldId::TimeSpent),
+                    message: "Time spent is sum of worklogs".to_string(),
+                }])
+                .into());
             }
             (IssueFieldId::TimeRemaining, PayloadVariant::OptionI32(o)) => {
                 msg.time_remaining = o;
             }
-            _ => (),
+            (field_id, _) => {
+                return Err(ServiceErrors::InvalidPayload(vec![FieldError {
+                    field: format!("{:?}", field_id),
+                    message: "Payload does not match field".to_string(),
+                }])
+                .into());
+            }
         };
 
         let mut issue: jirs_data::Issue = match block_on(self.db.send(msg)) {
             Ok(Ok(issue)) => issue.into(),
-            _ => return Ok(None),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
         };
 
         let assignees: Vec<IssueAssignee> =
@@ -73,77 +178,174 @@ impl WsHandler<UpdateIssueHandler> for WebSocketActor {
                 Ok(Ok(v)) => v,
                 Ok(Err(e)) => {
                     error!("{:?}", e);
-                    return Ok(None);
+                    return Err(e.into());
                 }
                 Err(e) => {
                     error!("{}", e);
-                    return Ok(None);
+                    return Err(ServiceErrors::DatabaseConnectionLost.into());
                 }
             };
 
         for assignee in assignees {
             issue.user_ids.push(assignee.user_id);
         }
+        issue.label_ids = self.load_issue_label_ids(issue.id)?;
+        issue.custom_values = self.load_issue_custom_values(issue.id)?;
+        let changes = issue_changes(&old_issue, &issue);
+        if !changes.is_empty() {
+            self.notify_issue_watchers(
+                issue.id,
+                MessageType::IssueChanged,
+                changes_description(changes.as_slice()),
+            );
+        }
+        self.record_issue_events(issue.id, issue.project_id, changes);
         self.broadcast(&WsMsg::IssueUpdated(issue));
 
-        Ok(None)
+        Ok(warning)
     }
 }
 
 impl WsHandler<CreateIssuePayload> for WebSocketActor {
     fn handle_msg(&mut self, msg: CreateIssuePayload, _ctx: &mut Self::Context) -> WsResult {
-        self.require_user()?;
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
         let msg = crate::db::issues::CreateIssue {
             title: msg.title,
             issue_type: msg.issue_type,
             issue_status_id: msg.issue_status_id,
             priority: msg.priority,
             description: msg.description,
-            description_text: msg.description_text,
             estimate: msg.estimate,
             time_spent: msg.time_spent,
             time_remaining: msg.time_remaining,
-            project_id: msg.project_id,
+            project_id,
             reporter_id: msg.reporter_id,
             user_ids: msg.user_ids,
+            parent_id: msg.parent_id,
+            user_id,
         };
         let m = match block_on(self.db.send(msg)) {
             Ok(Ok(issue)) => Some(WsMsg::IssueCreated(issue.into())),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(m)
     }
 }
 
+pub struct LoadIssueKey {
+    pub key: String,
+}
+
+impl WsHandler<LoadIssueKey> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadIssueKey, _ctx: &mut Self::Context) -> WsResult {
+        let user_id = self.require_user()?.id;
+        let issue = match block_on(self.db.send(LoadIssueByKey {
+            user_id,
+            key: msg.key,
+        })) {
+            Ok(Ok(issue)) => issue,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(Some(WsMsg::IssueByKeyLoaded(issue.project_id, issue.id)))
+    }
+}
+
 pub struct DeleteIssue {
     pub id: IssueId,
 }
 
 impl WsHandler<DeleteIssue> for WebSocketActor {
     fn handle_msg(&mut self, msg: DeleteIssue, _ctx: &mut Self::Context) -> WsResult {
-        self.require_user()?;
-        let m = match block_on(
+        self.require_role(UserRole::Manager)?;
+        let issue = self.load_issue(msg.id)?;
+        self.require_project_role(UserRole::Manager, issue.project_id)?;
+        let subtasks = match block_on(
             self.db
                 .send(crate::db::issues::DeleteIssue { issue_id: msg.id }),
         ) {
-            Ok(Ok(_)) => Some(WsMsg::IssueDeleted(msg.id)),
+            Ok(Ok(subtasks)) => subtasks,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
-        Ok(m)
+        let project_id = issue.project_id;
+        for (issue_id, title) in deleted_issues(issue.id, issue.title, subtasks) {
+            self.record_issue_events(
+                issue_id,
+                project_id,
+                vec![IssueChange::new(IssueEventType::Deleted, Some(title), None)],
+            );
+            self.broadcast(&WsMsg::IssueDeleted(issue_id));
+        }
+        Ok(None)
+    }
+}
+
+pub struct UpdateIssueParent {
+    pub issue_id: IssueId,
+    pub parent_id: Option<IssueId>,
+}
+
+impl WsHandler<UpdateIssueParent> for WebSocketActor {
+    fn handle_msg(&mut self, msg: UpdateIssueParent, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::User)?.project_id;
+
+        let old_issue = self.load_issue(msg.issue_id)?;
+        if old_issue.project_id != project_id {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()).into());
+        }
+
+        let mut issue: jirs_data::Issue = match block_on(self.db.send(SetIssueParent {
+            issue_id: msg.issue_id,
+            parent_id: msg.parent_id,
+        })) {
+            Ok(Ok(issue)) => issue.into(),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        issue.user_ids = old_issue.user_ids.clone();
+        issue.label_ids = old_issue.label_ids.clone();
+        issue.custom_values = old_issue.custom_values.clone();
+
+        let changes = issue_changes(&old_issue, &issue);
+        if !changes.is_empty() {
+            self.notify_issue_watchers(
+                issue.id,
+                MessageType::IssueChanged,
+                changes_description(changes.as_slice()),
+            );
+        }
+        self.record_issue_events(issue.id, issue.project_id, changes);
+        self.broadcast(&WsMsg::IssueUpdated(issue));
+        Ok(None)
     }
 }
 
@@ -156,7 +358,14 @@ impl WsHandler<LoadIssues> for WebSocketActor {
         let issues: Vec<jirs_data::Issue> =
             match block_on(self.db.send(LoadProjectIssues { project_id })) {
                 Ok(Ok(v)) => v.into_iter().map(|i| i.into()).collect(),
-                _ => return Ok(None),
+                Ok(Err(e)) => {
+                    error!("{:?}", e);
+                    return Err(e.into());
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    return Err(ServiceErrors::DatabaseConnectionLost.into());
+                }
             };
         let mut issue_map = HashMap::new();
         let mut queue = vec![];
@@ -174,6 +383,36 @@ impl WsHandler<LoadIssues> for WebSocketActor {
                 }
             };
         }
+        let mut queue = vec![];
+        for issue_id in issue_map.keys() {
+            queue.push(self.db.send(LoadIssueLabels {
+                issue_id: *issue_id,
+            }));
+        }
+        for f in queue {
+            if let Ok(Ok(issue_labels)) = block_on(f) {
+                for issue_label in issue_labels {
+                    if let Some(issue) = issue_map.get_mut(&issue_label.issue_id) {
+                        issue.label_ids.push(issue_label.label_id);
+                    }
+                }
+            };
+        }
+        let mut queue = vec![];
+        for issue_id in issue_map.keys() {
+            queue.push(self.db.send(LoadIssueCustomFieldValues {
+                issue_id: *issue_id,
+            }));
+        }
+        for f in queue {
+            if let Ok(Ok(values)) = block_on(f) {
+                for value in values {
+                    if let Some(issue) = issue_map.get_mut(&value.issue_id) {
+                        issue.custom_values.push(value.into());
+                    }
+                }
+            };
+        }
         let mut issues = vec![];
         for (_, issue) in issue_map.into_iter() {
             issues.push(issue);
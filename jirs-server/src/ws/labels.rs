@@ -0,0 +1,85 @@
+use futures::executor::block_on;
+
+use jirs_data::{LabelId, UserRole, WsMsg};
+
+use crate::db::labels;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct LoadLabels;
+
+impl WsHandler<LoadLabels> for WebSocketActor {
+    fn handle_msg(&mut self, _msg: LoadLabels, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let msg = match block_on(self.db.send(labels::LoadLabels { project_id })) {
+            Ok(Ok(v)) => Some(WsMsg::LabelsLoaded(v)),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(msg)
+    }
+}
+
+pub struct CreateLabel {
+    pub name: String,
+    pub color: String,
+}
+
+impl WsHandler<CreateLabel> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateLabel, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let CreateLabel { name, color } = msg;
+        let label = match block_on(self.db.send(labels::CreateLabel {
+            project_id,
+            name,
+            color,
+        })) {
+            Ok(Ok(label)) => label,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::LabelCreated(label));
+        Ok(None)
+    }
+}
+
+pub struct DeleteLabel {
+    pub label_id: LabelId,
+}
+
+impl WsHandler<DeleteLabel> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteLabel, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let label_id = match block_on(self.db.send(labels::DeleteLabel {
+            label_id: msg.label_id,
+            project_id,
+        })) {
+            Ok(Ok(label_id)) => label_id,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::LabelDeleted(label_id));
+        Ok(None)
+    }
+}
@@ -3,6 +3,7 @@ use futures::executor::block_on;
 use jirs_data::{MessageId, WsMsg};
 
 use crate::db::messages;
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
 pub struct LoadMessages;
@@ -14,11 +15,11 @@ impl WsHandler<LoadMessages> for WebSocketActor {
             Ok(Ok(v)) => Ok(Some(WsMsg::MessagesResponse(v))),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
@@ -38,11 +39,11 @@ impl WsHandler<MarkMessageSeen> for WebSocketActor {
             Ok(Ok(id)) => Ok(Some(WsMsg::MessageMarkedSeen(id))),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
@@ -8,31 +8,58 @@ use actix_web::{get, web, Error, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
 use futures::executor::block_on;
 
-use jirs_data::{Project, ProjectId, User, UserId, UserProject, WsMsg};
+use jirs_data::{Project, ProjectId, User, UserId, UserProject, UserRole, WsMsg};
 
 use crate::db::projects::LoadCurrentProject;
 use crate::db::user_projects::CurrentUserProject;
 use crate::db::DbExecutor;
 use crate::mail::MailExecutor;
+use crate::middleware::authorize::{check_project_role, check_role};
 use crate::ws::auth::*;
 use crate::ws::comments::*;
+use crate::ws::custom_fields::*;
+use crate::ws::epics::*;
 use crate::ws::invitations::*;
+use crate::ws::issue_events::*;
+use crate::ws::issue_links::*;
+use crate::ws::issue_query::*;
+use crate::ws::issue_search::*;
+use crate::ws::issue_status_transitions::*;
 use crate::ws::issue_statuses::*;
+use crate::ws::issue_watchers::*;
 use crate::ws::issues::*;
+use crate::ws::labels::*;
 use crate::ws::messages::*;
 use crate::ws::projects::*;
+use crate::ws::reports::*;
+use crate::ws::saved_filters::*;
+use crate::ws::sprints::*;
 use crate::ws::user_projects::{LoadUserProjects, SetCurrentUserProject};
 use crate::ws::users::*;
+use crate::ws::worklogs::*;
 
 pub mod auth;
 pub mod comments;
+pub mod custom_fields;
+pub mod epics;
 pub mod invitations;
+pub mod issue_events;
+pub mod issue_links;
+pub mod issue_query;
+pub mod issue_search;
+pub mod issue_status_transitions;
 pub mod issue_statuses;
+pub mod issue_watchers;
 pub mod issues;
+pub mod labels;
 pub mod messages;
 pub mod projects;
+pub mod reports;
+pub mod saved_filters;
+pub mod sprints;
 pub mod user_projects;
 pub mod users;
+pub mod worklogs;
 
 pub type WsResult = std::result::Result<Option<WsMsg>, WsMsg>;
 
@@ -103,21 +130,40 @@ impl WebSocketActor {
             )?,
             WsMsg::IssueCreateRequest(payload) => self.handle_msg(payload, ctx)?,
             WsMsg::IssueDeleteRequest(id) => self.handle_msg(DeleteIssue { id }, ctx)?,
+            WsMsg::IssueByKeyRequest(key) => self.handle_msg(LoadIssueKey { key }, ctx)?,
             WsMsg::ProjectIssuesRequest => self.handle_msg(LoadIssues, ctx)?,
+            WsMsg::IssueParentUpdateRequest(issue_id, parent_id) => self.handle_msg(
+                UpdateIssueParent {
+                    issue_id,
+                    parent_id,
+                },
+                ctx,
+            )?,
+
+            // epics
+            WsMsg::EpicCreateRequest(name) => self.handle_msg(CreateEpic { name }, ctx)?,
+            WsMsg::EpicAttachIssueRequest(epic_id, issue_id) => {
+                self.handle_msg(AttachEpic { epic_id, issue_id }, ctx)?
+            }
+            WsMsg::EpicDetachIssueRequest(issue_id) => {
+                self.handle_msg(DetachEpic { issue_id }, ctx)?
+            }
 
             // issue statuses
             WsMsg::IssueStatusesRequest => self.handle_msg(LoadIssueStatuses, ctx)?,
             WsMsg::IssueStatusDelete(issue_status_id) => {
                 self.handle_msg(DeleteIssueStatus { issue_status_id }, ctx)?
             }
-            WsMsg::IssueStatusUpdate(issue_status_id, name, position) => self.handle_msg(
-                UpdateIssueStatus {
-                    issue_status_id,
-                    name,
-                    position,
-                },
-                ctx,
-            )?,
+            WsMsg::IssueStatusUpdate(issue_status_id, name, position, wip_limit) => self
+                .handle_msg(
+                    UpdateIssueStatus {
+                        issue_status_id,
+                        name,
+                        position,
+                        wip_limit,
+                    },
+                    ctx,
+                )?,
             WsMsg::IssueStatusCreate(name, position) => {
                 self.handle_msg(CreateIssueStatus { name, position }, ctx)?
             }
@@ -171,6 +217,103 @@ impl WebSocketActor {
                 self.handle_msg(DeleteComment { comment_id }, ctx)?
             }
 
+            // labels
+            WsMsg::LabelsRequest => self.handle_msg(LoadLabels, ctx)?,
+            WsMsg::LabelCreateRequest(name, color) => {
+                self.handle_msg(CreateLabel { name, color }, ctx)?
+            }
+            WsMsg::LabelDeleteRequest(label_id) => {
+                self.handle_msg(DeleteLabel { label_id }, ctx)?
+            }
+
+            // sprints
+            WsMsg::SprintsRequest => self.handle_msg(LoadSprints, ctx)?,
+            WsMsg::SprintCreateRequest(name) => self.handle_msg(CreateSprint { name }, ctx)?,
+            WsMsg::SprintUpdateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::SprintStartRequest(sprint_id) => {
+                self.handle_msg(StartSprint { sprint_id }, ctx)?
+            }
+            WsMsg::SprintCompleteRequest(sprint_id) => {
+                self.handle_msg(CompleteSprint { sprint_id }, ctx)?
+            }
+            WsMsg::SprintDeleteRequest(sprint_id) => {
+                self.handle_msg(DeleteSprint { sprint_id }, ctx)?
+            }
+            WsMsg::IssueSprintUpdateRequest(issue_id, sprint_id) => self.handle_msg(
+                UpdateIssueSprint {
+                    issue_id,
+                    sprint_id,
+                },
+                ctx,
+            )?,
+
+            // custom fields
+            WsMsg::CustomFieldsRequest => self.handle_msg(LoadCustomFields, ctx)?,
+            WsMsg::CustomFieldCreateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::CustomFieldDeleteRequest(custom_field_id) => {
+                self.handle_msg(DeleteCustomField { custom_field_id }, ctx)?
+            }
+
+            // workflow
+            WsMsg::IssueStatusTransitionsRequest => {
+                self.handle_msg(LoadIssueStatusTransitions, ctx)?
+            }
+            WsMsg::IssueStatusTransitionCreateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::IssueStatusTransitionDeleteRequest(transition_id) => {
+                self.handle_msg(DeleteIssueStatusTransition { transition_id }, ctx)?
+            }
+
+            // search
+            WsMsg::IssueSearchRequest { text, all_projects } => {
+                self.handle_msg(SearchIssues { text, all_projects }, ctx)?
+            }
+            WsMsg::IssueQueryRequest(query) => self.handle_msg(QueryIssues { query }, ctx)?,
+
+            // saved filters
+            WsMsg::SavedFiltersRequest => self.handle_msg(LoadSavedFilters, ctx)?,
+            WsMsg::SavedFilterCreateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::SavedFilterDeleteRequest(saved_filter_id) => {
+                self.handle_msg(DeleteSavedFilter { saved_filter_id }, ctx)?
+            }
+
+            // issue watchers
+            WsMsg::IssueWatchersRequest(issue_id) => {
+                self.handle_msg(LoadIssueWatchers { issue_id }, ctx)?
+            }
+            WsMsg::IssueWatchRequest(issue_id, watch) => {
+                self.handle_msg(WatchIssue { issue_id, watch }, ctx)?
+            }
+
+            // worklogs
+            WsMsg::WorklogsRequest(issue_id) => self.handle_msg(LoadWorklogs { issue_id }, ctx)?,
+            WsMsg::WorklogCreateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::WorklogDeleteRequest(worklog_id) => {
+                self.handle_msg(DeleteWorklog { worklog_id }, ctx)?
+            }
+
+            // reports
+            WsMsg::TimeTrackingReportRequest(from, to) => {
+                self.handle_msg(LoadTimeTrackingReport { from, to }, ctx)?
+            }
+            WsMsg::BurndownRequest(from, to) => self.handle_msg(LoadBurndown { from, to }, ctx)?,
+            WsMsg::CumulativeFlowRequest(from, to) => {
+                self.handle_msg(LoadCumulativeFlow { from, to }, ctx)?
+            }
+
+            // issue history
+            WsMsg::IssueEventsRequest(issue_id) => {
+                self.handle_msg(LoadIssueEvents { issue_id }, ctx)?
+            }
+
+            // issue links
+            WsMsg::IssueLinksRequest(issue_id) => {
+                self.handle_msg(LoadIssueLinks { issue_id }, ctx)?
+            }
+            WsMsg::IssueLinkCreateRequest(payload) => self.handle_msg(payload, ctx)?,
+            WsMsg::IssueLinkDeleteRequest(link_id) => {
+                self.handle_msg(DeleteIssueLink { link_id }, ctx)?
+            }
+
             // invitations
             WsMsg::InvitationSendRequest { name, email, role } => {
                 self.handle_msg(CreateInvitation { name, email, role }, ctx)?
@@ -242,6 +385,28 @@ impl WebSocketActor {
             })
     }
 
+    fn require_role(&self, min: UserRole) -> Result<&UserProject, WsMsg> {
+        let user_project = self.require_user_project()?;
+        match check_role(user_project.role, min) {
+            Ok(()) => Ok(user_project),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Same as `require_role` but also requires record to belong to current
+    /// project of user
+    fn require_project_role(
+        &self,
+        min: UserRole,
+        project_id: ProjectId,
+    ) -> Result<&UserProject, WsMsg> {
+        let user_project = self.require_user_project()?;
+        match check_project_role(user_project, project_id, min) {
+            Ok(()) => Ok(user_project),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     fn load_user_project(&self) -> Result<UserProject, WsMsg> {
         let user_id = self.require_user()?.id;
         match block_on(self.db.send(CurrentUserProject { user_id })) {
@@ -282,12 +447,17 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WebSocketActor {
             Ok(ws::Message::Binary(bin)) => {
                 let ws_msg: bincode::Result<jirs_data::WsMsg> =
                     bincode::deserialize(bin.to_vec().as_slice());
-                let msg = match ws_msg {
-                    Ok(m) => m,
+                let (request_id, msg) = match ws_msg {
+                    Ok(WsMsg::Request(request_id, m)) => (Some(request_id), *m),
+                    Ok(m) => (None, m),
                     _ => return,
                 };
                 match self.handle_ws_msg(msg, ctx) {
                     Ok(Some(msg)) => ctx.send_msg(&msg),
+                    Err(WsMsg::Error(mut e)) => {
+                        e.request_id = request_id;
+                        ctx.send_msg(&WsMsg::Error(e))
+                    }
                     Err(e) => ctx.send_msg(&e),
                     _ => (),
                 };
@@ -448,3 +618,87 @@ pub async fn index(
         stream,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use actix::dev::channel::channel;
+
+    use crate::errors::ServiceErrors;
+    use crate::test_support::{user, user_project};
+
+    use super::*;
+
+    /// Address without running actor, handlers must reject message before
+    /// they reach database, mailer or other sessions
+    fn unreachable<A: Actor>() -> Addr<A> {
+        Addr::new(channel(16).0)
+    }
+
+    fn handle<M>(role: UserRole, msg: M) -> WsResult
+    where
+        M: 'static,
+        WebSocketActor: WsHandler<M>,
+    {
+        let result = Rc::new(RefCell::new(None));
+        let slot = result.clone();
+        let _ = ws::WebsocketContext::with_factory(futures::stream::empty(), move |ctx| {
+            let mut actor = WebSocketActor {
+                db: Data::new(unreachable()),
+                mail: Data::new(unreachable()),
+                addr: unreachable(),
+                current_user: Some(user(1, "foo@example.com")),
+                current_user_project: Some(user_project(1, role)),
+                current_project: None,
+            };
+            *slot.borrow_mut() = Some(actor.handle_msg(msg, ctx));
+            actor
+        });
+        let result = result.borrow_mut().take();
+        result.expect("handler was not called")
+    }
+
+    fn forbidden() -> WsResult {
+        Err(ServiceErrors::Forbidden.into())
+    }
+
+    fn invite(role: UserRole) -> CreateInvitation {
+        CreateInvitation {
+            email: "bar@example.com".to_string(),
+            name: "bar".to_string(),
+            role,
+        }
+    }
+
+    #[test]
+    fn user_can_not_invite() {
+        assert_eq!(handle(UserRole::User, invite(UserRole::User)), forbidden());
+    }
+
+    #[test]
+    fn manager_can_not_invite_owner() {
+        assert_eq!(
+            handle(UserRole::Manager, invite(UserRole::Owner)),
+            forbidden()
+        );
+    }
+
+    #[test]
+    fn user_can_not_delete_or_revoke_invitation() {
+        assert_eq!(
+            handle(UserRole::User, DeleteInvitation { id: 1 }),
+            forbidden()
+        );
+        assert_eq!(
+            handle(UserRole::User, RevokeInvitation { id: 1 }),
+            forbidden()
+        );
+    }
+
+    #[test]
+    fn user_can_not_delete_issue() {
+        assert_eq!(handle(UserRole::User, DeleteIssue { id: 1 }), forbidden());
+    }
+}
@@ -1,8 +1,9 @@
 use futures::executor::block_on;
 
-use jirs_data::{UpdateProjectPayload, UserProject, WsMsg};
+use jirs_data::{UpdateProjectPayload, UserProject, UserRole, WsMsg};
 
 use crate::db;
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
 impl WsHandler<UpdateProjectPayload> for WebSocketActor {
@@ -11,7 +12,7 @@ impl WsHandler<UpdateProjectPayload> for WebSocketActor {
             user_id,
             project_id,
             ..
-        } = self.require_user_project()?;
+        } = self.require_role(UserRole::Owner)?;
         match block_on(self.db.send(crate::db::projects::UpdateProject {
             project_id: *project_id,
             name: msg.name,
@@ -19,15 +20,17 @@ impl WsHandler<UpdateProjectPayload> for WebSocketActor {
             description: msg.description,
             category: msg.category,
             time_tracking: msg.time_tracking,
+            wip_limit_policy: msg.wip_limit_policy,
+            key: msg.key,
         })) {
             Ok(Ok(_)) => (),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         let projects = match block_on(
@@ -37,11 +40,11 @@ impl WsHandler<UpdateProjectPayload> for WebSocketActor {
             Ok(Ok(projects)) => projects,
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(Some(WsMsg::ProjectsLoaded(projects)))
@@ -57,11 +60,11 @@ impl WsHandler<LoadProjects> for WebSocketActor {
             Ok(Ok(v)) => Ok(Some(WsMsg::ProjectsLoaded(v))),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
@@ -0,0 +1,95 @@
+use chrono::NaiveDate;
+use futures::executor::block_on;
+
+use jirs_data::WsMsg;
+
+use crate::db::reports;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct LoadTimeTrackingReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl WsHandler<LoadTimeTrackingReport> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadTimeTrackingReport, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let report = match block_on(self.db.send(reports::LoadTimeTrackingReport {
+            project_id,
+            from: msg.from,
+            to: msg.to,
+        })) {
+            Ok(Ok(report)) => report,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::TimeTrackingReportLoaded(report)))
+    }
+}
+
+pub struct LoadBurndown {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl WsHandler<LoadBurndown> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadBurndown, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let report = match block_on(self.db.send(reports::LoadBurndown {
+            project_id,
+            from: msg.from,
+            to: msg.to,
+        })) {
+            Ok(Ok(report)) => report,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::BurndownLoaded(report)))
+    }
+}
+
+pub struct LoadCumulativeFlow {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl WsHandler<LoadCumulativeFlow> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadCumulativeFlow, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let report = match block_on(self.db.send(reports::LoadCumulativeFlow {
+            project_id,
+            from: msg.from,
+            to: msg.to,
+        })) {
+            Ok(Ok(report)) => report,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::CumulativeFlowLoaded(report)))
+    }
+}
@@ -0,0 +1,97 @@
+use futures::executor::block_on;
+
+use jirs_data::{CreateSavedFilterPayload, SavedFilterId, WsMsg};
+
+use crate::db::saved_filters;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+pub struct LoadSavedFilters;
+
+impl WsHandler<LoadSavedFilters> for WebSocketActor {
+    fn handle_msg(&mut self, _msg: LoadSavedFilters, _ctx: &mut Self::Context) -> WsResult {
+        let user_project = self.require_user_project()?;
+        let (project_id, user_id) = (user_project.project_id, user_project.user_id);
+
+        let msg = match block_on(self.db.send(saved_filters::LoadSavedFilters {
+            project_id,
+            user_id,
+        })) {
+            Ok(Ok(v)) => Some(WsMsg::SavedFiltersLoaded(v)),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(msg)
+    }
+}
+
+impl WsHandler<CreateSavedFilterPayload> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateSavedFilterPayload, _ctx: &mut Self::Context) -> WsResult {
+        let user_project = self.require_user_project()?;
+        let (project_id, user_id) = (user_project.project_id, user_project.user_id);
+
+        let CreateSavedFilterPayload {
+            name,
+            shared,
+            filter,
+        } = msg;
+        let saved_filter = match block_on(self.db.send(saved_filters::CreateSavedFilter {
+            project_id,
+            user_id,
+            name,
+            shared,
+            filter,
+        })) {
+            Ok(Ok(saved_filter)) => saved_filter,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        if !saved_filter.shared {
+            return Ok(Some(WsMsg::SavedFilterCreated(saved_filter)));
+        }
+        self.broadcast(&WsMsg::SavedFilterCreated(saved_filter));
+        Ok(None)
+    }
+}
+
+pub struct DeleteSavedFilter {
+    pub saved_filter_id: SavedFilterId,
+}
+
+impl WsHandler<DeleteSavedFilter> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteSavedFilter, _ctx: &mut Self::Context) -> WsResult {
+        let user_id = self.require_user()?.id;
+
+        let saved_filter = match block_on(self.db.send(saved_filters::DeleteSavedFilter {
+            saved_filter_id: msg.saved_filter_id,
+            user_id,
+        })) {
+            Ok(Ok(saved_filter)) => saved_filter,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        if !saved_filter.shared {
+            return Ok(Some(WsMsg::SavedFilterDeleted(saved_filter.id)));
+        }
+        self.broadcast(&WsMsg::SavedFilterDeleted(saved_filter.id));
+        Ok(None)
+    }
+}
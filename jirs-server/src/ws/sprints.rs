@@ -0,0 +1,237 @@
+use futures::executor::block_on;
+
+use jirs_data::{IssueEventType, IssueId, SprintId, UpdateSprintPayload, UserRole, WsMsg};
+
+use crate::db::issue_events::IssueChange;
+use crate::db::sprints;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+impl WebSocketActor {
+    /// Notify project members about issues moved between sprints and store
+    /// this change in issues history.
+    fn broadcast_sprint_change(
+        &self,
+        issue_ids: Vec<IssueId>,
+        from: Option<SprintId>,
+        target: Option<SprintId>,
+    ) -> Result<(), WsMsg> {
+        if issue_ids.is_empty() {
+            return Ok(());
+        }
+        let project_id = self.require_user_project()?.project_id;
+        for issue_id in issue_ids.iter() {
+            self.record_issue_events(
+                *issue_id,
+                project_id,
+                vec![IssueChange::new(
+                    IssueEventType::Sprint,
+                    from.map(|id| id.to_string()),
+                    target.map(|id| id.to_string()),
+                )],
+            );
+        }
+        self.broadcast(&WsMsg::IssuesSprintChanged(issue_ids, target));
+        Ok(())
+    }
+
+    fn broadcast_transition(&self, transition: sprints::SprintTransition) -> Result<(), WsMsg> {
+        let sprints::SprintTransition {
+            sprints,
+            moved_issue_ids,
+            from,
+            target,
+        } = transition;
+        for sprint in sprints {
+            self.broadcast(&WsMsg::SprintUpdated(sprint));
+        }
+        self.broadcast_sprint_change(moved_issue_ids, Some(from), target)
+    }
+}
+
+pub struct LoadSprints;
+
+impl WsHandler<LoadSprints> for WebSocketActor {
+    fn handle_msg(&mut self, _msg: LoadSprints, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+
+        let msg = match block_on(self.db.send(sprints::LoadSprints { project_id })) {
+            Ok(Ok(v)) => Some(WsMsg::SprintsLoaded(v)),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        Ok(msg)
+    }
+}
+
+pub struct CreateSprint {
+    pub name: String,
+}
+
+impl WsHandler<CreateSprint> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateSprint, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let sprint = match block_on(self.db.send(sprints::CreateSprint {
+            project_id,
+            name: msg.name,
+        })) {
+            Ok(Ok(sprint)) => sprint,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::SprintCreated(sprint));
+        Ok(None)
+    }
+}
+
+impl WsHandler<UpdateSprintPayload> for WebSocketActor {
+    fn handle_msg(&mut self, msg: UpdateSprintPayload, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let sprint = match block_on(self.db.send(sprints::UpdateSprint {
+            payload: msg,
+            project_id,
+        })) {
+            Ok(Ok(sprint)) => sprint,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast(&WsMsg::SprintUpdated(sprint));
+        Ok(None)
+    }
+}
+
+pub struct DeleteSprint {
+    pub sprint_id: SprintId,
+}
+
+impl WsHandler<DeleteSprint> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteSprint, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let moved = match block_on(self.db.send(sprints::DeleteSprint {
+            sprint_id: msg.sprint_id,
+            project_id,
+        })) {
+            Ok(Ok(moved)) => moved,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast_sprint_change(moved, Some(msg.sprint_id), None)?;
+        self.broadcast(&WsMsg::SprintDeleted(msg.sprint_id));
+        Ok(None)
+    }
+}
+
+pub struct StartSprint {
+    pub sprint_id: SprintId,
+}
+
+impl WsHandler<StartSprint> for WebSocketActor {
+    fn handle_msg(&mut self, msg: StartSprint, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let transition = match block_on(self.db.send(sprints::StartSprint {
+            sprint_id: msg.sprint_id,
+            project_id,
+        })) {
+            Ok(Ok(transition)) => transition,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast_transition(transition)?;
+        Ok(None)
+    }
+}
+
+pub struct CompleteSprint {
+    pub sprint_id: SprintId,
+}
+
+impl WsHandler<CompleteSprint> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CompleteSprint, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::Manager)?.project_id;
+
+        let transition = match block_on(self.db.send(sprints::CompleteSprint {
+            sprint_id: msg.sprint_id,
+            project_id,
+        })) {
+            Ok(Ok(transition)) => transition,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast_transition(transition)?;
+        Ok(None)
+    }
+}
+
+pub struct UpdateIssueSprint {
+    pub issue_id: IssueId,
+    pub sprint_id: Option<SprintId>,
+}
+
+impl WsHandler<UpdateIssueSprint> for WebSocketActor {
+    fn handle_msg(&mut self, msg: UpdateIssueSprint, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_role(UserRole::User)?.project_id;
+        let old_sprint_id = self.load_issue(msg.issue_id)?.sprint_id;
+
+        let issues = match block_on(self.db.send(sprints::SetIssueSprint {
+            issue_id: msg.issue_id,
+            sprint_id: msg.sprint_id,
+            project_id,
+        })) {
+            Ok(Ok(issues)) => issues,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        if old_sprint_id == msg.sprint_id {
+            return Ok(None);
+        }
+        let ids = issues.into_iter().map(|issue| issue.id).collect();
+        self.broadcast_sprint_change(ids, old_sprint_id, msg.sprint_id)?;
+        Ok(None)
+    }
+}
@@ -3,6 +3,7 @@ use futures::executor::block_on;
 use jirs_data::{UserProjectId, WsMsg};
 
 use crate::db;
+use crate::errors::ServiceErrors;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
 pub struct LoadUserProjects;
@@ -17,11 +18,11 @@ impl WsHandler<LoadUserProjects> for WebSocketActor {
             Ok(Ok(v)) => Ok(Some(WsMsg::UserProjectsLoaded(v))),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
@@ -44,11 +45,11 @@ impl WsHandler<SetCurrentUserProject> for WebSocketActor {
             }
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
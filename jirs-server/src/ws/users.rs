@@ -1,9 +1,10 @@
 use futures::executor::block_on;
 
-use jirs_data::{UserId, UserProject, WsMsg};
+use jirs_data::{UserId, UserProject, UserRole, WsMsg};
 
 use crate::db;
 use crate::db::users::Register as DbRegister;
+use crate::errors::ServiceErrors;
 use crate::ws::auth::Authenticate;
 use crate::ws::{WebSocketActor, WsHandler, WsResult};
 
@@ -18,11 +19,11 @@ impl WsHandler<LoadProjectUsers> for WebSocketActor {
             Ok(Ok(v)) => Some(WsMsg::ProjectUsersLoaded(v)),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
         Ok(m)
@@ -46,7 +47,7 @@ impl WsHandler<Register> for WebSocketActor {
             Ok(Err(_)) => Some(WsMsg::SignUpPairTaken),
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
 
@@ -67,7 +68,14 @@ impl WsHandler<LoadInvitedUsers> for WebSocketActor {
 
         let users = match block_on(self.db.send(crate::db::users::LoadInvitedUsers { user_id })) {
             Ok(Ok(users)) => users,
-            _ => return Ok(None),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
         };
 
         Ok(Some(WsMsg::InvitedUsersLoaded(users)))
@@ -92,11 +100,11 @@ impl WsHandler<ProfileUpdate> for WebSocketActor {
             Ok(Ok(_users)) => (),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                return Ok(None);
+                return Err(e.into());
             }
             Err(e) => {
                 error!("{}", e);
-                return Ok(None);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
             }
         };
 
@@ -117,7 +125,7 @@ impl WsHandler<RemoveInvitedUser> for WebSocketActor {
             user_id: inviter_id,
             project_id,
             ..
-        } = self.require_user_project()?.clone();
+        } = self.require_role(UserRole::Owner)?.clone();
         match block_on(self.db.send(db::user_projects::RemoveInvitedUser {
             invited_id,
             inviter_id,
@@ -126,11 +134,11 @@ impl WsHandler<RemoveInvitedUser> for WebSocketActor {
             Ok(Ok(_users)) => Ok(Some(WsMsg::InvitedUserRemoveSuccess(invited_id))),
             Ok(Err(e)) => {
                 error!("{:?}", e);
-                Ok(None)
+                Err(e.into())
             }
             Err(e) => {
                 error!("{}", e);
-                Ok(None)
+                Err(ServiceErrors::DatabaseConnectionLost.into())
             }
         }
     }
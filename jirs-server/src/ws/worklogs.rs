@@ -0,0 +1,150 @@
+use futures::executor::block_on;
+
+use jirs_data::{CreateWorklogPayload, IssueId, MessageType, UserRole, WorklogId, WsMsg};
+
+use crate::db::issue_events::issue_changes;
+use crate::db::issue_watchers::changes_description;
+use crate::db::worklogs;
+use crate::errors::ServiceErrors;
+use crate::ws::{WebSocketActor, WsHandler, WsResult};
+
+impl WebSocketActor {
+    /// Worklogs changed time spent of issue so new issue is sent to project
+    /// members together with issue worklogs.
+    fn broadcast_time_spent(&self, old_issue: jirs_data::Issue) -> Result<(), WsMsg> {
+        let issue = self.load_issue(old_issue.id)?;
+        let worklogs = match block_on(self.db.send(worklogs::LoadWorklogs { issue_id: issue.id })) {
+            Ok(Ok(worklogs)) => worklogs,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        let changes = issue_changes(&old_issue, &issue);
+        if !changes.is_empty() {
+            self.notify_issue_watchers(
+                issue.id,
+                MessageType::IssueChanged,
+                changes_description(changes.as_slice()),
+            );
+        }
+        self.record_issue_events(issue.id, issue.project_id, changes);
+        self.broadcast(&WsMsg::WorklogsLoaded(issue.id, worklogs));
+        self.broadcast(&WsMsg::IssueUpdated(issue));
+        Ok(())
+    }
+}
+
+pub struct LoadWorklogs {
+    pub issue_id: IssueId,
+}
+
+impl WsHandler<LoadWorklogs> for WebSocketActor {
+    fn handle_msg(&mut self, msg: LoadWorklogs, _ctx: &mut Self::Context) -> WsResult {
+        let project_id = self.require_user_project()?.project_id;
+        if self.load_issue(msg.issue_id)?.project_id != project_id {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()).into());
+        }
+
+        let worklogs = match block_on(self.db.send(worklogs::LoadWorklogs {
+            issue_id: msg.issue_id,
+        })) {
+            Ok(Ok(worklogs)) => worklogs,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+
+        Ok(Some(WsMsg::WorklogsLoaded(msg.issue_id, worklogs)))
+    }
+}
+
+impl WsHandler<CreateWorklogPayload> for WebSocketActor {
+    fn handle_msg(&mut self, msg: CreateWorklogPayload, _ctx: &mut Self::Context) -> WsResult {
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
+        let old_issue = self.load_issue(msg.issue_id)?;
+        if old_issue.project_id != project_id {
+            return Err(ServiceErrors::RecordNotFound("issue".to_string()).into());
+        }
+
+        match block_on(self.db.send(worklogs::CreateWorklog {
+            issue_id: msg.issue_id,
+            user_id,
+            time_spent: msg.time_spent,
+            started_at: msg.started_at,
+            comment: msg.comment,
+        })) {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast_time_spent(old_issue)?;
+        Ok(None)
+    }
+}
+
+pub struct DeleteWorklog {
+    pub worklog_id: WorklogId,
+}
+
+impl WsHandler<DeleteWorklog> for WebSocketActor {
+    fn handle_msg(&mut self, msg: DeleteWorklog, _ctx: &mut Self::Context) -> WsResult {
+        let (user_id, project_id) = {
+            let user_project = self.require_role(UserRole::User)?;
+            (user_project.user_id, user_project.project_id)
+        };
+        let worklog = match block_on(self.db.send(worklogs::LoadWorklog {
+            worklog_id: msg.worklog_id,
+        })) {
+            Ok(Ok(worklog)) => worklog,
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        let old_issue = self.load_issue(worklog.issue_id)?;
+        if old_issue.project_id != project_id {
+            return Err(ServiceErrors::RecordNotFound("worklog".to_string()).into());
+        }
+
+        match block_on(self.db.send(worklogs::DeleteWorklog {
+            worklog_id: worklog.id,
+            user_id,
+        })) {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => {
+                error!("{:?}", e);
+                return Err(e.into());
+            }
+            Err(e) => {
+                error!("{}", e);
+                return Err(ServiceErrors::DatabaseConnectionLost.into());
+            }
+        };
+        self.broadcast_time_spent(old_issue)?;
+        Ok(None)
+    }
+}